@@ -0,0 +1,81 @@
+//! Functional vs imperative word counting.
+//!
+//! The crate counts words with folds: `borrowed_counts` folds the token
+//! iterator into a map through `IteratorExt::counts`. The baseline is the
+//! same tokenizer feeding a hand-written `for` loop with `entry` updates,
+//! and `map_reduce` runs the fold over chunks of lines on every core,
+//! merging one partial map per chunk with its `Monoid` instance. Inputs
+//! come from `fp::corpus`, so every run measures the same Zipf-distributed
+//! text.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::thread;
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use fp::analysis::{Filters, borrowed_counts, clean_words};
+use fp::corpus::{CorpusSpec, generate};
+use fp::iter_ext::IteratorExt;
+use fp::parallel::map_reduce;
+
+const SIZES: [usize; 2] = [100_000, 1_000_000];
+const LINES_PER_CHUNK: usize = 4096;
+
+fn for_loop<'a>(text: &'a str, filters: &Filters) -> HashMap<Cow<'a, str>, usize> {
+    let matcher = filters.matcher();
+    let mut counts = HashMap::new();
+    for line in text.lines() {
+        for word in clean_words(line, filters) {
+            if matcher.accepts(&word) {
+                *counts.entry(word).or_insert(0) += 1;
+            }
+        }
+    }
+    counts
+}
+
+/// The fold over one chunk of lines, as each `map_reduce` worker runs it
+fn count_lines<'a>(lines: &[&'a str], filters: &Filters) -> HashMap<Cow<'a, str>, usize> {
+    let matcher = filters.matcher();
+    lines
+        .iter()
+        .flat_map(|line| clean_words(line, filters))
+        .filter(|word| matcher.accepts(word))
+        .counts()
+}
+
+fn counting(c: &mut Criterion) {
+    let filters = Filters::default();
+    let workers = thread::available_parallelism().map_or(1, |n| n.get());
+    let mut group = c.benchmark_group("counting");
+    for words in SIZES {
+        let text = generate(&CorpusSpec {
+            words,
+            ..CorpusSpec::default()
+        });
+        group.throughput(Throughput::Bytes(text.len() as u64));
+        group.bench_with_input(BenchmarkId::new("fold", words), &text, |b, text| {
+            b.iter(|| borrowed_counts(black_box(text), &filters).len())
+        });
+        group.bench_with_input(BenchmarkId::new("for loop", words), &text, |b, text| {
+            b.iter(|| for_loop(black_box(text), &filters).len())
+        });
+        let lines: Vec<&str> = text.lines().collect();
+        group.bench_with_input(BenchmarkId::new("map_reduce", words), &lines, |b, lines| {
+            b.iter(|| {
+                map_reduce(
+                    black_box(lines).chunks(LINES_PER_CHUNK),
+                    1,
+                    workers,
+                    |chunk| count_lines(chunk, &filters),
+                )
+                .len()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, counting);
+criterion_main!(benches);
@@ -0,0 +1,76 @@
+//! PVec vs Vec clone-on-write.
+//!
+//! Keeping every version of a `Vec` means cloning it before each change,
+//! which is O(n) per update. `PVec` only copies the path to the changed leaf.
+
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use fp::persistent::PVec;
+
+const SIZES: [usize; 3] = [100, 1_000, 10_000];
+
+fn push_keeping_versions(c: &mut Criterion) {
+    let mut group = c.benchmark_group("push_keeping_versions");
+    for n in SIZES {
+        group.bench_with_input(BenchmarkId::new("PVec", n), &n, |b, &n| {
+            b.iter(|| {
+                let versions: Vec<PVec<usize>> = (0..n).fold(vec![PVec::new()], |mut vs, i| {
+                    let next = vs[vs.len() - 1].push(i);
+                    vs.push(next);
+                    vs
+                });
+                black_box(versions)
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("Vec clone", n), &n, |b, &n| {
+            b.iter(|| {
+                let versions: Vec<Vec<usize>> = (0..n).fold(vec![Vec::new()], |mut vs, i| {
+                    let mut next = vs[vs.len() - 1].clone();
+                    next.push(i);
+                    vs.push(next);
+                    vs
+                });
+                black_box(versions)
+            })
+        });
+    }
+    group.finish();
+}
+
+fn update_one(c: &mut Criterion) {
+    let mut group = c.benchmark_group("update_one");
+    for n in SIZES {
+        let pvec: PVec<usize> = (0..n).collect();
+        let vec: Vec<usize> = (0..n).collect();
+        group.bench_with_input(BenchmarkId::new("PVec", n), &n, |b, &n| {
+            b.iter(|| black_box(pvec.update(n / 2, 0)))
+        });
+        group.bench_with_input(BenchmarkId::new("Vec clone", n), &n, |b, &n| {
+            b.iter(|| {
+                let mut copy = vec.clone();
+                copy[n / 2] = 0;
+                black_box(copy)
+            })
+        });
+    }
+    group.finish();
+}
+
+fn get_all(c: &mut Criterion) {
+    let mut group = c.benchmark_group("get_all");
+    for n in SIZES {
+        let pvec: PVec<usize> = (0..n).collect();
+        let vec: Vec<usize> = (0..n).collect();
+        group.bench_with_input(BenchmarkId::new("PVec", n), &n, |b, &n| {
+            b.iter(|| (0..n).filter_map(|i| pvec.get(i)).sum::<usize>())
+        });
+        group.bench_with_input(BenchmarkId::new("Vec", n), &n, |b, &n| {
+            b.iter(|| (0..n).filter_map(|i| vec.get(i)).sum::<usize>())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, push_keeping_versions, update_one, get_all);
+criterion_main!(benches);
@@ -0,0 +1,89 @@
+//! ASCII fast path and zero-copy counting vs the allocating tokenizer.
+//!
+//! `clean_words` scans ASCII lines with a byte table instead of decoding
+//! chars and lowercasing them one by one, and borrows words that need no
+//! cleaning. The baseline copies every word into a `String`, as the
+//! tokenizer did before. On non-ASCII lines both sides run the Unicode path.
+//! The `hasher` group counts the same text with each `--hasher`.
+
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use fp::analysis::{
+    Filters, borrowed_counts, borrowed_counts_with_hasher, clean_word, clean_words,
+};
+use fp::hashing::{AHashBuilder, FxBuilder, SipBuilder};
+use fp::iter_ext::IteratorExt;
+
+const LOG_LINE: &str = r#"127.0.0.1 - - [10/Oct/2025:13:55:36 +0000] "GET /index.html HTTP/1.1" 200 2326 "Mozilla/5.0""#;
+const CLEAN_LINE: &str = "the quick brown fox jumps over the lazy dog and the cat";
+const PROSE_LINE: &str = "Ça, c'est la vie: Zoë mangea une crème brûlée au café près de l'église.";
+
+fn unicode_words(line: &str) -> impl Iterator<Item = String> + '_ {
+    line.split_whitespace()
+        .map(|word| clean_word(word).into_owned())
+        .filter(|word| !word.is_empty())
+}
+
+fn tokenize(c: &mut Criterion) {
+    let filters = Filters::default();
+    let mut group = c.benchmark_group("tokenize");
+    for (name, line) in [("ascii log", LOG_LINE), ("unicode prose", PROSE_LINE)] {
+        let text = format!("{}\n", line).repeat(1_000);
+        group.bench_with_input(BenchmarkId::new("clean_words", name), &text, |b, text| {
+            b.iter(|| {
+                text.lines()
+                    .map(|line| clean_words(black_box(line), &filters).count())
+                    .sum::<usize>()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("unicode path", name), &text, |b, text| {
+            b.iter(|| {
+                text.lines()
+                    .map(|line| unicode_words(black_box(line)).count())
+                    .sum::<usize>()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn count(c: &mut Criterion) {
+    let filters = Filters::default();
+    let mut group = c.benchmark_group("count");
+    for (name, line) in [("clean corpus", CLEAN_LINE), ("ascii log", LOG_LINE)] {
+        let text = format!("{}\n", line).repeat(1_000);
+        group.bench_with_input(BenchmarkId::new("borrowed", name), &text, |b, text| {
+            b.iter(|| borrowed_counts(black_box(text), &filters).len())
+        });
+        group.bench_with_input(BenchmarkId::new("owned", name), &text, |b, text| {
+            b.iter(|| {
+                black_box(text)
+                    .lines()
+                    .flat_map(unicode_words)
+                    .counts()
+                    .len()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn hasher(c: &mut Criterion) {
+    let filters = Filters::default();
+    let text = format!("{}\n{}\n", CLEAN_LINE, LOG_LINE).repeat(1_000);
+    let mut group = c.benchmark_group("hasher");
+    group.bench_function("sip", |b| {
+        b.iter(|| borrowed_counts_with_hasher::<SipBuilder>(black_box(&text), &filters).len())
+    });
+    group.bench_function("fx", |b| {
+        b.iter(|| borrowed_counts_with_hasher::<FxBuilder>(black_box(&text), &filters).len())
+    });
+    group.bench_function("ahash", |b| {
+        b.iter(|| borrowed_counts_with_hasher::<AHashBuilder>(black_box(&text), &filters).len())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, tokenize, count, hasher);
+criterion_main!(benches);
@@ -0,0 +1,175 @@
+//! Counting Core
+//!
+//! The tokenizer and counting fold of the `fp` word analyzer, written
+//! against `core` and `alloc` only. There is no file system, process
+//! environment or hasher to depend on, so words can be counted on embedded
+//! targets and in WASM without WASI. `fp` builds its filters, readers and
+//! CLI on top of these functions.
+//!
+//! Counts go into any [`Tally`]: a `BTreeMap` needs nothing but an
+//! allocator, and with the `std` feature a `HashMap` with any hasher works
+//! too.
+//!
+//! ```
+//! use std::borrow::Cow;
+//! use std::collections::BTreeMap;
+//!
+//! let counts: BTreeMap<Cow<str>, usize> = fp_core::count_words("The cat, the hat.\nA cat!");
+//! assert_eq!(counts["the"], 2);
+//! assert_eq!(counts["cat"], 2);
+//! assert_eq!(counts.len(), 4);
+//! ```
+
+#![no_std]
+
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+use alloc::borrow::Cow;
+use alloc::collections::BTreeMap;
+
+/// Clean a word by removing punctuation and lowercasing it, borrowing the
+/// word when it is already clean.
+///
+/// ```
+/// use std::borrow::Cow;
+///
+/// assert_eq!(fp_core::clean_word("Rust's!"), "rusts");
+/// assert!(matches!(fp_core::clean_word("ferris"), Cow::Borrowed("ferris")));
+/// ```
+pub fn clean_word(word: &str) -> Cow<'_, str> {
+    let is_clean = |c: char| c.is_alphanumeric() && c.to_lowercase().eq([c]);
+    if word.chars().all(is_clean) {
+        return Cow::Borrowed(word);
+    }
+    word.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase()) // handles Unicode case folding
+        .collect()
+}
+
+/// The cleaned, non-empty words of one line, as [`clean_word`] makes them.
+/// ASCII lines take the fast path of [`ascii_words`].
+///
+/// ```
+/// let words: Vec<_> = fp_core::words("Ça va, Zoë? OK").collect();
+/// assert_eq!(words, ["ça", "va", "zoë", "ok"]);
+/// ```
+pub fn words(line: &str) -> impl Iterator<Item = Cow<'_, str>> {
+    // Exactly one of the two is `Some`, so the chain is one path or the other
+    let ascii = ascii_words(line);
+    let unicode = ascii.is_none().then(|| {
+        line.split_whitespace()
+            .map(clean_word)
+            .filter(|word| !word.is_empty())
+    });
+    ascii
+        .into_iter()
+        .flatten()
+        .chain(unicode.into_iter().flatten())
+}
+
+/// What the ASCII fast path makes of each byte: [`SEPARATOR`] for
+/// whitespace, [`DROPPED`] for punctuation, otherwise the lowercased byte
+const ASCII_CLEAN: [u8; 128] = ascii_clean_table();
+const SEPARATOR: u8 = b' ';
+const DROPPED: u8 = 0;
+
+const fn ascii_clean_table() -> [u8; 128] {
+    let mut table = [DROPPED; 128];
+    let mut byte = 0;
+    while byte < 128 {
+        let b = byte as u8;
+        table[byte] = match b {
+            // The White_Space characters below 128, as `split_whitespace` uses
+            b'\t'..=b'\r' | b' ' => SEPARATOR,
+            _ if b.is_ascii_alphanumeric() => b.to_ascii_lowercase(),
+            _ => DROPPED,
+        };
+        byte += 1;
+    }
+    table
+}
+
+/// The cleaned words of an ASCII line, `None` if the line is not ASCII.
+/// The ASCII check is a word-at-a-time scan, and a byte table then finds
+/// word boundaries, drops punctuation and lowercases in one pass. Words
+/// already clean are borrowed. The words are the ones [`clean_word`] makes.
+///
+/// ```
+/// use std::borrow::Cow;
+///
+/// let words: Vec<_> = fp_core::ascii_words("GET /index.html -- 200 OK").unwrap().collect();
+/// assert_eq!(words, ["get", "indexhtml", "200", "ok"]);
+/// assert!(matches!(words[2], Cow::Borrowed("200")));
+/// assert!(fp_core::ascii_words("Ça va").is_none());
+/// ```
+pub fn ascii_words(line: &str) -> Option<impl Iterator<Item = Cow<'_, str>>> {
+    if !line.is_ascii() {
+        return None;
+    }
+    // Every char of an ASCII line is below 128
+    let words = line
+        .split(|c: char| ASCII_CLEAN[c as usize] == SEPARATOR)
+        .map(|word| {
+            if word
+                .bytes()
+                .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit())
+            {
+                Cow::Borrowed(word)
+            } else {
+                word.bytes()
+                    .map(|b| ASCII_CLEAN[b as usize])
+                    .filter(|&b| b != DROPPED)
+                    .map(char::from)
+                    .collect()
+            }
+        })
+        .filter(|word| !word.is_empty());
+    Some(words)
+}
+
+/// A map that counts how often it is given each key
+pub trait Tally<K>: Default {
+    /// Count one more `key`
+    fn tally(&mut self, key: K);
+}
+
+impl<K: Ord> Tally<K> for BTreeMap<K, usize> {
+    fn tally(&mut self, key: K) {
+        *self.entry(key).or_insert(0) += 1;
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K, S> Tally<K> for std::collections::HashMap<K, usize, S>
+where
+    K: Eq + core::hash::Hash,
+    S: core::hash::BuildHasher + Default,
+{
+    fn tally(&mut self, key: K) {
+        *self.entry(key).or_insert(0) += 1;
+    }
+}
+
+/// The counting fold: how often each item occurs, in any [`Tally`].
+///
+/// ```
+/// use std::collections::BTreeMap;
+///
+/// let counts: BTreeMap<char, usize> = fp_core::tally("abracadabra".chars());
+/// assert_eq!(counts[&'a'], 5);
+/// ```
+pub fn tally<K, M: Tally<K>>(items: impl IntoIterator<Item = K>) -> M {
+    items.into_iter().fold(M::default(), |mut counts, item| {
+        counts.tally(item);
+        counts
+    })
+}
+
+/// Count the cleaned words of a whole text, borrowing every word that
+/// needed no cleaning.
+pub fn count_words<'a, M: Tally<Cow<'a, str>>>(text: &'a str) -> M {
+    tally(text.lines().flat_map(words))
+}
@@ -0,0 +1,17 @@
+//! Arbitrary `--filter-expr` sources, evaluated on an arbitrary word when
+//! they parse. A filter that parses is type-checked, so evaluating it must
+//! never fail (`matches` debug-asserts it does not), and nesting is bounded
+//! so deep input is an error rather than a stack overflow.
+
+#![no_main]
+
+use fp::filter_expr::FilterExpr;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: (&str, &str)| {
+    let (source, word) = input;
+    if let Ok(filter) = FilterExpr::parse(source) {
+        filter.matches(word);
+        assert_eq!(filter.source(), source);
+    }
+});
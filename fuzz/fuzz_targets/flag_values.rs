@@ -0,0 +1,31 @@
+//! Arbitrary command-line values through every parser the `file_parser`
+//! flags hand them to: the `FromStr` settings, token filter specs and
+//! report templates. Parsing may fail but must not panic, and a template
+//! that parses must render.
+
+#![no_main]
+
+use fp::analysis::{LengthMode, word_counts};
+use fp::hashing::HashAlgorithm;
+use fp::input::{Column, InputFormat};
+use fp::locale::Locale;
+use fp::logging::Format;
+use fp::normalize::Normalization;
+use fp::template::Template;
+use fp::token_filter::Registry;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|value: &str| {
+    let _ = value.parse::<InputFormat>();
+    let _ = value.parse::<Column>();
+    let _ = value.parse::<Normalization>();
+    let _ = value.parse::<Locale>();
+    let _ = value.parse::<LengthMode>();
+    let _ = value.parse::<HashAlgorithm>();
+    let _ = value.parse::<Format>();
+    let _ = Registry::default().chain(value.split(','));
+
+    if let Ok(template) = Template::parse(value) {
+        template.render(&word_counts("fold map fold filter"));
+    }
+});
@@ -0,0 +1,40 @@
+//! Arbitrary bytes through the tokenizer and counting fold, as the server
+//! and the WASM playground receive them.
+//!
+//! Besides not panicking, the ASCII fast path must find the same words as
+//! the general Unicode path, and the counts must add up to the words.
+
+#![no_main]
+
+use fp::analysis::{Filters, borrowed_counts, clean_words};
+use fp::locale::Locale;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let text = String::from_utf8_lossy(data);
+
+    for line in text.lines() {
+        if let Some(fast) = fp_core::ascii_words(line) {
+            let general = line
+                .split_whitespace()
+                .map(fp_core::clean_word)
+                .filter(|word| !word.is_empty());
+            assert!(fast.eq(general), "paths disagree on {:?}", line);
+        }
+    }
+
+    let turkish = Filters {
+        locale: Some(Locale::Turkish),
+        strip_accents: true,
+        ..Filters::default()
+    };
+    for filters in [Filters::default(), turkish] {
+        let words = text
+            .lines()
+            .flat_map(|line| clean_words(line, &filters))
+            .filter(|word| filters.accepts(word))
+            .count();
+        let counted: usize = borrowed_counts(&text, &filters).values().sum();
+        assert_eq!(counted, words);
+    }
+});
@@ -0,0 +1,53 @@
+//! Anagrams
+//!
+//! Words are anagrams of one another when they use the same letters the
+//! same number of times, so sorting a word's letters gives a signature that
+//! all its anagrams share. Grouping a vocabulary by signature, with
+//! [`MultiMap::group_by`], finds every set of anagrams in one pass.
+//!
+//! ```
+//! use fp::anagrams::anagram_groups;
+//!
+//! let groups = anagram_groups(["listen", "map", "silent", "enlist", "pam", "fold", "200", "404"]);
+//! assert_eq!(groups, [vec!["enlist", "listen", "silent"], vec!["map", "pam"]]);
+//! ```
+
+use crate::multimap::MultiMap;
+
+/// The letters of `word` in sorted order, ignoring anything else
+///
+/// ```
+/// use fp::anagrams::signature;
+///
+/// assert_eq!(signature("listen"), "eilnst");
+/// assert_eq!(signature("don't"), signature("dont"));
+/// ```
+pub fn signature(word: &str) -> String {
+    let mut letters: Vec<char> = word.chars().filter(|c| c.is_alphabetic()).collect();
+    letters.sort_unstable();
+    letters.into_iter().collect()
+}
+
+/// The groups of two or more distinct words in `words` that are anagrams of
+/// each other, each in alphabetical order; the largest groups first, then
+/// by their first word
+///
+/// ```
+/// use fp::anagrams::anagram_groups;
+///
+/// let groups = anagram_groups("listen silent enlist google stone notes listen".split(' '));
+/// assert_eq!(groups, [vec!["enlist", "listen", "silent"], vec!["notes", "stone"]]);
+/// ```
+pub fn anagram_groups<'a>(words: impl IntoIterator<Item = &'a str>) -> Vec<Vec<&'a str>> {
+    let mut words: Vec<&str> = words.into_iter().collect();
+    words.sort_unstable();
+    words.dedup();
+    let mut groups: Vec<Vec<&str>> = MultiMap::group_by(words, |word| signature(word))
+        .into_iter()
+        // Words without letters, such as numbers, share the empty signature
+        .filter(|(signature, group)| !signature.is_empty() && group.len() > 1)
+        .map(|(_, group)| group)
+        .collect();
+    groups.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    groups
+}
@@ -0,0 +1,1070 @@
+//! Word Frequency Analysis
+//!
+//! The analyzer's core counting, usable without the `file_parser` binary:
+//! words are cleaned (punctuation dropped, lowercased) and counted per line,
+//! and the per-line counts merge through the `HashMap` `Monoid` instance.
+//! With the `async` feature, readers and whole directories can be analyzed
+//! on a tokio runtime without blocking it.
+//!
+//! ```
+//! use fp::analysis::word_counts;
+//!
+//! let counts = word_counts("The cat, the hat.\nA cat!");
+//! assert_eq!(counts["the"], 2);
+//! assert_eq!(counts["cat"], 2);
+//! assert_eq!(counts.len(), 4);
+//! ```
+
+use crate::cancel::{CancellationToken, Outcome};
+use crate::composition::Pipe;
+use crate::either::Either;
+use crate::fuzzy::{fuzzy_contains, fuzzy_ends_with, fuzzy_starts_with, tolerance};
+use crate::iter_ext::IteratorExt;
+use crate::locale::Locale;
+use crate::monoid::{Semigroup, fold_map};
+use crate::normalize::{self, Normalization};
+use fp_core::ascii_words;
+pub use fp_core::clean_word;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+use std::str::FromStr;
+use std::time::Duration;
+use unicode_segmentation::UnicodeSegmentation;
+
+#[cfg(feature = "async")]
+use crate::checkpoint::Checkpoint;
+#[cfg(feature = "async")]
+use std::io;
+#[cfg(feature = "async")]
+use std::path::{Path, PathBuf};
+#[cfg(feature = "async")]
+use tokio::io::{AsyncBufRead, AsyncBufReadExt};
+
+/// [`clean_word`] with the casing rules of `locale`.
+///
+/// ```
+/// use fp::analysis::clean_word_in;
+/// use fp::locale::Locale;
+///
+/// assert_eq!(clean_word_in("İstanbul'da", Locale::Turkish), "istanbulda");
+/// assert_eq!(clean_word_in("IRMAK", Locale::Turkish), "ırmak");
+/// assert_eq!(clean_word_in("IRMAK", Locale::German), "irmak");
+/// ```
+pub fn clean_word_in(word: &str, locale: Locale) -> Cow<'_, str> {
+    if !locale.tailors_case() {
+        return clean_word(word);
+    }
+    let is_clean = |c: char| c.is_alphanumeric() && c.to_lowercase().eq([c]);
+    if word.chars().all(is_clean) {
+        return Cow::Borrowed(word);
+    }
+    // Lowercase first: a combining dot above is not alphanumeric, but it
+    // decides how the "I" before it lowercases
+    locale
+        .lowercase(word)
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect()
+}
+
+/// Unicode full case folding (`CaseFolding.txt`, statuses C and F), the
+/// canonical form for caseless matching. Unlike lowercasing it maps "ß" to
+/// "ss" and final "ς" to "σ". The Turkic tailoring is not applied, so "I"
+/// folds to "i" and dotless "ı" stays distinct; [`Locale::fold_case`]
+/// applies it.
+///
+/// ```
+/// use fp::analysis::fold_case;
+///
+/// assert_eq!(fold_case("Straße"), fold_case("STRASSE"));
+/// assert_eq!(fold_case("ΟΔΟΣ"), fold_case("οδος"));
+/// assert_ne!(fold_case("ı"), fold_case("I"));
+/// ```
+pub fn fold_case(s: &str) -> String {
+    caseless::default_case_fold_str(s)
+}
+
+/// How many of the most frequent words a report keeps unless told otherwise
+pub const DEFAULT_TOP: usize = 10;
+
+/// How a word's length is measured by the length filters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LengthMode {
+    /// UTF-8 bytes
+    Bytes,
+    /// Unicode scalar values
+    Chars,
+    /// Extended grapheme clusters, what a reader sees as characters
+    #[default]
+    Graphemes,
+}
+
+impl LengthMode {
+    /// The length of `word` in this unit.
+    ///
+    /// ```
+    /// use fp::analysis::LengthMode;
+    ///
+    /// let word = "cafe\u{301}"; // "café" with a combining accent
+    /// assert_eq!(LengthMode::Bytes.measure(word), 6);
+    /// assert_eq!(LengthMode::Chars.measure(word), 5);
+    /// assert_eq!(LengthMode::Graphemes.measure(word), 4);
+    /// assert_eq!(LengthMode::Graphemes.measure("日本語"), 3);
+    /// ```
+    pub fn measure(self, word: &str) -> usize {
+        match self {
+            LengthMode::Bytes => word.len(),
+            LengthMode::Chars => word.chars().count(),
+            LengthMode::Graphemes => word.graphemes(true).count(),
+        }
+    }
+}
+
+impl FromStr for LengthMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bytes" => Ok(LengthMode::Bytes),
+            "chars" => Ok(LengthMode::Chars),
+            "graphemes" => Ok(LengthMode::Graphemes),
+            _ => Err(format!(
+                "Unknown length mode '{}', expected bytes, chars or graphemes",
+                s
+            )),
+        }
+    }
+}
+
+/// Word normalization and filters applied before counting. The string
+/// filters compare case folded text (see [`fold_case`]), so
+/// `--starts-with A` matches "apple" whatever the tokenizer did to its
+/// case. With a `locale`, words are lowercased and folded by its rules
+/// instead.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Filters {
+    /// Normalization form each word is brought to before it is cleaned
+    pub normalization: Normalization,
+    /// Remove diacritics after normalizing, so "café" counts as "cafe"
+    pub strip_accents: bool,
+    /// Keep words at least this long, measured by `length_mode`
+    pub min_length: Option<usize>,
+    /// Keep words at most this long, measured by `length_mode`
+    pub max_length: Option<usize>,
+    /// How `min_length` and `max_length` are measured
+    pub length_mode: LengthMode,
+    /// Keep words starting with this prefix
+    pub starts_with: Option<String>,
+    /// Keep words ending with this suffix
+    pub ends_with: Option<String>,
+    /// Keep words containing this substring
+    pub contains: Option<String>,
+    /// Edits by which a word may miss `starts_with`, `ends_with` and
+    /// `contains` and still pass, as [`tolerance`] allows for each pattern
+    pub fuzziness: usize,
+    /// Language whose casing rules apply, Unicode's defaults if `None`
+    pub locale: Option<Locale>,
+}
+
+impl Filters {
+    /// Normalize a raw word, strip its accents if asked to, and clean it.
+    ///
+    /// ```
+    /// use fp::analysis::Filters;
+    /// use fp::locale::Locale;
+    ///
+    /// let filters = Filters { strip_accents: true, ..Filters::default() };
+    /// assert_eq!(filters.clean("Café!"), "cafe");
+    /// assert_eq!(Filters::default().clean("Café!"), "café");
+    ///
+    /// let turkish = Filters { locale: Some(Locale::Turkish), ..Filters::default() };
+    /// assert_eq!(turkish.clean("KIŞ"), "kış");
+    /// assert_eq!(Filters::default().clean("KIŞ"), "kiş");
+    /// ```
+    pub fn clean<'a>(&self, word: &'a str) -> Cow<'a, str> {
+        let word = self.normalization.apply(word);
+        let word = if self.strip_accents {
+            and_then_cow(word, normalize::strip_accents)
+        } else {
+            word
+        };
+        match self.locale {
+            Some(locale) => and_then_cow(word, |word| clean_word_in(word, locale)),
+            None => and_then_cow(word, clean_word),
+        }
+    }
+
+    /// Whether a cleaned word passes every filter that is set. The string
+    /// filters' patterns are folded on every call; to test many words,
+    /// build a [`Matcher`] once.
+    ///
+    /// ```
+    /// use fp::analysis::Filters;
+    ///
+    /// let filters = Filters {
+    ///     min_length: Some(3),
+    ///     starts_with: Some("fo".to_string()),
+    ///     ends_with: Some("d".to_string()),
+    ///     contains: Some("l".to_string()),
+    ///     ..Filters::default()
+    /// };
+    /// assert!(filters.accepts("fold"));
+    /// assert!(!filters.accepts("fod"));
+    /// assert!(!filters.accepts("fp"));
+    /// assert!(!filters.accepts("mold"));
+    ///
+    /// let german = Filters { ends_with: Some("SS".to_string()), ..Filters::default() };
+    /// assert!(german.accepts("Gruß"));
+    ///
+    /// let short = Filters { max_length: Some(4), ..Filters::default() };
+    /// assert!(short.accepts("café"));
+    /// assert!(!short.accepts("https"));
+    ///
+    /// let ocr = Filters { starts_with: Some("func".to_string()), fuzziness: 1, ..Filters::default() };
+    /// assert!(ocr.accepts("fnnctional"));
+    /// assert!(!ocr.accepts("fold"));
+    /// ```
+    pub fn accepts(&self, word: &str) -> bool {
+        self.matcher().accepts(word)
+    }
+
+    /// The filters ready to test many words, with the string filters'
+    /// patterns folded and their tolerances worked out once
+    ///
+    /// ```
+    /// use fp::analysis::Filters;
+    ///
+    /// let filters = Filters { starts_with: Some("FO".to_string()), ..Filters::default() };
+    /// let matcher = filters.matcher();
+    /// let kept: Vec<&str> = ["fold", "map", "food"].into_iter().filter(|w| matcher.accepts(w)).collect();
+    /// assert_eq!(kept, ["fold", "food"]);
+    /// ```
+    pub fn matcher(&self) -> Matcher {
+        let pattern = |pattern: &Option<String>, test: Test| {
+            pattern.as_deref().map(|pattern| {
+                let pattern = fold_in(self.locale, pattern);
+                let tolerance = tolerance(&pattern, self.fuzziness);
+                (pattern, tolerance, test)
+            })
+        };
+        Matcher {
+            min_length: self.min_length,
+            max_length: self.max_length,
+            length_mode: self.length_mode,
+            locale: self.locale,
+            patterns: [
+                pattern(&self.starts_with, fuzzy_starts_with),
+                pattern(&self.ends_with, fuzzy_ends_with),
+                pattern(&self.contains, fuzzy_contains),
+            ]
+            .into_iter()
+            .flatten()
+            .collect(),
+        }
+    }
+}
+
+/// Case fold by `locale`'s rules, or Unicode's defaults if `None`
+fn fold_in(locale: Option<Locale>, s: &str) -> String {
+    match locale {
+        Some(locale) => locale.fold_case(s),
+        None => fold_case(s),
+    }
+}
+
+/// A fuzzy string test: word, pattern, edits allowed
+type Test = fn(&str, &str, usize) -> bool;
+
+/// The word filters of a [`Filters`], prepared by [`Filters::matcher`]
+#[derive(Debug, Clone, Default)]
+pub struct Matcher {
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    length_mode: LengthMode,
+    locale: Option<Locale>,
+    /// Each string filter that is set: its folded pattern, the edits a
+    /// word may miss it by, and how it is tested
+    patterns: Vec<(String, usize, Test)>,
+}
+
+impl Matcher {
+    /// Whether a cleaned word passes every filter that is set
+    ///
+    /// ```
+    /// use fp::analysis::Filters;
+    ///
+    /// let matcher = Filters { min_length: Some(4), contains: Some("L".to_string()), ..Filters::default() }.matcher();
+    /// assert!(matcher.accepts("fold"));
+    /// assert!(!matcher.accepts("old"));
+    /// assert!(matcher.accepts("FILTER"));
+    /// assert!(!matcher.accepts("maps"));
+    /// ```
+    pub fn accepts(&self, word: &str) -> bool {
+        self.accepts_length(word) && self.accepts_text(word)
+    }
+
+    /// Whether the word is within the length bounds, measuring it only if
+    /// a bound is set
+    fn accepts_length(&self, word: &str) -> bool {
+        if self.min_length.is_none() && self.max_length.is_none() {
+            return true;
+        }
+        let length = self.length_mode.measure(word);
+        self.min_length.is_none_or(|n| length >= n) && self.max_length.is_none_or(|n| length <= n)
+    }
+
+    /// Whether the case folded word passes the string filters
+    fn accepts_text(&self, word: &str) -> bool {
+        if self.patterns.is_empty() {
+            return true;
+        }
+        let word = fold_in(self.locale, word);
+        self.patterns
+            .iter()
+            .all(|(pattern, tolerance, test)| test(&word, pattern, *tolerance))
+    }
+}
+
+/// Count the cleaned words of one line.
+///
+/// ```
+/// use fp::analysis::line_counts;
+///
+/// let counts = line_counts("Fold, fold and MAP!");
+/// assert_eq!(counts["fold"], 2);
+/// assert_eq!(counts["map"], 1);
+/// assert_eq!(counts.len(), 3);
+/// ```
+pub fn line_counts(line: &str) -> HashMap<String, usize> {
+    filtered_line_counts(line, &Filters::default())
+}
+
+/// The cleaned, non-empty words of one line, as [`Filters::clean`] makes
+/// them. ASCII lines, the common case for logs and code, take a fast path:
+/// [`fp_core::ascii_words`] finds word boundaries, drops punctuation and
+/// lowercases with a byte table in one pass. Normalization and accent
+/// stripping leave ASCII unchanged, so both paths give the same words. A
+/// locale that tailors casing, where ASCII "I" is not lowercased to "i",
+/// always takes the general path.
+///
+/// ```
+/// use fp::analysis::{Filters, clean_words};
+///
+/// let filters = Filters::default();
+/// let words: Vec<_> = clean_words("GET /index.html -- 200 OK", &filters).collect();
+/// assert_eq!(words, ["get", "indexhtml", "200", "ok"]);
+/// assert!(matches!(words[2], std::borrow::Cow::Borrowed("200")));
+///
+/// let words: Vec<_> = clean_words("Ça va, Zoë?", &filters).collect();
+/// assert_eq!(words, ["ça", "va", "zoë"]);
+/// ```
+pub fn clean_words<'a>(line: &'a str, filters: &Filters) -> impl Iterator<Item = Cow<'a, str>> {
+    let ascii = ascii_words(line).filter(|_| !filters.locale.is_some_and(Locale::tailors_case));
+    match ascii {
+        Some(words) => Either::Left(words),
+        None => Either::Right(
+            line.split_whitespace()
+                .map(|word| filters.clean(word))
+                .filter(|word| !word.is_empty()),
+        ),
+    }
+}
+
+/// Count the cleaned words of one line that pass `filters`.
+///
+/// ```
+/// use fp::analysis::{Filters, filtered_line_counts};
+///
+/// let filters = Filters { starts_with: Some("f".to_string()), ..Filters::default() };
+/// let counts = filtered_line_counts("fold filter map fold", &filters);
+/// assert_eq!(counts["fold"], 2);
+/// assert_eq!(counts["filter"], 1);
+/// assert!(!counts.contains_key("map"));
+/// ```
+pub fn filtered_line_counts(line: &str, filters: &Filters) -> HashMap<String, usize> {
+    into_owned(borrowed_line_counts(line, filters))
+}
+
+/// Count the words of one line that pass `filters`, borrowing every word
+/// that needed no cleaning from `line`.
+///
+/// ```
+/// use fp::analysis::{Filters, borrowed_line_counts};
+/// use std::borrow::Cow;
+///
+/// let counts = borrowed_line_counts("map Map!", &Filters::default());
+/// assert_eq!(counts["map"], 2);
+/// assert!(counts.keys().all(|word| matches!(word, Cow::Borrowed("map"))));
+/// ```
+pub fn borrowed_line_counts<'a>(line: &'a str, filters: &Filters) -> HashMap<Cow<'a, str>, usize> {
+    matched_line_counts(line, filters, &filters.matcher())
+}
+
+/// [`borrowed_line_counts`] with the filters' [`Matcher`] already built,
+/// for counting many lines
+fn matched_line_counts<'a>(
+    line: &'a str,
+    filters: &Filters,
+    matcher: &Matcher,
+) -> HashMap<Cow<'a, str>, usize> {
+    clean_words(line, filters)
+        .filter(|word| matcher.accepts(word))
+        .counts()
+}
+
+/// Count the words of a whole text that pass `filters` without copying
+/// them: a word is only allocated if cleaning changed it. Repeated clean
+/// words cost a hash lookup each.
+///
+/// ```
+/// use fp::analysis::{Filters, borrowed_counts};
+/// use std::borrow::Cow;
+///
+/// let counts = borrowed_counts("fold map\nFold fold", &Filters::default());
+/// assert_eq!(counts["fold"], 3);
+/// assert!(counts.keys().any(|word| matches!(word, Cow::Borrowed("map"))));
+/// ```
+pub fn borrowed_counts<'a>(text: &'a str, filters: &Filters) -> HashMap<Cow<'a, str>, usize> {
+    borrowed_counts_with_hasher(text, filters)
+}
+
+/// [`borrowed_counts`] in a map built with the hasher `S`; see
+/// [`hashing`](crate::hashing) for faster ones than std's SipHash.
+///
+/// ```
+/// use fp::analysis::{Filters, borrowed_counts_with_hasher};
+/// use std::collections::hash_map::DefaultHasher;
+/// use std::hash::BuildHasherDefault;
+///
+/// let counts = borrowed_counts_with_hasher::<BuildHasherDefault<DefaultHasher>>(
+///     "fold map\nfold",
+///     &Filters::default(),
+/// );
+/// assert_eq!(counts["fold"], 2);
+/// ```
+pub fn borrowed_counts_with_hasher<'a, S: BuildHasher + Default>(
+    text: &'a str,
+    filters: &Filters,
+) -> HashMap<Cow<'a, str>, usize, S> {
+    let matcher = filters.matcher();
+    text.lines()
+        .flat_map(|line| clean_words(line, filters))
+        .filter(|word| matcher.accepts(word))
+        .counts_with_hasher()
+}
+
+/// Copy the borrowed words of a count, hashed any way, into owned strings
+///
+/// ```
+/// use fp::analysis::{Filters, borrowed_counts, into_owned};
+/// use std::collections::HashMap;
+///
+/// let owned: HashMap<String, usize> = into_owned(borrowed_counts("Fold fold", &Filters::default()));
+/// assert_eq!(owned, HashMap::from([("fold".to_string(), 2)]));
+/// ```
+pub fn into_owned<S>(counts: HashMap<Cow<'_, str>, usize, S>) -> HashMap<String, usize> {
+    counts
+        .into_iter()
+        .map(|(word, count)| (word.into_owned(), count))
+        .collect()
+}
+
+/// Apply a borrowing transformation to a `Cow`, keeping the original borrow
+/// when both steps borrow
+fn and_then_cow<'a>(word: Cow<'a, str>, f: impl Fn(&str) -> Cow<'_, str>) -> Cow<'a, str> {
+    match word {
+        Cow::Borrowed(word) => f(word),
+        Cow::Owned(word) => Cow::Owned(f(&word).into_owned()),
+    }
+}
+
+/// Count the cleaned words of a whole text, line by line.
+///
+/// ```
+/// use fp::analysis::word_counts;
+///
+/// let counts = word_counts("The cat.\nthe HAT");
+/// assert_eq!(counts["the"], 2);
+/// assert_eq!(counts.values().sum::<usize>(), 4);
+/// ```
+pub fn word_counts(text: &str) -> HashMap<String, usize> {
+    fold_map(text.lines(), line_counts)
+}
+
+/// Count the words of `text` that pass `filters` and summarize them, keeping
+/// the `top` most frequent. The entry point for embedding the analyzer.
+///
+/// ```
+/// use fp::analysis::{Filters, analyze};
+///
+/// let filters = Filters { min_length: Some(4), ..Filters::default() };
+/// let report = analyze("map fold fold filter", &filters, 1);
+/// assert_eq!(report.total_words, 3);
+/// assert_eq!(report.top_words, vec![("fold".to_string(), 2)]);
+/// ```
+pub fn analyze(text: &str, filters: &Filters, top: usize) -> Report {
+    Report::new(&filtered_counts(text, filters), top)
+}
+
+/// Count the words of a whole text that pass `filters`, line by line.
+///
+/// ```
+/// use fp::analysis::{Filters, filtered_counts};
+///
+/// let filters = Filters { max_length: Some(3), ..Filters::default() };
+/// let counts = filtered_counts("map fold\nmap filter", &filters);
+/// assert_eq!(counts.len(), 1);
+/// assert_eq!(counts["map"], 2);
+/// ```
+pub fn filtered_counts(text: &str, filters: &Filters) -> HashMap<String, usize> {
+    into_owned(borrowed_counts(text, filters))
+}
+
+/// How many lines are counted between two prunings of a capped vocabulary
+pub const PRUNE_INTERVAL: usize = 1024;
+
+/// Keep only the `max_vocab` most frequent words, ties broken alphabetically,
+/// dropping the counts of the rest.
+///
+/// ```
+/// use fp::analysis::{prune, word_counts};
+///
+/// let counts = prune(word_counts("b a b c d d"), 2);
+/// assert_eq!(counts.len(), 2);
+/// assert_eq!((counts["b"], counts["d"]), (2, 2));
+/// ```
+pub fn prune<K: Ord + Hash, S: BuildHasher + Default>(
+    counts: HashMap<K, usize, S>,
+    max_vocab: usize,
+) -> HashMap<K, usize, S> {
+    if counts.len() <= max_vocab {
+        return counts;
+    }
+    let mut ranked: Vec<(K, usize)> = counts.into_iter().collect();
+    if max_vocab > 0 {
+        ranked.select_nth_unstable_by(max_vocab - 1, by_rank);
+    }
+    ranked.truncate(max_vocab);
+    ranked.into_iter().collect()
+}
+
+/// [`filtered_counts`] with memory bounded by a vocabulary of `max_vocab`
+/// words. Lines are counted in batches of [`PRUNE_INTERVAL`]; after each
+/// batch is merged, the map is [`prune`]d back to `max_vocab` entries. A
+/// pruned word that shows up again starts over from zero, so counts are
+/// never too high but rare words may be undercounted or missing. The result
+/// depends only on the text, never on timing.
+///
+/// ```
+/// use fp::analysis::{Filters, capped_counts};
+///
+/// let counts = capped_counts("the cat\nthe hat\nthe end", &Filters::default(), 1);
+/// assert_eq!(counts.len(), 1);
+/// assert_eq!(counts["the"], 3);
+/// ```
+pub fn capped_counts(text: &str, filters: &Filters, max_vocab: usize) -> HashMap<String, usize> {
+    let lines: Vec<&str> = text.lines().collect();
+    let matcher = filters.matcher();
+    lines
+        .chunks(PRUNE_INTERVAL)
+        .fold(HashMap::new(), |counts, batch| {
+            let batch_counts = fold_map(batch, |line| matched_line_counts(line, filters, &matcher));
+            prune(counts.combine(batch_counts), max_vocab)
+        })
+        .pipe(into_owned)
+}
+
+/// [`filtered_counts`] that stops once `token` is cancelled, checked every
+/// [`PRUNE_INTERVAL`] lines. A cancelled count covers the lines before it
+/// stopped.
+///
+/// ```
+/// use fp::analysis::{Filters, cancellable_counts};
+/// use fp::cancel::{CancellationToken, Outcome};
+///
+/// let token = CancellationToken::new();
+/// let counts = cancellable_counts("fold map\nfold", &Filters::default(), &token);
+/// assert_eq!(counts.into_inner()["fold"], 2);
+///
+/// token.cancel();
+/// let counts = cancellable_counts("fold map\nfold", &Filters::default(), &token);
+/// assert_eq!(counts, Outcome::Cancelled(Default::default()));
+/// ```
+pub fn cancellable_counts(
+    text: &str,
+    filters: &Filters,
+    token: &CancellationToken,
+) -> Outcome<HashMap<String, usize>> {
+    let lines: Vec<&str> = text.lines().collect();
+    let matcher = filters.matcher();
+    token
+        .fold(
+            lines.chunks(PRUNE_INTERVAL),
+            HashMap::new(),
+            |counts, batch| {
+                counts.combine(fold_map(batch, |line| {
+                    matched_line_counts(line, filters, &matcher)
+                }))
+            },
+        )
+        .map(into_owned)
+}
+
+/// Most frequent first, then alphabetical
+fn by_rank<K: Ord>((a, x): &(K, usize), (b, y): &(K, usize)) -> std::cmp::Ordering {
+    y.cmp(x).then_with(|| a.cmp(b))
+}
+
+/// Reading and speaking speeds in words per minute, for the time estimates
+/// in reports. The defaults are typical of adults reading silently (238)
+/// and of a talk or audiobook (150).
+///
+/// ```
+/// use std::time::Duration;
+/// use fp::analysis::Speeds;
+///
+/// let speeds = Speeds::default();
+/// assert_eq!(speeds.reading_time(476), Duration::from_secs(120));
+/// assert_eq!(speeds.speaking_time(75), Duration::from_secs(30));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Speeds {
+    /// Words a minute read silently
+    pub reading: usize,
+    /// Words a minute read aloud
+    pub speaking: usize,
+}
+
+impl Default for Speeds {
+    fn default() -> Self {
+        Speeds {
+            reading: 238,
+            speaking: 150,
+        }
+    }
+}
+
+impl Speeds {
+    /// How long reading `words` takes, rounded up to the second
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use fp::analysis::Speeds;
+    ///
+    /// let speeds = Speeds { reading: 200, speaking: 100 };
+    /// assert_eq!(speeds.reading_time(400), Duration::from_secs(120));
+    /// // A partial second counts as a whole one
+    /// assert_eq!(speeds.reading_time(1), Duration::from_secs(1));
+    /// ```
+    pub fn reading_time(&self, words: usize) -> Duration {
+        time_at(words, self.reading)
+    }
+
+    /// How long reading `words` aloud takes, rounded up to the second
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use fp::analysis::Speeds;
+    ///
+    /// let speeds = Speeds { reading: 200, speaking: 100 };
+    /// assert_eq!(speeds.speaking_time(400), Duration::from_secs(240));
+    /// ```
+    pub fn speaking_time(&self, words: usize) -> Duration {
+        time_at(words, self.speaking)
+    }
+}
+
+/// `words` at `per_minute` words a minute, in whole seconds rounded up
+fn time_at(words: usize, per_minute: usize) -> Duration {
+    let seconds = (words as u64 * 60).div_ceil(per_minute.max(1) as u64);
+    Duration::from_secs(seconds)
+}
+
+/// A duration the way a reading time is given: `45 s`, `3 min 20 s`, or
+/// `1 h 5 min` once seconds no longer matter
+///
+/// ```
+/// use std::time::Duration;
+/// use fp::analysis::format_duration;
+///
+/// assert_eq!(format_duration(Duration::from_secs(45)), "45 s");
+/// assert_eq!(format_duration(Duration::from_secs(200)), "3 min 20 s");
+/// assert_eq!(format_duration(Duration::from_secs(180)), "3 min");
+/// assert_eq!(format_duration(Duration::from_secs(3930)), "1 h 5 min");
+/// ```
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    match (hours, minutes, seconds) {
+        (0, 0, s) => format!("{} s", s),
+        (0, m, 0) => format!("{} min", m),
+        (0, m, s) => format!("{} min {} s", m, s),
+        (h, m, _) => format!("{} h {} min", h, m),
+    }
+}
+
+/// Summary of a word count: totals, the most frequent words, and how long
+/// the text takes to read and to speak, ready to be rendered as JSON.
+///
+/// ```
+/// use std::time::Duration;
+/// use fp::analysis::{Report, Speeds, word_counts};
+///
+/// let report = Report::new(&word_counts("b a b c"), 2);
+/// assert_eq!(report.total_words, 4);
+/// assert_eq!(report.top_words, vec![("b".to_string(), 2), ("a".to_string(), 1)]);
+/// assert_eq!(
+///     report.to_json(),
+///     r#"{"total_words":4,"unique_words":3,"reading_seconds":2,"speaking_seconds":2,"top_words":[{"word":"b","count":2},{"word":"a","count":1}]}"#
+/// );
+///
+/// let slow = report.with_speeds(Speeds { reading: 60, speaking: 30 });
+/// assert_eq!(slow.reading_time, Duration::from_secs(4));
+/// assert_eq!(slow.speaking_time, Duration::from_secs(8));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    /// Words counted, repeats included
+    pub total_words: usize,
+    /// Distinct words
+    pub unique_words: usize,
+    /// Most frequent first; ties in alphabetical order
+    pub top_words: Vec<(String, usize)>,
+    /// At the default [`Speeds`] unless set with [`Report::with_speeds`]
+    pub reading_time: Duration,
+    /// How long reading the text aloud takes, at the same speeds as
+    /// `reading_time`
+    pub speaking_time: Duration,
+}
+
+impl Report {
+    /// Summarize `counts`, keeping the `top` most frequent words.
+    ///
+    /// ```
+    /// use fp::analysis::{Report, word_counts};
+    ///
+    /// let report = Report::new(&word_counts("map fold filter fold map"), 2);
+    /// assert_eq!((report.total_words, report.unique_words), (5, 3));
+    /// // Ties are broken alphabetically
+    /// assert_eq!(report.top_words, [("fold".to_string(), 2), ("map".to_string(), 2)]);
+    /// ```
+    pub fn new(counts: &HashMap<String, usize>, top: usize) -> Self {
+        let mut ranked: Vec<(String, usize)> = counts
+            .iter()
+            .map(|(word, &count)| (word.clone(), count))
+            .collect();
+        ranked.sort_by(by_rank);
+        ranked.truncate(top);
+        let total_words = counts.values().sum();
+        Report {
+            total_words,
+            unique_words: counts.len(),
+            top_words: ranked,
+            reading_time: Speeds::default().reading_time(total_words),
+            speaking_time: Speeds::default().speaking_time(total_words),
+        }
+    }
+
+    /// The same report with the times estimated at `speeds`
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use fp::analysis::{Report, Speeds, word_counts};
+    ///
+    /// let report = Report::new(&word_counts(&"word ".repeat(300)), 1);
+    /// let fast = report.with_speeds(Speeds { reading: 600, speaking: 300 });
+    /// assert_eq!(fast.reading_time, Duration::from_secs(30));
+    /// assert_eq!(fast.speaking_time, Duration::from_secs(60));
+    /// ```
+    pub fn with_speeds(self, speeds: Speeds) -> Self {
+        Report {
+            reading_time: speeds.reading_time(self.total_words),
+            speaking_time: speeds.speaking_time(self.total_words),
+            ..self
+        }
+    }
+
+    /// Render the report as a compact JSON object.
+    ///
+    /// ```
+    /// use fp::analysis::{Report, word_counts};
+    ///
+    /// let json = Report::new(&word_counts("\"quoted\" word"), 1).to_json();
+    /// assert!(json.starts_with(r#"{"total_words":2,"unique_words":2,"#));
+    /// assert!(json.ends_with(r#""top_words":[{"word":"quoted","count":1}]}"#));
+    /// ```
+    pub fn to_json(&self) -> String {
+        let top_words: Vec<String> = self
+            .top_words
+            .iter()
+            .map(|(word, count)| format!(r#"{{"word":{},"count":{}}}"#, json_string(word), count))
+            .collect();
+        format!(
+            r#"{{"total_words":{},"unique_words":{},"reading_seconds":{},"speaking_seconds":{},"top_words":[{}]}}"#,
+            self.total_words,
+            self.unique_words,
+            self.reading_time.as_secs(),
+            self.speaking_time.as_secs(),
+            top_words.join(",")
+        )
+    }
+}
+
+/// Quote and escape a string as a JSON string literal.
+///
+/// ```
+/// assert_eq!(fp::analysis::json_string("say \"hi\"\n"), r#""say \"hi\"\n""#);
+/// ```
+pub fn json_string(s: &str) -> String {
+    let mut quoted = s.chars().fold(String::from("\""), |mut out, c| {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+        out
+    });
+    quoted.push('"');
+    quoted
+}
+
+/// Count the words of an async reader line by line, awaiting each line so
+/// the runtime is never blocked.
+///
+/// ```
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// use fp::analysis::analyze_reader_async;
+///
+/// let upload: &[u8] = b"fold map\nfold";
+/// let counts = analyze_reader_async(upload).await.unwrap();
+/// assert_eq!(counts["fold"], 2);
+/// assert_eq!(counts["map"], 1);
+/// # });
+/// ```
+#[cfg(feature = "async")]
+pub async fn analyze_reader_async(
+    reader: impl AsyncBufRead + Unpin,
+) -> io::Result<HashMap<String, usize>> {
+    count_reader_until(reader, &CancellationToken::new())
+        .await
+        .map(Outcome::into_inner)
+}
+
+/// Count the lines of an async reader until `token` is cancelled
+#[cfg(feature = "async")]
+async fn count_reader_until(
+    reader: impl AsyncBufRead + Unpin,
+    token: &CancellationToken,
+) -> io::Result<Outcome<HashMap<String, usize>>> {
+    let mut lines = reader.lines();
+    let mut counts = HashMap::new();
+    while let Some(line) = lines.next_line().await? {
+        if token.is_cancelled() {
+            return Ok(Outcome::Cancelled(counts));
+        }
+        counts = counts.combine(line_counts(&line));
+    }
+    Ok(Outcome::Complete(counts))
+}
+
+/// Every file below `root`, sorted, found without recursion by keeping the
+/// directories still to visit on a stack.
+///
+/// ```
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// use fp::analysis::walk_dir_async;
+///
+/// let root = std::env::temp_dir().join("fp_walk_dir_async");
+/// std::fs::create_dir_all(root.join("nested")).unwrap();
+/// std::fs::write(root.join("b.txt"), "").unwrap();
+/// std::fs::write(root.join("nested/a.txt"), "").unwrap();
+///
+/// let files = walk_dir_async(&root).await.unwrap();
+/// assert_eq!(files, [root.join("b.txt"), root.join("nested/a.txt")]);
+/// # std::fs::remove_dir_all(&root).unwrap();
+/// # });
+/// ```
+#[cfg(feature = "async")]
+pub async fn walk_dir_async(root: impl AsRef<Path>) -> io::Result<Vec<PathBuf>> {
+    let mut pending = vec![root.as_ref().to_path_buf()];
+    let mut files = Vec::new();
+    while let Some(dir) = pending.pop() {
+        let mut entries = tokio::fs::read_dir(&dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            if entry.file_type().await?.is_dir() {
+                pending.push(entry.path());
+            } else {
+                files.push(entry.path());
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Analyze every file below `root`, returning the counts per file.
+///
+/// ```
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// use fp::analysis::analyze_dir_async;
+///
+/// let root = std::env::temp_dir().join("fp_analyze_dir_async");
+/// std::fs::create_dir_all(root.join("nested")).unwrap();
+/// std::fs::write(root.join("a.txt"), "fold map").unwrap();
+/// std::fs::write(root.join("nested/b.txt"), "fold").unwrap();
+///
+/// let per_file = analyze_dir_async(&root).await.unwrap();
+/// assert_eq!(per_file.len(), 2);
+/// assert_eq!(per_file[&root.join("nested/b.txt")]["fold"], 1);
+/// # std::fs::remove_dir_all(&root).unwrap();
+/// # });
+/// ```
+#[cfg(feature = "async")]
+pub async fn analyze_dir_async(
+    root: impl AsRef<Path>,
+) -> io::Result<HashMap<PathBuf, HashMap<String, usize>>> {
+    analyze_dir_async_cancellable(root, &CancellationToken::new())
+        .await
+        .map(Outcome::into_inner)
+}
+
+/// [`analyze_dir_async`] that stops once `token` is cancelled, checked
+/// before every line. A cancelled scan returns the files counted so far,
+/// the last one possibly in part.
+///
+/// ```
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// use fp::analysis::analyze_dir_async_cancellable;
+/// use fp::cancel::CancellationToken;
+/// use std::time::Duration;
+///
+/// let root = std::env::temp_dir().join("fp_analyze_dir_async_cancellable");
+/// std::fs::create_dir_all(&root).unwrap();
+/// std::fs::write(root.join("a.txt"), "fold map").unwrap();
+///
+/// let token = CancellationToken::with_timeout(Duration::ZERO);
+/// let outcome = analyze_dir_async_cancellable(&root, &token).await.unwrap();
+/// assert!(outcome.is_cancelled());
+/// assert!(outcome.into_inner().is_empty());
+/// # std::fs::remove_dir_all(&root).unwrap();
+/// # });
+/// ```
+#[cfg(feature = "async")]
+pub async fn analyze_dir_async_cancellable(
+    root: impl AsRef<Path>,
+    token: &CancellationToken,
+) -> io::Result<Outcome<HashMap<PathBuf, HashMap<String, usize>>>> {
+    let mut per_file = HashMap::new();
+    for path in walk_dir_async(root).await? {
+        if token.is_cancelled() {
+            return Ok(Outcome::Cancelled(per_file));
+        }
+        let file = tokio::io::BufReader::new(tokio::fs::File::open(&path).await?);
+        let outcome = count_reader_until(file, token).await?;
+        let cancelled = outcome.is_cancelled();
+        per_file.insert(path, outcome.into_inner());
+        if cancelled {
+            return Ok(Outcome::Cancelled(per_file));
+        }
+    }
+    Ok(Outcome::Complete(per_file))
+}
+
+/// Count every file below `root` into one total, resuming from the
+/// [`Checkpoint`] at `state` if there is one. The checkpoint is saved after
+/// every `every` finished files, when `token` is cancelled, and at the end.
+/// A rerun after a crash or cancellation skips the files already counted.
+/// Keep `state` outside `root`.
+///
+/// ```
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// use fp::analysis::{analyze_dir_async_resumable, word_counts};
+/// use fp::cancel::{CancellationToken, Outcome};
+/// use fp::checkpoint::Checkpoint;
+///
+/// let root = std::env::temp_dir().join("fp_analyze_dir_async_resumable");
+/// let state = std::env::temp_dir().join("fp_analyze_dir_async_resumable.state");
+/// std::fs::create_dir_all(&root).unwrap();
+/// std::fs::write(root.join("a.txt"), "fold map").unwrap();
+/// std::fs::write(root.join("b.txt"), "fold").unwrap();
+///
+/// // An earlier run finished a.txt before it was stopped
+/// let mut earlier = Checkpoint::default();
+/// earlier.record(&root.join("a.txt"), word_counts("fold"));
+/// earlier.save(&state).unwrap();
+///
+/// let token = CancellationToken::new();
+/// let outcome = analyze_dir_async_resumable(&root, &state, 100, &token).await.unwrap();
+/// let Outcome::Complete(counts) = outcome else { panic!("not cancelled") };
+/// assert_eq!(counts["fold"], 2);
+/// assert!(!counts.contains_key("map")); // a.txt was not counted again
+/// assert_eq!(Checkpoint::load(&state).unwrap().files(), 2);
+/// # std::fs::remove_dir_all(&root).unwrap();
+/// # std::fs::remove_file(&state).unwrap();
+/// # });
+/// ```
+#[cfg(feature = "async")]
+pub async fn analyze_dir_async_resumable(
+    root: impl AsRef<Path>,
+    state: &Path,
+    every: usize,
+    token: &CancellationToken,
+) -> io::Result<Outcome<HashMap<String, usize>>> {
+    let mut checkpoint = load_checkpoint(state).await?;
+    let mut unsaved = 0;
+    for path in walk_dir_async(root).await? {
+        if checkpoint.is_done(&path) || path == state {
+            continue;
+        }
+        if token.is_cancelled() {
+            let checkpoint = save_checkpoint(checkpoint, state).await?;
+            return Ok(Outcome::Cancelled(checkpoint.into_counts()));
+        }
+        let file = tokio::io::BufReader::new(tokio::fs::File::open(&path).await?);
+        match count_reader_until(file, token).await? {
+            Outcome::Complete(counts) => checkpoint.record(&path, counts),
+            // A file cut short is counted again from the start on resume
+            Outcome::Cancelled(_) => {
+                let checkpoint = save_checkpoint(checkpoint, state).await?;
+                return Ok(Outcome::Cancelled(checkpoint.into_counts()));
+            }
+        }
+        unsaved += 1;
+        if unsaved >= every {
+            checkpoint = save_checkpoint(checkpoint, state).await?;
+            unsaved = 0;
+        }
+    }
+    let checkpoint = save_checkpoint(checkpoint, state).await?;
+    Ok(Outcome::Complete(checkpoint.into_counts()))
+}
+
+/// The checkpoint at `state`, or an empty one if there is none yet, read on
+/// the blocking pool rather than the executor thread
+#[cfg(feature = "async")]
+async fn load_checkpoint(state: &Path) -> io::Result<Checkpoint> {
+    let state = state.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        if state.exists() {
+            Checkpoint::load(&state)
+        } else {
+            Ok(Checkpoint::default())
+        }
+    })
+    .await
+    .map_err(io::Error::other)?
+}
+
+/// Save `checkpoint` to `state` on the blocking pool, handing it back
+#[cfg(feature = "async")]
+async fn save_checkpoint(checkpoint: Checkpoint, state: &Path) -> io::Result<Checkpoint> {
+    let state = state.to_path_buf();
+    tokio::task::spawn_blocking(move || checkpoint.save(&state).map(|()| checkpoint))
+        .await
+        .map_err(io::Error::other)?
+}
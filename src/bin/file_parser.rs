@@ -4,34 +4,242 @@
 //! Demonstrates functional programming in Rust using iterators, closures, and combinators.
 //! Supports dynamic filtering: minimum word length and words starting with a specific character.
 
-/// September 2025
+//! September 2025
 
-use std::collections::HashMap;
-use std::env;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
+use std::hash::BuildHasher;
+use std::io::BufRead;
+#[cfg(feature = "parquet")]
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::process;
+use std::sync::LazyLock;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use log::LevelFilter;
+#[cfg(feature = "tui")]
+use ratatui::DefaultTerminal;
+#[cfg(feature = "tui")]
+use ratatui::crossterm::event::{self, Event};
+
+use fp::anagrams::anagram_groups;
+use fp::analysis::{
+    DEFAULT_TOP, Filters, LengthMode, Matcher, PRUNE_INTERVAL, Report, Speeds, clean_words,
+    format_duration, into_owned, json_string, prune, word_counts,
+};
+use fp::cancel::{CancellationToken, Outcome};
+use fp::checkpoint::Checkpoint;
+use fp::clustering::kmeans;
+#[cfg(feature = "parquet")]
+use fp::clustering::tf_idf;
+use fp::composition::Pipe;
+use fp::curiosities::{curiosities, distinct_letters};
+#[cfg(feature = "tui")]
+use fp::dashboard::Dashboard;
+use fp::diversity::diversity;
+use fp::either::Either;
+use fp::export::ExportFormat;
+use fp::filter_expr::FilterExpr;
+use fp::fuzzy::{BkTree, DEFAULT_MAX_DIST, within};
+use fp::goal::{Progress, words_per_minute};
+use fp::hashing::{AHashBuilder, FxBuilder, HashAlgorithm, SipBuilder};
+use fp::huffman::{HuffmanCode, entropy};
+use fp::input::{
+    AccessEntry, Column, Extracted, InputFormat, LogField, extract_column, extract_json_field,
+    parse_access_line,
+};
+use fp::iter_ext::IteratorExt;
+use fp::keyness::{Keyword, SIGNIFICANT, keywords};
+use fp::keywords::rake;
+use fp::lens;
+use fp::locale::Locale;
+use fp::logging::{Format, Logger};
+use fp::markov::{DEFAULT_ORDER, Markov};
+use fp::monads::free::Level;
+use fp::monads::{Io, Program, Reader, Writer};
+use fp::monoid::{Monoid, Semigroup, fold_map};
+use fp::multimap::MultiMap;
+use fp::normalize::Normalization;
+use fp::ocr::{RejoinHyphens, clean_characters, clean_ocr, rejoin_hyphenated};
+use fp::optics::Lens;
+use fp::parallel::map_reduce;
+#[cfg(feature = "parquet")]
+use fp::parquet::{frequency_table, tf_idf_table};
+use fp::persistent::{PMap, PTreeMap, PersistentList};
+use fp::phonetic::phonetic_groups;
+use fp::random::Rng;
+use fp::results::ResultExt;
+use fp::sections::split_sections;
+use fp::similarity::{
+    MinHash, NEAR_DUPLICATE, SHINGLE_WORDS, near_duplicates, shingles, similarity_matrix,
+};
+use fp::spelling::{SUGGESTIONS, Suggestion, suggest};
+use fp::spill::{DEFAULT_RUN_ENTRIES, Spill};
+#[cfg(feature = "sqlite")]
+use fp::sqlite::Database;
+use fp::summary::{frequency_score, summarize};
+use fp::template::Template;
+use fp::timing::debounce;
+use fp::token_filter::{FilterChain, Registry, TokenFilter};
+use fp::transducers::{self as xf, Transducer, TransducerExt};
+use fp::trie::Trie;
+use fp::validated::Validated;
+use fp::viz::{bar, bar_chart};
+use regex::Regex;
+
+/// How often `--watch` checks the file for changes
+const WATCH_POLL: Duration = Duration::from_millis(100);
+
+/// How long edits must pause before `--watch` re-analyzes
+const WATCH_SETTLE: Duration = Duration::from_millis(300);
+
+/// How many lines are counted between two merges into a `--spill`
+const SPILL_BATCH: usize = 1024;
+
+/// How many files `--checkpoint` counts between two saves
+const CHECKPOINT_EVERY: usize = 100;
+
+/// How many words each `--fun-facts` list shows
+const FUN_FACTS_LISTED: usize = 5;
+
+/// How many lines each `--jobs` worker counts at a time
+const LINES_PER_CHUNK: usize = 256;
+
+/// Exit code when `--timeout` cut the analysis short, as with timeout(1)
+const TIMED_OUT: i32 = 124;
+
+/// Exit code after Ctrl-C in watch or spill mode, as shells report SIGINT
+const INTERRUPTED: i32 = 130;
+
+/// Cancelled by Ctrl-C once `catch_interrupt` has installed the handler
+static INTERRUPT: LazyLock<CancellationToken> = LazyLock::new(CancellationToken::new);
 
 /// Configuration for analysis
+#[derive(Default)]
 struct Config {
     file_path: String,
-    min_length: Option<usize>,
-    starts_with: Option<char>,
+    input_format: InputFormat,
+    column: Option<Column>,
+    json_field: Option<String>,
+    preset: Option<Preset>,
+    clean_ocr: bool,
+    rejoin_hyphens: bool,
+    filters: Filters,
+    /// `filters` ready to test words, built once every flag is in
+    matcher: Matcher,
+    sorted: bool,
+    verbose: bool,
+    by_first_letter: bool,
+    chart: Option<usize>,
+    reference: Option<String>,
+    key_phrases: Option<usize>,
+    summarize: Option<usize>,
+    goal: Option<usize>,
+    diversity: bool,
+    compress_demo: bool,
+    fun_facts: bool,
+    anagrams: bool,
+    phonetic: bool,
+    complete: Option<String>,
+    fuzzy: Option<String>,
+    max_dist: Option<usize>,
+    speeds: Speeds,
+    export: Option<ExportFormat>,
+    output: Option<Output>,
+    format: ReportFormat,
+    template: Option<Template>,
+    split_on: Option<Regex>,
+    near_dupes: bool,
+    similarity: Option<MatrixFormat>,
+    cluster: Option<usize>,
+    watch: bool,
+    tui: bool,
+    jobs: Option<usize>,
+    max_vocab: Option<usize>,
+    spill: Option<PathBuf>,
+    checkpoint: Option<PathBuf>,
+    resume: bool,
+    hasher: HashAlgorithm,
+    timeout: Option<Duration>,
+    token_filters: FilterChain,
+    filter_expr: Option<FilterExpr>,
+    logger: Logger,
+    dry_run: bool,
+    strict: bool,
 }
 
 impl Config {
+    /// Lens onto `config.filters.min_length`
+    fn min_length_lens() -> Lens<Config, Option<usize>> {
+        lens!(Config, filters).then(&lens!(Filters, min_length))
+    }
+
+    /// Lens onto `config.filters.normalization`
+    fn normalization_lens() -> Lens<Config, Normalization> {
+        lens!(Config, filters).then(&lens!(Filters, normalization))
+    }
+
+    /// Lens onto `config.filters.strip_accents`
+    fn strip_accents_lens() -> Lens<Config, bool> {
+        lens!(Config, filters).then(&lens!(Filters, strip_accents))
+    }
+
+    /// Lens onto `config.filters.locale`
+    fn locale_lens() -> Lens<Config, Option<Locale>> {
+        lens!(Config, filters).then(&lens!(Filters, locale))
+    }
+
+    /// Lens onto `config.speeds.reading`
+    fn reading_wpm_lens() -> Lens<Config, usize> {
+        lens!(Config, speeds).then(&lens!(Speeds, reading))
+    }
+
+    /// Lens onto `config.speeds.speaking`
+    fn speaking_wpm_lens() -> Lens<Config, usize> {
+        lens!(Config, speeds).then(&lens!(Speeds, speaking))
+    }
+
+    /// Lens onto `config.filters.max_length`
+    fn max_length_lens() -> Lens<Config, Option<usize>> {
+        lens!(Config, filters).then(&lens!(Filters, max_length))
+    }
+
+    /// Lens onto `config.filters.length_mode`
+    fn length_mode_lens() -> Lens<Config, LengthMode> {
+        lens!(Config, filters).then(&lens!(Filters, length_mode))
+    }
+
+    /// Lens onto `config.filters.starts_with`
+    fn starts_with_lens() -> Lens<Config, Option<String>> {
+        lens!(Config, filters).then(&lens!(Filters, starts_with))
+    }
+
+    /// Lens onto `config.filters.ends_with`
+    fn ends_with_lens() -> Lens<Config, Option<String>> {
+        lens!(Config, filters).then(&lens!(Filters, ends_with))
+    }
+
+    /// Lens onto `config.filters.contains`
+    fn contains_lens() -> Lens<Config, Option<String>> {
+        lens!(Config, filters).then(&lens!(Filters, contains))
+    }
+
+    /// Lens onto `config.filters.fuzziness`
+    fn fuzziness_lens() -> Lens<Config, usize> {
+        lens!(Config, filters).then(&lens!(Filters, fuzziness))
+    }
+
     /// Parse CLI arguments into a Config
-    fn from_args() -> Result<Self, String> {
-        let args: Vec<String> = env::args().collect();
+    fn from_args(args: &[String]) -> Result<Self, String> {
         if args.len() < 2 {
-            return Err(format!(
-                "Usage: {} <file_path> [--min-length N] [--starts-with C]",
-                args[0]
-            ));
+            return Err(usage(&args[0]));
         }
 
-        let file_path = args[1].clone();
-        let mut min_length: Option<usize> = None;
-        let mut starts_with: Option<char> = None;
+        let file_path = validate_file_path(&args[1]);
 
         // let mut i = 2;
         // while i < args.len() {
@@ -53,89 +261,1754 @@ impl Config {
         //     i += 1;
         // }
 
-         // Parse cli flags
-        let mut iter = args.iter().skip(2);
-        while let Some(flag) = iter.next() {
-            match flag.as_str() {
-                "--min-length" => {
-                    min_length = Some(
-                        iter
-                            .next()
-                            .ok_or("--min-length requires a number")?
-                            .parse::<usize>()
-                            .map_err(|_| "Invalid number for --min-length")?,
-                    );
-                }
-                "--starts-with" => {
-                    starts_with = Some(
-                        iter
-                            .next()
-                            .ok_or("--starts-with requires a character")?
-                            .chars()
-                            .next()
-                            .ok_or("Invalid character for --starts-with")?,
-                    );
-                }
-                _ => {}
+        // Parse cli flags, reporting every invalid flag rather than only the first.
+        // Errors name the offending argument's index; --strict also rejects
+        // unknown arguments and flags whose value looks like another flag.
+        let strict = args.iter().any(|arg| arg == "--strict");
+        let mut iter = args.iter().enumerate().skip(2);
+        let settings: Validated<Vec<Setting>, String> = std::iter::from_fn(|| {
+            while let Some((index, arg)) = iter.next() {
+                let Some(flag) = FLAGS.iter().find(|flag| flag.name == arg) else {
+                    if strict {
+                        return Some(Validated::invalid(unexpected_argument(index, arg)));
+                    }
+                    continue;
+                };
+                let value = flag.value.and_then(|_| iter.next());
+                let at = value.map_or(index, |(i, _)| i);
+                let setting = match value {
+                    Some((_, value)) if strict && value.starts_with("--") => {
+                        Validated::invalid(format!(
+                            "{} is missing its value, found the flag '{}'",
+                            flag.name, value
+                        ))
+                    }
+                    _ => (flag.parse)(value.map(|(_, value)| value)),
+                };
+                return Some(setting.map_err(|err| format!("argument {}: {}", at, err)));
             }
+            None
+        })
+        .collect();
+
+        file_path
+            .zip(settings)
+            .map(|(file_path, settings)| {
+                let config = Self {
+                    file_path,
+                    ..Self::default()
+                };
+                settings
+                    .into_iter()
+                    .fold(config, |cfg, setting| match setting {
+                        Setting::InputFormat(format) => {
+                            lens!(Config, input_format).set(cfg, format)
+                        }
+                        Setting::Column(column) => lens!(Config, column).set(cfg, Some(column)),
+                        Setting::JsonField(field) => {
+                            lens!(Config, json_field).set(cfg, Some(field))
+                        }
+                        Setting::Preset(preset) => lens!(Config, preset).set(cfg, Some(preset)),
+                        Setting::Normalize(form) => Self::normalization_lens().set(cfg, form),
+                        Setting::StripAccents => Self::strip_accents_lens().set(cfg, true),
+                        Setting::CleanOcr => lens!(Config, clean_ocr).set(cfg, true),
+                        Setting::RejoinHyphens => lens!(Config, rejoin_hyphens).set(cfg, true),
+                        Setting::Locale(locale) => Self::locale_lens().set(cfg, Some(locale)),
+                        Setting::MinLength(n) => Self::min_length_lens().set(cfg, Some(n)),
+                        Setting::MaxLength(n) => Self::max_length_lens().set(cfg, Some(n)),
+                        Setting::LengthMode(mode) => Self::length_mode_lens().set(cfg, mode),
+                        Setting::StartsWith(s) => Self::starts_with_lens().set(cfg, Some(s)),
+                        Setting::EndsWith(s) => Self::ends_with_lens().set(cfg, Some(s)),
+                        Setting::Contains(s) => Self::contains_lens().set(cfg, Some(s)),
+                        Setting::FuzzyFilters(n) => Self::fuzziness_lens().set(cfg, n),
+                        Setting::Sorted => lens!(Config, sorted).set(cfg, true),
+                        Setting::ByFirstLetter => lens!(Config, by_first_letter).set(cfg, true),
+                        Setting::Chart(n) => lens!(Config, chart).set(cfg, Some(n)),
+                        Setting::Reference(path) => lens!(Config, reference).set(cfg, Some(path)),
+                        Setting::KeyPhrases(n) => lens!(Config, key_phrases).set(cfg, Some(n)),
+                        Setting::Summarize(n) => lens!(Config, summarize).set(cfg, Some(n)),
+                        Setting::Goal(n) => lens!(Config, goal).set(cfg, Some(n)),
+                        Setting::Diversity => lens!(Config, diversity).set(cfg, true),
+                        Setting::CompressDemo => lens!(Config, compress_demo).set(cfg, true),
+                        Setting::FunFacts => lens!(Config, fun_facts).set(cfg, true),
+                        Setting::Anagrams => lens!(Config, anagrams).set(cfg, true),
+                        Setting::Phonetic => lens!(Config, phonetic).set(cfg, true),
+                        Setting::Complete(prefix) => lens!(Config, complete).set(cfg, Some(prefix)),
+                        Setting::Fuzzy(word) => lens!(Config, fuzzy).set(cfg, Some(word)),
+                        Setting::MaxDist(n) => lens!(Config, max_dist).set(cfg, Some(n)),
+                        Setting::ReadingWpm(n) => Self::reading_wpm_lens().set(cfg, n),
+                        Setting::SpeakingWpm(n) => Self::speaking_wpm_lens().set(cfg, n),
+                        Setting::Export(format) => lens!(Config, export).set(cfg, Some(format)),
+                        Setting::Output(output) => lens!(Config, output).set(cfg, Some(output)),
+                        Setting::Format(format) => lens!(Config, format).set(cfg, format),
+                        Setting::Template(template) => {
+                            lens!(Config, template).set(cfg, Some(template))
+                        }
+                        Setting::SplitOn(delimiter) => {
+                            lens!(Config, split_on).set(cfg, Some(delimiter))
+                        }
+                        Setting::NearDupes => lens!(Config, near_dupes).set(cfg, true),
+                        Setting::Similarity(format) => {
+                            lens!(Config, similarity).set(cfg, Some(format))
+                        }
+                        Setting::Cluster(k) => lens!(Config, cluster).set(cfg, Some(k)),
+                        Setting::Verbose => lens!(Config, verbose).set(cfg, true),
+                        Setting::Watch => lens!(Config, watch).set(cfg, true),
+                        Setting::Tui => lens!(Config, tui).set(cfg, true),
+                        Setting::Jobs(n) => lens!(Config, jobs).set(cfg, Some(n)),
+                        Setting::MaxVocab(n) => lens!(Config, max_vocab).set(cfg, Some(n)),
+                        Setting::Spill(dir) => lens!(Config, spill).set(cfg, Some(dir)),
+                        Setting::Checkpoint(path) => lens!(Config, checkpoint).set(cfg, Some(path)),
+                        Setting::Resume => lens!(Config, resume).set(cfg, true),
+                        Setting::Hasher(hasher) => lens!(Config, hasher).set(cfg, hasher),
+                        Setting::Timeout(limit) => lens!(Config, timeout).set(cfg, Some(limit)),
+                        Setting::TokenFilters(chain) => {
+                            lens!(Config, token_filters).set(cfg, chain)
+                        }
+                        Setting::FilterExpr(expr) => {
+                            lens!(Config, filter_expr).set(cfg, Some(expr))
+                        }
+                        Setting::LogFormat(format) => lens!(Config, logger)
+                            .then(&lens!(Logger, format))
+                            .set(cfg, format),
+                        Setting::LogLevel(level) => lens!(Config, logger)
+                            .then(&lens!(Logger, level))
+                            .set(cfg, level),
+                        Setting::DryRun => lens!(Config, dry_run).set(cfg, true),
+                        Setting::Strict => lens!(Config, strict).set(cfg, true),
+                    })
+            })
+            .map(Config::localize_token_filters)
+            .map(Config::prepare_matcher)
+            .into_result()
+            .map_err(|errors| errors.join("\n"))
+    }
+}
+
+impl Config {
+    /// Rebuild the `--filters` chain for `--locale` and `--fuzzy-filters`,
+    /// so `lowercase` and a bare `stopwords` follow the locale, and
+    /// `stopwords` the fuzziness, wherever the flags appear. The specs were
+    /// already checked against the same filter names.
+    fn localize_token_filters(self) -> Self {
+        let tailored = self.filters.locale.is_some() || self.filters.fuzziness > 0;
+        if !tailored || self.token_filters.is_empty() {
+            return self;
+        }
+        let locale = self.filters.locale.unwrap_or(Locale::English);
+        let chain = Registry::tolerant(locale, self.filters.fuzziness)
+            .chain(self.token_filters.specs().iter().map(String::as_str))
+            .into_result();
+        match chain {
+            Ok(token_filters) => Config {
+                token_filters,
+                ..self
+            },
+            Err(_) => self,
+        }
+    }
+
+    /// Fold the string filters' patterns once, rather than for every word
+    fn prepare_matcher(self) -> Self {
+        Config {
+            matcher: self.filters.matcher(),
+            ..self
+        }
+    }
+}
+
+impl Config {
+    /// What the run does, or why the flags given cannot be honored
+    /// together; flags a mode has no use for are rejected rather than
+    /// silently ignored
+    fn mode(&self) -> Result<Mode, String> {
+        /// The flags that only make sense in the long-running modes, or
+        /// that replace the report
+        const REPORT_ONLY: &[&str] = &["--watch", "--tui", "--spill", "--export", "--template"];
+        if self.dry_run {
+            return Ok(Mode::DryRun);
+        }
+        if self.resume && self.checkpoint.is_none() {
+            return Err("--resume requires --checkpoint".to_string());
+        }
+        let compared = self.near_dupes
+            || self.similarity.is_some()
+            || self.cluster.is_some()
+            || (self.output.is_some() && Path::new(&self.file_path).is_dir());
+        let (mode, name, allowed) = if self.preset.is_some() {
+            (
+                Mode::Weblog,
+                "--preset weblog",
+                Allowed::Only(&["--preset"]),
+            )
+        } else if self.checkpoint.is_some() {
+            (Mode::Scan, "--checkpoint", Allowed::Only(CHECKPOINT_FLAGS))
+        } else if self.format == ReportFormat::Parquet {
+            let rejected = Allowed::AllBut([REPORT_ONLY, &["--output"]].concat());
+            (Mode::Table, "--format parquet", rejected)
+        } else if compared {
+            let name = "comparing the files below a directory";
+            (Mode::Compare, name, Allowed::AllBut(REPORT_ONLY.to_vec()))
+        } else if self.spill.is_some() {
+            (Mode::Spill, "--spill", Allowed::Only(SPILL_FLAGS))
+        } else if self.tui {
+            (Mode::Dashboard, "--tui", Allowed::Only(DASHBOARD_FLAGS))
+        } else if self.watch {
+            (Mode::Watch, "--watch", Allowed::AllBut(Vec::new()))
+        } else {
+            (Mode::Report, "", Allowed::AllBut(Vec::new()))
+        };
+        let conflicts: Vec<&str> = self
+            .given()
+            .into_iter()
+            .filter(|flag| match &allowed {
+                Allowed::Only(flags) => !flags.contains(flag),
+                Allowed::AllBut(flags) => flags.contains(flag),
+            })
+            .collect();
+        if conflicts.is_empty() {
+            Ok(mode)
+        } else {
+            Err(format!(
+                "{} cannot be combined with {}",
+                conflicts.join(", "),
+                name
+            ))
+        }
+    }
+
+    /// The flags that changed the configuration, other than those for
+    /// logging and argument checking, which every mode honors
+    fn given(&self) -> Vec<&'static str> {
+        let filters = &self.filters;
+        let speeds = Speeds::default();
+        [
+            ("--input-format", self.input_format != InputFormat::Text),
+            ("--column", self.column.is_some()),
+            ("--json-field", self.json_field.is_some()),
+            ("--preset", self.preset.is_some()),
+            (
+                "--normalize",
+                filters.normalization != Normalization::default(),
+            ),
+            ("--strip-accents", filters.strip_accents),
+            ("--clean-ocr", self.clean_ocr),
+            ("--rejoin-hyphens", self.rejoin_hyphens),
+            ("--locale", filters.locale.is_some()),
+            ("--min-length", filters.min_length.is_some()),
+            ("--max-length", filters.max_length.is_some()),
+            (
+                "--length-mode",
+                filters.length_mode != LengthMode::default(),
+            ),
+            ("--starts-with", filters.starts_with.is_some()),
+            ("--ends-with", filters.ends_with.is_some()),
+            ("--contains", filters.contains.is_some()),
+            ("--fuzzy-filters", filters.fuzziness > 0),
+            ("--sorted", self.sorted),
+            ("--by-first-letter", self.by_first_letter),
+            ("--chart", self.chart.is_some()),
+            ("--reference", self.reference.is_some()),
+            ("--keywords", self.key_phrases.is_some()),
+            ("--summarize", self.summarize.is_some()),
+            ("--goal", self.goal.is_some()),
+            ("--diversity", self.diversity),
+            ("--compress-demo", self.compress_demo),
+            ("--fun-facts", self.fun_facts),
+            ("--anagrams", self.anagrams),
+            ("--phonetic", self.phonetic),
+            ("--complete", self.complete.is_some()),
+            ("--fuzzy", self.fuzzy.is_some()),
+            ("--max-dist", self.max_dist.is_some()),
+            ("--reading-wpm", self.speeds.reading != speeds.reading),
+            ("--speaking-wpm", self.speeds.speaking != speeds.speaking),
+            ("--export", self.export.is_some()),
+            ("--output", self.output.is_some()),
+            ("--format", self.format != ReportFormat::Text),
+            ("--template", self.template.is_some()),
+            ("--split-on", self.split_on.is_some()),
+            ("--near-dupes", self.near_dupes),
+            ("--similarity", self.similarity.is_some()),
+            ("--cluster", self.cluster.is_some()),
+            ("--verbose", self.verbose),
+            ("--watch", self.watch),
+            ("--tui", self.tui),
+            ("--jobs", self.jobs.is_some()),
+            ("--max-vocab", self.max_vocab.is_some()),
+            ("--spill", self.spill.is_some()),
+            ("--checkpoint", self.checkpoint.is_some()),
+            ("--resume", self.resume),
+            ("--hasher", self.hasher != HashAlgorithm::default()),
+            ("--timeout", self.timeout.is_some()),
+            ("--filters", !self.token_filters.is_empty()),
+            ("--filter-expr", self.filter_expr.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(flag, given)| given.then_some(flag))
+        .collect()
+    }
+}
+
+/// Which of the flags given a mode honors
+enum Allowed {
+    /// Only these
+    Only(&'static [&'static str]),
+    /// All but these
+    AllBut(Vec<&'static str>),
+}
+
+/// What `--spill` honors: the word filters, and the report sections it can
+/// render from the merged counts as they stream past
+const SPILL_FLAGS: &[&str] = &[
+    "--spill",
+    "--normalize",
+    "--strip-accents",
+    "--clean-ocr",
+    "--rejoin-hyphens",
+    "--locale",
+    "--min-length",
+    "--max-length",
+    "--length-mode",
+    "--starts-with",
+    "--ends-with",
+    "--contains",
+    "--fuzzy-filters",
+    "--sorted",
+    "--by-first-letter",
+    "--chart",
+    "--goal",
+    "--reading-wpm",
+    "--speaking-wpm",
+    "--verbose",
+    "--hasher",
+    "--timeout",
+    "--filters",
+    "--filter-expr",
+];
+
+/// What `--checkpoint` honors: how each file is read and filtered, and the
+/// report sections it can render from the merged counts
+const CHECKPOINT_FLAGS: &[&str] = &[
+    "--checkpoint",
+    "--resume",
+    "--input-format",
+    "--column",
+    "--json-field",
+    "--normalize",
+    "--strip-accents",
+    "--clean-ocr",
+    "--rejoin-hyphens",
+    "--locale",
+    "--min-length",
+    "--max-length",
+    "--length-mode",
+    "--starts-with",
+    "--ends-with",
+    "--contains",
+    "--fuzzy-filters",
+    "--sorted",
+    "--by-first-letter",
+    "--chart",
+    "--goal",
+    "--reading-wpm",
+    "--speaking-wpm",
+    "--timeout",
+    "--filters",
+    "--filter-expr",
+];
+
+/// What `--tui` honors: how the file is read and counted, and the word
+/// filters; the dashboard draws its own panels in place of the report
+/// sections, and has nowhere to show the `--verbose` trace
+const DASHBOARD_FLAGS: &[&str] = &[
+    "--tui",
+    "--input-format",
+    "--column",
+    "--json-field",
+    "--normalize",
+    "--strip-accents",
+    "--clean-ocr",
+    "--rejoin-hyphens",
+    "--locale",
+    "--min-length",
+    "--max-length",
+    "--length-mode",
+    "--starts-with",
+    "--ends-with",
+    "--contains",
+    "--fuzzy-filters",
+    "--jobs",
+    "--max-vocab",
+    "--hasher",
+    "--timeout",
+    "--filters",
+    "--filter-expr",
+];
+
+/// The `--strict` error for an argument that is not a known flag
+fn unexpected_argument(index: usize, arg: &str) -> String {
+    if arg.starts_with("--") {
+        format!("argument {}: unknown flag '{}'", index, arg)
+    } else {
+        format!("argument {}: unexpected argument '{}'", index, arg)
+    }
+}
+
+/// Check that the first argument is a file path rather than a flag
+fn validate_file_path(arg: &str) -> Validated<String, String> {
+    if arg.starts_with("--") {
+        Validated::invalid(format!("Expected a file path before flags, found '{}'", arg))
+    } else {
+        Validated::Valid(arg.to_string())
+    }
+}
+
+/// A command-line flag: the single source for parsing, usage and help
+struct Flag {
+    name: &'static str,
+    /// Placeholder for the flag's value, if it takes one
+    value: Option<&'static str>,
+    help: &'static str,
+    parse: fn(Option<&String>) -> Validated<Setting, String>,
+}
+
+/// Every flag the analyzer accepts, in the order they are documented
+const FLAGS: &[Flag] = &[
+    Flag {
+        name: "--input-format",
+        value: Some("text|mbox|csv|tsv"),
+        help: "Read the file as plain text (default), an mbox mailbox without headers and quoted replies, or a CSV/TSV table.",
+        parse: |value| parse_input_format(value).into(),
+    },
+    Flag {
+        name: "--column",
+        value: Some("NAME|N"),
+        help: "Analyze only one column of a CSV table (TSV with --input-format tsv or a .tsv file), by header name or position from 1.",
+        parse: |value| parse_column(value).into(),
+    },
+    Flag {
+        name: "--json-field",
+        value: Some("FIELD"),
+        help: "Read the file as JSON lines and analyze only FIELD of each (dots reach nested fields); bad lines are skipped and counted.",
+        parse: |value| {
+            parse_pattern("--json-field", value)
+                .map(Setting::JsonField)
+                .into()
+        },
+    },
+    Flag {
+        name: "--preset",
+        value: Some("weblog"),
+        help: "Read an Apache/nginx access log and report the most frequent paths, status codes and user agents.",
+        parse: |value| parse_preset(value).into(),
+    },
+    Flag {
+        name: "--normalize",
+        value: Some("FORM"),
+        help: "Bring words to Unicode normal form nfc or nfkc before counting (default none).",
+        parse: |value| parse_normalize(value).into(),
+    },
+    Flag {
+        name: "--strip-accents",
+        value: None,
+        help: "Remove diacritics after normalizing, so café and cafe count as one word.",
+        parse: |_| Validated::Valid(Setting::StripAccents),
+    },
+    Flag {
+        name: "--clean-ocr",
+        value: None,
+        help: "Clean up scanned text first: spell out ligatures such as ﬁ, drop soft hyphens, and rejoin words broken across lines with a hyphen.",
+        parse: |_| Validated::Valid(Setting::CleanOcr),
+    },
+    Flag {
+        name: "--rejoin-hyphens",
+        value: None,
+        help: "Rejoin words broken across lines with a hyphen, func- and tional as functional, also with --spill.",
+        parse: |_| Validated::Valid(Setting::RejoinHyphens),
+    },
+    Flag {
+        name: "--locale",
+        value: Some("LOCALE"),
+        help: "Lowercase and match words by the rules of LOCALE (en, de, fr, es, it, pt, nl, tr, or e.g. tr_TR); --filters lowercase and stopwords follow it too.",
+        parse: |value| parse_locale(value).into(),
+    },
+    Flag {
+        name: "--min-length",
+        value: Some("N"),
+        help: "Only count words at least N characters long, measured by --length-mode.",
+        parse: |value| parse_min_length(value).into(),
+    },
+    Flag {
+        name: "--max-length",
+        value: Some("N"),
+        help: "Only count words at most N characters long, e.g. to skip URLs and hashes.",
+        parse: |value| parse_max_length(value).into(),
+    },
+    Flag {
+        name: "--length-mode",
+        value: Some("MODE"),
+        help: "Measure --min-length and --max-length in bytes, chars or graphemes (the default).",
+        parse: |value| parse_length_mode(value).into(),
+    },
+    Flag {
+        name: "--starts-with",
+        value: Some("PREFIX"),
+        help: "Only count words starting with PREFIX, ignoring case.",
+        parse: |value| {
+            parse_pattern("--starts-with", value)
+                .map(Setting::StartsWith)
+                .into()
+        },
+    },
+    Flag {
+        name: "--ends-with",
+        value: Some("SUFFIX"),
+        help: "Only count words ending with SUFFIX, ignoring case.",
+        parse: |value| {
+            parse_pattern("--ends-with", value)
+                .map(Setting::EndsWith)
+                .into()
+        },
+    },
+    Flag {
+        name: "--contains",
+        value: Some("TEXT"),
+        help: "Only count words containing TEXT, ignoring case.",
+        parse: |value| {
+            parse_pattern("--contains", value)
+                .map(Setting::Contains)
+                .into()
+        },
+    },
+    Flag {
+        name: "--fuzzy-filters",
+        value: Some("N"),
+        help: "Let --starts-with, --ends-with, --contains and the stopwords filter match up to N edits off, one per 3 letters of the pattern or stop word (substitutions only for stop words), for OCR'd text.",
+        parse: |value| parse_fuzzy_filters(value).into(),
+    },
+    Flag {
+        name: "--sorted",
+        value: None,
+        help: "List every word frequency in alphabetical order.",
+        parse: |_| Validated::Valid(Setting::Sorted),
+    },
+    Flag {
+        name: "--by-first-letter",
+        value: None,
+        help: "Add total and unique word counts grouped by initial letter.",
+        parse: |_| Validated::Valid(Setting::ByFirstLetter),
+    },
+    Flag {
+        name: "--chart",
+        value: Some("N"),
+        help: "Add a bar chart of the N most frequent words, as wide as the terminal.",
+        parse: |value| parse_chart(value).into(),
+    },
+    Flag {
+        name: "--reference",
+        value: Some("FILE"),
+        help: "Compare with the reference corpus in FILE and list the words unusually frequent in this file, by log-likelihood keyness.",
+        parse: |value| parse_reference(value).into(),
+    },
+    Flag {
+        name: "--keywords",
+        value: Some("N"),
+        help: "Add the N best multiword key phrases, extracted with RAKE from the runs of words between stop words and punctuation.",
+        parse: |value| parse_key_phrases(value).into(),
+    },
+    Flag {
+        name: "--summarize",
+        value: Some("N"),
+        help: "Add an extractive summary: the N sentences whose words, stop words aside, are the most frequent, in document order.",
+        parse: |value| parse_summarize(value).into(),
+    },
+    Flag {
+        name: "--goal",
+        value: Some("N"),
+        help: "Report progress toward a target of N words; with --watch, also the words per minute since starting.",
+        parse: |value| parse_goal(value).into(),
+    },
+    Flag {
+        name: "--diversity",
+        value: None,
+        help: "Add lexical diversity measures: type-token ratio, root TTR and MTLD.",
+        parse: |_| Validated::Valid(Setting::Diversity),
+    },
+    Flag {
+        name: "--compress-demo",
+        value: None,
+        help: "Add how many bits per word and per character Huffman codes of the text's own frequencies achieve, against the entropy.",
+        parse: |_| Validated::Valid(Setting::CompressDemo),
+    },
+    Flag {
+        name: "--fun-facts",
+        value: None,
+        help: "Add curiosities: the longest and shortest words, palindromes, and the words with the most distinct letters.",
+        parse: |_| Validated::Valid(Setting::FunFacts),
+    },
+    Flag {
+        name: "--anagrams",
+        value: None,
+        help: "Add the groups of counted words that are anagrams of each other, such as listen, silent and enlist.",
+        parse: |_| Validated::Valid(Setting::Anagrams),
+    },
+    Flag {
+        name: "--phonetic",
+        value: None,
+        help: "Add the groups of counted words with the same Soundex code, such as smith and smyth, to spot spelling variants.",
+        parse: |_| Validated::Valid(Setting::Phonetic),
+    },
+    Flag {
+        name: "--complete",
+        value: Some("PREFIX"),
+        help: "Add the counted words starting with PREFIX, most frequent first, as autocompletions.",
+        parse: |value| {
+            parse_pattern("--complete", value)
+                .map(Setting::Complete)
+                .into()
+        },
+    },
+    Flag {
+        name: "--fuzzy",
+        value: Some("WORD"),
+        help: "Add the counted words within --max-dist edits (Levenshtein distance) of WORD, nearest first.",
+        parse: |value| parse_pattern("--fuzzy", value).map(Setting::Fuzzy).into(),
+    },
+    Flag {
+        name: "--max-dist",
+        value: Some("N"),
+        help: "Allow --fuzzy matches up to N insertions, deletions or substitutions away (default 2).",
+        parse: |value| parse_max_dist(value).into(),
+    },
+    Flag {
+        name: "--reading-wpm",
+        value: Some("N"),
+        help: "Estimate the reading time at N words per minute (default 238).",
+        parse: |value| parse_wpm(value, "--reading-wpm", Setting::ReadingWpm).into(),
+    },
+    Flag {
+        name: "--speaking-wpm",
+        value: Some("N"),
+        help: "Estimate the speaking time at N words per minute (default 150).",
+        parse: |value| parse_wpm(value, "--speaking-wpm", Setting::SpeakingWpm).into(),
+    },
+    Flag {
+        name: "--export",
+        value: Some("wordfreq|hunspell"),
+        help: "Print the counts as a frequency list for other tools instead of the report: `word count` lines, or a Hunspell .dic skeleton.",
+        parse: |value| parse_export(value).into(),
+    },
+    Flag {
+        name: "--output",
+        value: Some("sqlite:FILE"),
+        help: "Also store the documents, words, counts and word positions in the SQLite database FILE, created if missing, to query many runs with SQL; needs the sqlite feature.",
+        parse: |value| parse_output(value).into(),
+    },
+    Flag {
+        name: "--format",
+        value: Some("text|parquet"),
+        help: "Write the report as text (default), or as a Parquet table for pandas and polars instead: the word counts of the file, or the TF-IDF weights of every file below a directory; needs the parquet feature.",
+        parse: |value| parse_format(value).into(),
+    },
+    Flag {
+        name: "--template",
+        value: Some("FILE"),
+        help: "Render the report with the template in FILE, such as '{{total}} words: {{#top 3}}{{word}} {{/top}}' (placeholders in the readme).",
+        parse: |value| parse_template(value).into(),
+    },
+    Flag {
+        name: "--split-on",
+        value: Some("REGEX"),
+        help: "Split the file before every line matching REGEX (e.g. '^From:' or '^#') and report each section, then the total.",
+        parse: |value| parse_split_on(value).into(),
+    },
+    Flag {
+        name: "--near-dupes",
+        value: None,
+        help: "Read every file below the directory given as the path and report the clusters of near-duplicate files, by MinHash estimates of their shared 3-word shingles.",
+        parse: |_| Validated::Valid(Setting::NearDupes),
+    },
+    Flag {
+        name: "--similarity",
+        value: Some("csv|json"),
+        help: "Read every file below the directory given as the path and print the cosine similarity of each pair's word frequencies as a CSV or JSON matrix.",
+        parse: |value| parse_similarity(value).into(),
+    },
+    Flag {
+        name: "--cluster",
+        value: Some("K"),
+        help: "Read every file below the directory given as the path and group the files into at most K clusters by k-means over TF-IDF vectors, with each cluster's distinguishing terms.",
+        parse: |value| parse_cluster(value).into(),
+    },
+    Flag {
+        name: "--verbose",
+        value: None,
+        help: "Log a trace line for every input line.",
+        parse: |_| Validated::Valid(Setting::Verbose),
+    },
+    Flag {
+        name: "--watch",
+        value: None,
+        help: "Keep running and re-analyze the file when it changes; type `undo`, `history N` or `startswith PREFIX` on stdin.",
+        parse: |_| Validated::Valid(Setting::Watch),
+    },
+    Flag {
+        name: "--tui",
+        value: None,
+        help: "Show a live dashboard of the top words, totals and word lengths, with a search box, updated as the file changes; Esc quits.",
+        parse: |_| Validated::Valid(Setting::Tui),
+    },
+    Flag {
+        name: "--jobs",
+        value: Some("N"),
+        help: "Count lines on N worker threads; the report is unchanged.",
+        parse: |value| parse_jobs(value).into(),
+    },
+    Flag {
+        name: "--max-vocab",
+        value: Some("N"),
+        help: "Bound memory by keeping at most N distinct words, pruning the rarest; counts become approximate.",
+        parse: |value| parse_max_vocab(value).into(),
+    },
+    Flag {
+        name: "--spill",
+        value: Some("DIR"),
+        help: "Count vocabularies larger than memory by spilling sorted partial counts to DIR and merging them.",
+        parse: |value| parse_spill(value).into(),
+    },
+    Flag {
+        name: "--checkpoint",
+        value: Some("FILE"),
+        help: "Count every file below the directory into one report, saving the files finished and their counts to FILE as it goes.",
+        parse: |value| parse_checkpoint(value).into(),
+    },
+    Flag {
+        name: "--resume",
+        value: None,
+        help: "With --checkpoint, skip the files FILE records as finished and start from their counts.",
+        parse: |_| Validated::Valid(Setting::Resume),
+    },
+    Flag {
+        name: "--hasher",
+        value: Some("ahash|fx|sip"),
+        help: "Hash words with aHash or FxHash instead of SipHash (default); faster on large texts, not DoS resistant.",
+        parse: |value| parse_hasher(value).into(),
+    },
+    Flag {
+        name: "--timeout",
+        value: Some("SECS"),
+        help: "Stop counting after SECS seconds and report the lines counted so far, exiting with 124.",
+        parse: |value| parse_timeout(value).into(),
+    },
+    Flag {
+        name: "--filters",
+        value: Some("F1,F2,..."),
+        help: "Chain token filter plugins, e.g. lowercase, stem, stopwords:en.",
+        parse: parse_token_filters,
+    },
+    Flag {
+        name: "--filter-expr",
+        value: Some("EXPR"),
+        help: "Keep only words for which EXPR holds, e.g. 'len(word) >= 5 && !startswith(word, \"un\")'.",
+        parse: |value| parse_filter_expr(value).into(),
+    },
+    Flag {
+        name: "--strict",
+        value: None,
+        help: "Reject unknown flags, stray arguments and flags missing their value instead of ignoring them.",
+        parse: |_| Validated::Valid(Setting::Strict),
+    },
+    Flag {
+        name: "--dry-run",
+        value: None,
+        help: "Print the resolved configuration, tokenizer and filter chain without reading the file.",
+        parse: |_| Validated::Valid(Setting::DryRun),
+    },
+    Flag {
+        name: "--log-format",
+        value: Some("text|json"),
+        help: "Write diagnostics as timestamped text (default) or JSON lines.",
+        parse: |value| parse_log_format(value).into(),
+    },
+    Flag {
+        name: "--log-level",
+        value: Some("LEVEL"),
+        help: "Lowest level logged: off, error, warn, info (default), debug or trace.",
+        parse: |value| parse_log_level(value).into(),
+    },
+];
+
+/// What the analyzer prints, for the OUTPUT section of the help
+const OUTPUT_FORMATS: &[(&str, &str)] = &[
+    (
+        "Summary",
+        "Total words, unique words, the most common word, and the reading and speaking times, always printed to stdout.",
+    ),
+    (
+        "Word Frequencies",
+        "With --sorted, one `word: count` line per word in alphabetical order.",
+    ),
+    (
+        "By First Letter",
+        "With --by-first-letter, one `letter: total (N unique)` line per initial letter.",
+    ),
+    (
+        "Top Words Chart",
+        "With --chart N, one bar of Unicode blocks per word for the N most frequent, as wide as the terminal ($COLUMNS if set).",
+    ),
+    (
+        "Keywords",
+        "With --reference FILE, `word: G² (count here vs in FILE)` for the words significantly more frequent than in FILE (p < 0.05), most characteristic first.",
+    ),
+    (
+        "Key Phrases",
+        "With --keywords N, `phrase: score (count)` for the N phrases with the highest RAKE scores.",
+    ),
+    (
+        "Summary",
+        "With --summarize N, the N best-scoring sentences in the order they appear, one per line.",
+    ),
+    (
+        "Goal",
+        "With --goal N, the words so far out of N with a percentage and a progress bar; with --watch, the pace in words per minute since starting.",
+    ),
+    (
+        "Lexical Diversity",
+        "With --diversity, the type-token ratio, root TTR and MTLD of the words counted.",
+    ),
+    (
+        "Compression",
+        "With --compress-demo, the bits per word and per character of the text Huffman-coded by its own frequencies, with the entropy and the fixed-length or UTF-8 size for comparison, and whether decoding gave the text back.",
+    ),
+    (
+        "Fun Facts",
+        "With --fun-facts, the longest and shortest words, palindromes of three letters or more, and the words using the most distinct letters.",
+    ),
+    (
+        "Anagrams",
+        "With --anagrams, one line per group of anagrams, `word (count)` in alphabetical order, the largest groups first.",
+    ),
+    (
+        "Sound-alikes",
+        "With --phonetic, one line per group of words sharing a Soundex code, `CODE: word (count), ...` in alphabetical order, the largest groups first.",
+    ),
+    (
+        "Completions",
+        "With --complete PREFIX, `word: count` for the counted words starting with PREFIX, the 10 most frequent first.",
+    ),
+    (
+        "Fuzzy matches",
+        "With --fuzzy WORD, `word: count (N edits)` for the counted words within --max-dist edits of WORD, the 10 nearest first.",
+    ),
+    (
+        "Near-duplicate Files",
+        "With --near-dupes, instead of the sections above: the number of files compared, then one line per cluster of near-duplicate files (estimated similarity of 0.8 or more).",
+    ),
+    (
+        "Similarity Matrix",
+        "With --similarity csv, a header row of the files compared, then one row per file of its cosine similarity to each; with --similarity json, {\"files\": [...], \"matrix\": [[...], ...]}. Values have three decimals.",
+    ),
+    (
+        "Clusters",
+        "With --cluster K, one `Cluster N: file, ...` line per cluster of files, followed by its five distinguishing terms.",
+    ),
+    (
+        "Export",
+        "With --export wordfreq, one `word count` line per word, most frequent first, replaces the sections above; with --export hunspell, the number of words, then one word per line in alphabetical order.",
+    ),
+    (
+        "Parquet",
+        "With --format parquet, a Parquet file replaces the report on stdout: `word` (string) and `count` (int64) columns, most frequent word first; for a directory, `document`, `word` and `tf_idf` (double) columns, one row per word of each file.",
+    ),
+    (
+        "SQLite Database",
+        "With --output sqlite:FILE, nothing more is printed; FILE gains a row in `documents` (id, path, analyzed_at, total_words) per file analyzed, its distinct words in `words` (id, word), and `counts` (document_id, word_id, count) and `positions` (document_id, word_id, position) rows. Every run adds new documents.",
+    ),
+    (
+        "Template",
+        "With --template, the template filled in with the counts replaces the sections above.",
+    ),
+    (
+        "Diagnostics",
+        "Errors and --verbose traces go to stderr as text or JSON lines (see --log-format).",
+    ),
+];
+
+/// `[--flag VALUE]` as shown in the usage line
+fn flag_usage(flag: &Flag) -> String {
+    match flag.value {
+        Some(value) => format!("[{} {}]", flag.name, value),
+        None => format!("[{}]", flag.name),
+    }
+}
+
+/// The usage line, generated from [`FLAGS`]
+fn usage(program: &str) -> String {
+    let flags: Vec<String> = FLAGS.iter().map(flag_usage).collect();
+    format!(
+        "Usage: {} <file_path> {}\n       {} generate <file_path> [--order K] [--words N] [--seed S]\n       {} suggest <file_path> <word> [--max-dist N]\n       {} help export [--format markdown|roff]",
+        program,
+        flags.join(" "),
+        program,
+        program,
+        program
+    )
+}
+
+/// Where `--output` stores the analyzed documents
+#[derive(Debug, Clone)]
+enum Output {
+    Sqlite(PathBuf),
+}
+
+/// What `--format` writes the report as
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum ReportFormat {
+    #[default]
+    Text,
+    Parquet,
+}
+
+/// What a run does, decided once from the flags
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    /// `--dry-run`: print the resolved configuration
+    DryRun,
+    /// `--preset weblog`: report the fields of an access log
+    Weblog,
+    /// `--format parquet`: write a table instead of the report
+    Table,
+    /// `--near-dupes`, `--similarity`, `--cluster`, or `--output` given a
+    /// directory: compare or store the files below it
+    Compare,
+    /// `--spill`: count in bounded memory and report the totals
+    Spill,
+    /// `--checkpoint`: count the files below a directory, saving progress
+    Scan,
+    /// `--tui`: show the live dashboard
+    Dashboard,
+    /// `--watch`: report, then report again on every change
+    Watch,
+    /// Report once
+    Report,
+}
+
+/// How `--similarity` prints its matrix
+#[derive(Debug, Clone, Copy)]
+enum MatrixFormat {
+    Csv,
+    Json,
+}
+
+/// What `generate` does, for the help
+const GENERATE_HELP: &str = "Print N words (default 50) of random text from an order-K Markov model (default 2) of the file's words; the same seed S gives the same text.";
+
+/// How many words `generate` prints unless told otherwise
+const GENERATED_WORDS: usize = 50;
+
+/// The options of `generate <file_path> [--order K] [--words N] [--seed S]`
+struct Generate {
+    file_path: String,
+    order: usize,
+    words: usize,
+    seed: Option<u64>,
+}
+
+/// Parse the arguments following `generate`
+fn parse_generate(program: &str, args: &[String]) -> Result<Generate, String> {
+    let Some((file_path, options)) = args.split_first() else {
+        return Err(usage(program));
+    };
+    let number = |flag: &str, value: &str| {
+        value
+            .parse::<u64>()
+            .map_err(|_| format!("Invalid number for {}", flag))
+    };
+    let count = |flag: &str, value: &str| {
+        usize::try_from(number(flag, value)?)
+            .map_err(|_| format!("{} is larger than this platform can count", flag))
+    };
+    options.chunks(2).try_fold(
+        Generate {
+            file_path: file_path.clone(),
+            order: DEFAULT_ORDER,
+            words: GENERATED_WORDS,
+            seed: None,
+        },
+        |options, pair| match pair {
+            [flag, value] if flag == "--order" => Ok(Generate {
+                order: count(flag, value)?.max(1),
+                ..options
+            }),
+            [flag, value] if flag == "--words" => Ok(Generate {
+                words: count(flag, value)?,
+                ..options
+            }),
+            [flag, value] if flag == "--seed" => Ok(Generate {
+                seed: Some(number(flag, value)?),
+                ..options
+            }),
+            _ => Err(usage(program)),
+        },
+    )
+}
+
+/// Handle `generate`: read the file and print text generated from a Markov
+/// model of its words, as they appear, punctuation and case kept
+///
+/// Without `--seed`, the seed comes from the clock and is logged, so that a
+/// text worth keeping can be generated again.
+fn generate_command(program: &str, args: &[String]) -> Program<'static, i32> {
+    let options = match parse_generate(program, args) {
+        Ok(options) => options,
+        Err(msg) => return Program::log_at(Level::Error, msg).map(|_| 1),
+    };
+    let seed = options.seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos() as u64)
+    });
+    let announce = match options.seed {
+        Some(_) => Program::pure(()),
+        None => Program::log_at(Level::Info, format!("Seed: {}", seed)),
+    };
+    announce.then(read_file(&options.file_path).and_then(move |content| {
+        let text = match content {
+            Ok(text) => text,
+            Err(msg) => return Program::log_at(Level::Error, msg).map(|_| 1),
+        };
+        let tokens: Vec<&str> = text.split_whitespace().collect();
+        if tokens.len() < options.order {
+            let msg = format!(
+                "{} has fewer than {} words to generate from",
+                options.file_path, options.order
+            );
+            return Program::log_at(Level::Error, msg).map(|_| 1);
         }
+        let model = Markov::new(&tokens, options.order);
+        let (words, _) = model.generate(options.words, Rng::new(seed));
+        Program::write_output(words.join(" ")).map(|_| 0)
+    }))
+}
+
+/// What `suggest` does, for the help
+const SUGGEST_HELP: &str = "Print up to 5 corrections of WORD among the file's words within N edits (default 2), fewest edits first, then the most frequent; a word the file has is its own first suggestion.";
+
+/// Handle `suggest <file_path> <word> [--max-dist N]`: read the file and
+/// print the likeliest corrections of the word among the words it has
+fn suggest_command(program: &str, args: &[String]) -> Program<'static, i32> {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let parsed = match args.as_slice() {
+        [file_path, word] => Ok((*file_path, *word, DEFAULT_MAX_DIST)),
+        [file_path, word, "--max-dist", n] => n
+            .parse::<usize>()
+            .map(|n| (*file_path, *word, n))
+            .map_err(|_| "Invalid number for --max-dist".to_string()),
+        _ => Err(usage(program)),
+    };
+    let (file_path, word, max_dist) = match parsed {
+        Ok(parsed) => parsed,
+        Err(msg) => return Program::log_at(Level::Error, msg).map(|_| 1),
+    };
+    let word = word.to_lowercase();
+    read_file(file_path).and_then(move |content| match content {
+        Err(msg) => Program::log_at(Level::Error, msg).map(|_| 1),
+        Ok(text) => {
+            let tree: BkTree<usize> = word_counts(&text).into_iter().collect();
+            let suggestions = suggest(&tree, &word, max_dist);
+            Program::write_output(format_suggestions(&suggestions, &word, max_dist)).map(|_| 0)
+        }
+    })
+}
+
+/// Format the best [`SUGGESTIONS`] of `suggest`, `word: count (N edits)`
+fn format_suggestions(suggestions: &[Suggestion], word: &str, max_dist: usize) -> String {
+    let edits = |n: usize| if n == 1 { "edit" } else { "edits" };
+    if suggestions.is_empty() {
+        return format!(
+            "No words within {} {} of '{}'.",
+            max_dist,
+            edits(max_dist),
+            word
+        );
+    }
+    suggestions.iter().take(SUGGESTIONS).fold(
+        format!("Suggestions for '{}':", word),
+        |out, suggestion| match suggestion.distance {
+            0 => format!("{}\n{}: {} (known)", out, suggestion.word, suggestion.count),
+            n => format!(
+                "{}\n{}: {} ({} {})",
+                out,
+                suggestion.word,
+                suggestion.count,
+                n,
+                edits(n)
+            ),
+        },
+    )
+}
+
+/// Long-form documentation formats for `help export`
+enum HelpFormat {
+    Markdown,
+    Roff,
+}
+
+/// Handle `help export [--format markdown|roff]`, returning the documentation
+fn help_command(program: &str, args: &[String]) -> Result<String, String> {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let format = match args.as_slice() {
+        ["export"] | ["export", "--format", "markdown"] => HelpFormat::Markdown,
+        ["export", "--format", "roff"] => HelpFormat::Roff,
+        _ => return Err(usage(program)),
+    };
+    let name = Path::new(program)
+        .file_name()
+        .map_or(program.into(), |name| name.to_string_lossy());
+    Ok(render_help(&name, format))
+}
+
+/// Render the full documentation from [`FLAGS`] and [`OUTPUT_FORMATS`]
+fn render_help(program: &str, format: HelpFormat) -> String {
+    let synopsis = FLAGS.iter().map(flag_usage).collect::<Vec<_>>().join(" ");
+    let description = "Counts word frequencies in a text file and reports statistics.";
+    let generate = GENERATE_HELP;
+    let suggest = SUGGEST_HELP;
+    match format {
+        HelpFormat::Markdown => {
+            let flags = FLAGS.iter().fold(String::new(), |out, flag| {
+                let name = flag.value.map_or(flag.name.to_string(), |value| {
+                    format!("{} {}", flag.name, value)
+                });
+                format!("{}- `{}`: {}\n", out, name, flag.help)
+            });
+            let outputs = OUTPUT_FORMATS
+                .iter()
+                .fold(String::new(), |out, (name, help)| {
+                    format!("{}- **{}**: {}\n", out, name, help)
+                });
+            format!(
+                "# {program}\n\n{description}\n\n## Synopsis\n\n```\n{program} <file_path> {synopsis}\n{program} generate <file_path> [--order K] [--words N] [--seed S]\n{program} suggest <file_path> <word> [--max-dist N]\n{program} help export [--format markdown|roff]\n```\n\n## Flags\n\n{flags}\n## Subcommands\n\n- `generate <file_path> [--order K] [--words N] [--seed S]`: {generate}\n- `suggest <file_path> <word> [--max-dist N]`: {suggest}\n- `help export [--format markdown|roff]`: Print this documentation.\n\n## Output\n\n{outputs}"
+            )
+        }
+        HelpFormat::Roff => {
+            let escape = |text: &str| text.replace('\\', "\\e").replace('-', "\\-");
+            let flags = FLAGS.iter().fold(String::new(), |out, flag| {
+                let value = flag
+                    .value
+                    .map_or(String::new(), |value| format!(" \\fI{}\\fR", escape(value)));
+                format!(
+                    "{}.TP\n\\fB{}\\fR{}\n{}\n",
+                    out,
+                    escape(flag.name),
+                    value,
+                    escape(flag.help)
+                )
+            });
+            let outputs = OUTPUT_FORMATS
+                .iter()
+                .fold(String::new(), |out, (name, help)| {
+                    format!("{}.TP\n\\fB{}\\fR\n{}\n", out, name, escape(help))
+                });
+            format!(
+                ".TH {name} 1\n.SH NAME\n{name} \\- {description}\n.SH SYNOPSIS\n\\fB{name}\\fR \\fIfile_path\\fR {synopsis}\n.br\n\\fB{name}\\fR generate \\fIfile_path\\fR [\\-\\-order K] [\\-\\-words N] [\\-\\-seed S]\n.br\n\\fB{name}\\fR suggest \\fIfile_path\\fR \\fIword\\fR [\\-\\-max\\-dist N]\n.br\n\\fB{name}\\fR help export [\\-\\-format markdown|roff]\n.SH OPTIONS\n{flags}.SH SUBCOMMANDS\n.TP\n\\fBgenerate\\fR \\fIfile_path\\fR [\\-\\-order K] [\\-\\-words N] [\\-\\-seed S]\n{generate}\n.TP\n\\fBsuggest\\fR \\fIfile_path\\fR \\fIword\\fR [\\-\\-max\\-dist N]\n{suggest}\n.TP\n\\fBhelp export\\fR [\\-\\-format markdown|roff]\nPrint this documentation.\n.SH OUTPUT\n{outputs}",
+                name = escape(program),
+                synopsis = escape(&synopsis),
+                generate = escape(GENERATE_HELP),
+                suggest = escape(SUGGEST_HELP),
+            )
+        }
+    }
+}
+
+/// A single setting parsed from a CLI flag
+enum Setting {
+    InputFormat(InputFormat),
+    Column(Column),
+    JsonField(String),
+    Preset(Preset),
+    Normalize(Normalization),
+    StripAccents,
+    CleanOcr,
+    RejoinHyphens,
+    Locale(Locale),
+    MinLength(usize),
+    MaxLength(usize),
+    LengthMode(LengthMode),
+    StartsWith(String),
+    EndsWith(String),
+    Contains(String),
+    FuzzyFilters(usize),
+    Sorted,
+    ByFirstLetter,
+    Chart(usize),
+    Reference(String),
+    KeyPhrases(usize),
+    Summarize(usize),
+    Goal(usize),
+    Diversity,
+    CompressDemo,
+    FunFacts,
+    Anagrams,
+    Phonetic,
+    Complete(String),
+    Fuzzy(String),
+    MaxDist(usize),
+    ReadingWpm(usize),
+    SpeakingWpm(usize),
+    Export(ExportFormat),
+    Output(Output),
+    Format(ReportFormat),
+    Template(Template),
+    SplitOn(Regex),
+    NearDupes,
+    Similarity(MatrixFormat),
+    Cluster(usize),
+    Verbose,
+    Watch,
+    Tui,
+    Jobs(usize),
+    MaxVocab(usize),
+    Spill(PathBuf),
+    Checkpoint(PathBuf),
+    Resume,
+    Hasher(HashAlgorithm),
+    Timeout(Duration),
+    TokenFilters(FilterChain),
+    FilterExpr(FilterExpr),
+    LogFormat(Format),
+    LogLevel(LevelFilter),
+    DryRun,
+    Strict,
+}
+
+/// Parse the value following `--input-format`
+fn parse_input_format(value: Option<&String>) -> Result<Setting, String> {
+    value
+        .ok_or("--input-format requires text, mbox, csv or tsv")?
+        .parse()
+        .map(Setting::InputFormat)
+}
+
+/// Parse the value following `--column`
+fn parse_column(value: Option<&String>) -> Result<Setting, String> {
+    value
+        .ok_or("--column requires a column name or position")?
+        .parse()
+        .map(Setting::Column)
+        .map_err(|err| format!("Invalid --column: {}", err))
+}
+
+/// A ready-made analysis for a kind of file
+#[derive(Debug, Clone, Copy)]
+enum Preset {
+    /// Access logs in the Common or Combined Log Format
+    Weblog,
+}
+
+/// Parse the value following `--preset`
+fn parse_preset(value: Option<&String>) -> Result<Setting, String> {
+    match value.map(String::as_str) {
+        Some("weblog") => Ok(Setting::Preset(Preset::Weblog)),
+        Some(other) => Err(format!("Unknown preset '{}', expected weblog", other)),
+        None => Err("--preset requires weblog".to_string()),
+    }
+}
+
+/// Parse the value following `--normalize`
+fn parse_normalize(value: Option<&String>) -> Result<Setting, String> {
+    value
+        .ok_or("--normalize requires nfc, nfkc or none")?
+        .parse()
+        .map(Setting::Normalize)
+}
+
+/// Parse the value following `--locale`
+fn parse_locale(value: Option<&String>) -> Result<Setting, String> {
+    value
+        .ok_or("--locale requires a locale, e.g. tr_TR")?
+        .parse()
+        .map(Setting::Locale)
+}
+
+/// Parse the value following `--hasher`
+fn parse_hasher(value: Option<&String>) -> Result<Setting, String> {
+    value
+        .ok_or("--hasher requires ahash, fx or sip")?
+        .parse()
+        .map(Setting::Hasher)
+}
+
+/// Parse the value following `--min-length`
+fn parse_min_length(value: Option<&String>) -> Result<Setting, String> {
+    value
+        .ok_or("--min-length requires a number")?
+        .parse::<usize>()
+        .bimap(Setting::MinLength, |_| "Invalid number for --min-length".to_string())
+}
 
-        Ok(Self {
-            file_path,
-            min_length,
-            starts_with,
+/// Parse the value following `--max-length`
+fn parse_max_length(value: Option<&String>) -> Result<Setting, String> {
+    value
+        .ok_or("--max-length requires a number")?
+        .parse::<usize>()
+        .bimap(Setting::MaxLength, |_| {
+            "Invalid number for --max-length".to_string()
         })
+}
+
+/// Parse the value following `--length-mode`
+fn parse_length_mode(value: Option<&String>) -> Result<Setting, String> {
+    value
+        .ok_or("--length-mode requires bytes, chars or graphemes")?
+        .parse()
+        .map(Setting::LengthMode)
+}
+
+/// Read and check the template file following `--template`
+fn parse_template(value: Option<&String>) -> Result<Setting, String> {
+    let path = value.ok_or("--template requires a file")?;
+    let source = fs::read_to_string(path)
+        .map_err(|err| format!("Cannot read template {}: {}", path, err))?;
+    Template::parse(&source)
+        .map(Setting::Template)
+        .map_err(|err| format!("Invalid template {}: {}", path, err))
+}
+
+/// Parse the format following `--export`
+fn parse_export(value: Option<&String>) -> Result<Setting, String> {
+    value
+        .ok_or("--export requires wordfreq or hunspell")?
+        .parse()
+        .map(Setting::Export)
+}
+
+/// Parse the target following `--output`, `sqlite:FILE`
+fn parse_output(value: Option<&String>) -> Result<Setting, String> {
+    value
+        .and_then(|target| target.strip_prefix("sqlite:"))
+        .filter(|path| !path.is_empty())
+        .map(|path| Setting::Output(Output::Sqlite(PathBuf::from(path))))
+        .ok_or_else(|| "--output requires sqlite:FILE".to_string())
+}
+
+/// Parse the format following `--format`
+fn parse_format(value: Option<&String>) -> Result<Setting, String> {
+    match value.map(String::as_str) {
+        Some("text") => Ok(Setting::Format(ReportFormat::Text)),
+        Some("parquet") => Ok(Setting::Format(ReportFormat::Parquet)),
+        Some(other) => Err(format!(
+            "Invalid --format '{}', expected text or parquet",
+            other
+        )),
+        None => Err("--format requires text or parquet".to_string()),
     }
 }
 
-/// Reads a file into a String
-fn read_file(path: &str) -> Result<String, String> {
-    fs::read_to_string(path).map_err(|err| format!("Error reading file {}: {}", path, err))
+/// Parse the pattern following `--split-on`
+fn parse_split_on(value: Option<&String>) -> Result<Setting, String> {
+    let pattern = parse_pattern("--split-on", value)?;
+    Regex::new(&pattern)
+        .map(Setting::SplitOn)
+        .map_err(|err| format!("Invalid --split-on pattern: {}", err))
 }
 
-/// Clean a word: keep only alphanumeric characters, lowercase it
-fn clean_word(word: &str) -> String {
-    word.chars()
-        .filter(|c| c.is_alphanumeric())
-        .flat_map(|c| c.to_lowercase()) // handles Unicode case folding
-        .collect()
+/// Parse the value following `--jobs`
+fn parse_jobs(value: Option<&String>) -> Result<Setting, String> {
+    value
+        .ok_or("--jobs requires a number")?
+        .parse::<usize>()
+        .ok()
+        .filter(|&n| n > 0)
+        .map(Setting::Jobs)
+        .ok_or_else(|| "Invalid number for --jobs".to_string())
+}
+
+/// Parse the value following `--timeout`: positive, possibly fractional seconds
+fn parse_timeout(value: Option<&String>) -> Result<Setting, String> {
+    value
+        .ok_or("--timeout requires a number of seconds")?
+        .parse::<f64>()
+        .ok()
+        .filter(|secs| *secs > 0.0)
+        .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+        .map(Setting::Timeout)
+        .ok_or_else(|| "Invalid number of seconds for --timeout".to_string())
+}
+
+/// Parse the value following `--max-vocab`
+fn parse_max_vocab(value: Option<&String>) -> Result<Setting, String> {
+    value
+        .ok_or("--max-vocab requires a number")?
+        .parse::<usize>()
+        .ok()
+        .filter(|&n| n > 0)
+        .map(Setting::MaxVocab)
+        .ok_or_else(|| "Invalid number for --max-vocab".to_string())
+}
+
+/// Parse the number following `--chart`
+fn parse_chart(value: Option<&String>) -> Result<Setting, String> {
+    value
+        .ok_or("--chart requires a number")?
+        .parse::<usize>()
+        .ok()
+        .filter(|&n| n > 0)
+        .map(Setting::Chart)
+        .ok_or_else(|| "Invalid number for --chart".to_string())
+}
+
+/// Parse the number following `--keywords`
+fn parse_key_phrases(value: Option<&String>) -> Result<Setting, String> {
+    value
+        .ok_or("--keywords requires a number")?
+        .parse::<usize>()
+        .ok()
+        .filter(|&n| n > 0)
+        .map(Setting::KeyPhrases)
+        .ok_or_else(|| "Invalid number for --keywords".to_string())
+}
+
+/// Parse the number of sentences following `--summarize`
+fn parse_summarize(value: Option<&String>) -> Result<Setting, String> {
+    value
+        .ok_or("--summarize requires a number")?
+        .parse::<usize>()
+        .ok()
+        .filter(|&n| n > 0)
+        .map(Setting::Summarize)
+        .ok_or_else(|| "Invalid number for --summarize".to_string())
+}
+
+/// Parse the number following `--goal`
+fn parse_goal(value: Option<&String>) -> Result<Setting, String> {
+    value
+        .ok_or("--goal requires a number")?
+        .parse::<usize>()
+        .ok()
+        .filter(|&n| n > 0)
+        .map(Setting::Goal)
+        .ok_or_else(|| "Invalid number for --goal".to_string())
+}
+
+/// Parse the number of edits following `--max-dist`; 0 finds exact matches
+fn parse_max_dist(value: Option<&String>) -> Result<Setting, String> {
+    value
+        .ok_or("--max-dist requires a number")?
+        .parse::<usize>()
+        .map(Setting::MaxDist)
+        .map_err(|_| "Invalid number for --max-dist".to_string())
+}
+
+/// Parse the number of edits following `--fuzzy-filters`
+fn parse_fuzzy_filters(value: Option<&String>) -> Result<Setting, String> {
+    value
+        .ok_or("--fuzzy-filters requires a number")?
+        .parse::<usize>()
+        .map(Setting::FuzzyFilters)
+        .map_err(|_| "Invalid number for --fuzzy-filters".to_string())
+}
+
+/// Parse the number of clusters following `--cluster`
+fn parse_cluster(value: Option<&String>) -> Result<Setting, String> {
+    value
+        .ok_or("--cluster requires a number")?
+        .parse::<usize>()
+        .ok()
+        .filter(|&k| k > 0)
+        .map(Setting::Cluster)
+        .ok_or_else(|| "Invalid number for --cluster".to_string())
+}
+
+/// Parse the format following `--similarity`
+fn parse_similarity(value: Option<&String>) -> Result<Setting, String> {
+    match value.map(String::as_str) {
+        Some("csv") => Ok(Setting::Similarity(MatrixFormat::Csv)),
+        Some("json") => Ok(Setting::Similarity(MatrixFormat::Json)),
+        Some(other) => Err(format!(
+            "Invalid --similarity format '{}', expected csv or json",
+            other
+        )),
+        None => Err("--similarity requires csv or json".to_string()),
+    }
+}
+
+/// Parse the words per minute following `--reading-wpm` or `--speaking-wpm`
+fn parse_wpm(
+    value: Option<&String>,
+    flag: &str,
+    setting: fn(usize) -> Setting,
+) -> Result<Setting, String> {
+    value
+        .ok_or_else(|| format!("{} requires a number", flag))?
+        .parse::<usize>()
+        .ok()
+        .filter(|&n| n > 0)
+        .map(setting)
+        .ok_or_else(|| format!("Invalid number for {}", flag))
+}
+
+/// Parse the file name following `--reference`
+fn parse_reference(value: Option<&String>) -> Result<Setting, String> {
+    value
+        .map(|path| Setting::Reference(path.clone()))
+        .ok_or_else(|| "--reference requires a file".to_string())
+}
+
+/// Parse the directory following `--spill`
+fn parse_spill(value: Option<&String>) -> Result<Setting, String> {
+    value
+        .filter(|dir| Path::new(dir).is_dir())
+        .map(|dir| Setting::Spill(PathBuf::from(dir)))
+        .ok_or_else(|| "--spill requires an existing directory".to_string())
+}
+
+/// Parse the file following `--checkpoint`
+fn parse_checkpoint(value: Option<&String>) -> Result<Setting, String> {
+    value
+        .map(|path| Setting::Checkpoint(PathBuf::from(path)))
+        .ok_or_else(|| "--checkpoint requires a file".to_string())
+}
+
+/// Build the comma-separated token filters following `--filters`
+fn parse_token_filters(value: Option<&String>) -> Validated<Setting, String> {
+    match value {
+        Some(specs) => Registry::default()
+            .chain(specs.split(','))
+            .map(Setting::TokenFilters),
+        None => Validated::invalid("--filters requires a list of filter names".to_string()),
+    }
+}
+
+/// Parse and type-check the expression following `--filter-expr`
+fn parse_filter_expr(value: Option<&String>) -> Result<Setting, String> {
+    FilterExpr::parse(value.ok_or("--filter-expr requires an expression")?)
+        .bimap(Setting::FilterExpr, |err| {
+            format!("Invalid --filter-expr: {}", err)
+        })
+}
+
+/// Parse the value following `--log-format`
+fn parse_log_format(value: Option<&String>) -> Result<Setting, String> {
+    value
+        .ok_or("--log-format requires text or json")?
+        .parse()
+        .map(Setting::LogFormat)
+}
+
+/// Parse the value following `--log-level`
+fn parse_log_level(value: Option<&String>) -> Result<Setting, String> {
+    value
+        .ok_or("--log-level requires a level")?
+        .parse()
+        .bimap(Setting::LogLevel, |_| {
+            "Invalid --log-level, expected off, error, warn, info, debug or trace".to_string()
+        })
+}
+
+/// Parse the non-empty text following a string filter such as `--starts-with`
+fn parse_pattern(flag: &str, value: Option<&String>) -> Result<String, String> {
+    value
+        .filter(|pattern| !pattern.is_empty())
+        .cloned()
+        .ok_or_else(|| format!("{} requires non-empty text", flag))
+}
+
+/// Describes reading a file into a String
+fn read_file<'a>(path: &str) -> Program<'a, Result<String, String>> {
+    let shown = path.to_string();
+    Program::read_file(path)
+        .map(move |result| result.map_err(|err| format!("Error reading file {}: {}", shown, err)))
+}
+
+/// Describes reading the `--reference` corpus, if there is one
+fn read_reference<'a>(config: &Config) -> Program<'a, Result<Option<String>, String>> {
+    match &config.reference {
+        Some(path) => read_file(path).map(|result| result.map(Some)),
+        None => Program::pure(Ok(None)),
+    }
+}
+
+/// The word counts of the `--reference` corpus, read as plain text and
+/// filtered like the file so that both count the same kind of words
+fn reference_counts(reference: &str, config: &Config) -> HashMap<String, usize> {
+    analyze_text(reference).run(config).into_inner().run().0
 }
 
 /// Analyze text and count word frequencies functionally
-fn analyze_text(text: &str, config: &Config) -> HashMap<String, usize> {
-    // Filtering closure
-    let filter = |word: &str| {
-        let long_enough = config.min_length.map_or(true, |n| word.len() >= n);
-        let starts_correct = config.starts_with.map_or(true, |c| word.starts_with(c));
-        long_enough && starts_correct
-    };
+///
+/// With `--verbose`, a trace entry per line is collected in the Writer's log
+/// rather than printed, so analysis stays free of side effects. The result is
+/// a Reader: the analysis is assembled first and run against a Config later.
+/// With `--jobs N`, lines are counted by N worker threads; merging in chunk
+/// order keeps the result (and trace) identical to the sequential run.
+/// With `--max-vocab N`, lines are counted in batches and the counts are
+/// pruned to N words after each one, as in `analysis::capped_counts`.
+/// With `--hasher`, words are counted in a map using that hasher. With
+/// `--timeout`, counting stops between batches once the time is up, and the
+/// counts so far come back as `Outcome::Cancelled`.
+fn analyze_text(text: &str) -> Reader<'_, Config, Analysis<HashMap<String, usize>>> {
+    Reader::new(move |config: &Config| match config.hasher {
+        HashAlgorithm::Sip => analyze_borrowed::<SipBuilder>(text)
+            .run(config)
+            .map(|writer| writer.map(into_owned)),
+        HashAlgorithm::Fx => analyze_borrowed::<FxBuilder>(text)
+            .run(config)
+            .map(|writer| writer.map(into_owned)),
+        HashAlgorithm::AHash => analyze_borrowed::<AHashBuilder>(text)
+            .run(config)
+            .map(|writer| writer.map(into_owned)),
+    })
+}
+
+/// Counts with their verbose trace, complete or cut short by `--timeout`
+type Analysis<C> = Outcome<Writer<C, Vec<String>>>;
 
-    text.split_whitespace()
-        .map(clean_word)
-        // .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()))
-        // .map(|w| w.to_lowercase())
-        .map(|w| w.trim().to_string())   
-        .filter(|w| !w.is_empty())
-        .filter(|w| filter(w))
-        .fold(HashMap::new(), |mut acc, word| {
-            *acc.entry(word).or_insert(0) += 1;
-            acc
+/// Word counts whose words may borrow from the analyzed text, hashed with `S`
+type Counts<'a, S> = HashMap<Cow<'a, str>, usize, S>;
+
+/// [`analyze_text`] before its words are copied out of the text, so that
+/// repeated words are never allocated while counting
+fn analyze_borrowed<'a, S>(text: &'a str) -> Reader<'a, Config, Analysis<Counts<'a, S>>>
+where
+    S: BuildHasher + Default + Send + 'static,
+{
+    Reader::new(move |config: &Config| {
+        let lines: Vec<(usize, &'a str)> = text.lines().enumerate().collect();
+        let count = |&(i, line): &(usize, &'a str)| count_line(i + 1, line).run(config);
+        // Count line by line, then merge the partial counts (and traces) with the Monoid instance
+        let count_batch = |batch: &[(usize, &'a str)]| match config.jobs {
+            Some(workers) => map_reduce(batch, LINES_PER_CHUNK, workers, count),
+            None => fold_map(batch, count),
+        };
+        if config.max_vocab.is_none() && config.timeout.is_none() {
+            return Outcome::Complete(count_batch(&lines));
+        }
+        // Pruning and the deadline both apply between batches of lines
+        let prune_to = |counts| match config.max_vocab {
+            Some(max_vocab) => prune(counts, max_vocab),
+            None => counts,
+        };
+        cancellation(config).fold(
+            lines.chunks(PRUNE_INTERVAL),
+            Writer::empty(),
+            |counts, batch| counts.combine(count_batch(batch)).map(prune_to),
+        )
+    })
+}
+
+/// A token cancelled by Ctrl-C or once the `--timeout`, counted from now,
+/// is up
+fn cancellation(config: &Config) -> CancellationToken {
+    match config.timeout {
+        Some(timeout) => INTERRUPT.limited_to(timeout),
+        None => INTERRUPT.clone(),
+    }
+}
+
+/// Count one line's words, tracing the line when verbose
+fn count_line<S: BuildHasher + Default + 'static>(
+    number: usize,
+    line: &str,
+) -> Reader<'_, Config, Writer<Counts<'_, S>, Vec<String>>> {
+    count_words(line)
+        .zip(Reader::asks(|config: &Config| config.verbose))
+        .map(move |(counts, verbose)| {
+            let trace = if verbose {
+                vec![trace_line(number, line, &counts)]
+            } else {
+                Vec::new()
+            };
+            Writer::new(counts, trace)
         })
 }
 
-/// Display statistics of word frequencies
-fn display_stats(freqs: &HashMap<String, usize>) {
+/// Describe how many of a line's words survived cleaning and filtering
+fn trace_line<S>(number: usize, line: &str, counts: &Counts<'_, S>) -> String {
+    format!(
+        "line {}: kept {} of {} words",
+        number,
+        counts.values().sum::<usize>(),
+        line.split_whitespace().count()
+    )
+}
+
+/// Count the words of a single line that pass the configured filters
+fn count_words<S: BuildHasher + Default + 'static>(
+    line: &str,
+) -> Reader<'_, Config, Counts<'_, S>> {
+    Reader::new(move |config: &Config| {
+        xf::sequence(word_pipeline(config), clean_words(line, &config.filters)).counts_with_hasher()
+    })
+}
+
+/// The words of `text` that pass the configured filters, in order
+fn words<'a>(text: &'a str, config: &'a Config) -> impl Iterator<Item = Cow<'a, str>> {
+    text.lines().flat_map(move |line| {
+        xf::sequence(word_pipeline(config), clean_words(line, &config.filters))
+    })
+}
+
+/// The filtering steps after `clean_words`, independent of where the words
+/// come from. Words stay borrowed from the line unless a token filter runs.
+fn word_pipeline<'c, 'w>(
+    config: &'c Config,
+) -> impl Transducer<Cow<'w, str>, Out = Cow<'w, str>> + 'c {
+    xf::flat_map(move |w: Cow<'w, str>| {
+        if config.token_filters.is_empty() {
+            Some(w)
+        } else {
+            config.token_filters.map(w.into_owned()).map(Cow::Owned)
+        }
+    })
+    .then(xf::filter(move |w: &Cow<str>| {
+        config
+            .filter_expr
+            .as_ref()
+            .is_none_or(|expr| expr.matches(w))
+    }))
+    .then(xf::filter(move |w: &Cow<str>| config.matcher.accepts(w)))
+}
+
+/// Format statistics of word frequencies
+fn format_stats(freqs: &HashMap<String, usize>, speeds: Speeds) -> String {
     let total_words: usize = freqs.values().sum();
-    let unique_words = freqs.len();
-    let most_common = freqs.iter().max_by_key(|&(_, count)| count);
+    // Ties go to the alphabetically first word, as in the spilled report
+    let most_common = freqs
+        .iter()
+        .min_by(|(a, x), (b, y)| y.cmp(x).then_with(|| a.cmp(b)));
+    format_summary(
+        total_words,
+        freqs.len(),
+        most_common.map(|(word, &count)| (word.as_str(), count)),
+        speeds,
+    )
+}
 
-    println!("Total words: {}", total_words);
-    println!("Unique words: {}", unique_words);
+/// Format totals, the most common word, and the time it takes to read and
+/// to speak the words at `speeds`
+fn format_summary(
+    total_words: usize,
+    unique_words: usize,
+    most_common: Option<(&str, usize)>,
+    speeds: Speeds,
+) -> String {
+    let summary = format!(
+        "Total words: {}\nUnique words: {}",
+        total_words, unique_words
+    );
 
     if let Some((word, count)) = most_common {
-        println!("Most common word: '{}' ({} occurrences)", word, count);
+        format!(
+            "{}\nMost common word: '{}' ({} occurrences)\nReading time: {}\nSpeaking time: {}",
+            summary,
+            word,
+            count,
+            format_duration(speeds.reading_time(total_words)),
+            format_duration(speeds.speaking_time(total_words))
+        )
     } else {
-        println!("No words found after filtering.");
+        format!("{}\nNo words found after filtering.", summary)
     }
     //   println!("\n--- Word Frequencies ---");
     // for (word, count) in freqs {
@@ -143,23 +2016,1708 @@ fn display_stats(freqs: &HashMap<String, usize>) {
     // }
 }
 
-fn main() {
-    let config = match Config::from_args() {
-        Ok(cfg) => cfg,
-        Err(msg) => {
-            eprintln!("{}", msg);
-            process::exit(1);
+/// Format every word frequency in alphabetical order
+fn format_sorted(freqs: &HashMap<String, usize>) -> String {
+    let sorted: PTreeMap<&str, usize> = freqs
+        .iter()
+        .map(|(word, &count)| (word.as_str(), count))
+        .collect();
+
+    sorted.iter().fold(
+        "\n--- Word Frequencies ---".to_string(),
+        |out, (word, count)| format!("{}\n{}: {}", out, word, count),
+    )
+}
+
+/// Format word counts grouped by their first letter, in letter order
+fn format_by_first_letter(freqs: &HashMap<String, usize>) -> String {
+    let groups = MultiMap::group_by(freqs.iter(), |(word, _)| word.chars().next());
+    let mut letters: Vec<_> = groups.iter().collect();
+    letters.sort_by_key(|&(letter, _)| *letter);
+
+    letters
+        .into_iter()
+        .filter_map(|(letter, words)| letter.map(|c| (c, words)))
+        .fold(
+            "\n--- By First Letter ---".to_string(),
+            |out, (letter, words)| {
+                let total: usize = words.iter().map(|&(_, count)| count).sum();
+                format!("{}\n{}", out, letter_line(letter, total, words.len()))
+            },
+        )
+}
+
+/// One letter's line in the by-first-letter section
+fn letter_line(letter: char, total: usize, unique: usize) -> String {
+    format!("{}: {} ({} unique)", letter, total, unique)
+}
+
+/// Format the `--chart` of the most frequent words, scaled to the terminal
+fn format_chart(top: &[(String, usize)], n: usize) -> String {
+    let chart = bar_chart(
+        top.iter().map(|(word, count)| (word, *count)),
+        terminal_width(),
+    );
+    format!("\n--- Top {} Words ---\n{}", n, chart)
+}
+
+/// Format the `--reference` keywords: the words significantly more frequent
+/// than in the reference corpus at `path`, at most [`DEFAULT_TOP`]
+fn format_keywords(
+    freqs: &HashMap<String, usize>,
+    reference: &HashMap<String, usize>,
+    path: &str,
+) -> String {
+    let keywords: Vec<Keyword> = keywords(freqs, reference)
+        .into_iter()
+        .take_while(|keyword| keyword.log_likelihood >= SIGNIFICANT)
+        .take(DEFAULT_TOP)
+        .collect();
+    if keywords.is_empty() {
+        return format!(
+            "\n--- Keywords vs {} ---\nNo word is significantly more frequent.",
+            path
+        );
+    }
+    keywords
+        .iter()
+        .fold(format!("\n--- Keywords vs {} ---", path), |out, keyword| {
+            format!(
+                "{}\n{}: {:.2} ({} vs {})",
+                out, keyword.word, keyword.log_likelihood, keyword.count, keyword.reference_count
+            )
+        })
+}
+
+/// Format the `--keywords` section: the `n` best RAKE key phrases of
+/// `text`, split at the stop words of the `--locale` language or English
+fn format_key_phrases(text: &str, n: usize, locale: Option<Locale>) -> String {
+    let phrases = rake(text, locale.unwrap_or(Locale::English));
+    if phrases.is_empty() {
+        return "\n--- Key phrases ---\nNo key phrases found.".to_string();
+    }
+    phrases
+        .iter()
+        .take(n)
+        .fold("\n--- Key phrases ---".to_string(), |out, phrase| {
+            format!(
+                "{}\n{}: {:.2} ({})",
+                out, phrase.phrase, phrase.score, phrase.count
+            )
+        })
+}
+
+/// Format the `--summarize` section: the `n` sentences of `text` with the
+/// highest total of word counts in `freqs`, each on one line
+fn format_summary_sentences(
+    text: &str,
+    freqs: &HashMap<String, usize>,
+    n: usize,
+    locale: Option<Locale>,
+) -> String {
+    let locale = locale.unwrap_or(Locale::English);
+    summarize(text, n, |sentence| frequency_score(sentence, freqs, locale))
+        .iter()
+        .fold("\n--- Summary ---".to_string(), |out, sentence| {
+            // Sentences may span lines of the file
+            let sentence: Vec<&str> = sentence.split_whitespace().collect();
+            format!("{}\n{}", out, sentence.join(" "))
+        })
+}
+
+/// Format the `--goal` progress of `words` toward `goal`, with a bar as wide
+/// as the terminal
+fn format_goal(words: usize, goal: usize) -> String {
+    let progress = Progress::new(words, goal);
+    let cells = terminal_width().saturating_sub(2);
+    format!(
+        "\n--- Goal ---\n{}\n[{}]",
+        progress,
+        bar(words.min(goal), goal, cells)
+    )
+}
+
+/// Format the `--watch --goal` pace since `started`, an instant and the
+/// word count at that instant, now that there are `words`
+fn format_pace(started: (Instant, usize), words: usize) -> String {
+    let (since, from) = started;
+    format!(
+        "Pace: {:.1} words per minute since start",
+        words_per_minute(from, words, since.elapsed())
+    )
+}
+
+/// Format the `--diversity` measures of the words of `text`
+fn format_diversity(text: &str, config: &Config) -> String {
+    let tokens: Vec<Cow<str>> = words(text, config).collect();
+    let measures = diversity(&tokens);
+    format!(
+        "\n--- Lexical Diversity ---\nType-token ratio: {:.3}\nRoot TTR: {:.2}\nMTLD: {:.2}",
+        measures.ttr, measures.root_ttr, measures.mtld
+    )
+}
+
+/// Format the `--compress-demo` results: the words of `text` and its
+/// characters, each Huffman-coded with their own frequencies
+fn format_compression(text: &str, config: &Config) -> String {
+    let tokens: Vec<Cow<str>> = words(text, config).collect();
+    let chars: Vec<char> = text.chars().collect();
+    let word_code = HuffmanCode::from_symbols(tokens.iter().cloned());
+    let char_code = HuffmanCode::from_symbols(chars.iter().copied());
+    let (Some(word_code), Some(char_code)) = (word_code, char_code) else {
+        return "\n--- Compression ---\nNo words to compress.".to_string();
+    };
+    let word_bits = word_code.encode(&tokens).unwrap_or_default();
+    let char_bits = char_code.encode(&chars).unwrap_or_default();
+    let per = |bits: &[bool], symbols: usize| bits.len() as f64 / symbols as f64;
+    let fixed = (word_code.len() as f64).log2().ceil().max(1.0);
+    let decoded = word_code.decode(&word_bits).as_deref() == Some(tokens.as_slice())
+        && char_code.decode(&char_bits).as_deref() == Some(chars.as_slice());
+    format!(
+        "\n--- Compression ---\nWords: {:.2} bits per word with Huffman (entropy {:.2}, fixed-length {})\nCharacters: {:.2} bits per character with Huffman (entropy {:.2}, UTF-8 {:.2})\nDecoded: {}",
+        per(&word_bits, tokens.len()),
+        entropy(tokens.iter().counts().into_values()),
+        fixed,
+        per(&char_bits, chars.len()),
+        entropy(chars.iter().counts().into_values()),
+        (text.len() * 8) as f64 / chars.len() as f64,
+        if decoded { "identical" } else { "mismatch" }
+    )
+}
+
+/// Format the `--fun-facts` about the words counted
+fn format_fun_facts(freqs: &HashMap<String, usize>) -> String {
+    let facts = curiosities(freqs.keys().map(String::as_str));
+    let letters = |n: usize| match n {
+        1 => "1 letter".to_string(),
+        n => format!("{} letters", n),
+    };
+    let length = |words: &[String]| letters(words.first().map_or(0, |word| word.chars().count()));
+    let distinct = |words: &[String]| words.first().map_or(0, |word| distinct_letters(word));
+    [
+        format!(
+            "Longest words ({}): {}",
+            length(&facts.longest),
+            list_words(&facts.longest)
+        ),
+        format!(
+            "Shortest words ({}): {}",
+            length(&facts.shortest),
+            list_words(&facts.shortest)
+        ),
+        format!("Palindromes: {}", list_words(&facts.palindromes)),
+        format!(
+            "Most distinct letters ({}): {}",
+            distinct(&facts.most_distinct),
+            list_words(&facts.most_distinct)
+        ),
+    ]
+    .iter()
+    .fold("\n--- Fun Facts ---".to_string(), |out, line| {
+        format!("{}\n{}", out, line)
+    })
+}
+
+/// Format the `--anagrams` groups among the words counted, with their counts
+fn format_anagrams(freqs: &HashMap<String, usize>) -> String {
+    let groups = anagram_groups(freqs.keys().map(String::as_str));
+    if groups.is_empty() {
+        return "\n--- Anagrams ---\nNo anagrams found.".to_string();
+    }
+    groups
+        .iter()
+        .map(|group| {
+            group
+                .iter()
+                .map(|word| format!("{} ({})", word, freqs[*word]))
+                .collect::<Vec<_>>()
+                .join(", ")
+        })
+        .fold("\n--- Anagrams ---".to_string(), |out, line| {
+            format!("{}\n{}", out, line)
+        })
+}
+
+/// Format the `--phonetic` groups among the words counted, with their
+/// Soundex codes and counts
+fn format_sound_alikes(freqs: &HashMap<String, usize>) -> String {
+    let groups = phonetic_groups(freqs.keys().map(String::as_str));
+    if groups.is_empty() {
+        return "\n--- Sound-alikes ---\nNo words sound alike.".to_string();
+    }
+    groups
+        .iter()
+        .map(|(code, group)| {
+            let words = group
+                .iter()
+                .map(|word| format!("{} ({})", word, freqs[*word]))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("{}: {}", code, words)
+        })
+        .fold("\n--- Sound-alikes ---".to_string(), |out, line| {
+            format!("{}\n{}", out, line)
+        })
+}
+
+/// Format the words of `trie` starting with `prefix` as completions: the
+/// [`DEFAULT_TOP`] most frequent, ties alphabetical
+fn format_completions(trie: &Trie<usize>, prefix: &str) -> String {
+    let prefix = prefix.to_lowercase();
+    let mut words = trie.words_with_prefix(&prefix);
+    words.sort_by(|(a, x), (b, y)| y.cmp(x).then_with(|| a.cmp(b)));
+    let heading = format!("\n--- Words starting with '{}' ---", prefix);
+    if words.is_empty() {
+        return format!("{}\nNone.", heading);
+    }
+    let more = words.len().saturating_sub(DEFAULT_TOP);
+    let listed = words
+        .iter()
+        .take(DEFAULT_TOP)
+        .fold(heading, |out, (word, count)| {
+            format!("{}\n{}: {}", out, word, count)
+        });
+    match more {
+        0 => listed,
+        n => format!("{}\n(and {} more)", listed, n),
+    }
+}
+
+/// Format the counted words within `max_dist` edits of `word`: the
+/// [`DEFAULT_TOP`] nearest, ties alphabetical
+///
+/// A single query compares every word once, which is cheaper than building
+/// a BK-tree to ask it once.
+fn format_fuzzy(freqs: &HashMap<String, usize>, word: &str, max_dist: usize) -> String {
+    let word = word.to_lowercase();
+    let matches = within(&word, freqs.keys().map(String::as_str), max_dist);
+    let heading = format!(
+        "\n--- Words within {} {} of '{}' ---",
+        max_dist,
+        if max_dist == 1 { "edit" } else { "edits" },
+        word
+    );
+    if matches.is_empty() {
+        return format!("{}\nNone.", heading);
+    }
+    let more = matches.len().saturating_sub(DEFAULT_TOP);
+    let listed = matches
+        .iter()
+        .take(DEFAULT_TOP)
+        .fold(heading, |out, &(word, distance)| {
+            let edits = if distance == 1 { "edit" } else { "edits" };
+            format!(
+                "{}\n{}: {} ({} {})",
+                out, word, freqs[word], distance, edits
+            )
+        });
+    match more {
+        0 => listed,
+        n => format!("{}\n(and {} more)", listed, n),
+    }
+}
+
+/// `words` separated by commas, at most [`FUN_FACTS_LISTED`] of them
+fn list_words(words: &[String]) -> String {
+    match words.len() {
+        0 => "none".to_string(),
+        n if n > FUN_FACTS_LISTED => format!(
+            "{} and {} more",
+            words[..FUN_FACTS_LISTED].join(", "),
+            n - FUN_FACTS_LISTED
+        ),
+        _ => words.join(", "),
+    }
+}
+
+/// Columns available for charts: `$COLUMNS`, else the width of the
+/// terminal on stdout, else 80
+fn terminal_width() -> usize {
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.parse().ok())
+        .or_else(|| terminal_size::terminal_size().map(|(width, _)| usize::from(width.0)))
+        .unwrap_or(80)
+}
+
+/// The report of `config` as a description of its effects, returning the
+/// exit code
+///
+/// Building it performs nothing: `run` interprets it with real IO, and it can
+/// equally be run by the in-memory interpreter.
+fn program(config: &Config) -> Program<'_, i32> {
+    if config.dry_run {
+        return Program::write_output(describe_config(config)).map(|_| 0);
+    }
+    read_reference(config).and_then(move |reference| match reference {
+        Err(msg) => Program::log_at(Level::Error, msg).map(|_| 1),
+        Ok(reference) => read_file(&config.file_path).and_then(move |content| match content {
+            Err(msg) => Program::log_at(Level::Error, msg).map(|_| 1),
+            Ok(text) if config.preset.is_some() => {
+                Program::write_output(weblog_report(&text)).map(|_| 0)
+            }
+            Ok(text) => match extract(&text, config) {
+                Ok(input) => report(&input, reference.as_deref(), config),
+                Err(msg) => Program::log_at(Level::Error, msg).map(|_| 1),
+            },
+        }),
+    })
+}
+
+/// The `generate`, `suggest` or `help` subcommand, if `args` name one
+fn subcommand(args: &[String]) -> Option<Program<'static, i32>> {
+    let (program, command, args) = match args {
+        [program, command, args @ ..] => (program, command.as_str(), args),
+        _ => return None,
+    };
+    match command {
+        "generate" => Some(generate_command(program, args)),
+        "suggest" => Some(suggest_command(program, args)),
+        "help" => Some(match help_command(program, args) {
+            Ok(help) => Program::write_output(help).map(|_| 0),
+            Err(msg) => Program::log_at(Level::Error, msg).map(|_| 1),
+        }),
+        _ => None,
+    }
+}
+
+/// The fully resolved configuration, as printed by `--dry-run`
+fn describe_config(config: &Config) -> String {
+    let or_none = |value: Option<String>| value.unwrap_or_else(|| "none".to_string());
+    let token_filters = config.token_filters.specs().join(" -> ");
+    let sections: Vec<&str> = [
+        Some("summary"),
+        config.sorted.then_some("sorted frequencies"),
+        config.by_first_letter.then_some("by first letter"),
+        config.chart.is_some().then_some("top words chart"),
+        config.reference.is_some().then_some("keywords"),
+        config.key_phrases.is_some().then_some("key phrases"),
+        config.summarize.is_some().then_some("summary"),
+        config.goal.is_some().then_some("goal"),
+        config.diversity.then_some("lexical diversity"),
+        config.compress_demo.then_some("compression demo"),
+        config.fun_facts.then_some("fun facts"),
+        config.anagrams.then_some("anagrams"),
+        config.phonetic.then_some("sound-alikes"),
+        config.complete.is_some().then_some("completions"),
+        config.fuzzy.is_some().then_some("fuzzy matches"),
+        config.export.map(|format| match format {
+            ExportFormat::WordFreq => "export (wordfreq)",
+            ExportFormat::Hunspell => "export (hunspell)",
+        }),
+        (config.format == ReportFormat::Parquet).then_some("parquet table"),
+        config.template.is_some().then_some("template"),
+        config.split_on.is_some().then_some("per section"),
+        config.near_dupes.then_some("near-duplicate files"),
+        config.similarity.map(|format| match format {
+            MatrixFormat::Csv => "similarity matrix (csv)",
+            MatrixFormat::Json => "similarity matrix (json)",
+        }),
+        config.cluster.is_some().then_some("clusters"),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    let settings = [
+        ("file", format!("{} (not read)", config.file_path)),
+        (
+            "input format",
+            format!("{:?}", config.input_format).to_lowercase(),
+        ),
+        (
+            "column",
+            or_none(config.column.as_ref().map(|column| match column {
+                Column::Name(name) => format!("'{}'", name),
+                Column::Position(n) => format!("#{}", n),
+            })),
+        ),
+        ("json field", or_none(config.json_field.clone())),
+        (
+            "preset",
+            or_none(config.preset.map(|preset| format!("{:?}", preset).to_lowercase())),
+        ),
+        (
+            "tokenizer",
+            format!(
+                "{}split on whitespace, normalize ({:?}), {}clean_word (keep alphanumerics, lowercase)",
+                match (config.clean_ocr, config.rejoin_hyphens) {
+                    (true, _) => "clean ocr, ",
+                    (false, true) => "rejoin hyphens, ",
+                    (false, false) => "",
+                },
+                config.filters.normalization,
+                if config.filters.strip_accents { "strip accents, " } else { "" }
+            )
+            .to_lowercase(),
+        ),
+        (
+            "locale",
+            or_none(config.filters.locale.map(|locale| locale.code().to_string())),
+        ),
+        (
+            "token filters",
+            or_none((!token_filters.is_empty()).then_some(token_filters)),
+        ),
+        (
+            "filter expression",
+            or_none(
+                config
+                    .filter_expr
+                    .as_ref()
+                    .map(|expr| expr.source().to_string()),
+            ),
+        ),
+        (
+            "min length",
+            or_none(config.filters.min_length.map(|n| n.to_string())),
+        ),
+        (
+            "max length",
+            or_none(config.filters.max_length.map(|n| n.to_string())),
+        ),
+        (
+            "length mode",
+            format!("{:?}", config.filters.length_mode).to_lowercase(),
+        ),
+        ("starts with", or_none(config.filters.starts_with.clone())),
+        ("ends with", or_none(config.filters.ends_with.clone())),
+        ("contains", or_none(config.filters.contains.clone())),
+        (
+            "fuzzy filters",
+            or_none(
+                (config.filters.fuzziness > 0)
+                    .then(|| format!("up to {} edits", config.filters.fuzziness)),
+            ),
+        ),
+        ("report", sections.join(", ")),
+        (
+            "template",
+            or_none(config.template.as_ref().map(|t| format!("{:?}", t.source()))),
+        ),
+        (
+            "output",
+            or_none(config.output.as_ref().map(|output| match output {
+                Output::Sqlite(path) => format!("sqlite:{}", path.display()),
+            })),
+        ),
+        (
+            "reference",
+            or_none(config.reference.as_ref().map(|path| format!("{} (not read)", path))),
+        ),
+        ("goal", or_none(config.goal.map(|n| format!("{} words", n)))),
+        (
+            "speeds",
+            format!(
+                "reading {} wpm, speaking {} wpm",
+                config.speeds.reading, config.speeds.speaking
+            ),
+        ),
+        (
+            "split on",
+            or_none(config.split_on.as_ref().map(|re| re.as_str().to_string())),
+        ),
+        ("verbose", config.verbose.to_string()),
+        ("strict", config.strict.to_string()),
+        ("watch", config.watch.to_string()),
+        ("tui", config.tui.to_string()),
+        (
+            "spill",
+            or_none(
+                config
+                    .spill
+                    .as_ref()
+                    .map(|dir| format!("{} (runs of {} words)", dir.display(), DEFAULT_RUN_ENTRIES)),
+            ),
+        ),
+        (
+            "checkpoint",
+            or_none(config.checkpoint.as_ref().map(|path| {
+                let start = if config.resume { "resumed" } else { "started over" };
+                format!("{} ({})", path.display(), start)
+            })),
+        ),
+        ("hasher", format!("{:?}", config.hasher).to_lowercase()),
+        (
+            "timeout",
+            or_none(config.timeout.map(|limit| format!("{:?}", limit))),
+        ),
+        (
+            "max vocab",
+            or_none(config.max_vocab.map(|n| n.to_string())),
+        ),
+        (
+            "jobs",
+            config
+                .jobs
+                .map_or("sequential".to_string(), |n| format!("{} workers", n)),
+        ),
+        (
+            "log",
+            format!("{:?}, {}", config.logger.format, config.logger.level).to_lowercase(),
+        ),
+    ];
+    settings.iter().fold(
+        "--- Resolved Configuration ---".to_string(),
+        |out, (name, value)| format!("{}\n{:<18} {}", out, format!("{}:", name), value),
+    )
+}
+
+/// Describes printing the analysis of `text`, compared with the
+/// `--reference` corpus if one was read, then storing the analyzed text
+/// with `--output`, returning the exit code
+fn report<'a>(input: &Extracted, reference: Option<&str>, config: &Config) -> Program<'a, i32> {
+    let text = &input.text;
+    let outcome = analyze_text(text).run(config);
+    let cancelled = outcome.is_cancelled();
+    let (freqs, trace) = outcome.into_inner().run();
+    let reference = reference.map(|reference| reference_counts(reference, config));
+    let rendered =
+        sections(text, config).then(render(text, &freqs, trace, reference.as_ref(), config));
+    let rendered = if input.skipped > 0 {
+        rendered.then(Program::write_output(format!(
+            "Skipped records: {}",
+            input.skipped
+        )))
+    } else {
+        rendered
+    };
+    if cancelled {
+        let (warning, code) = stopped(config, "lines");
+        Program::log_at(Level::Warn, warning)
+            .then(rendered)
+            .map(move |_| code)
+    } else {
+        rendered.then(store_text(text, config))
+    }
+}
+
+/// Describes storing the words of the analyzed `text` in the `--output`
+/// database, if one was given, returning the exit code
+fn store_text<'a>(text: &str, config: &Config) -> Program<'a, i32> {
+    if config.output.is_none() {
+        return Program::pure(0);
+    }
+    let document = Document {
+        path: PathBuf::from(&config.file_path),
+        words: words(text, config).map(Cow::into_owned).collect(),
+    };
+    match store(&[document], config) {
+        Ok(()) => Program::pure(0),
+        Err(msg) => Program::log_at(Level::Error, msg).map(|_| 1),
+    }
+}
+
+/// The text to analyze: one `--column` of a table, one `--json-field` of
+/// every JSON line, or the whole file read as `--input-format` says, then
+/// cleaned of OCR artifacts with `--clean-ocr`, or with `--rejoin-hyphens`
+/// of words broken across lines only
+fn extract<'a>(text: &'a str, config: &Config) -> Result<Extracted<'a>, String> {
+    let extracted = match (&config.column, &config.json_field) {
+        (Some(_), Some(_)) => Err("--column and --json-field cannot be combined".to_string()),
+        (Some(column), None) => {
+            let table = match config.input_format {
+                InputFormat::Text if config.file_path.ends_with(".tsv") => InputFormat::Tsv,
+                format => format,
+            };
+            extract_column(text, table.delimiter(), column)
+        }
+        (None, Some(field)) => Ok(extract_json_field(text, field)),
+        (None, None) => Ok(config.input_format.extract(text)),
+    }?;
+    let clean: fn(&str) -> Cow<'_, str> = match (config.clean_ocr, config.rejoin_hyphens) {
+        (true, _) => clean_ocr,
+        (false, true) => rejoin_hyphenated,
+        (false, false) => return Ok(extracted),
+    };
+    let text = match extracted.text {
+        Cow::Borrowed(text) => clean(text),
+        Cow::Owned(text) => Cow::Owned(clean(&text).into_owned()),
+    };
+    Ok(Extracted { text, ..extracted })
+}
+
+/// The `--preset weblog` report: the most frequent whole values of each
+/// access log field, rather than the words in them
+fn weblog_report(text: &str) -> String {
+    let lines: Vec<&str> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    let entries: Vec<AccessEntry> = lines
+        .iter()
+        .filter_map(|line| parse_access_line(line))
+        .collect();
+    let fields: Vec<String> = LogField::ALL
+        .iter()
+        .map(|&field| {
+            let counts = entries
+                .iter()
+                .filter_map(|entry| entry.get(field))
+                .map(str::to_string)
+                .counts();
+            let report = Report::new(&counts, DEFAULT_TOP);
+            let heading = match field {
+                LogField::Path => "Paths",
+                LogField::Status => "Status codes",
+                LogField::UserAgent => "User agents",
+            };
+            report.top_words.iter().fold(
+                format!("--- {} ({} unique) ---", heading, report.unique_words),
+                |out, (value, count)| format!("{}\n{}: {}", out, value, count),
+            )
+        })
+        .collect();
+    format!(
+        "Requests: {}\nSkipped records: {}\n\n{}",
+        entries.len(),
+        lines.len() - entries.len(),
+        fields.join("\n\n")
+    )
+}
+
+/// Describes printing the `--split-on` sections of `text`, if it was given
+fn sections<'a>(text: &str, config: &Config) -> Program<'a, ()> {
+    match &config.split_on {
+        Some(delimiter) => Program::write_output(section_stats(text, delimiter, config)),
+        None => Program::pure(()),
+    }
+}
+
+/// The summary of every `--split-on` section, followed by the heading of the
+/// whole file's report
+fn section_stats(text: &str, delimiter: &Regex, config: &Config) -> String {
+    let sections: Vec<String> = split_sections(text, delimiter)
+        .iter()
+        .enumerate()
+        .map(|(i, section)| {
+            let (freqs, _) = analyze_text(section.text).run(config).into_inner().run();
+            format!(
+                "--- Section {}: {} ---\n{}\n",
+                i + 1,
+                section.title.unwrap_or("(preamble)"),
+                format_stats(&freqs, config.speeds)
+            )
+        })
+        .collect();
+    format!("{}\n--- Total ---", sections.join("\n"))
+}
+
+/// The warning and exit code for a report cut short by Ctrl-C or
+/// `--timeout`, having counted only some of its `units`
+fn stopped(config: &Config, units: &str) -> (String, i32) {
+    if INTERRUPT.is_cancelled() {
+        (
+            format!(
+                "Interrupted; the report covers only the {} counted so far",
+                units
+            ),
+            INTERRUPTED,
+        )
+    } else {
+        (
+            format!(
+                "--timeout of {:?} reached; the report covers only the {} counted so far",
+                config.timeout.unwrap_or_default(),
+                units
+            ),
+            TIMED_OUT,
+        )
+    }
+}
+
+/// Describes printing the already computed frequencies and trace of
+/// `text`, with the keywords against the `--reference` counts if given
+fn render<'a>(
+    text: &str,
+    freqs: &HashMap<String, usize>,
+    trace: Vec<String>,
+    reference: Option<&HashMap<String, usize>>,
+    config: &Config,
+) -> Program<'a, ()> {
+    if let Some(format) = config.export {
+        let exported = format.render(freqs);
+        return Program::write_output(exported.trim_end_matches('\n').to_string())
+            .after_logs(trace);
+    }
+    if let Some(template) = &config.template {
+        let rendered = template.render_with(freqs, config.speeds);
+        return Program::write_output(rendered.trim_end_matches('\n').to_string())
+            .after_logs(trace);
+    }
+    let sorted = if config.sorted {
+        Program::write_output(format_sorted(freqs))
+    } else {
+        Program::pure(())
+    };
+    let by_first_letter = if config.by_first_letter {
+        Program::write_output(format_by_first_letter(freqs))
+    } else {
+        Program::pure(())
+    };
+    let chart = match config.chart {
+        Some(n) => Program::write_output(format_chart(&Report::new(freqs, n).top_words, n)),
+        None => Program::pure(()),
+    };
+    let keywords = match (reference, &config.reference) {
+        (Some(reference), Some(path)) => {
+            Program::write_output(format_keywords(freqs, reference, path))
+        }
+        _ => Program::pure(()),
+    };
+    let key_phrases = match config.key_phrases {
+        Some(n) => Program::write_output(format_key_phrases(text, n, config.filters.locale)),
+        None => Program::pure(()),
+    };
+    let summary = match config.summarize {
+        Some(n) => Program::write_output(format_summary_sentences(
+            text,
+            freqs,
+            n,
+            config.filters.locale,
+        )),
+        None => Program::pure(()),
+    };
+    let goal = match config.goal {
+        Some(goal) => Program::write_output(format_goal(freqs.values().sum(), goal)),
+        None => Program::pure(()),
+    };
+    let diversity = if config.diversity {
+        Program::write_output(format_diversity(text, config))
+    } else {
+        Program::pure(())
+    };
+    let compression = if config.compress_demo {
+        Program::write_output(format_compression(text, config))
+    } else {
+        Program::pure(())
+    };
+    let fun_facts = if config.fun_facts {
+        Program::write_output(format_fun_facts(freqs))
+    } else {
+        Program::pure(())
+    };
+    let anagrams = if config.anagrams {
+        Program::write_output(format_anagrams(freqs))
+    } else {
+        Program::pure(())
+    };
+    let fuzzy_matches = match &config.fuzzy {
+        Some(word) => Program::write_output(format_fuzzy(
+            freqs,
+            word,
+            config.max_dist.unwrap_or(DEFAULT_MAX_DIST),
+        )),
+        None => Program::pure(()),
+    };
+    let sound_alikes = if config.phonetic {
+        Program::write_output(format_sound_alikes(freqs))
+    } else {
+        Program::pure(())
+    };
+    let completions = match &config.complete {
+        Some(prefix) => {
+            let trie: Trie<usize> = freqs.iter().map(|(word, &count)| (word, count)).collect();
+            Program::write_output(format_completions(&trie, prefix))
         }
+        None => Program::pure(()),
     };
+    Program::write_output(format_stats(freqs, config.speeds))
+        .then(sorted)
+        .then(by_first_letter)
+        .then(chart)
+        .then(keywords)
+        .then(key_phrases)
+        .then(summary)
+        .then(goal)
+        .then(diversity)
+        .then(compression)
+        .then(fun_facts)
+        .then(anagrams)
+        .then(sound_alikes)
+        .then(completions)
+        .then(fuzzy_matches)
+        .after_logs(trace)
+}
 
-    let content = match read_file(&config.file_path) {
-        Ok(txt) => txt,
+/// Run the mode the flags ask for: analyze once, storing the file with
+/// `--output`, then keep watching it with `--watch`; show the `--tui`
+/// dashboard; count with `--spill`; count the files below a directory with
+/// `--checkpoint`; compare the files below a directory; or write a
+/// `--format parquet` table
+///
+/// The long-running modes, `--watch`, `--tui`, `--spill` and
+/// `--checkpoint`, catch Ctrl-C to report what they have; a one-off
+/// analysis is simply killed by it.
+fn run(args: Vec<String>) -> Io<'static, i32> {
+    if let Some(command) = subcommand(&args) {
+        return init(Logger::default(), false).then(command.into_io());
+    }
+    let config = match Config::from_args(&args) {
+        Ok(config) => config,
         Err(msg) => {
-            eprintln!("{}", msg);
-            process::exit(1);
+            return init(Logger::default(), false)
+                .then(Program::log_at(Level::Error, msg).map(|_| 1).into_io());
         }
     };
+    let mode = config.mode();
+    let interruptible = matches!(
+        mode,
+        Ok(Mode::Spill | Mode::Scan | Mode::Dashboard | Mode::Watch)
+    );
+    let init = init(config.logger, interruptible);
+    // The modes below read their input when run, not when described
+    let deferred = |report: fn(&Config) -> Program<'static, i32>, config: Config| {
+        Io::new(move || report(&config).into_io().run())
+    };
+    match mode {
+        Err(msg) => init.then(Program::log_at(Level::Error, msg).map(|_| 1).into_io()),
+        Ok(Mode::Table) => init.then(deferred(parquet_report, config)),
+        Ok(Mode::Compare) => init.then(deferred(directory_report, config)),
+        Ok(Mode::Spill) => init.then(deferred(spilled_report, config)),
+        Ok(Mode::Scan) => init.then(deferred(checkpointed_report, config)),
+        Ok(Mode::Dashboard) => init.then(tui(config)),
+        // The first report is the same as without --watch
+        Ok(Mode::Watch) => init.then(Io::new(move || match program(&config).into_io().run() {
+            0 => watch(config).run(),
+            code => code,
+        })),
+        Ok(Mode::DryRun | Mode::Weblog | Mode::Report) => {
+            init.then(Io::new(move || program(&config).into_io().run()))
+        }
+    }
+}
+
+/// Install `logger` and, for the long-running modes, the Ctrl-C handler
+fn init(logger: Logger, interruptible: bool) -> Io<'static, ()> {
+    Io::new(move || {
+        let _ = logger.init();
+        if interruptible {
+            catch_interrupt();
+        }
+    })
+}
+
+/// Cancel [`INTERRUPT`] on Ctrl-C instead of terminating the process
+fn catch_interrupt() {
+    if let Err(err) = ctrlc::set_handler(|| INTERRUPT.cancel()) {
+        log::warn!("Ctrl-C will not report partial results: {}", err);
+    }
+}
+
+/// One version of the word counts in the watch history
+///
+/// Each snapshot is derived from the previous one, so versions share the
+/// entries of words whose counts did not change.
+type Snapshot = PMap<String, usize>;
 
-    let freqs = analyze_text(&content, &config);
-    display_stats(&freqs);
+/// Re-run the analysis whenever the file changes, once edits have settled
+///
+/// Polls the modification time; the debounce collapses a burst of saves
+/// into a single re-analysis. Every analysis is kept in a persistent
+/// history, browsed with the `undo` and `history N` commands on stdin.
+/// Ctrl-C prints the statistics of the latest version and exits.
+fn watch(config: Config) -> Io<'static, i32> {
+    Io::new(move || {
+        let commands = read_commands();
+        let analyze = |text: &str| analyze_text(text).run(&config).into_inner().run();
+        // Read once: the first report already checked that it can be
+        let reference = config
+            .reference
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|reference| reference_counts(&reference, &config));
+        let initial = fs::read_to_string(&config.file_path)
+            .ok()
+            .and_then(|text| Some(analyze(&extract(&text, &config).ok()?.text).0))
+            .unwrap_or_default();
+        let mut history = PersistentList::new().cons(snapshot(&Snapshot::new(), &initial));
+        // The --goal pace counts from the words there were at the start
+        let started = (Instant::now(), initial.values().sum::<usize>());
+
+        let rerun = debounce(
+            |()| {
+                // The file may briefly vanish while an editor replaces it
+                let text = fs::read_to_string(&config.file_path).ok()?;
+                let extracted = extract(&text, &config).ok()?;
+                let (freqs, trace) = analyze(&extracted.text);
+                if INTERRUPT.is_cancelled() {
+                    // Cut short by Ctrl-C; the last complete version is reported instead
+                    return None;
+                }
+                let pace = match config.goal {
+                    Some(_) => Program::write_output(format_pace(started, freqs.values().sum())),
+                    None => Program::pure(()),
+                };
+                Program::write_output(format!("\n--- {} changed ---", config.file_path))
+                    .then(sections(&extracted.text, &config))
+                    .then(render(
+                        &extracted.text,
+                        &freqs,
+                        trace,
+                        reference.as_ref(),
+                        &config,
+                    ))
+                    .then(pace)
+                    .into_io()
+                    .run();
+                Some(freqs)
+            },
+            WATCH_SETTLE,
+        );
+        let mut last_seen = modified(&config.file_path);
+        loop {
+            thread::sleep(WATCH_POLL);
+            if INTERRUPT.is_cancelled() {
+                let latest = history.head().map(to_counts).unwrap_or_default();
+                return Program::write_output(format!(
+                    "\n--- interrupted ---\n{}",
+                    format_stats(&latest, config.speeds)
+                ))
+                .map(|_| INTERRUPTED)
+                .into_io()
+                .run();
+            }
+            let seen = modified(&config.file_path);
+            if seen != last_seen {
+                last_seen = seen;
+                rerun.call(());
+            }
+            if let Some(Some(freqs)) = rerun.flush() {
+                let previous = history.head().cloned().unwrap_or_default();
+                history = history.cons(snapshot(&previous, &freqs));
+            }
+            for command in commands.try_iter() {
+                let (next, output) = history_command(command.trim(), history, config.speeds);
+                history = next;
+                Program::write_output(output).into_io().run();
+            }
+        }
+    })
+}
+
+/// The word counts `--tui` shows: the file analyzed as configured, or why
+/// it could not be
+#[cfg(feature = "tui")]
+fn dashboard_counts(config: &Config) -> Result<HashMap<String, usize>, String> {
+    let text = fs::read_to_string(&config.file_path)
+        .map_err(|err| format!("Error reading file {}: {}", config.file_path, err))?;
+    let extracted = extract(&text, config)?;
+    Ok(analyze_text(&extracted.text)
+        .run(config)
+        .into_inner()
+        .run()
+        .0)
+}
+
+/// Show the `--tui` dashboard until Esc or Ctrl-C, re-analyzing the file
+/// whenever it changes
+///
+/// The terminal is in raw mode meanwhile, so Ctrl-C arrives as a key
+/// rather than a signal. A file that cannot be read or extracted from on a
+/// change keeps the last counts on screen until the next change.
+#[cfg(feature = "tui")]
+fn tui(config: Config) -> Io<'static, i32> {
+    Io::new(move || {
+        let mut dashboard = Dashboard::new(config.file_path.clone());
+        match dashboard_counts(&config) {
+            Ok(freqs) => dashboard.update(freqs),
+            Err(msg) => {
+                log::error!("{}", msg);
+                return 1;
+            }
+        }
+        let mut terminal = ratatui::init();
+        let shown = show_dashboard(&mut terminal, &mut dashboard, &config);
+        ratatui::restore();
+        match shown {
+            Ok(()) => 0,
+            Err(err) => {
+                log::error!("Error drawing the dashboard: {}", err);
+                1
+            }
+        }
+    })
+}
+
+/// The `--tui` event loop: draw, wait up to [`WATCH_POLL`] for a key, and
+/// pick up changes to the file
+#[cfg(feature = "tui")]
+fn show_dashboard(
+    terminal: &mut DefaultTerminal,
+    dashboard: &mut Dashboard,
+    config: &Config,
+) -> std::io::Result<()> {
+    let mut last_seen = modified(&config.file_path);
+    loop {
+        terminal.draw(|frame| dashboard.draw(frame))?;
+        if event::poll(WATCH_POLL)?
+            && let Event::Key(key) = event::read()?
+            && dashboard.handle_key(key).is_break()
+        {
+            return Ok(());
+        }
+        let seen = modified(&config.file_path);
+        if seen != last_seen
+            && let Ok(freqs) = dashboard_counts(config)
+        {
+            last_seen = seen;
+            dashboard.update(freqs);
+        }
+    }
+}
+
+/// Without the `tui` feature there is no dashboard to show
+#[cfg(not(feature = "tui"))]
+fn tui(_config: Config) -> Io<'static, i32> {
+    Io::new(|| {
+        log::error!(
+            "--tui needs file_parser built with the tui feature (cargo build --features tui)"
+        );
+        1
+    })
+}
+
+/// Analyze with `--spill`: count batches of lines streamed from the file,
+/// spill the partial counts to disk, and render the report in one pass over
+/// the merged, alphabetical counts
+fn spilled_report(config: &Config) -> Program<'static, i32> {
+    match spill_analysis(config) {
+        Ok(Outcome::Complete(report)) => Program::write_output(report).map(|_| 0),
+        Ok(Outcome::Cancelled(report)) => {
+            let (warning, code) = stopped(config, "lines");
+            Program::log_at(Level::Warn, warning)
+                .then(Program::write_output(report))
+                .map(move |_| code)
+        }
+        Err(err) => {
+            let msg = format!("Error counting {} with --spill: {}", config.file_path, err);
+            Program::log_at(Level::Error, msg).map(|_| 1)
+        }
+    }
+}
+
+/// Count every file below the directory with `--checkpoint`, saving the
+/// files finished and their counts as it goes, and render the report of
+/// the totals; a run cut short can be continued with `--resume`
+fn checkpointed_report(config: &Config) -> Program<'static, i32> {
+    let Some(state) = &config.checkpoint else {
+        return Program::log_at(Level::Error, "--checkpoint requires a file").map(|_| 1);
+    };
+    match checkpointed_analysis(state, config) {
+        Ok(Outcome::Complete(report)) => Program::write_output(report).map(|_| 0),
+        Ok(Outcome::Cancelled(report)) => {
+            let (warning, code) = stopped(config, "files");
+            let saved = format!(
+                "Progress saved to {}; add --resume to continue",
+                state.display()
+            );
+            Program::log_at(Level::Warn, warning)
+                .then(Program::log_at(Level::Warn, saved))
+                .then(Program::write_output(report))
+                .map(move |_| code)
+        }
+        Err(err) => {
+            let msg = format!(
+                "Error counting the files below {} with --checkpoint: {}",
+                config.file_path, err
+            );
+            Program::log_at(Level::Error, msg).map(|_| 1)
+        }
+    }
+}
+
+/// Compare every file below the directory with `--near-dupes`,
+/// `--similarity` or `--cluster`, print the results, and store the files
+/// with `--output`
+fn directory_report(config: &Config) -> Program<'static, i32> {
+    let documents = match read_documents(config) {
+        Ok(documents) => documents,
+        Err(err) => {
+            let msg = format!(
+                "Error comparing the files below {}: {}",
+                config.file_path, err
+            );
+            return Program::log_at(Level::Error, msg).map(|_| 1);
+        }
+    };
+    let report = compare_files(&documents, config);
+    let printed = if report.is_empty() {
+        Program::pure(())
+    } else {
+        Program::write_output(report)
+    };
+    printed.then(match store(&documents, config) {
+        Ok(()) => Program::pure(0),
+        Err(msg) => Program::log_at(Level::Error, msg).map(|_| 1),
+    })
+}
+
+/// Write `--format parquet`'s table to stdout: the word counts of the
+/// file, or the TF-IDF weights of the files below the directory
+///
+/// The table is binary, so it is written straight to stdout rather than as
+/// a line of the Program's output.
+#[cfg(feature = "parquet")]
+fn parquet_report(config: &Config) -> Program<'static, i32> {
+    let table = if Path::new(&config.file_path).is_dir() {
+        read_documents(config)
+            .map_err(|err| {
+                format!(
+                    "Error reading the files below {}: {}",
+                    config.file_path, err
+                )
+            })
+            .and_then(|documents| {
+                let names: Vec<String> = documents
+                    .iter()
+                    .map(|doc| doc.path.display().to_string())
+                    .collect();
+                let counts: Vec<HashMap<String, usize>> =
+                    documents.iter().map(Document::counts).collect();
+                tf_idf_table(&names, &tf_idf(&counts)).map_err(|err| err.to_string())
+            })
+    } else {
+        read_document(PathBuf::from(&config.file_path), config)
+            .and_then(|document| frequency_table(&document.counts()).map_err(|err| err.to_string()))
+    };
+    let written = table.and_then(|bytes| {
+        std::io::stdout()
+            .write_all(&bytes)
+            .map_err(|err| format!("Error writing the table: {}", err))
+    });
+    match written {
+        Ok(()) => Program::pure(0),
+        Err(msg) => Program::log_at(Level::Error, msg).map(|_| 1),
+    }
+}
+
+/// Without the `parquet` feature there is no table to write
+#[cfg(not(feature = "parquet"))]
+fn parquet_report(_config: &Config) -> Program<'static, i32> {
+    Program::log_at(
+        Level::Error,
+        "--format parquet needs file_parser built with the parquet feature (cargo build --features parquet)",
+    )
+    .map(|_| 1)
+}
+
+/// Add `documents` to the `--output` database, if one was given
+#[cfg(feature = "sqlite")]
+fn store(documents: &[Document], config: &Config) -> Result<(), String> {
+    let Some(Output::Sqlite(path)) = &config.output else {
+        return Ok(());
+    };
+    let failed = |err: rusqlite::Error| format!("Error writing to {}: {}", path.display(), err);
+    let mut database = Database::open(path).map_err(failed)?;
+    documents
+        .iter()
+        .try_for_each(|doc| {
+            database
+                .add_document(&doc.path.to_string_lossy(), &doc.words)
+                .map(|_| ())
+        })
+        .map_err(failed)
+}
+
+/// Without the `sqlite` feature there is no database to store in
+#[cfg(not(feature = "sqlite"))]
+fn store(_documents: &[Document], config: &Config) -> Result<(), String> {
+    match config.output {
+        Some(Output::Sqlite(_)) => Err(
+            "--output sqlite: needs file_parser built with the sqlite feature (cargo build --features sqlite)"
+                .to_string(),
+        ),
+        None => Ok(()),
+    }
+}
+
+/// A file below the compared directory, or the one stored with
+/// `--output`, with its words as configured
+struct Document {
+    path: PathBuf,
+    words: Vec<String>,
+}
+
+impl Document {
+    /// How often each of the document's words occurs
+    fn counts(&self) -> HashMap<String, usize> {
+        self.words.iter().fold(HashMap::new(), |mut counts, word| {
+            *counts.entry(word.clone()).or_insert(0) += 1;
+            counts
+        })
+    }
+}
+
+/// The sections of the directory report, one per comparison requested
+fn compare_files(documents: &[Document], config: &Config) -> String {
+    let sections: Vec<String> = [
+        config.near_dupes.then(|| format_near_dupes(documents)),
+        config
+            .similarity
+            .map(|format| format_similarity(documents, format)),
+        config.cluster.map(|k| format_clusters(documents, k)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    sections.join("\n\n")
+}
+
+/// Every readable file below the directory, extracted and filtered like a
+/// single file; the others are skipped with a warning
+fn read_documents(config: &Config) -> std::io::Result<Vec<Document>> {
+    let documents = files_below(Path::new(&config.file_path))?
+        .into_iter()
+        .filter_map(|path| {
+            read_document(path, config)
+                .map_err(|err| log::warn!("Skipping {}", err))
+                .ok()
+        })
+        .collect();
+    Ok(documents)
+}
+
+/// The file at `path`, extracted and filtered as configured
+fn read_document(path: PathBuf, config: &Config) -> Result<Document, String> {
+    let words = fs::read_to_string(&path)
+        .map_err(|err| err.to_string())
+        .and_then(|text| {
+            let input = extract(&text, config)?;
+            Ok(words(&input.text, config).map(Cow::into_owned).collect())
+        });
+    match words {
+        Ok(words) => Ok(Document { path, words }),
+        Err(err) => Err(format!("{}: {}", path.display(), err)),
+    }
+}
+
+/// The `--near-dupes` section: each document shingled and reduced to a
+/// MinHash signature, then clustered
+fn format_near_dupes(documents: &[Document]) -> String {
+    let signatures: Vec<MinHash> = documents
+        .iter()
+        .map(|doc| MinHash::new(&shingles(&doc.words, SHINGLE_WORDS)))
+        .collect();
+    let clusters = near_duplicates(&signatures, NEAR_DUPLICATE);
+    let heading = format!(
+        "--- Near-duplicate files ---\nFiles compared: {}",
+        documents.len()
+    );
+    if clusters.is_empty() {
+        return format!("{}\nNo near-duplicate files found.", heading);
+    }
+    clusters.iter().fold(heading, |out, cluster| {
+        let files: Vec<String> = cluster
+            .iter()
+            .map(|&i| documents[i].path.display().to_string())
+            .collect();
+        format!("{}\n{}", out, files.join(", "))
+    })
+}
+
+/// The `--similarity` matrix of the documents' word frequencies, rounded to
+/// three decimals
+fn format_similarity(documents: &[Document], format: MatrixFormat) -> String {
+    let counts: Vec<HashMap<String, usize>> = documents.iter().map(Document::counts).collect();
+    let matrix = similarity_matrix(&counts);
+    let files: Vec<String> = documents
+        .iter()
+        .map(|doc| doc.path.display().to_string())
+        .collect();
+    let row = |similarities: &[f64]| -> Vec<String> {
+        similarities.iter().map(|x| format!("{:.3}", x)).collect()
+    };
+    match format {
+        MatrixFormat::Csv => {
+            let header = std::iter::once("file".to_string())
+                .chain(files.iter().map(|file| csv_field(file)))
+                .collect::<Vec<_>>()
+                .join(",");
+            files
+                .iter()
+                .zip(&matrix)
+                .fold(header, |out, (file, similarities)| {
+                    format!(
+                        "{}\n{},{}",
+                        out,
+                        csv_field(file),
+                        row(similarities).join(",")
+                    )
+                })
+        }
+        MatrixFormat::Json => {
+            let files: Vec<String> = files.iter().map(|file| json_string(file)).collect();
+            let rows: Vec<String> = matrix
+                .iter()
+                .map(|similarities| format!("[{}]", row(similarities).join(",")))
+                .collect();
+            format!(
+                r#"{{"files":[{}],"matrix":[{}]}}"#,
+                files.join(","),
+                rows.join(",")
+            )
+        }
+    }
+}
+
+/// The `--cluster` section: the documents in at most `k` k-means clusters,
+/// each with its files and distinguishing terms
+fn format_clusters(documents: &[Document], k: usize) -> String {
+    let counts: Vec<HashMap<String, usize>> = documents.iter().map(Document::counts).collect();
+    kmeans(&counts, k).iter().enumerate().fold(
+        format!("--- Clusters (k = {}) ---", k),
+        |out, (i, cluster)| {
+            let files: Vec<String> = cluster
+                .members
+                .iter()
+                .map(|&m| documents[m].path.display().to_string())
+                .collect();
+            let terms = match cluster.terms.as_slice() {
+                [] => "(none)".to_string(),
+                terms => terms.join(", "),
+            };
+            format!(
+                "{}\nCluster {}: {}\n  Terms: {}",
+                out,
+                i + 1,
+                files.join(", "),
+                terms
+            )
+        },
+    )
+}
+
+/// `field` as a CSV field, quoted if it contains a comma, quote or newline
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Every file below `root`, sorted, found without recursion by keeping the
+/// directories still to visit on a stack
+fn files_below(root: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut pending = vec![root.to_path_buf()];
+    let mut files = Vec::new();
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                pending.push(entry.path());
+            } else {
+                files.push(entry.path());
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Count one batch of numbered lines for `--spill`, with the configured hasher
+fn spill_batch<S: BuildHasher + Default + 'static>(
+    batch: &[(usize, String)],
+    config: &Config,
+) -> (HashMap<String, usize>, Vec<String>) {
+    let (counts, trace) = fold_map(batch, |(number, line)| {
+        count_line::<S>(*number, line).run(config)
+    })
+    .run();
+    (into_owned(counts), trace)
+}
+
+/// The report of a `--spill` analysis; traces are logged as they are made
+fn spill_analysis(config: &Config) -> std::io::Result<Outcome<String>> {
+    let dir = config.spill.as_deref().unwrap_or(Path::new("."));
+    let mut spill = Spill::new(dir, DEFAULT_RUN_ENTRIES)?;
+    let token = cancellation(config);
+    let file = fs::File::open(&config.file_path)?;
+    let lines = std::io::BufReader::new(file).lines();
+    let mut lines = if config.rejoin_hyphens || config.clean_ocr {
+        Either::Left(RejoinHyphens::new(lines))
+    } else {
+        Either::Right(lines)
+    }
+    .map(|line| match (line, config.clean_ocr) {
+        (Ok(line), true) => Ok(clean_characters(&line).into_owned()),
+        (line, _) => line,
+    })
+    .enumerate();
+    let mut cancelled = false;
+    loop {
+        let batch: Vec<(usize, String)> = lines
+            .by_ref()
+            .take(SPILL_BATCH)
+            .map(|(i, line)| line.map(|line| (i + 1, line)))
+            .collect::<std::io::Result<_>>()?;
+        if batch.is_empty() {
+            break;
+        }
+        if token.is_cancelled() {
+            cancelled = true;
+            break;
+        }
+        let (counts, trace) = match config.hasher {
+            HashAlgorithm::Sip => spill_batch::<SipBuilder>(&batch, config),
+            HashAlgorithm::Fx => spill_batch::<FxBuilder>(&batch, config),
+            HashAlgorithm::AHash => spill_batch::<AHashBuilder>(&batch, config),
+        };
+        trace.iter().for_each(|entry| log::info!("{}", entry));
+        spill.add(counts)?;
+    }
+    let totals = spill
+        .finish()?
+        .try_fold(Totals::default(), |totals, entry| {
+            entry.map(|(word, count)| totals.add(word, count, config))
+        })?;
+    let report = totals.render(config);
+    Ok(if cancelled {
+        Outcome::Cancelled(report)
+    } else {
+        Outcome::Complete(report)
+    })
+}
+
+/// The report of a `--checkpoint` scan of the files below the directory,
+/// resumed from `state` with `--resume`. The checkpoint is saved before the
+/// first file, so an unwritable `state` fails at once, then every
+/// [`CHECKPOINT_EVERY`] files and at the end. Ctrl-C and `--timeout` are
+/// checked between files; files that cannot be read are skipped and tried
+/// again on resume.
+fn checkpointed_analysis(state: &Path, config: &Config) -> std::io::Result<Outcome<String>> {
+    let mut checkpoint = if config.resume && state.exists() {
+        Checkpoint::load(state)?
+    } else {
+        Checkpoint::default()
+    };
+    checkpoint.save(state)?;
+    // Canonical paths, so that the state is recognized below the directory
+    // and a resumed run matches files however the directory was named
+    let state = fs::canonicalize(state)?;
+    let root = fs::canonicalize(&config.file_path)?;
+    let token = cancellation(config);
+    let mut cancelled = false;
+    let mut unsaved = 0;
+    for path in files_below(&root)? {
+        if checkpoint.is_done(&path) || path == state {
+            continue;
+        }
+        if token.is_cancelled() {
+            cancelled = true;
+            break;
+        }
+        match read_document(path, config) {
+            Ok(document) => checkpoint.record(&document.path, document.counts()),
+            Err(err) => log::warn!("Skipping {}", err),
+        }
+        unsaved += 1;
+        if unsaved == CHECKPOINT_EVERY {
+            checkpoint.save(&state)?;
+            unsaved = 0;
+        }
+    }
+    checkpoint.save(&state)?;
+    let mut counts: Vec<(String, usize)> = checkpoint.into_counts().into_iter().collect();
+    counts.sort_unstable();
+    let report = counts
+        .into_iter()
+        .fold(Totals::default(), |totals, (word, count)| {
+            totals.add(word, count, config)
+        })
+        .render(config);
+    Ok(if cancelled {
+        Outcome::Cancelled(report)
+    } else {
+        Outcome::Complete(report)
+    })
+}
+
+/// Running totals over the merged counts of `--spill` or `--checkpoint`,
+/// which arrive in alphabetical order; only `--sorted` keeps a line per word
+#[derive(Default)]
+struct Totals {
+    total_words: usize,
+    unique_words: usize,
+    most_common: Option<(String, usize)>,
+    sorted: String,
+    letters: BTreeMap<char, (usize, usize)>,
+    /// The most frequent words for `--chart`, most frequent first
+    top: Vec<(String, usize)>,
+}
+
+impl Totals {
+    /// Account for one word; the first of equally common words is kept
+    fn add(mut self, word: String, count: usize, config: &Config) -> Self {
+        self.total_words += count;
+        self.unique_words += 1;
+        if let Some(letter) = word.chars().next() {
+            let (total, unique) = self.letters.entry(letter).or_default();
+            *total += count;
+            *unique += 1;
+        }
+        if config.sorted {
+            self.sorted.push_str(&format!("\n{}: {}", word, count));
+        }
+        if let Some(n) = config.chart {
+            // After the words already there with the same count, which
+            // came first alphabetically
+            let at = self.top.partition_point(|&(_, kept)| kept >= count);
+            if at < n {
+                self.top.insert(at, (word.clone(), count));
+                self.top.truncate(n);
+            }
+        }
+        if self
+            .most_common
+            .as_ref()
+            .is_none_or(|&(_, most)| count > most)
+        {
+            self.most_common = Some((word, count));
+        }
+        self
+    }
+
+    /// The same sections `render` prints for in-memory counts
+    fn render(&self, config: &Config) -> String {
+        let most_common = self
+            .most_common
+            .as_ref()
+            .map(|(word, count)| (word.as_str(), *count));
+        let mut out = format_summary(
+            self.total_words,
+            self.unique_words,
+            most_common,
+            config.speeds,
+        );
+        if config.sorted {
+            out = format!("{}\n\n--- Word Frequencies ---{}", out, self.sorted);
+        }
+        if config.by_first_letter {
+            out = self.letters.iter().fold(
+                format!("{}\n\n--- By First Letter ---", out),
+                |out, (&letter, &(total, unique))| {
+                    format!("{}\n{}", out, letter_line(letter, total, unique))
+                },
+            );
+        }
+        if let Some(n) = config.chart {
+            out = format!("{}\n{}", out, format_chart(&self.top, n));
+        }
+        if let Some(goal) = config.goal {
+            out = format!("{}\n{}", out, format_goal(self.total_words, goal));
+        }
+        out
+    }
+}
+
+/// Lines typed on stdin, delivered by a background thread
+fn read_commands() -> Receiver<String> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for line in std::io::stdin().lines().map_while(Result::ok) {
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+/// Derive the next snapshot from the previous one, touching only changed words
+fn snapshot(previous: &Snapshot, freqs: &HashMap<String, usize>) -> Snapshot {
+    let removed = previous
+        .iter()
+        .filter(|(word, _)| !freqs.contains_key(*word))
+        .fold(previous.clone(), |snap, (word, _)| snap.remove(word));
+    freqs
+        .iter()
+        .filter(|&(word, count)| previous.get(word) != Some(count))
+        .fold(removed, |snap, (word, &count)| {
+            snap.insert(word.clone(), count)
+        })
+}
+
+/// Apply a watch-mode command to the history, returning the new history and
+/// what to print
+fn history_command(
+    command: &str,
+    history: PersistentList<Snapshot>,
+    speeds: Speeds,
+) -> (PersistentList<Snapshot>, String) {
+    let words: Vec<&str> = command.split_whitespace().collect();
+    match words.as_slice() {
+        ["undo"] => match history.tail().filter(|rest| !rest.is_empty()) {
+            Some(rest) => {
+                let output = format!(
+                    "Back to version {}:\n{}",
+                    rest.len(),
+                    format_stats(&to_counts(rest.head().unwrap_or(&Snapshot::new())), speeds,)
+                );
+                (rest, output)
+            }
+            None => (history, "Nothing to undo".to_string()),
+        },
+        ["history", n] => match n.parse::<usize>() {
+            Ok(n) => {
+                let output = format_history(&history, n);
+                (history, output)
+            }
+            Err(_) => (history, "Usage: history N".to_string()),
+        },
+        ["startswith", prefix] => {
+            let latest = history.head().cloned().unwrap_or_default();
+            let trie: Trie<usize> = latest.iter().map(|(word, &count)| (word, count)).collect();
+            let output = format_completions(&trie, prefix);
+            (history, output)
+        }
+        _ => (
+            history,
+            "Commands: undo, history N, startswith PREFIX".to_string(),
+        ),
+    }
+}
+
+/// Summarize the latest `n` versions, newest first, with the change in total words
+fn format_history(history: &PersistentList<Snapshot>, n: usize) -> String {
+    let totals: Vec<(usize, usize)> = history
+        .iter()
+        .take(n + 1)
+        .map(|snap| (snap.iter().map(|(_, count)| count).sum(), snap.len()))
+        .collect();
+    let lines: Vec<String> = totals
+        .iter()
+        .enumerate()
+        .take(n)
+        .map(|(i, &(total, unique))| {
+            let change = totals
+                .get(i + 1)
+                .map(|&(before, _)| format!(" ({:+})", total as i64 - before as i64))
+                .unwrap_or_default();
+            format!(
+                "version {}: {} words{}, {} unique",
+                history.len() - i,
+                total,
+                change,
+                unique
+            )
+        })
+        .collect();
+    lines.join("\n")
+}
+
+/// Copy a snapshot back into a plain map for formatting
+fn to_counts(snap: &Snapshot) -> HashMap<String, usize> {
+    snap.iter()
+        .map(|(word, &count)| (word.clone(), count))
+        .collect()
+}
+
+/// When the file was last modified, if it can be read
+fn modified(path: &str) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+fn main() {
+    Io::args().and_then(run).run().pipe(process::exit)
 }
@@ -11,6 +11,28 @@
 //!
 //! September 2025
 
+use fp::church::Numeral;
+use fp::classic::{collatz, fizzbuzz, gcd};
+use fp::combinators::{apply_n, constant, fix, flip, identity, tap};
+use fp::composition::{Compose, Pipe, Pipeline};
+use fp::currying::{curry2, curry3, uncurry2};
+use fp::either::Either;
+use fp::expr::{Expr, eval, eval_tramp, random_expr};
+use fp::iter_ext::FoldableExt;
+use fp::monads::State;
+use fp::monoid::Sum;
+use fp::optics::{expr_add, expr_const};
+use fp::parallel::map_reduce;
+use fp::persistent::PersistentList;
+use fp::random::Rng;
+use fp::sequences::{Fib, Primes, unfold};
+use fp::slice_ext::SliceFnExt;
+use fp::trampoline::Trampoline;
+use fp::transducers::{Collecting, TransducerExt, filter, map, reducer, take, transduce};
+use fp::typeclasses::{Applicative, Functor, Monad};
+use fp::zipper::{ExprZipper, ListZipper};
+use fp::{compose, pipe};
+
 /// # Pure Function
 /// A pure function always returns the same result for the same input
 /// and does not produce any side effects.
@@ -31,15 +53,15 @@ pub fn closure_example(factor: i32) -> impl Fn(i32) -> i32 {
 }
 
 /// # Map Generic
-/// Applies a transformation to each element in a slice.
+/// Applies a transformation to each element in a slice, via the
+/// `SliceFnExt` extension trait that adds `fmap` to every slice.
 pub fn map_generic<T, U, F>(nums: &[T], f: F) -> Vec<U>
 where
     F: Fn(&T) -> U,
 {
-    nums.iter().map(|x| f(x)).collect()
+    nums.fmap(f)
 }
 
-
 /// # Filter + Sum
 /// Filters positive numbers from a slice and returns their sum.
 pub fn sum_positive(nums: &[i32]) -> i32 {
@@ -58,6 +80,20 @@ pub fn factorial(n: u64) -> u64 {
     if n == 0 { 1 } else { n * factorial(n - 1) }
 }
 
+/// # Trampolined Recursion
+/// Computes the factorial with an accumulator on a trampoline, so the
+/// recursion runs in a loop instead of on the stack.
+pub fn factorial_tramp(n: u64) -> u64 {
+    fn go(n: u64, acc: u64) -> Trampoline<'static, u64> {
+        if n == 0 {
+            Trampoline::Done(acc)
+        } else {
+            Trampoline::more(move || go(n - 1, acc * n))
+        }
+    }
+    go(n, 1).run()
+}
+
 /// # Function Composition
 /// Composes two functions: (f ∘ g)(x) = f(g(x))
 pub fn compose<A, B, C, F, G>(f: F, g: G) -> impl Fn(A) -> C
@@ -84,27 +120,21 @@ where
     move |b| f(a, b)
 }
 
-/// # Enum + Pattern Matching Example
-/// Represents a some arithmetic expressions.
-#[derive(Debug)]
-pub enum Expr {
-    Const(i32),
-    Add(Box<Expr>, Box<Expr>),
-    Mul(Box<Expr>, Box<Expr>),
-    Sub(Box<Expr>, Box<Expr>),
-}
-
-/// Evaluates an arithmetic expression recursively.
-pub fn eval(expr: &Expr) -> i32 {
-    match expr {
-        Expr::Const(n) => *n,
-        Expr::Add(a, b) => eval(a) + eval(b),
-        Expr::Mul(a, b) => eval(a) * eval(b),
-        Expr::Sub(a, b) => eval(a) - eval(b),
-    }
+/// # State Monad
+/// Evaluates an expression while counting the operations performed,
+/// threading the counter through `State` instead of a `&mut` parameter.
+pub fn eval_counting(expr: &Expr) -> State<'_, usize, i32> {
+    let (l, r, op): (&Expr, &Expr, fn(i32, i32) -> i32) = match expr {
+        Expr::Const(n) => return State::pure(*n),
+        Expr::Add(l, r) => (l, r, |a, b| a + b),
+        Expr::Mul(l, r) => (l, r, |a, b| a * b),
+        Expr::Sub(l, r) => (l, r, |a, b| a - b),
+    };
+    State::modify(|n| n + 1)
+        .and_then(move |_| eval_counting(l))
+        .and_then(move |a| eval_counting(r).map(move |b| op(a, b)))
 }
 
-
 /// # Option Handling Example
 /// Represents a human being with a name.
 #[derive(Debug)]
@@ -141,22 +171,121 @@ fn main() {
     let double = closure_example(2);
     println!("closure double 7 = {}", double(7));
     
+    // Church numerals: numbers made only of functions
+    let two = Numeral::zero().succ().succ();
+    let three = two.succ();
+    println!(
+        "church 2 + 3 = {}, 2 * 3 = {}",
+        two.add(&three).to_u64(),
+        two.mul(&three).to_u64()
+    );
+
     let squared = map_generic(&[1, 2, 3], |x| x * x);
     println!("[1,2,3] squared-> {:?}", squared);
 
+    // Slice extension trait
+    let nums = [3, -1, 4, -1, 5];
+    println!(
+        "fold_left(+) = {}, zip_with(*) = {:?}, partition_by(>0) = {:?}",
+        nums.fold_left(0, |acc, x| acc + x),
+        nums.zip_with(&[2, 2, 2], |a, b| a * b),
+        nums.partition_by(|&x| x > 0)
+    );
+
     println!("sum_positive([-2, 3, 5]) = {}", sum_positive(&[-2, 3, 5]));
     println!("squares_of_positive([-1, 2, 3]) = {:?}", squares_of_positive(&[-1, 2, 3]));
 
+    let running: Vec<i32> = [1, 2, 3, 4].into_iter().scan_left(0, |acc, x| acc + x).collect();
+    println!("scan_left(+) over [1,2,3,4] = {:?}", running);
+    println!("dedup_count([a,a,b,a]) = {:?}", ["a", "a", "b", "a"].into_iter().dedup_count());
+
+    // Transducers: one pipeline, any source and sink
+    let odd_squares = || {
+        map(|x: i32| x * x)
+            .then(filter(|x: &i32| x % 2 == 1))
+            .then(take(2))
+    };
+    println!(
+        "transduce(square, odd, take 2) into Vec = {:?}, into sum = {}",
+        transduce(odd_squares(), Collecting, Vec::new(), 1..10),
+        transduce(odd_squares(), reducer(|acc: i32, x| acc + x), 0, [3, 4, 5])
+    );
+
+    // Parallel map-reduce: chunks counted on a worker pool, merged in order
+    let total = map_reduce(1..=1000u64, 100, 4, Sum);
+    println!("map_reduce(Sum, 1..=1000, 4 workers) = {}", total.0);
+
     println!("factorial(5) = {}", factorial(5));
+    println!("factorial_tramp(20) = {}", factorial_tramp(20));
+
+    // Classic exercises as reusable functions
+    println!("collatz(6) = {:?}", collatz(6).collect::<Vec<_>>());
+    println!(
+        "fizzbuzz 1..=15 = {:?}",
+        fizzbuzz().take(15).collect::<Vec<_>>()
+    );
+    println!("gcd(48, 18) = {}", gcd(48, 18));
+
+    // Hand-written iterators
+    println!(
+        "Fib first 10 = {:?}",
+        Fib::new().take(10).collect::<Vec<_>>()
+    );
+    println!(
+        "Primes first 10 = {:?}",
+        Primes::new().take(10).collect::<Vec<_>>()
+    );
+    let countdown: Vec<u32> = unfold(5, |n| (n > 0).then(|| (n, n - 1))).collect();
+    println!("unfold countdown from 5 = {:?}", countdown);
+
+    // Anonymous recursion via the fixed-point combinator
+    let fact = fix(|rec: &dyn Fn(u64) -> u64, n: u64| if n == 0 { 1 } else { n * rec(n - 1) });
+    println!("fix(factorial)(5) = {}", fact(5));
 
     let f = compose(|x| x + 7, |x| x * 5);
     println!("compose (x*2)+1 for 3 = {}", f(3));
 
+    // Variadic composition
+    let piped = pipe!(|x: i32| x + 1, |x| x * 2, |x| x - 3);
+    println!("pipe!(+1, *2, -3) for 4 = {}", piped(4));
+
+    let composed = compose!(|x: i32| x + 1, |x: i32| x * 2, |x: i32| x - 3);
+    println!("compose!(+1, *2, -3) for 4 = {}", composed(4));
+
+    // Pipe and Compose as methods
+    println!(
+        "3.pipe(+1).pipe(*2) = {}",
+        3.pipe(|x| x + 1).pipe(|x| x * 2)
+    );
+    let inc_then_double = (|x: i32| x + 1).and_then_fn(|x| x * 2);
+    println!("(+1).and_then_fn(*2) for 3 = {}", inc_then_double(3));
+
+    let pipeline = Pipeline::new(|x: i32| x * x).then(|x| x + 1).then(|x| format!("<{}>", x));
+    println!("Pipeline square.then(+1).then(wrap) for 3 = {}", pipeline.run(3));
+
     println!("curry_add(5)(7) = {}", curry_add(5)(7));
 
+    // Currying beyond two-argument addition
+    let volume = curry3(|l: u32, w: u32, h: u32| l * w * h);
+    println!("curry3(volume)(2)(3)(4) = {}", volume(2)(3)(4));
+
+    let greet_with = curry2(|greeting: &str, name: &str| format!("{}, {}!", greeting, name));
+    println!("curry2(greet)(\"Hi\")(\"Bob\") = {}", greet_with("Hi")("Bob"));
+
+    println!("uncurry2(curry_add)(5, 7) = {}", uncurry2(curry_add)(5, 7));
+
     let add10 = partial_first(|a, b| a + b, 10);
     println!("partial add10(3) = {}", add10(3));
 
+    // Point-free combinators
+    println!("identity(42) = {}", identity(42));
+    let sevens: Vec<i32> = [1, 2, 3].iter().map(constant(7)).collect();
+    println!("constant(7) over [1,2,3] = {:?}", sevens);
+    println!("flip(sub)(1, 10) = {}", flip(|a: i32, b: i32| a - b)(1, 10));
+    println!("apply_n(double, 5, 1) = {}", apply_n(|x| x * 2, 5, 1));
+    let logged = tap(|x: &i32| println!("  tapped {}", x));
+    println!("tap then square = {}", sq(logged(6)));
+
     // Enum + Pattern Matching
     let expr = Expr::Add(Box::new(Expr::Const(2)), Box::new(Expr::Const(4)));
     println!("eval(Add(Const 2, Const 4)) = {}", eval(&expr));
@@ -169,6 +298,33 @@ fn main() {
 
     println!("Expression: {:?} = {}", expr2, eval(&expr2));
 
+    println!("eval_tramp(expr2) = {}", eval_tramp(&expr2));
+
+    let random = random_expr(Rng::new(7), 3);
+    println!("random_expr(seed 7) = {:?} = {}", random, eval(&random));
+
+    let (value, ops) = eval_counting(&expr2).run(0);
+    println!("State eval_counting = {} using {} operations", value, ops);
+
+    // Zippers: focus on one part of a structure and edit it locally
+    let edited = ExprZipper::new(expr2)
+        .down_right()
+        .down_left()
+        .replace(Expr::Const(9))
+        .into_expr();
+    println!("Zipper edit 5 -> 9: {:?} = {}", edited, eval(&edited));
+
+    // Prisms: focus on one enum variant
+    let doubled = expr_const().modify(Expr::Const(21), |n| n * 2);
+    let swapped = expr_add().modify(expr, |(l, r)| (r, l));
+    println!(
+        "Prism double Const(21) = {:?}, swap Add = {:?}",
+        doubled, swapped
+    );
+
+    let list = ListZipper::from_vec(vec![1, 2, 3]).map(|z| z.right().set(20).into_vec());
+    println!("ListZipper set index 1 to 20: {:?}", list);
+
     // Option Handling
     match get_human("Alice") {
         Some(human) => println!("Created human: {:?}", human.name),
@@ -183,4 +339,22 @@ fn main() {
     // Pattern matching with Option
     greet(Some("Alice"));
     greet(None);
+
+    // Functor / Applicative / Monad
+    println!("Some(3).fmap(+1) = {:?}", Some(3).fmap(|x| x + 1));
+    println!("[1, 2].ap([+1, *10]) = {:?}", vec![1, 2].ap(vec![|x| x + 1, |x| x * 10]));
+    println!("[1, 2].bind(x -> [x, -x]) = {:?}", vec![1, 2].bind(|x| vec![x, -x]));
+    println!("Option::pure(5) = {:?}", Option::pure(5));
+
+    // Persistent list: new versions share the old one's nodes
+    let base: PersistentList<i32> = [2, 3].into_iter().collect();
+    let extended = base.cons(1);
+    println!("base = {:?}, base.cons(1) = {:?}, shares tail: {}", base, extended, extended.tail().is_some_and(|t| t.ptr_eq(&base)));
+
+    // Either: a symmetric sum type
+    let inputs: Vec<Either<i32, &str>> = vec![Either::Left(4), Either::Right("four")];
+    for input in inputs {
+        let shown = input.map_left(|n| n * 2).either(|n| format!("doubled {}", n), |s| s.to_uppercase());
+        println!("Either -> {}", shown);
+    }
 }
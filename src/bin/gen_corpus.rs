@@ -0,0 +1,68 @@
+//! Synthetic Corpus Generator
+//!
+//! Writes text with a known word distribution to stdout: words drawn from a
+//! Zipf distribution over a fixed vocabulary, reproducible from the seed.
+//! Benchmarks and tests generate large inputs with it instead of shipping
+//! them; the generation itself is `fp::corpus`.
+//!
+//! Usage: gen_corpus [--words N] [--vocabulary N] [--zipf S] [--seed N]
+//! [--line-words N]
+//!
+//! September 2025
+
+use std::io::{self, BufWriter, Write};
+use std::process;
+use std::str::FromStr;
+
+use fp::corpus::{CorpusSpec, lines};
+
+/// The value after `flag`, parsed
+fn value<T: FromStr>(flag: &str, value: Option<&String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("{} requires a value", flag))?;
+    value
+        .parse()
+        .map_err(|_| format!("Invalid {} '{}'", flag, value))
+}
+
+fn parse_args(args: &[String]) -> Result<CorpusSpec, String> {
+    let mut spec = CorpusSpec::default();
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--words" => spec.words = value(arg, iter.next())?,
+            "--vocabulary" => spec.vocabulary = value(arg, iter.next())?,
+            "--zipf" => spec.exponent = value(arg, iter.next())?,
+            "--seed" => spec.seed = value(arg, iter.next())?,
+            "--line-words" => spec.words_per_line = value(arg, iter.next())?,
+            flag => return Err(format!("Unknown flag '{}'", flag)),
+        }
+    }
+    if spec.vocabulary == 0 || spec.words_per_line == 0 {
+        return Err("--vocabulary and --line-words must be at least 1".to_string());
+    }
+    if !spec.exponent.is_finite() || spec.exponent < 0.0 {
+        return Err("--zipf must be a non-negative number".to_string());
+    }
+    Ok(spec)
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let spec = parse_args(&args).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(2)
+    });
+
+    let mut out = BufWriter::new(io::stdout().lock());
+    let written = lines(&spec)
+        .try_for_each(|line| out.write_all(line.as_bytes()))
+        .and_then(|()| out.flush());
+    // A closed pipe (`gen_corpus | head`) is not an error
+    match written {
+        Err(e) if e.kind() != io::ErrorKind::BrokenPipe => {
+            eprintln!("Cannot write corpus: {}", e);
+            process::exit(1)
+        }
+        _ => {}
+    }
+}
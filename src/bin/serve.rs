@@ -0,0 +1,230 @@
+//! Word Statistics HTTP Service
+//!
+//! Serves the analyzer over HTTP: `POST /analyze` with a plain-text body (or a
+//! `multipart/form-data` file upload) returns the JSON report, and
+//! `GET /metrics` the service's counters and latencies for Prometheus.
+//! Bodies over 16 MiB are refused with 413.
+//! Everything but the request loop is the `fp` library; `respond` is a pure
+//! function from a request and the metrics so far to a reply.
+//!
+//! Usage: serve [ADDR] [--log-format text|json] [--log-level LEVEL]
+//! (ADDR defaults to 127.0.0.1:8080)
+//!
+//! September 2025
+
+use std::io::Read;
+use std::process;
+use std::time::Instant;
+
+use fp::analysis::{DEFAULT_TOP, Report, json_string, word_counts};
+use fp::logging::Logger;
+use fp::metrics::{self, Metrics};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+const DEFAULT_ADDR: &str = "127.0.0.1:8080";
+
+/// The largest body read, in bytes; a larger upload is answered with 413
+/// rather than held in memory
+const MAX_BODY: u64 = 16 * 1024 * 1024;
+
+/// The answer to a request
+struct Reply {
+    status: u16,
+    content_type: &'static str,
+    body: String,
+    /// The bytes of text analyzed and the words counted in them, if any
+    analyzed: Option<(usize, usize)>,
+}
+
+impl Reply {
+    fn json(status: u16, body: String) -> Self {
+        Reply {
+            status,
+            content_type: "application/json",
+            body,
+            analyzed: None,
+        }
+    }
+}
+
+/// The path of `url` as the metrics label it: the paths served, or `other`,
+/// so that unknown paths cannot grow the label set without bound
+fn route(url: &str) -> &'static str {
+    match url.split_once('?').map_or(url, |(path, _)| path) {
+        "/analyze" => "/analyze",
+        "/metrics" => "/metrics",
+        _ => "other",
+    }
+}
+
+/// Answer a request, given the metrics of the requests before it
+fn respond(
+    method: &Method,
+    url: &str,
+    content_type: Option<&str>,
+    body: &[u8],
+    metrics: &Metrics,
+) -> Reply {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    match (method, path) {
+        (Method::Post, "/analyze") => analyze(query, content_type, body),
+        (_, "/analyze") => Reply::json(405, error_json("use POST /analyze")),
+        (Method::Get, "/metrics") => Reply {
+            status: 200,
+            content_type: metrics::CONTENT_TYPE,
+            body: metrics.render(),
+            analyzed: None,
+        },
+        (_, "/metrics") => Reply::json(405, error_json("use GET /metrics")),
+        _ => Reply::json(404, error_json("not found")),
+    }
+}
+
+/// Analyze the uploaded text, keeping `?top=N` most frequent words
+fn analyze(query: &str, content_type: Option<&str>, body: &[u8]) -> Reply {
+    let top = query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("top="))
+        .map_or(Ok(DEFAULT_TOP), |n| n.parse::<usize>());
+    let text = match content_type.and_then(boundary) {
+        Some(boundary) => first_part(body, &boundary),
+        None => Some(body),
+    }
+    .map(String::from_utf8_lossy);
+
+    match (top, text) {
+        (Err(_), _) => Reply::json(400, error_json("top must be a number")),
+        (_, None) => Reply::json(400, error_json("no file found in multipart body")),
+        (Ok(top), Some(text)) => {
+            let counts = word_counts(&text);
+            Reply {
+                analyzed: Some((text.len(), counts.values().sum())),
+                ..Reply::json(200, Report::new(&counts, top).to_json())
+            }
+        }
+    }
+}
+
+/// The boundary of a `multipart/form-data` content type
+fn boundary(content_type: &str) -> Option<String> {
+    let (mime, params) = content_type.split_once(';')?;
+    (mime.trim() == "multipart/form-data").then_some(())?;
+    params
+        .split(';')
+        .find_map(|param| param.trim().strip_prefix("boundary="))
+        .map(|b| b.trim_matches('"').to_string())
+}
+
+/// The content of the first part of a multipart body
+fn first_part<'a>(body: &'a [u8], boundary: &str) -> Option<&'a [u8]> {
+    let delimiter = format!("--{}", boundary);
+    let start = find(body, delimiter.as_bytes())? + delimiter.len();
+    let part = &body[start..];
+    let content = &part[find(part, b"\r\n\r\n")? + 4..];
+    let end = find(content, format!("\r\n{}", delimiter).as_bytes())?;
+    Some(&content[..end])
+}
+
+/// Position of the first occurrence of `needle` in `haystack`
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+fn error_json(message: &str) -> String {
+    format!(r#"{{"error":{}}}"#, json_string(message))
+}
+
+/// Read a request's body, answer it, send the response and record it in
+/// `metrics`
+fn handle(mut request: Request, metrics: &mut Metrics) -> std::io::Result<()> {
+    let started = Instant::now();
+    let mut body = Vec::new();
+    request
+        .as_reader()
+        .take(MAX_BODY + 1)
+        .read_to_end(&mut body)?;
+    let content_type = request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("Content-Type"))
+        .map(|h| h.value.as_str().to_string());
+
+    let reply = if body.len() as u64 > MAX_BODY {
+        let message = format!("body larger than {} bytes", MAX_BODY);
+        Reply::json(413, error_json(&message))
+    } else {
+        respond(
+            request.method(),
+            request.url(),
+            content_type.as_deref(),
+            &body,
+            metrics,
+        )
+    };
+    log::info!("{} {} -> {}", request.method(), request.url(), reply.status);
+    if let Some((bytes, tokens)) = reply.analyzed {
+        metrics.record_analysis(bytes, tokens);
+    }
+    let path = route(request.url());
+    let header =
+        Header::from_bytes("Content-Type", reply.content_type).expect("static header is valid");
+    let sent = request.respond(
+        Response::from_string(reply.body)
+            .with_status_code(reply.status)
+            .with_header(header),
+    );
+    metrics.record_request(path, reply.status, started.elapsed().as_secs_f64());
+    sent
+}
+
+/// Parse `[ADDR] [--log-format F] [--log-level L]`
+fn parse_args(args: &[String]) -> Result<(String, Logger), String> {
+    let mut addr = DEFAULT_ADDR.to_string();
+    let mut logger = Logger::default();
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--log-format" => {
+                logger.format = iter
+                    .next()
+                    .ok_or("--log-format requires text or json")?
+                    .parse()?
+            }
+            "--log-level" => {
+                let level = iter.next().ok_or("--log-level requires a level")?;
+                logger.level = level
+                    .parse()
+                    .map_err(|_| format!("Invalid --log-level '{}'", level))?
+            }
+            flag if flag.starts_with("--") => return Err(format!("Unknown flag '{}'", flag)),
+            _ => addr = arg.clone(),
+        }
+    }
+    Ok((addr, logger))
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let (addr, logger) = parse_args(&args).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(2)
+    });
+    logger.init().expect("no logger is installed yet");
+
+    let server = Server::http(&addr).unwrap_or_else(|e| {
+        log::error!("Cannot listen on {}: {}", addr, e);
+        process::exit(1)
+    });
+    log::info!("Listening on http://{}/analyze", addr);
+
+    server
+        .incoming_requests()
+        .fold(Metrics::new(), |mut metrics, request| {
+            if let Err(e) = handle(request, &mut metrics) {
+                log::error!("Failed to answer request: {}", e);
+            }
+            metrics
+        });
+}
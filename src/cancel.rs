@@ -0,0 +1,196 @@
+//! Cancellation
+//!
+//! A [`CancellationToken`] lets long-running work stop early without being
+//! killed. It is cancelled explicitly (from another thread, a signal
+//! handler or a UI) or by a deadline. The work checks it between steps and
+//! returns what it has so far as an [`Outcome`], so partial results are
+//! never lost and the caller knows that they are partial.
+//!
+//! ```
+//! use fp::cancel::{CancellationToken, Outcome};
+//!
+//! let token = CancellationToken::new();
+//! let sum = token.fold(1..=4, 0, |sum, n| {
+//!     if n == 2 {
+//!         token.cancel();
+//!     }
+//!     sum + n
+//! });
+//! assert_eq!(sum, Outcome::Cancelled(3));
+//! assert_eq!(CancellationToken::new().fold(1..=4, 0, |sum, n| sum + n), Outcome::Complete(10));
+//! ```
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Shared flag telling work to stop; clones observe the same cancellation
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+    deadline: Option<Instant>,
+}
+
+impl CancellationToken {
+    /// A token that is only cancelled by [`cancel`](Self::cancel)
+    ///
+    /// ```
+    /// use fp::cancel::CancellationToken;
+    ///
+    /// let token = CancellationToken::new();
+    /// assert!(!token.is_cancelled());
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A token that also cancels itself `timeout` from now; a timeout too
+    /// far off for the clock to represent never expires
+    ///
+    /// ```
+    /// use fp::cancel::CancellationToken;
+    /// use std::time::Duration;
+    ///
+    /// assert!(CancellationToken::with_timeout(Duration::ZERO).is_cancelled());
+    /// assert!(!CancellationToken::with_timeout(Duration::from_secs(60)).is_cancelled());
+    /// assert!(!CancellationToken::with_timeout(Duration::MAX).is_cancelled());
+    /// ```
+    pub fn with_timeout(timeout: Duration) -> Self {
+        CancellationToken {
+            cancelled: Arc::default(),
+            deadline: Instant::now().checked_add(timeout),
+        }
+    }
+
+    /// A token cancelled along with this one, or on its own `timeout` from
+    /// now, whichever comes first; like [`with_timeout`](Self::with_timeout),
+    /// a timeout beyond the clock's range adds no deadline
+    ///
+    /// ```
+    /// use fp::cancel::CancellationToken;
+    /// use std::time::Duration;
+    ///
+    /// let interrupt = CancellationToken::new();
+    /// let limited = interrupt.limited_to(Duration::from_secs(60));
+    /// assert!(!limited.is_cancelled());
+    /// interrupt.cancel();
+    /// assert!(limited.is_cancelled());
+    /// ```
+    pub fn limited_to(&self, timeout: Duration) -> Self {
+        let deadline = match (self.deadline, Instant::now().checked_add(timeout)) {
+            (Some(own), Some(deadline)) => Some(own.min(deadline)),
+            (own, deadline) => own.or(deadline),
+        };
+        CancellationToken {
+            cancelled: Arc::clone(&self.cancelled),
+            deadline,
+        }
+    }
+
+    /// Ask every holder of this token (and its clones) to stop
+    ///
+    /// ```
+    /// use fp::cancel::CancellationToken;
+    ///
+    /// let token = CancellationToken::new();
+    /// let worker = token.clone();
+    /// std::thread::spawn(move || token.cancel()).join().unwrap();
+    /// assert!(worker.is_cancelled());
+    /// ```
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether work should stop: cancelled, or past the deadline
+    ///
+    /// ```
+    /// use fp::cancel::CancellationToken;
+    /// use std::time::Duration;
+    ///
+    /// let token = CancellationToken::with_timeout(Duration::from_millis(10));
+    /// assert!(!token.is_cancelled());
+    /// std::thread::sleep(Duration::from_millis(20));
+    /// assert!(token.is_cancelled(), "past the deadline");
+    /// ```
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+            || self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Fold `items` until the token is cancelled, checked before each item.
+    /// The items already folded are kept in [`Outcome::Cancelled`].
+    ///
+    /// ```
+    /// use fp::cancel::{CancellationToken, Outcome};
+    /// use std::time::Duration;
+    ///
+    /// let expired = CancellationToken::with_timeout(Duration::ZERO);
+    /// assert_eq!(expired.fold(1..=4, 0, |sum, n| sum + n), Outcome::Cancelled(0));
+    /// ```
+    pub fn fold<I, B, F>(&self, items: I, init: B, mut f: F) -> Outcome<B>
+    where
+        I: IntoIterator,
+        F: FnMut(B, I::Item) -> B,
+    {
+        let mut acc = init;
+        for item in items {
+            if self.is_cancelled() {
+                return Outcome::Cancelled(acc);
+            }
+            acc = f(acc, item);
+        }
+        Outcome::Complete(acc)
+    }
+}
+
+/// The result of cancellable work: complete, or what was done before it
+/// was cancelled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome<T> {
+    Complete(T),
+    Cancelled(T),
+}
+
+impl<T> Outcome<T> {
+    /// Whether the work stopped early
+    ///
+    /// ```
+    /// use fp::cancel::Outcome;
+    ///
+    /// assert!(Outcome::Cancelled(1).is_cancelled());
+    /// assert!(!Outcome::Complete(3).is_cancelled());
+    /// ```
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self, Outcome::Cancelled(_))
+    }
+
+    /// The result, complete or partial
+    ///
+    /// ```
+    /// use fp::cancel::Outcome;
+    ///
+    /// assert_eq!(Outcome::Complete(3).into_inner(), 3);
+    /// assert_eq!(Outcome::Cancelled(1).into_inner(), 1);
+    /// ```
+    pub fn into_inner(self) -> T {
+        match self {
+            Outcome::Complete(value) | Outcome::Cancelled(value) => value,
+        }
+    }
+
+    /// Transform the result, keeping the status
+    ///
+    /// ```
+    /// use fp::cancel::Outcome;
+    ///
+    /// assert_eq!(Outcome::Cancelled(2).map(|n| n * 10), Outcome::Cancelled(20));
+    /// ```
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Outcome<U> {
+        match self {
+            Outcome::Complete(value) => Outcome::Complete(f(value)),
+            Outcome::Cancelled(value) => Outcome::Cancelled(f(value)),
+        }
+    }
+}
@@ -0,0 +1,217 @@
+//! Checkpoints for Long Scans
+//!
+//! A [`Checkpoint`] records which files a scan has finished and the merged
+//! counts of those files. Saved every few files, it lets a scan that crashed
+//! or was interrupted resume where it left off: finished files are skipped
+//! and their counts are already in the totals. A file cut short is not
+//! recorded, so it is counted again from the start.
+//!
+//! The file format is plain text: a version line, then an `F` line per
+//! finished file and a `W` line per word with its count. Words never contain
+//! whitespace; paths must not contain newlines. Saving writes a temporary
+//! file and renames it over the old checkpoint, so a crash while saving
+//! leaves the previous checkpoint intact.
+//!
+//! ```
+//! use fp::analysis::word_counts;
+//! use fp::checkpoint::Checkpoint;
+//! use std::path::Path;
+//!
+//! let mut checkpoint = Checkpoint::default();
+//! checkpoint.record(Path::new("a.txt"), word_counts("fold map fold"));
+//!
+//! let state = std::env::temp_dir().join("fp_checkpoint_doc.state");
+//! checkpoint.save(&state).unwrap();
+//! let resumed = Checkpoint::load(&state).unwrap();
+//! assert!(resumed.is_done(Path::new("a.txt")));
+//! assert_eq!(resumed.counts()["fold"], 2);
+//! assert_eq!(resumed, checkpoint);
+//! # std::fs::remove_file(&state).unwrap();
+//! ```
+
+use crate::monoid::Semigroup;
+use std::collections::{BTreeSet, HashMap};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// First line of every checkpoint file
+const HEADER: &str = "fp-checkpoint 1";
+
+/// Finished files and the merged counts of their words
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Checkpoint {
+    done: BTreeSet<PathBuf>,
+    counts: HashMap<String, usize>,
+}
+
+impl Checkpoint {
+    /// Mark `path` as finished and merge its counts into the totals
+    ///
+    /// ```
+    /// use fp::analysis::word_counts;
+    /// use fp::checkpoint::Checkpoint;
+    /// use std::path::Path;
+    ///
+    /// let mut checkpoint = Checkpoint::default();
+    /// checkpoint.record(Path::new("a.txt"), word_counts("fold map"));
+    /// checkpoint.record(Path::new("b.txt"), word_counts("fold"));
+    /// assert_eq!(checkpoint.counts()["fold"], 2);
+    /// assert_eq!(checkpoint.files(), 2);
+    /// ```
+    pub fn record(&mut self, path: &Path, counts: HashMap<String, usize>) {
+        self.done.insert(path.to_path_buf());
+        self.counts = std::mem::take(&mut self.counts).combine(counts);
+    }
+
+    /// Whether `path` was finished before
+    ///
+    /// ```
+    /// use fp::checkpoint::Checkpoint;
+    /// use std::collections::HashMap;
+    /// use std::path::Path;
+    ///
+    /// let mut checkpoint = Checkpoint::default();
+    /// checkpoint.record(Path::new("a.txt"), HashMap::new());
+    /// assert!(checkpoint.is_done(Path::new("a.txt")));
+    /// assert!(!checkpoint.is_done(Path::new("b.txt")));
+    /// ```
+    pub fn is_done(&self, path: &Path) -> bool {
+        self.done.contains(path)
+    }
+
+    /// How many files are finished
+    ///
+    /// ```
+    /// use fp::checkpoint::Checkpoint;
+    /// use std::collections::HashMap;
+    /// use std::path::Path;
+    ///
+    /// let mut checkpoint = Checkpoint::default();
+    /// checkpoint.record(Path::new("a.txt"), HashMap::new());
+    /// checkpoint.record(Path::new("b.txt"), HashMap::new());
+    /// assert_eq!(checkpoint.files(), 2);
+    /// ```
+    pub fn files(&self) -> usize {
+        self.done.len()
+    }
+
+    /// The merged counts of the finished files
+    ///
+    /// ```
+    /// use fp::analysis::word_counts;
+    /// use fp::checkpoint::Checkpoint;
+    /// use std::path::Path;
+    ///
+    /// let mut checkpoint = Checkpoint::default();
+    /// assert!(checkpoint.counts().is_empty());
+    /// checkpoint.record(Path::new("a.txt"), word_counts("fold map fold"));
+    /// assert_eq!(checkpoint.counts()["fold"], 2);
+    /// ```
+    pub fn counts(&self) -> &HashMap<String, usize> {
+        &self.counts
+    }
+
+    /// The merged counts, consuming the checkpoint
+    ///
+    /// ```
+    /// use fp::analysis::word_counts;
+    /// use fp::checkpoint::Checkpoint;
+    /// use std::path::Path;
+    ///
+    /// let mut checkpoint = Checkpoint::default();
+    /// checkpoint.record(Path::new("a.txt"), word_counts("fold map"));
+    /// checkpoint.record(Path::new("b.txt"), word_counts("fold"));
+    /// assert_eq!(checkpoint.into_counts()["fold"], 2);
+    /// ```
+    pub fn into_counts(self) -> HashMap<String, usize> {
+        self.counts
+    }
+
+    /// Write the checkpoint to `path`, replacing any previous one atomically
+    ///
+    /// ```
+    /// use fp::checkpoint::Checkpoint;
+    /// use std::collections::HashMap;
+    /// use std::path::Path;
+    ///
+    /// let state = std::env::temp_dir().join("fp_checkpoint_save.state");
+    /// let mut checkpoint = Checkpoint::default();
+    /// checkpoint.record(Path::new("a.txt"), HashMap::from([("fold".to_string(), 2)]));
+    /// checkpoint.save(&state).unwrap();
+    /// let saved = std::fs::read_to_string(&state).unwrap();
+    /// assert_eq!(saved, "fp-checkpoint 1\nF a.txt\nW 2 fold\n");
+    ///
+    /// // A path that would break the line format is refused
+    /// let mut bad = Checkpoint::default();
+    /// bad.record(Path::new("a\nb.txt"), HashMap::new());
+    /// assert!(bad.save(&state).is_err());
+    /// # std::fs::remove_file(&state).unwrap();
+    /// ```
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut partial = path.as_os_str().to_owned();
+        partial.push(".partial");
+        let partial = PathBuf::from(partial);
+        let mut out = BufWriter::new(File::create(&partial)?);
+        writeln!(out, "{}", HEADER)?;
+        for file in &self.done {
+            let shown = file.to_str().filter(|shown| !shown.contains('\n'));
+            let shown = shown.ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("cannot checkpoint the path {}", file.display()),
+                )
+            })?;
+            writeln!(out, "F {}", shown)?;
+        }
+        for (word, count) in &self.counts {
+            writeln!(out, "W {} {}", count, word)?;
+        }
+        out.flush()?;
+        drop(out);
+        fs::rename(&partial, path)
+    }
+
+    /// Read a checkpoint written by [`save`](Self::save)
+    ///
+    /// ```
+    /// use fp::checkpoint::Checkpoint;
+    ///
+    /// let state = std::env::temp_dir().join("fp_checkpoint_load.state");
+    /// std::fs::write(&state, "fp-checkpoint 1\nF a.txt\nW 3 fold\n").unwrap();
+    /// let checkpoint = Checkpoint::load(&state).unwrap();
+    /// assert!(checkpoint.is_done(std::path::Path::new("a.txt")));
+    /// assert_eq!(checkpoint.counts()["fold"], 3);
+    ///
+    /// std::fs::write(&state, "not a checkpoint\n").unwrap();
+    /// assert!(Checkpoint::load(&state).is_err());
+    /// # std::fs::remove_file(&state).unwrap();
+    /// ```
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let invalid = |line: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("bad checkpoint line '{}'", line),
+            )
+        };
+        let mut lines = BufReader::new(File::open(path)?).lines();
+        match lines.next().transpose()? {
+            Some(header) if header == HEADER => {}
+            other => return Err(invalid(other.as_deref().unwrap_or(""))),
+        }
+        lines.try_fold(Checkpoint::default(), |mut checkpoint, line| {
+            let line = line?;
+            if let Some(file) = line.strip_prefix("F ") {
+                checkpoint.done.insert(PathBuf::from(file));
+            } else {
+                let (count, word) = line
+                    .strip_prefix("W ")
+                    .and_then(|entry| entry.split_once(' '))
+                    .ok_or_else(|| invalid(&line))?;
+                let count = count.parse().map_err(|_| invalid(&line))?;
+                checkpoint.counts.insert(word.to_string(), count);
+            }
+            Ok(checkpoint)
+        })
+    }
+}
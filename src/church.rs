@@ -0,0 +1,263 @@
+//! Church encodings: numbers and booleans made only of functions.
+//!
+//! In the lambda calculus there are no built-in numbers or booleans, only
+//! functions. The Church numeral for `n` is "apply `f` to `x`, `n` times",
+//! and a Church boolean is "choose the first or the second of two values".
+//! Here both are closures behind `Rc`, so the encodings can be built,
+//! combined and converted back to ordinary Rust values.
+//!
+//! ```
+//! use fp::church::{Boolean, Numeral};
+//!
+//! let two = Numeral::zero().succ().succ();
+//! let three = two.succ();
+//! assert_eq!(two.add(&three).to_u64(), 5);
+//! assert_eq!(two.mul(&three).to_u64(), 6);
+//! assert_eq!(Numeral::from_u64(7).to_u64(), 7);
+//!
+//! // The numeral decides how often a function runs, whatever it does
+//! let laugh = Numeral::<String>::from_u64(3).apply(std::rc::Rc::new(|s| s + "ha"));
+//! assert_eq!(laugh(String::new()), "hahaha");
+//!
+//! let yes = Boolean::<bool>::truth();
+//! let no = Boolean::falsity();
+//! assert!(yes.and(&no.not()).to_bool());
+//! assert!(!yes.and(&no).to_bool());
+//! assert!(no.or(&yes).to_bool());
+//! assert_eq!(Boolean::truth().if_then_else("then", "else"), "then");
+//!
+//! assert!(Numeral::zero().is_zero().to_bool());
+//! assert!(!Numeral::from_u64(3).is_zero().to_bool());
+//! ```
+
+use std::rc::Rc;
+
+/// A function from a type to itself.
+pub type Endo<T> = Rc<dyn Fn(T) -> T>;
+
+/// A Church numeral: `n` is the function `f -> f∘f∘...∘f` (`n` times).
+pub struct Numeral<T>(Rc<dyn Fn(Endo<T>) -> Endo<T>>);
+
+impl<T> Clone for Numeral<T> {
+    fn clone(&self) -> Self {
+        Numeral(Rc::clone(&self.0))
+    }
+}
+
+impl<T: 'static> Numeral<T> {
+    /// Zero: applies `f` no times.
+    ///
+    /// ```
+    /// use fp::church::Numeral;
+    ///
+    /// assert_eq!(Numeral::zero().to_u64(), 0);
+    /// ```
+    pub fn zero() -> Self {
+        Numeral(Rc::new(|_f| Rc::new(|x| x)))
+    }
+
+    /// The numeral `n`, built from zero with `n` successors.
+    ///
+    /// ```
+    /// use fp::church::Numeral;
+    ///
+    /// assert_eq!(Numeral::from_u64(4).to_u64(), 4);
+    /// ```
+    pub fn from_u64(n: u64) -> Self {
+        (0..n).fold(Numeral::zero(), |acc, _| acc.succ())
+    }
+
+    /// Applies the numeral to `f`, giving `f` composed with itself `n` times.
+    ///
+    /// ```
+    /// use fp::church::Numeral;
+    /// use std::rc::Rc;
+    ///
+    /// let double_thrice = Numeral::from_u64(3).apply(Rc::new(|x: u32| x * 2));
+    /// assert_eq!(double_thrice(1), 8);
+    /// ```
+    pub fn apply(&self, f: Endo<T>) -> Endo<T> {
+        (self.0)(f)
+    }
+
+    /// Successor: `λn.λf.λx. f (n f x)`.
+    ///
+    /// ```
+    /// use fp::church::Numeral;
+    ///
+    /// assert_eq!(Numeral::from_u64(2).succ().to_u64(), 3);
+    /// ```
+    pub fn succ(&self) -> Self {
+        let n = self.clone();
+        Numeral(Rc::new(move |f: Endo<T>| {
+            let nf = n.apply(Rc::clone(&f));
+            Rc::new(move |x| f(nf(x)))
+        }))
+    }
+
+    /// Addition: `λm.λn.λf.λx. m f (n f x)`.
+    ///
+    /// ```
+    /// use fp::church::Numeral;
+    ///
+    /// let sum = Numeral::from_u64(2).add(&Numeral::from_u64(5));
+    /// assert_eq!(sum.to_u64(), 7);
+    /// ```
+    pub fn add(&self, other: &Self) -> Self {
+        let (m, n) = (self.clone(), other.clone());
+        Numeral(Rc::new(move |f: Endo<T>| {
+            let (mf, nf) = (m.apply(Rc::clone(&f)), n.apply(f));
+            Rc::new(move |x| mf(nf(x)))
+        }))
+    }
+
+    /// Multiplication: `λm.λn.λf. m (n f)`.
+    ///
+    /// ```
+    /// use fp::church::Numeral;
+    ///
+    /// let product = Numeral::from_u64(4).mul(&Numeral::from_u64(5));
+    /// assert_eq!(product.to_u64(), 20);
+    /// assert_eq!(product.mul(&Numeral::zero()).to_u64(), 0);
+    /// ```
+    pub fn mul(&self, other: &Self) -> Self {
+        let (m, n) = (self.clone(), other.clone());
+        Numeral(Rc::new(move |f| m.apply(n.apply(f))))
+    }
+}
+
+impl Numeral<u64> {
+    /// Converts back by counting: applies `+1` to `0`.
+    ///
+    /// ```
+    /// use fp::church::Numeral;
+    ///
+    /// assert_eq!(Numeral::zero().succ().to_u64(), 1);
+    /// ```
+    pub fn to_u64(&self) -> u64 {
+        self.apply(Rc::new(|x| x + 1))(0)
+    }
+}
+
+impl<T: 'static> Numeral<Boolean<T>> {
+    /// `true` for zero: `λn. n (λ_. false) true`.
+    ///
+    /// ```
+    /// use fp::church::Numeral;
+    ///
+    /// assert!(Numeral::zero().is_zero().to_bool());
+    /// assert!(!Numeral::zero().succ().is_zero().to_bool());
+    /// ```
+    pub fn is_zero(&self) -> Boolean<T> {
+        self.apply(Rc::new(|_| Boolean::falsity()))(Boolean::truth())
+    }
+}
+
+/// A Church boolean: chooses one of two values.
+pub struct Boolean<T>(Rc<dyn Fn(T, T) -> T>);
+
+impl<T> Clone for Boolean<T> {
+    fn clone(&self) -> Self {
+        Boolean(Rc::clone(&self.0))
+    }
+}
+
+impl<T: 'static> Boolean<T> {
+    /// True: `λa.λb. a`.
+    ///
+    /// ```
+    /// use fp::church::Boolean;
+    ///
+    /// assert_eq!(Boolean::truth().if_then_else(1, 2), 1);
+    /// ```
+    pub fn truth() -> Self {
+        Boolean(Rc::new(|a, _| a))
+    }
+
+    /// False: `λa.λb. b`.
+    ///
+    /// ```
+    /// use fp::church::Boolean;
+    ///
+    /// assert_eq!(Boolean::falsity().if_then_else(1, 2), 2);
+    /// ```
+    pub fn falsity() -> Self {
+        Boolean(Rc::new(|_, b| b))
+    }
+
+    /// Picks `then` if true, `otherwise` if false.
+    ///
+    /// ```
+    /// use fp::church::Boolean;
+    ///
+    /// let answer = |b: Boolean<&'static str>| b.if_then_else("yes", "no");
+    /// assert_eq!(answer(Boolean::truth()), "yes");
+    /// assert_eq!(answer(Boolean::falsity()), "no");
+    /// ```
+    pub fn if_then_else(&self, then: T, otherwise: T) -> T {
+        (self.0)(then, otherwise)
+    }
+
+    /// Negation: swaps the choice.
+    ///
+    /// ```
+    /// use fp::church::Boolean;
+    ///
+    /// assert!(!Boolean::truth().not().to_bool());
+    /// assert!(Boolean::falsity().not().to_bool());
+    /// ```
+    pub fn not(&self) -> Self {
+        let b = self.clone();
+        Boolean(Rc::new(move |x, y| b.if_then_else(y, x)))
+    }
+}
+
+impl<T: Clone + 'static> Boolean<T> {
+    /// Conjunction: `λp.λq.λx.λy. p (q x y) y`.
+    ///
+    /// ```
+    /// use fp::church::Boolean;
+    ///
+    /// let (t, f) = (Boolean::truth(), Boolean::falsity());
+    /// assert!(t.and(&t).to_bool());
+    /// assert!(!t.and(&f).to_bool());
+    /// assert!(!f.and(&t).to_bool());
+    /// ```
+    pub fn and(&self, other: &Self) -> Self {
+        let (p, q) = (self.clone(), other.clone());
+        Boolean(Rc::new(move |x, y: T| {
+            p.if_then_else(q.if_then_else(x, y.clone()), y)
+        }))
+    }
+
+    /// Disjunction: `λp.λq.λx.λy. p x (q x y)`.
+    ///
+    /// ```
+    /// use fp::church::Boolean;
+    ///
+    /// let (t, f) = (Boolean::truth(), Boolean::falsity());
+    /// assert!(f.or(&t).to_bool());
+    /// assert!(t.or(&f).to_bool());
+    /// assert!(!f.or(&f).to_bool());
+    /// ```
+    pub fn or(&self, other: &Self) -> Self {
+        let (p, q) = (self.clone(), other.clone());
+        Boolean(Rc::new(move |x: T, y| {
+            p.if_then_else(x.clone(), q.if_then_else(x, y))
+        }))
+    }
+}
+
+impl Boolean<bool> {
+    /// Converts back by choosing between `true` and `false`.
+    ///
+    /// ```
+    /// use fp::church::Boolean;
+    ///
+    /// assert!(Boolean::truth().to_bool());
+    /// assert!(!Boolean::falsity().to_bool());
+    /// ```
+    pub fn to_bool(&self) -> bool {
+        self.if_then_else(true, false)
+    }
+}
@@ -0,0 +1,92 @@
+//! Classic exercises as reusable functions.
+//!
+//! Collatz, FizzBuzz and GCD are usually written as loops that print. Here
+//! each is a pure function (or a lazy iterator) driven by pattern matching,
+//! so the results can be tested, combined and reused.
+
+/// # Collatz Sequence
+/// The Collatz sequence starting at `n`: halve even numbers, map odd `k` to
+/// `3k + 1`, and stop after reaching 1. Starting at 0 yields nothing.
+///
+/// ```
+/// use fp::classic::collatz;
+///
+/// assert_eq!(collatz(6).collect::<Vec<_>>(), [6, 3, 10, 5, 16, 8, 4, 2, 1]);
+/// assert_eq!(collatz(27).count(), 112);
+/// assert_eq!(collatz(0).next(), None);
+/// ```
+pub fn collatz(n: u64) -> Collatz {
+    Collatz {
+        next: (n != 0).then_some(n),
+    }
+}
+
+/// Iterator returned by [`collatz`].
+#[derive(Debug, Clone)]
+pub struct Collatz {
+    next: Option<u64>,
+}
+
+impl Iterator for Collatz {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        let current = self.next?;
+        self.next = match current {
+            1 => None,
+            n if n % 2 == 0 => Some(n / 2),
+            n => Some(3 * n + 1),
+        };
+        Some(current)
+    }
+}
+
+/// # FizzBuzz
+/// The FizzBuzz word for a single number.
+///
+/// ```
+/// use fp::classic::fizzbuzz_word;
+///
+/// assert_eq!(fizzbuzz_word(9), "Fizz");
+/// assert_eq!(fizzbuzz_word(10), "Buzz");
+/// assert_eq!(fizzbuzz_word(30), "FizzBuzz");
+/// assert_eq!(fizzbuzz_word(7), "7");
+/// ```
+pub fn fizzbuzz_word(n: u64) -> String {
+    match (n % 3, n % 5) {
+        (0, 0) => "FizzBuzz".to_string(),
+        (0, _) => "Fizz".to_string(),
+        (_, 0) => "Buzz".to_string(),
+        _ => n.to_string(),
+    }
+}
+
+/// An endless, lazy FizzBuzz stream starting at 1.
+///
+/// ```
+/// use fp::classic::fizzbuzz;
+///
+/// let first: Vec<String> = fizzbuzz().take(5).collect();
+/// assert_eq!(first, ["1", "2", "Fizz", "4", "Buzz"]);
+/// assert_eq!(fizzbuzz().nth(14).unwrap(), "FizzBuzz");
+/// ```
+pub fn fizzbuzz() -> impl Iterator<Item = String> {
+    (1..).map(fizzbuzz_word)
+}
+
+/// # Greatest Common Divisor
+/// Euclid's algorithm, recursively: `gcd(a, 0) = a`, `gcd(a, b) = gcd(b, a mod b)`.
+///
+/// ```
+/// use fp::classic::gcd;
+///
+/// assert_eq!(gcd(48, 18), 6);
+/// assert_eq!(gcd(17, 5), 1);
+/// assert_eq!(gcd(0, 9), 9);
+/// ```
+pub fn gcd(a: u64, b: u64) -> u64 {
+    match b {
+        0 => a,
+        _ => gcd(b, a % b),
+    }
+}
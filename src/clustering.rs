@@ -0,0 +1,231 @@
+//! Document Clustering
+//!
+//! Groups documents by vocabulary with k-means. Each document becomes a
+//! TF-IDF vector: a word weighs its share of the document's words times the
+//! log of how rare it is across documents, so words every document uses
+//! weigh nothing and a document's own topic words weigh most. Vectors are
+//! scaled to unit length, making the distance between them depend on the
+//! proportions of words rather than the documents' lengths.
+//!
+//! The first centroid is the first document and each next one the document
+//! farthest from those chosen, so the same documents always give the same
+//! clusters. The highest weights of a cluster's centroid are its
+//! distinguishing terms.
+//!
+//! ```
+//! use fp::analysis::word_counts;
+//! use fp::clustering::kmeans;
+//!
+//! let documents = [
+//!     word_counts("fold map filter fold reduce"),
+//!     word_counts("pasta basil tomato pasta"),
+//!     word_counts("map fold reduce monad"),
+//!     word_counts("tomato basil garlic pasta"),
+//! ];
+//! let clusters = kmeans(&documents, 2);
+//! assert_eq!(clusters[0].members, [0, 2]);
+//! assert_eq!(clusters[1].members, [1, 3]);
+//! assert!(clusters[1].terms.contains(&"pasta".to_string()));
+//! ```
+
+use std::collections::{HashMap, HashSet};
+
+/// How many terms describe each cluster
+pub const CLUSTER_TERMS: usize = 5;
+
+/// Rounds of k-means at most, should the assignments keep changing
+pub const MAX_ITERATIONS: usize = 100;
+
+/// A sparse vector of word weights
+pub type Vector = HashMap<String, f64>;
+
+/// A group of similar documents
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cluster {
+    /// Indices of the documents, ascending
+    pub members: Vec<usize>,
+    /// The [`CLUSTER_TERMS`] heaviest words of the centroid, heaviest first
+    pub terms: Vec<String>,
+}
+
+/// The unit-length TF-IDF vectors of `documents`' word counts
+///
+/// ```
+/// use fp::analysis::word_counts;
+/// use fp::clustering::tf_idf;
+///
+/// let vectors = tf_idf(&[word_counts("fold map"), word_counts("fold filter")]);
+/// // "fold" is in every document, so it distinguishes neither
+/// assert_eq!(vectors[0]["fold"], 0.0);
+/// assert!((vectors[0]["map"] - 1.0).abs() < 1e-9);
+/// ```
+pub fn tf_idf(documents: &[HashMap<String, usize>]) -> Vec<Vector> {
+    let frequency = documents.iter().flat_map(HashMap::keys).fold(
+        HashMap::new(),
+        |mut df: HashMap<&str, usize>, word| {
+            *df.entry(word).or_insert(0) += 1;
+            df
+        },
+    );
+    let n = documents.len() as f64;
+    documents
+        .iter()
+        .map(|counts| {
+            let total = counts.values().sum::<usize>() as f64;
+            let vector = counts
+                .iter()
+                .map(|(word, &count)| {
+                    let idf = (n / frequency[word.as_str()] as f64).ln();
+                    (word.clone(), count as f64 / total * idf)
+                })
+                .collect();
+            normalize(vector)
+        })
+        .collect()
+}
+
+/// `documents` in at most `k` clusters of similar vocabulary, ordered by
+/// their first document; empty clusters are left out
+///
+/// ```
+/// use fp::analysis::word_counts;
+/// use fp::clustering::kmeans;
+///
+/// let documents = [
+///     word_counts("fold map filter fold"),
+///     word_counts("cat dog cat bird"),
+///     word_counts("map fold reduce map"),
+/// ];
+/// let clusters = kmeans(&documents, 2);
+/// assert_eq!(clusters.len(), 2);
+/// assert_eq!(clusters[0].members, [0, 2]);
+/// assert_eq!(clusters[1].members, [1]);
+/// ```
+pub fn kmeans(documents: &[HashMap<String, usize>], k: usize) -> Vec<Cluster> {
+    let vectors = tf_idf(documents);
+    if vectors.is_empty() || k == 0 {
+        return Vec::new();
+    }
+    let mut centroids = farthest_first(&vectors, k);
+    let mut assignments = assign(&vectors, &centroids);
+    for _ in 0..MAX_ITERATIONS {
+        centroids = (0..centroids.len())
+            .map(|c| {
+                let members: Vec<&Vector> = vectors
+                    .iter()
+                    .zip(&assignments)
+                    .filter(|&(_, &a)| a == c)
+                    .map(|(v, _)| v)
+                    .collect();
+                mean(&members)
+            })
+            .collect();
+        let next = assign(&vectors, &centroids);
+        if next == assignments {
+            break;
+        }
+        assignments = next;
+    }
+    let mut clusters: Vec<Cluster> = centroids
+        .iter()
+        .enumerate()
+        .map(|(c, centroid)| Cluster {
+            members: (0..vectors.len())
+                .filter(|&i| assignments[i] == c)
+                .collect(),
+            terms: top_terms(centroid, CLUSTER_TERMS),
+        })
+        .filter(|cluster| !cluster.members.is_empty())
+        .collect();
+    clusters.sort_by_key(|cluster| cluster.members[0]);
+    clusters
+}
+
+/// The squared Euclidean distance between two sparse vectors
+fn distance(a: &Vector, b: &Vector) -> f64 {
+    let words: HashSet<&String> = a.keys().chain(b.keys()).collect();
+    words
+        .into_iter()
+        .map(|word| {
+            let x = a.get(word).copied().unwrap_or(0.0);
+            let y = b.get(word).copied().unwrap_or(0.0);
+            (x - y).powi(2)
+        })
+        .sum()
+}
+
+/// `vector` scaled to length 1, or left as it is if it is all zeros
+fn normalize(vector: Vector) -> Vector {
+    let length = vector.values().map(|x| x * x).sum::<f64>().sqrt();
+    if length == 0.0 {
+        return vector;
+    }
+    vector
+        .into_iter()
+        .map(|(word, x)| (word, x / length))
+        .collect()
+}
+
+/// The first `k` centroids: the first vector, then repeatedly the vector
+/// farthest from its nearest centroid so far
+fn farthest_first(vectors: &[Vector], k: usize) -> Vec<Vector> {
+    let mut centroids = vec![vectors[0].clone()];
+    while centroids.len() < k.min(vectors.len()) {
+        let farthest = vectors
+            .iter()
+            .map(|v| nearest(v, &centroids).1)
+            .enumerate()
+            // The first of equally far vectors, for repeatable results
+            .fold(
+                (0, f64::MIN),
+                |best, (i, d)| if d > best.1 { (i, d) } else { best },
+            );
+        centroids.push(vectors[farthest.0].clone());
+    }
+    centroids
+}
+
+/// The index of the centroid nearest to `vector`, and the distance to it
+fn nearest(vector: &Vector, centroids: &[Vector]) -> (usize, f64) {
+    centroids
+        .iter()
+        .map(|c| distance(vector, c))
+        .enumerate()
+        .fold(
+            (0, f64::MAX),
+            |best, (i, d)| if d < best.1 { (i, d) } else { best },
+        )
+}
+
+/// The nearest centroid of every vector
+fn assign(vectors: &[Vector], centroids: &[Vector]) -> Vec<usize> {
+    vectors.iter().map(|v| nearest(v, centroids).0).collect()
+}
+
+/// The mean of `vectors`; empty for none
+fn mean(vectors: &[&Vector]) -> Vector {
+    let n = vectors.len() as f64;
+    vectors
+        .iter()
+        .flat_map(|v| v.iter())
+        .fold(Vector::new(), |mut sum, (word, x)| {
+            *sum.entry(word.clone()).or_insert(0.0) += x / n;
+            sum
+        })
+}
+
+/// The `n` heaviest words of `centroid` with positive weight, heaviest
+/// first, ties alphabetical
+fn top_terms(centroid: &Vector, n: usize) -> Vec<String> {
+    let mut terms: Vec<(&String, f64)> = centroid
+        .iter()
+        .filter(|&(_, &x)| x > 0.0)
+        .map(|(word, &x)| (word, x))
+        .collect();
+    terms.sort_by(|(a, x), (b, y)| y.total_cmp(x).then_with(|| a.cmp(b)));
+    terms
+        .into_iter()
+        .take(n)
+        .map(|(word, _)| word.clone())
+        .collect()
+}
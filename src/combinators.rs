@@ -0,0 +1,119 @@
+//! Point-free combinators.
+//!
+//! Small, general-purpose functions that make it possible to build behaviour
+//! by plugging functions together instead of writing new closures.
+
+/// # Identity
+/// Returns its argument unchanged.
+///
+/// ```
+/// use fp::combinators::identity;
+///
+/// let words: Vec<_> = vec![Some("a"), None, Some("b")].into_iter().filter_map(identity).collect();
+/// assert_eq!(words, ["a", "b"]);
+/// ```
+pub fn identity<T>(x: T) -> T {
+    x
+}
+
+/// # Constant
+/// Returns a function that ignores its input and always yields `x`.
+///
+/// ```
+/// use fp::combinators::constant;
+///
+/// let zeros: Vec<i32> = [1, 2, 3].iter().map(constant(0)).collect();
+/// assert_eq!(zeros, [0, 0, 0]);
+/// ```
+pub fn constant<A, T: Clone>(x: T) -> impl Fn(A) -> T {
+    move |_| x.clone()
+}
+
+/// # Flip
+/// Swaps the arguments of a two-argument function.
+///
+/// ```
+/// use fp::combinators::flip;
+///
+/// let sub = |a: i32, b: i32| a - b;
+/// assert_eq!(flip(sub)(1, 10), 9);
+/// ```
+pub fn flip<A, B, C, F>(f: F) -> impl Fn(B, A) -> C
+where
+    F: Fn(A, B) -> C,
+{
+    move |b, a| f(a, b)
+}
+
+/// # Tap
+/// Runs a side effect on a reference to the value, then passes it through.
+///
+/// ```
+/// use std::cell::RefCell;
+/// use fp::combinators::tap;
+///
+/// let seen = RefCell::new(Vec::new());
+/// let doubled: Vec<i32> = [1, 2]
+///     .into_iter()
+///     .map(tap(|x: &i32| seen.borrow_mut().push(*x)))
+///     .map(|x| x * 2)
+///     .collect();
+/// assert_eq!(doubled, [2, 4]);
+/// assert_eq!(*seen.borrow(), [1, 2]);
+/// ```
+pub fn tap<T, F>(f: F) -> impl Fn(T) -> T
+where
+    F: Fn(&T),
+{
+    move |x| {
+        f(&x);
+        x
+    }
+}
+
+/// # Apply N Times
+/// Applies `f` to `x` repeatedly, `n` times in total.
+///
+/// ```
+/// use fp::combinators::apply_n;
+///
+/// assert_eq!(apply_n(|x| x * 2, 10, 1), 1024);
+/// assert_eq!(apply_n(|x: i32| x + 1, 0, 7), 7);
+/// ```
+pub fn apply_n<T, F>(f: F, n: usize, x: T) -> T
+where
+    F: Fn(T) -> T,
+{
+    (0..n).fold(x, |acc, _| f(acc))
+}
+
+/// # Fixed-Point Combinator
+/// Builds a recursive function from a closure that receives "itself" as its
+/// first argument, so recursion needs no named function.
+///
+/// A closure cannot refer to its own (anonymous) type, so the recursive
+/// handle is passed as `&dyn Fn(A) -> B`. Each call builds a fresh trait
+/// object on the stack that re-enters `fix`'s inner step; nothing is boxed.
+///
+/// ```
+/// use fp::combinators::fix;
+///
+/// let factorial = fix(|rec: &dyn Fn(u64) -> u64, n: u64| if n == 0 { 1 } else { n * rec(n - 1) });
+/// assert_eq!(factorial(5), 120);
+///
+/// let fib = fix(|rec: &dyn Fn(u32) -> u32, n: u32| if n < 2 { n } else { rec(n - 1) + rec(n - 2) });
+/// assert_eq!(fib(10), 55);
+/// ```
+pub fn fix<A, B, F>(f: F) -> impl Fn(A) -> B
+where
+    F: Fn(&dyn Fn(A) -> B, A) -> B,
+{
+    fn step<A, B, F>(f: &F, x: A) -> B
+    where
+        F: Fn(&dyn Fn(A) -> B, A) -> B,
+    {
+        f(&|y| step(f, y), x)
+    }
+
+    move |x| step(&f, x)
+}
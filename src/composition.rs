@@ -0,0 +1,190 @@
+//! Variadic function composition.
+//!
+//! `compose` in the concepts demo only joins two functions. The macros and
+//! the `Pipeline` builder here chain any number of them while keeping full
+//! type inference: each step's input type is taken from the previous output.
+//! The `Pipe` and `Compose` extension traits offer the same as methods.
+
+/// # Forward Composition
+/// Runs `f` then `g`: `and_then(f, g)(x) = g(f(x))`.
+///
+/// ```
+/// use fp::composition::and_then;
+///
+/// let add_one_then_double = and_then(|x: i32| x + 1, |x| x * 2);
+/// assert_eq!(add_one_then_double(3), 8);
+/// ```
+pub fn and_then<A, B, C, F, G>(f: F, g: G) -> impl Fn(A) -> C
+where
+    F: Fn(A) -> B,
+    G: Fn(B) -> C,
+{
+    move |x| g(f(x))
+}
+
+/// # Backward Composition
+/// Mathematical composition: `compose(f, g)(x) = f(g(x))`.
+///
+/// ```
+/// use fp::composition::compose;
+///
+/// let double_after_add_one = compose(|x: i32| x * 2, |x: i32| x + 1);
+/// assert_eq!(double_after_add_one(3), 8);
+/// ```
+pub fn compose<A, B, C, F, G>(f: F, g: G) -> impl Fn(A) -> C
+where
+    F: Fn(B) -> C,
+    G: Fn(A) -> B,
+{
+    move |x| f(g(x))
+}
+
+/// # Pipe Macro
+/// Chains functions left to right: `pipe!(f, g, h)(x) = h(g(f(x)))`.
+///
+/// ```
+/// use fp::pipe;
+///
+/// let f = pipe!(|x: i32| x + 1, |x| x * 2, |x: i32| x.to_string());
+/// assert_eq!(f(3), "8");
+/// ```
+#[macro_export]
+macro_rules! pipe {
+    ($f:expr $(,)?) => {
+        $f
+    };
+    ($f:expr, $($rest:expr),+ $(,)?) => {
+        $crate::composition::and_then($f, $crate::pipe!($($rest),+))
+    };
+}
+
+/// # Compose Macro
+/// Chains functions right to left: `compose!(f, g, h)(x) = f(g(h(x)))`.
+///
+/// ```
+/// use fp::compose;
+///
+/// let f = compose!(|x: i32| x - 1, |x: i32| x * 10, |x: i32| x + 2);
+/// assert_eq!(f(1), 29);
+/// ```
+#[macro_export]
+macro_rules! compose {
+    ($f:expr $(,)?) => {
+        $f
+    };
+    ($f:expr, $($rest:expr),+ $(,)?) => {
+        $crate::composition::compose($f, $crate::compose!($($rest),+))
+    };
+}
+
+/// # Pipeline Builder
+/// Builds a left-to-right chain one step at a time with `.then()`.
+///
+/// ```
+/// use fp::composition::Pipeline;
+///
+/// let shout = Pipeline::new(str::trim)
+///     .then(str::to_uppercase)
+///     .then(|s| s + "!");
+/// assert_eq!(shout.run("  hello "), "HELLO!");
+/// ```
+pub struct Pipeline<F> {
+    f: F,
+}
+
+impl<F> Pipeline<F> {
+    /// Starts a pipeline with its first step.
+    ///
+    /// ```
+    /// use fp::composition::Pipeline;
+    ///
+    /// let length = Pipeline::new(str::len);
+    /// assert_eq!(length.run("fold"), 4);
+    /// ```
+    pub fn new(f: F) -> Self {
+        Self { f }
+    }
+
+    /// Appends a step that receives the output of the pipeline so far.
+    ///
+    /// ```
+    /// use fp::composition::Pipeline;
+    ///
+    /// let words = Pipeline::new(str::split_whitespace).then(Iterator::count);
+    /// assert_eq!(words.run("fold map filter"), 3);
+    /// ```
+    pub fn then<A, B, C, G>(self, g: G) -> Pipeline<impl Fn(A) -> C>
+    where
+        F: Fn(A) -> B,
+        G: Fn(B) -> C,
+    {
+        Pipeline::new(and_then(self.f, g))
+    }
+
+    /// Feeds a value through every step.
+    ///
+    /// ```
+    /// use fp::composition::Pipeline;
+    ///
+    /// let square = Pipeline::new(|x: i32| x * x);
+    /// assert_eq!(square.run(4), 16);
+    /// // A pipeline can be run any number of times
+    /// assert_eq!(square.run(-3), 9);
+    /// ```
+    pub fn run<A, B>(&self, x: A) -> B
+    where
+        F: Fn(A) -> B,
+    {
+        (self.f)(x)
+    }
+
+    /// Returns the composed function.
+    ///
+    /// ```
+    /// use fp::composition::Pipeline;
+    ///
+    /// let shout = Pipeline::new(|s: &str| s.to_uppercase()).then(|s| s + "!").into_fn();
+    /// assert_eq!(["hi", "yo"].map(shout), ["HI!", "YO!"]);
+    /// ```
+    pub fn into_fn(self) -> F {
+        self.f
+    }
+}
+
+/// # Pipe Operator
+/// Passes a value to a function as a method call, so a chain of calls reads
+/// left to right like a shell pipeline: `x.pipe(f).pipe(g) == g(f(x))`.
+///
+/// ```
+/// use fp::composition::Pipe;
+///
+/// let shouted = "  hi ".pipe(str::trim).pipe(str::to_uppercase).pipe(|s| s + "!");
+/// assert_eq!(shouted, "HI!");
+/// ```
+pub trait Pipe: Sized {
+    /// Applies `f` to `self`.
+    fn pipe<B, F: FnOnce(Self) -> B>(self, f: F) -> B {
+        f(self)
+    }
+}
+
+impl<T> Pipe for T {}
+
+/// # Compose Operator
+/// Forward composition as a method on any function: `f.and_then_fn(g)` is
+/// [`and_then(f, g)`](and_then).
+///
+/// ```
+/// use fp::composition::Compose;
+///
+/// let inc_then_double = (|x: i32| x + 1).and_then_fn(|x| x * 2);
+/// assert_eq!(inc_then_double(4), 10);
+/// ```
+pub trait Compose<A, B>: Fn(A) -> B + Sized {
+    /// Runs `self`, then `g` on its result.
+    fn and_then_fn<C, G: Fn(B) -> C>(self, g: G) -> impl Fn(A) -> C {
+        and_then(self, g)
+    }
+}
+
+impl<A, B, F: Fn(A) -> B> Compose<A, B> for F {}
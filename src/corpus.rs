@@ -0,0 +1,210 @@
+//! Synthetic Corpora
+//!
+//! Reproducible test input without shipping large fixtures. Words are drawn
+//! from a Zipf distribution, the shape of word frequencies in natural
+//! language: the word of rank `k` occurs with probability proportional to
+//! `1 / k^s`. Everything is determined by a [`CorpusSpec`], seed included,
+//! so a benchmark or test that names its spec always sees the same text,
+//! and [`Zipf::probability`] says how often each word is expected.
+//!
+//! ```
+//! use fp::analysis::word_counts;
+//! use fp::corpus::{CorpusSpec, generate, word};
+//!
+//! let spec = CorpusSpec { words: 10_000, vocabulary: 50, seed: 7, ..CorpusSpec::default() };
+//! let text = generate(&spec);
+//! assert_eq!(text, generate(&spec));
+//!
+//! let counts = word_counts(&text);
+//! assert_eq!(counts.values().sum::<usize>(), 10_000);
+//! assert!(counts[&word(1)] > counts[&word(2)]);
+//! ```
+
+use crate::random::Rng;
+
+/// What to generate
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorpusSpec {
+    /// How many words in total
+    pub words: usize,
+    /// How many distinct words to draw from
+    pub vocabulary: usize,
+    /// The Zipf exponent `s`; larger makes the top words more dominant, 0
+    /// makes every word equally likely
+    pub exponent: f64,
+    /// Seed of the generator
+    pub seed: u64,
+    /// Words per line; the last line may be shorter
+    pub words_per_line: usize,
+}
+
+impl Default for CorpusSpec {
+    fn default() -> Self {
+        CorpusSpec {
+            words: 100_000,
+            vocabulary: 10_000,
+            exponent: 1.0,
+            seed: 0,
+            words_per_line: 12,
+        }
+    }
+}
+
+/// The Zipf distribution over ranks `1..=n`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Zipf {
+    /// Cumulative probabilities, by rank from 1
+    cdf: Vec<f64>,
+}
+
+impl Zipf {
+    /// The distribution over `n` ranks with exponent `exponent`. `n` must
+    /// not be zero.
+    ///
+    /// ```
+    /// use fp::corpus::Zipf;
+    ///
+    /// let zipf = Zipf::new(3, 1.0);
+    /// // 1 : 1/2 : 1/3
+    /// assert!((zipf.probability(1) - 6.0 / 11.0).abs() < 1e-12);
+    /// assert!((zipf.probability(3) - 2.0 / 11.0).abs() < 1e-12);
+    /// assert_eq!(zipf.probability(4), 0.0);
+    /// ```
+    pub fn new(n: usize, exponent: f64) -> Self {
+        assert!(n > 0, "a Zipf distribution needs at least one rank");
+        let weights: Vec<f64> = (1..=n).map(|k| (k as f64).powf(-exponent)).collect();
+        let total: f64 = weights.iter().sum();
+        let cdf = weights
+            .iter()
+            .scan(0.0, |sum, weight| {
+                *sum += weight / total;
+                Some(*sum)
+            })
+            .collect();
+        Zipf { cdf }
+    }
+
+    /// How likely rank `rank` is; 0 outside `1..=n`
+    ///
+    /// ```
+    /// use fp::corpus::Zipf;
+    ///
+    /// let zipf = Zipf::new(2, 1.0);
+    /// // Rank 1 is twice as likely as rank 2 when the exponent is 1
+    /// assert!((zipf.probability(1) - 2.0 / 3.0).abs() < 1e-12);
+    /// assert!((zipf.probability(2) - 1.0 / 3.0).abs() < 1e-12);
+    /// assert_eq!(zipf.probability(3), 0.0);
+    /// ```
+    pub fn probability(&self, rank: usize) -> f64 {
+        match rank {
+            0 => 0.0,
+            1 => self.cdf.first().copied().unwrap_or(0.0),
+            _ => self
+                .cdf
+                .get(rank - 1)
+                .map_or(0.0, |cumulative| cumulative - self.cdf[rank - 2]),
+        }
+    }
+
+    /// Draw a rank and return the generator to continue with
+    ///
+    /// ```
+    /// use fp::corpus::Zipf;
+    /// use fp::random::Rng;
+    ///
+    /// let zipf = Zipf::new(10, 1.0);
+    /// let (rank, rng) = zipf.sample(Rng::new(1));
+    /// assert!((1..=10).contains(&rank));
+    /// assert_eq!(zipf.sample(Rng::new(1)).0, rank);
+    /// assert_ne!(rng, Rng::new(1));
+    /// ```
+    pub fn sample(&self, rng: Rng) -> (usize, Rng) {
+        let (u, rng) = rng.next_f64();
+        // Rounding can leave the last cumulative value just below 1
+        let index = self.cdf.partition_point(|&cumulative| cumulative <= u);
+        (index.min(self.cdf.len() - 1) + 1, rng)
+    }
+}
+
+/// The word of rank `rank` (from 1): syllables of a consonant and a vowel,
+/// numbered so that every rank has its own word and the frequent words
+/// are the short ones.
+///
+/// ```
+/// use fp::corpus::word;
+///
+/// assert_eq!(word(1), "ba");
+/// assert_eq!(word(2), "be");
+/// assert_eq!(word(71), "baba");
+/// ```
+pub fn word(rank: usize) -> String {
+    const CONSONANTS: &[u8] = b"bdfgklmnprstvz";
+    const VOWELS: &[u8] = b"aeiou";
+    let syllables = CONSONANTS.len() * VOWELS.len();
+    // Bijective numeration: every positive number has exactly one spelling
+    let mut n = rank;
+    let mut digits = Vec::new();
+    while n > 0 {
+        let digit = (n - 1) % syllables;
+        digits.push(digit);
+        n = (n - 1) / syllables;
+    }
+    digits
+        .iter()
+        .rev()
+        .flat_map(|&digit| {
+            [
+                CONSONANTS[digit / VOWELS.len()] as char,
+                VOWELS[digit % VOWELS.len()] as char,
+            ]
+        })
+        .collect()
+}
+
+/// The corpus as lines of space-separated words, each ending in `\n`,
+/// generated lazily so large corpora can be streamed
+///
+/// ```
+/// use fp::corpus::{CorpusSpec, lines};
+///
+/// let spec = CorpusSpec { words: 10, words_per_line: 4, ..CorpusSpec::default() };
+/// let lengths: Vec<usize> = lines(&spec).map(|line| line.split_whitespace().count()).collect();
+/// assert_eq!(lengths, [4, 4, 2]);
+/// ```
+pub fn lines(spec: &CorpusSpec) -> impl Iterator<Item = String> {
+    let zipf = Zipf::new(spec.vocabulary.max(1), spec.exponent);
+    let vocabulary: Vec<String> = (1..=spec.vocabulary.max(1)).map(word).collect();
+    let per_line = spec.words_per_line.max(1);
+    let words = spec.words;
+    let line_lengths = (0..words)
+        .step_by(per_line)
+        .map(move |start| per_line.min(words - start));
+    let mut rng = Rng::new(spec.seed);
+    line_lengths.map(move |length| {
+        let mut line = String::new();
+        for i in 0..length {
+            let (rank, next) = zipf.sample(rng);
+            rng = next;
+            if i > 0 {
+                line.push(' ');
+            }
+            line.push_str(&vocabulary[rank - 1]);
+        }
+        line.push('\n');
+        line
+    })
+}
+
+/// The whole corpus as one string
+///
+/// ```
+/// use fp::corpus::{CorpusSpec, generate};
+///
+/// let spec = CorpusSpec { words: 12, words_per_line: 5, ..CorpusSpec::default() };
+/// let text = generate(&spec);
+/// assert_eq!(text.lines().count(), 3);
+/// assert_eq!(text.split_whitespace().count(), 12);
+/// ```
+pub fn generate(spec: &CorpusSpec) -> String {
+    lines(spec).collect()
+}
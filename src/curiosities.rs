@@ -0,0 +1,108 @@
+//! Word Curiosities
+//!
+//! Trivia about a text's vocabulary for the `--fun-facts` report: its
+//! longest and shortest words, its palindromes, and the words using the
+//! most distinct letters. Lengths and letters are counted in characters,
+//! and every list is alphabetical with ties kept.
+//!
+//! ```
+//! use fp::curiosities::curiosities;
+//!
+//! let facts = curiosities("a level racecar is a kayak or a fold".split(' '));
+//! assert_eq!(facts.longest, ["racecar"]);
+//! assert_eq!(facts.shortest, ["a"]);
+//! assert_eq!(facts.palindromes, ["kayak", "level", "racecar"]);
+//! assert_eq!(facts.most_distinct, ["fold", "racecar"]);
+//! ```
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BTreeSet, HashSet};
+
+/// Words palindromes must be at least this long, so single letters and
+/// words like "oo" do not crowd out the interesting ones
+pub const MIN_PALINDROME: usize = 3;
+
+/// The trivia of a vocabulary
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Curiosities {
+    /// The words with the most characters
+    pub longest: Vec<String>,
+    /// The words with the fewest characters
+    pub shortest: Vec<String>,
+    /// Words of at least [`MIN_PALINDROME`] characters that read the same
+    /// backwards
+    pub palindromes: Vec<String>,
+    /// The words using the most different letters
+    pub most_distinct: Vec<String>,
+}
+
+/// The trivia of `words`; repeated words count once
+///
+/// ```
+/// use fp::curiosities::curiosities;
+///
+/// let facts = curiosities(["noon", "map", "noon", "fold"]);
+/// assert_eq!(facts.palindromes, ["noon"]);
+/// assert_eq!(facts.longest, ["fold", "noon"]);
+/// assert_eq!(facts.shortest, ["map"]);
+/// ```
+pub fn curiosities<'a>(words: impl IntoIterator<Item = &'a str>) -> Curiosities {
+    let vocabulary: BTreeSet<&str> = words.into_iter().collect();
+    let length = |word: &str| word.chars().count();
+    Curiosities {
+        longest: all_max_by_key(&vocabulary, length),
+        shortest: all_max_by_key(&vocabulary, |word| Reverse(length(word))),
+        palindromes: vocabulary
+            .iter()
+            .filter(|word| length(word) >= MIN_PALINDROME && is_palindrome(word))
+            .map(|word| word.to_string())
+            .collect(),
+        most_distinct: all_max_by_key(&vocabulary, distinct_letters),
+    }
+}
+
+/// Whether `word` reads the same backwards, character by character
+///
+/// ```
+/// use fp::curiosities::is_palindrome;
+///
+/// assert!(is_palindrome("kayak"));
+/// assert!(is_palindrome("été"));
+/// assert!(!is_palindrome("fold"));
+/// ```
+pub fn is_palindrome(word: &str) -> bool {
+    word.chars().eq(word.chars().rev())
+}
+
+/// How many different letters `word` uses
+///
+/// ```
+/// use fp::curiosities::distinct_letters;
+///
+/// assert_eq!(distinct_letters("banana"), 3);
+/// assert_eq!(distinct_letters("don't"), 4);
+/// ```
+pub fn distinct_letters(word: &str) -> usize {
+    word.chars()
+        .filter(|c| c.is_alphabetic())
+        .collect::<HashSet<char>>()
+        .len()
+}
+
+/// Every word of `vocabulary` with the greatest `key`, in its order
+fn all_max_by_key<K: Ord>(vocabulary: &BTreeSet<&str>, key: impl Fn(&str) -> K) -> Vec<String> {
+    vocabulary
+        .iter()
+        .fold((None, Vec::new()), |(best, mut words), &word| {
+            let k = key(word);
+            match best.as_ref().map(|best| k.cmp(best)) {
+                Some(Ordering::Less) => (best, words),
+                Some(Ordering::Equal) => {
+                    words.push(word.to_string());
+                    (best, words)
+                }
+                _ => (Some(k), vec![word.to_string()]),
+            }
+        })
+        .1
+}
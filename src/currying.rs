@@ -0,0 +1,93 @@
+//! Full currying for multi-argument functions.
+//!
+//! `curry_add` in the concepts demo hard-codes addition. These helpers turn
+//! any two- or three-argument function into a chain of single-argument
+//! functions and back again.
+//!
+//! Inner stages are boxed because `impl Fn` cannot appear in the return type
+//! of another `Fn`, so the shape is `impl Fn(A) -> Curried<B, C>`.
+
+/// A boxed single-argument stage of a curried function.
+pub type Curried<A, B> = Box<dyn Fn(A) -> B>;
+
+/// # Curry (2 arguments)
+/// Turns `f(a, b)` into `f(a)(b)`.
+///
+/// ```
+/// use fp::currying::curry2;
+///
+/// let pow = curry2(|base: u32, exp: u32| base.pow(exp));
+/// let pow2 = pow(2);
+/// assert_eq!(pow2(10), 1024);
+/// ```
+pub fn curry2<A, B, C, F>(f: F) -> impl Fn(A) -> Curried<B, C>
+where
+    F: Fn(A, B) -> C + Clone + 'static,
+    A: Clone + 'static,
+{
+    move |a| {
+        let f = f.clone();
+        Box::new(move |b| f(a.clone(), b))
+    }
+}
+
+/// # Curry (3 arguments)
+/// Turns `f(a, b, c)` into `f(a)(b)(c)`.
+///
+/// ```
+/// use fp::currying::curry3;
+///
+/// let volume = curry3(|l: u32, w: u32, h: u32| l * w * h);
+/// assert_eq!(volume(2)(3)(4), 24);
+/// ```
+pub fn curry3<A, B, C, D, F>(f: F) -> impl Fn(A) -> Curried<B, Curried<C, D>>
+where
+    F: Fn(A, B, C) -> D + Clone + 'static,
+    A: Clone + 'static,
+    B: Clone + 'static,
+{
+    move |a| {
+        let f = f.clone();
+        Box::new(move |b| {
+            let f = f.clone();
+            let a = a.clone();
+            Box::new(move |c| f(a.clone(), b.clone(), c))
+        })
+    }
+}
+
+/// # Uncurry (2 arguments)
+/// Turns `f(a)(b)` back into `f(a, b)`.
+///
+/// ```
+/// use fp::currying::uncurry2;
+///
+/// let add = uncurry2(|a: i32| move |b: i32| a + b);
+/// assert_eq!(add(2, 3), 5);
+/// ```
+pub fn uncurry2<A, B, C, F, G>(f: F) -> impl Fn(A, B) -> C
+where
+    F: Fn(A) -> G,
+    G: Fn(B) -> C,
+{
+    move |a, b| f(a)(b)
+}
+
+/// # Uncurry (3 arguments)
+/// Turns `f(a)(b)(c)` back into `f(a, b, c)`.
+///
+/// ```
+/// use fp::currying::{curry3, uncurry3};
+///
+/// let join = |a: &'static str, b: &'static str, c: &'static str| format!("{a}{b}{c}");
+/// let round_trip = uncurry3(curry3(join));
+/// assert_eq!(round_trip("f", "o", "o"), "foo");
+/// ```
+pub fn uncurry3<A, B, C, D, F, G, H>(f: F) -> impl Fn(A, B, C) -> D
+where
+    F: Fn(A) -> G,
+    G: Fn(B) -> H,
+    H: Fn(C) -> D,
+{
+    move |a, b, c| f(a)(b)(c)
+}
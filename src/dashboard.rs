@@ -0,0 +1,227 @@
+//! Terminal Dashboard
+//!
+//! The `--tui` front end of `file_parser`: panels for the most frequent
+//! words, the totals and the word-length histogram, above a search box that
+//! narrows the top words to those containing what has been typed. The
+//! [`Dashboard`] only holds state and draws it; whoever owns the terminal
+//! feeds it new counts with [`Dashboard::update`] and key presses with
+//! [`Dashboard::handle_key`], so the same screen follows a file being
+//! watched or is drawn once into a test backend.
+//!
+//! ```
+//! use fp::analysis::word_counts;
+//! use fp::dashboard::Dashboard;
+//! use ratatui::Terminal;
+//! use ratatui::backend::TestBackend;
+//!
+//! let mut dashboard = Dashboard::new("notes.txt");
+//! dashboard.update(word_counts("fold map fold filter fold map"));
+//! let mut terminal = Terminal::new(TestBackend::new(60, 12)).unwrap();
+//! terminal.draw(|frame| dashboard.draw(frame)).unwrap();
+//!
+//! let screen: String = terminal.backend().buffer().content.iter().map(|cell| cell.symbol()).collect();
+//! assert!(screen.contains("notes.txt"));
+//! assert!(screen.contains("Words: 6"));
+//! assert!(screen.contains("fold"));
+//! ```
+
+use std::collections::HashMap;
+use std::ops::ControlFlow;
+
+use ratatui::Frame;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::widgets::{Block, Paragraph};
+
+use crate::analysis::Report;
+use crate::viz::{bar_chart, length_histogram};
+
+/// What the dashboard shows: the latest counts and the search query
+#[derive(Debug, Clone, Default)]
+pub struct Dashboard {
+    title: String,
+    counts: HashMap<String, usize>,
+    query: String,
+    updates: usize,
+}
+
+impl Dashboard {
+    /// An empty dashboard headed `title`, typically the file being analyzed
+    ///
+    /// ```
+    /// use fp::dashboard::Dashboard;
+    ///
+    /// let dashboard = Dashboard::new("notes.txt");
+    /// assert_eq!(dashboard.query(), "");
+    /// assert!(dashboard.matches(10).is_empty());
+    /// ```
+    pub fn new(title: impl Into<String>) -> Self {
+        Dashboard {
+            title: title.into(),
+            ..Dashboard::default()
+        }
+    }
+
+    /// Show `counts` from now on
+    ///
+    /// ```
+    /// use fp::analysis::word_counts;
+    /// use fp::dashboard::Dashboard;
+    ///
+    /// let mut dashboard = Dashboard::new("notes.txt");
+    /// dashboard.update(word_counts("fold map"));
+    /// dashboard.update(word_counts("filter"));
+    /// // The new counts replace the old ones
+    /// assert_eq!(dashboard.matches(10), [("filter".to_string(), 1)]);
+    /// ```
+    pub fn update(&mut self, counts: HashMap<String, usize>) {
+        self.counts = counts;
+        self.updates += 1;
+    }
+
+    /// What has been typed into the search box
+    ///
+    /// ```
+    /// use fp::dashboard::Dashboard;
+    /// use ratatui::crossterm::event::{KeyCode, KeyEvent};
+    ///
+    /// let mut dashboard = Dashboard::new("notes.txt");
+    /// let _ = dashboard.handle_key(KeyEvent::from(KeyCode::Char('M')));
+    /// assert_eq!(dashboard.query(), "m");
+    /// ```
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Edit the search box: characters are typed into it, Backspace deletes
+    /// one, and Esc clears it. Esc on an empty box or Ctrl-C breaks.
+    ///
+    /// ```
+    /// use std::ops::ControlFlow;
+    /// use fp::dashboard::Dashboard;
+    /// use ratatui::crossterm::event::{KeyCode, KeyEvent};
+    ///
+    /// let mut dashboard = Dashboard::new("notes.txt");
+    /// for key in [KeyCode::Char('f'), KeyCode::Char('o'), KeyCode::Char('x'), KeyCode::Backspace] {
+    ///     assert_eq!(dashboard.handle_key(KeyEvent::from(key)), ControlFlow::Continue(()));
+    /// }
+    /// assert_eq!(dashboard.query(), "fo");
+    /// assert_eq!(dashboard.handle_key(KeyEvent::from(KeyCode::Esc)), ControlFlow::Continue(()));
+    /// assert_eq!(dashboard.handle_key(KeyEvent::from(KeyCode::Esc)), ControlFlow::Break(()));
+    /// ```
+    pub fn handle_key(&mut self, key: KeyEvent) -> ControlFlow<()> {
+        if key.kind != KeyEventKind::Press {
+            return ControlFlow::Continue(());
+        }
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return ControlFlow::Break(());
+            }
+            KeyCode::Esc if self.query.is_empty() => return ControlFlow::Break(()),
+            KeyCode::Esc => self.query.clear(),
+            KeyCode::Backspace => {
+                self.query.pop();
+            }
+            KeyCode::Char(c) => self.query.extend(c.to_lowercase()),
+            _ => {}
+        }
+        ControlFlow::Continue(())
+    }
+
+    /// The `limit` most frequent words containing the query
+    ///
+    /// ```
+    /// use fp::analysis::word_counts;
+    /// use fp::dashboard::Dashboard;
+    /// use ratatui::crossterm::event::{KeyCode, KeyEvent};
+    ///
+    /// let mut dashboard = Dashboard::new("notes.txt");
+    /// dashboard.update(word_counts("fold map fold filter fold map"));
+    /// let _ = dashboard.handle_key(KeyEvent::from(KeyCode::Char('f')));
+    /// assert_eq!(dashboard.matches(5), [("fold".to_string(), 3), ("filter".to_string(), 1)]);
+    /// ```
+    pub fn matches(&self, limit: usize) -> Vec<(String, usize)> {
+        let matching: HashMap<String, usize> = self
+            .counts
+            .iter()
+            .filter(|(word, _)| word.contains(&self.query))
+            .map(|(word, &count)| (word.clone(), count))
+            .collect();
+        Report::new(&matching, limit).top_words
+    }
+
+    /// Draw every panel over the whole frame
+    ///
+    /// ```
+    /// use fp::analysis::word_counts;
+    /// use fp::dashboard::Dashboard;
+    /// use ratatui::Terminal;
+    /// use ratatui::backend::TestBackend;
+    /// use ratatui::crossterm::event::{KeyCode, KeyEvent};
+    ///
+    /// let mut dashboard = Dashboard::new("notes.txt");
+    /// dashboard.update(word_counts("fold map fold"));
+    /// let _ = dashboard.handle_key(KeyEvent::from(KeyCode::Char('m')));
+    /// let mut terminal = Terminal::new(TestBackend::new(60, 12)).unwrap();
+    /// terminal.draw(|frame| dashboard.draw(frame)).unwrap();
+    ///
+    /// let screen: String = terminal.backend().buffer().content.iter().map(|cell| cell.symbol()).collect();
+    /// assert!(screen.contains("Top Words containing 'm'"));
+    /// ```
+    pub fn draw(&self, frame: &mut Frame) {
+        let [body, search] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(3)]).areas(frame.area());
+        let [top, side] =
+            Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+                .areas(body);
+        let [totals, histogram] =
+            Layout::vertical([Constraint::Length(5), Constraint::Min(0)]).areas(side);
+
+        let rows = usize::from(top.height.saturating_sub(2));
+        let top_title = match self.query.as_str() {
+            "" => " Top Words ".to_string(),
+            query => format!(" Top Words containing '{}' ", query),
+        };
+        frame.render_widget(
+            Paragraph::new(bar_chart(self.matches(rows), inner_width(top)))
+                .block(Block::bordered().title(top_title)),
+            top,
+        );
+
+        frame.render_widget(
+            Paragraph::new(format!(
+                "Words: {}\nUnique: {}\nUpdates: {}",
+                self.counts.values().sum::<usize>(),
+                self.counts.len(),
+                self.updates
+            ))
+            .block(Block::bordered().title(format!(" {} ", self.title))),
+            totals,
+        );
+
+        frame.render_widget(
+            Paragraph::new(bar_chart(
+                length_histogram(&self.counts),
+                inner_width(histogram),
+            ))
+            .block(Block::bordered().title(" Word Lengths ")),
+            histogram,
+        );
+
+        frame.render_widget(
+            Paragraph::new(format!("/{}", self.query))
+                .block(Block::bordered().title(" Search (Esc clears, Esc again quits) ")),
+            search,
+        );
+        let cursor = u16::try_from(self.query.chars().count()).unwrap_or(u16::MAX);
+        frame.set_cursor_position((
+            search.x.saturating_add(2).saturating_add(cursor),
+            search.y + 1,
+        ));
+    }
+}
+
+/// Columns inside the border of `area`
+fn inner_width(area: Rect) -> usize {
+    usize::from(area.width.saturating_sub(2))
+}
@@ -0,0 +1,139 @@
+//! Lexical Diversity
+//!
+//! How varied a text's vocabulary is. The type-token ratio (distinct words
+//! over words) is the classic measure but falls as a text grows, since
+//! common words keep repeating; Guiraud's root TTR divides by the square
+//! root of the length instead, and MTLD (McCarthy and Jarvis, 2010) avoids
+//! the dependence on length altogether: it is the mean length of the runs
+//! of words over which the running TTR stays above 0.72, read forwards and
+//! backwards. Higher means richer for all three.
+//!
+//! ```
+//! use fp::diversity::diversity;
+//!
+//! let varied: Vec<&str> = "a quick brown fox jumps over the lazy dog".split(' ').collect();
+//! let repetitive: Vec<&str> = "the fold and the map and the fold and the map".split(' ').collect();
+//! assert!(diversity(&varied).mtld > diversity(&repetitive).mtld);
+//! assert_eq!(diversity(&repetitive).types, 4);
+//! ```
+
+use std::collections::HashSet;
+
+/// The running TTR at which MTLD closes a factor
+pub const MTLD_THRESHOLD: f64 = 0.72;
+
+/// Every measure of a text at once
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Diversity {
+    /// Words
+    pub tokens: usize,
+    /// Distinct words
+    pub types: usize,
+    /// The type-token ratio, as [`type_token_ratio`] computes it
+    pub ttr: f64,
+    /// Guiraud's root TTR, as [`root_ttr`] computes it
+    pub root_ttr: f64,
+    /// MTLD, as [`mtld`] computes it
+    pub mtld: f64,
+}
+
+/// The measures of `tokens`, the text's words in order
+///
+/// ```
+/// use fp::diversity::diversity;
+///
+/// let measures = diversity(&["fold", "map", "fold", "filter"]);
+/// assert_eq!((measures.tokens, measures.types), (4, 3));
+/// assert_eq!(measures.ttr, 0.75);
+/// assert_eq!(measures.root_ttr, 1.5);
+/// ```
+pub fn diversity<S: AsRef<str>>(tokens: &[S]) -> Diversity {
+    let types = tokens
+        .iter()
+        .map(AsRef::as_ref)
+        .collect::<HashSet<&str>>()
+        .len();
+    Diversity {
+        tokens: tokens.len(),
+        types,
+        ttr: type_token_ratio(types, tokens.len()),
+        root_ttr: root_ttr(types, tokens.len()),
+        mtld: mtld(tokens),
+    }
+}
+
+/// Distinct words over words; 0 for no words
+///
+/// ```
+/// use fp::diversity::type_token_ratio;
+///
+/// assert_eq!(type_token_ratio(3, 4), 0.75);
+/// assert_eq!(type_token_ratio(0, 0), 0.0);
+/// ```
+pub fn type_token_ratio(types: usize, tokens: usize) -> f64 {
+    match tokens {
+        0 => 0.0,
+        n => types as f64 / n as f64,
+    }
+}
+
+/// Guiraud's root TTR: distinct words over the square root of words; 0 for
+/// no words
+///
+/// ```
+/// use fp::diversity::root_ttr;
+///
+/// assert_eq!(root_ttr(8, 16), 2.0);
+/// ```
+pub fn root_ttr(types: usize, tokens: usize) -> f64 {
+    match tokens {
+        0 => 0.0,
+        n => types as f64 / (n as f64).sqrt(),
+    }
+}
+
+/// The measure of textual lexical diversity of `tokens`: the mean of the
+/// forward and backward passes. A text too short to lower the TTR to the
+/// threshold at all counts as a single factor. 0 for no words.
+///
+/// ```
+/// use fp::diversity::mtld;
+///
+/// // The TTR falls to 2/3 at the third word, closing a factor, every time
+/// let tokens = ["a", "b", "a", "a", "b", "a"];
+/// assert_eq!(mtld(&tokens), 3.0);
+/// assert_eq!(mtld::<&str>(&[]), 0.0);
+/// ```
+pub fn mtld<S: AsRef<str>>(tokens: &[S]) -> f64 {
+    let forward = mtld_pass(tokens.iter().map(AsRef::as_ref));
+    let backward = mtld_pass(tokens.iter().rev().map(AsRef::as_ref));
+    (forward + backward) / 2.0
+}
+
+/// One MTLD pass: the number of words over the number of factors, where the
+/// run left at the end counts as the fraction of a factor it got through
+fn mtld_pass<'a>(tokens: impl Iterator<Item = &'a str>) -> f64 {
+    let (factors, types, length, words) = tokens.fold(
+        (0.0, HashSet::new(), 0, 0),
+        |(factors, mut types, length, words), token| {
+            types.insert(token);
+            let length = length + 1;
+            if type_token_ratio(types.len(), length) <= MTLD_THRESHOLD {
+                (factors + 1.0, HashSet::new(), 0, words + 1)
+            } else {
+                (factors, types, length, words + 1)
+            }
+        },
+    );
+    let partial = match length {
+        0 => 0.0,
+        _ => (1.0 - type_token_ratio(types.len(), length)) / (1.0 - MTLD_THRESHOLD),
+    };
+    let factors = factors + partial;
+    if factors == 0.0 {
+        // Also 0 words over 0 factors for an empty text
+        words as f64
+    } else {
+        words as f64 / factors
+    }
+}
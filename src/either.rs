@@ -0,0 +1,239 @@
+//! The `Either` sum type.
+//!
+//! A symmetric alternative to `Result`: neither side means "failure". By
+//! convention the API is right-biased (`and_then` continues on `Right`), so it
+//! converts losslessly to and from `Result<R, L>`.
+
+/// A value that is either a `Left(L)` or a `Right(R)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Either<L, R> {
+    Left(L),
+    Right(R),
+}
+
+use Either::{Left, Right};
+
+impl<L, R> Either<L, R> {
+    /// Returns `true` if this is a `Left`.
+    ///
+    /// ```
+    /// use fp::either::Either;
+    ///
+    /// assert!(Either::<i32, &str>::Left(1).is_left());
+    /// assert!(!Either::<i32, &str>::Right("one").is_left());
+    /// ```
+    pub fn is_left(&self) -> bool {
+        matches!(self, Left(_))
+    }
+
+    /// Returns `true` if this is a `Right`.
+    ///
+    /// ```
+    /// use fp::either::Either;
+    ///
+    /// assert!(Either::<i32, &str>::Right("one").is_right());
+    /// ```
+    pub fn is_right(&self) -> bool {
+        matches!(self, Right(_))
+    }
+
+    /// Returns the left value, if any.
+    ///
+    /// ```
+    /// use fp::either::Either;
+    ///
+    /// assert_eq!(Either::<i32, &str>::Left(1).left(), Some(1));
+    /// assert_eq!(Either::<i32, &str>::Right("one").left(), None);
+    /// ```
+    pub fn left(self) -> Option<L> {
+        match self {
+            Left(l) => Some(l),
+            Right(_) => None,
+        }
+    }
+
+    /// Returns the right value, if any.
+    ///
+    /// ```
+    /// use fp::either::Either;
+    ///
+    /// assert_eq!(Either::<i32, &str>::Right("one").right(), Some("one"));
+    /// assert_eq!(Either::<i32, &str>::Left(1).right(), None);
+    /// ```
+    pub fn right(self) -> Option<R> {
+        match self {
+            Left(_) => None,
+            Right(r) => Some(r),
+        }
+    }
+
+    /// Borrows both sides.
+    ///
+    /// ```
+    /// use fp::either::Either;
+    ///
+    /// let e: Either<String, i32> = Either::Left("owned".to_string());
+    /// assert_eq!(e.as_ref().map_left(|s| s.len()), Either::Left(5));
+    /// assert!(e.is_left());
+    /// ```
+    pub fn as_ref(&self) -> Either<&L, &R> {
+        match self {
+            Left(l) => Left(l),
+            Right(r) => Right(r),
+        }
+    }
+
+    /// Transforms the left value, leaving a `Right` untouched.
+    ///
+    /// ```
+    /// use fp::either::Either;
+    ///
+    /// let e: Either<&str, i32> = Either::Left("abc");
+    /// assert_eq!(e.map_left(str::len), Either::Left(3));
+    /// ```
+    pub fn map_left<M, F: FnOnce(L) -> M>(self, f: F) -> Either<M, R> {
+        match self {
+            Left(l) => Left(f(l)),
+            Right(r) => Right(r),
+        }
+    }
+
+    /// Transforms the right value, leaving a `Left` untouched.
+    ///
+    /// ```
+    /// use fp::either::Either;
+    ///
+    /// let e: Either<&str, i32> = Either::Right(2);
+    /// assert_eq!(e.map_right(|n| n * 10), Either::Right(20));
+    /// ```
+    pub fn map_right<S, F: FnOnce(R) -> S>(self, f: F) -> Either<L, S> {
+        match self {
+            Left(l) => Left(l),
+            Right(r) => Right(f(r)),
+        }
+    }
+
+    /// Chains a computation on the right value (right-biased bind).
+    ///
+    /// ```
+    /// use fp::either::Either;
+    ///
+    /// let half = |n: i32| if n % 2 == 0 { Either::Right(n / 2) } else { Either::Left(format!("{n} is odd")) };
+    /// assert_eq!(Either::Right(8).and_then(half).and_then(half), Either::Right(2));
+    /// assert_eq!(Either::Right(6).and_then(half).and_then(half), Either::Left("3 is odd".to_string()));
+    /// ```
+    pub fn and_then<S, F: FnOnce(R) -> Either<L, S>>(self, f: F) -> Either<L, S> {
+        match self {
+            Left(l) => Left(l),
+            Right(r) => f(r),
+        }
+    }
+
+    /// Collapses both sides into one value by handling each case.
+    ///
+    /// ```
+    /// use fp::either::Either;
+    ///
+    /// let describe = |e: Either<i32, &str>| e.either(|n| format!("number {n}"), |s| format!("text {s}"));
+    /// assert_eq!(describe(Either::Left(3)), "number 3");
+    /// assert_eq!(describe(Either::Right("hi")), "text hi");
+    /// ```
+    pub fn either<T, F, G>(self, f: F, g: G) -> T
+    where
+        F: FnOnce(L) -> T,
+        G: FnOnce(R) -> T,
+    {
+        match self {
+            Left(l) => f(l),
+            Right(r) => g(r),
+        }
+    }
+
+    /// Swaps the two sides.
+    ///
+    /// ```
+    /// use fp::either::Either;
+    ///
+    /// assert_eq!(Either::<i32, &str>::Left(1).swap(), Either::Right(1));
+    /// ```
+    pub fn swap(self) -> Either<R, L> {
+        match self {
+            Left(l) => Right(l),
+            Right(r) => Left(r),
+        }
+    }
+
+    /// Converts to a `Result`, treating `Right` as success.
+    ///
+    /// ```
+    /// use fp::either::Either;
+    ///
+    /// assert_eq!(Either::<&str, i32>::Right(1).into_result(), Ok(1));
+    /// assert_eq!(Either::<&str, i32>::Left("no").into_result(), Err("no"));
+    /// ```
+    pub fn into_result(self) -> Result<R, L> {
+        self.either(Err, Ok)
+    }
+}
+
+/// `Ok` becomes `Right`, `Err` becomes `Left`.
+///
+/// ```
+/// use fp::either::Either;
+///
+/// let parsed: Either<_, i32> = "12".parse::<i32>().into();
+/// assert_eq!(parsed, Either::Right(12));
+/// assert_eq!(parsed.into_result(), Ok(12));
+/// ```
+impl<L, R> From<Result<R, L>> for Either<L, R> {
+    fn from(result: Result<R, L>) -> Self {
+        match result {
+            Ok(r) => Right(r),
+            Err(l) => Left(l),
+        }
+    }
+}
+
+impl<L, R> From<Either<L, R>> for Result<R, L> {
+    fn from(either: Either<L, R>) -> Self {
+        either.into_result()
+    }
+}
+
+/// Either of two iterators with the same item type is itself an iterator,
+/// which lets a function return one of two differently-typed sources.
+///
+/// ```
+/// use fp::either::Either;
+///
+/// fn numbers(evens: bool) -> impl Iterator<Item = u32> {
+///     if evens {
+///         Either::Left((0..10).step_by(2))
+///     } else {
+///         Either::Right([1, 3, 5].into_iter())
+///     }
+/// }
+/// assert_eq!(numbers(true).sum::<u32>(), 20);
+/// assert_eq!(numbers(false).collect::<Vec<_>>(), [1, 3, 5]);
+/// ```
+impl<L, R> Iterator for Either<L, R>
+where
+    L: Iterator,
+    R: Iterator<Item = L::Item>,
+{
+    type Item = L::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Left(l) => l.next(),
+            Right(r) => r.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Left(l) => l.size_hint(),
+            Right(r) => r.size_hint(),
+        }
+    }
+}
@@ -0,0 +1,105 @@
+//! Frequency List Export
+//!
+//! Word counts in the formats other tools read them in, so the analyzer
+//! can feed spell checkers and keyboard prediction. A word frequency list
+//! is one `word count` line per word, the most frequent first, as corpus
+//! tools such as wordfreq load it. A Hunspell dictionary starts with the
+//! number of entries, followed by one word per line; this one is a
+//! skeleton without affix flags, to be paired with an `.aff` file of the
+//! language.
+//!
+//! ```
+//! use fp::analysis::word_counts;
+//! use fp::export::ExportFormat;
+//!
+//! let counts = word_counts("fold map fold filter fold map");
+//! assert_eq!(ExportFormat::WordFreq.render(&counts), "fold 3\nmap 2\nfilter 1\n");
+//! assert_eq!(ExportFormat::Hunspell.render(&counts), "3\nfilter\nfold\nmap\n");
+//! ```
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// A format to export word counts in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// `word count` lines, most frequent first, ties alphabetical
+    WordFreq,
+    /// A Hunspell `.dic` file: the number of words, then the words in
+    /// alphabetical order
+    Hunspell,
+}
+
+impl ExportFormat {
+    /// `counts` in this format, every line ending in a newline
+    ///
+    /// ```
+    /// use fp::analysis::word_counts;
+    /// use fp::export::ExportFormat;
+    ///
+    /// let counts = word_counts("map fold");
+    /// assert_eq!(ExportFormat::WordFreq.render(&counts), "fold 1\nmap 1\n");
+    /// assert_eq!(ExportFormat::Hunspell.render(&counts), "2\nfold\nmap\n");
+    /// ```
+    pub fn render(self, counts: &HashMap<String, usize>) -> String {
+        match self {
+            ExportFormat::WordFreq => frequency_list(counts),
+            ExportFormat::Hunspell => hunspell_dic(counts),
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "wordfreq" => Ok(ExportFormat::WordFreq),
+            "hunspell" => Ok(ExportFormat::Hunspell),
+            _ => Err(format!(
+                "Unknown export format '{}', expected wordfreq or hunspell",
+                s
+            )),
+        }
+    }
+}
+
+/// One `word count` line per word, most frequent first, ties alphabetical
+///
+/// ```
+/// use fp::analysis::word_counts;
+/// use fp::export::frequency_list;
+///
+/// let counts = word_counts("map fold filter map");
+/// assert_eq!(frequency_list(&counts), "map 2\nfilter 1\nfold 1\n");
+/// ```
+pub fn frequency_list(counts: &HashMap<String, usize>) -> String {
+    let mut entries: Vec<(&String, &usize)> = counts.iter().collect();
+    entries.sort_by(|(a, x), (b, y)| y.cmp(x).then_with(|| a.cmp(b)));
+    entries
+        .into_iter()
+        .map(|(word, count)| format!("{} {}\n", word, count))
+        .collect()
+}
+
+/// A Hunspell dictionary of the words of `counts`: their number, then the
+/// words in alphabetical order
+///
+/// A `/` would start the word's affix flags, so it is escaped.
+///
+/// ```
+/// use fp::export::hunspell_dic;
+/// use std::collections::HashMap;
+///
+/// let counts = HashMap::from([("and/or".to_string(), 1)]);
+/// assert_eq!(hunspell_dic(&counts), "1\nand\\/or\n");
+/// ```
+pub fn hunspell_dic(counts: &HashMap<String, usize>) -> String {
+    let mut words: Vec<&String> = counts.keys().collect();
+    words.sort();
+    words
+        .into_iter()
+        .fold(format!("{}\n", counts.len()), |out, word| {
+            out + &word.replace('/', "\\/") + "\n"
+        })
+}
@@ -0,0 +1,104 @@
+//! Arithmetic expression language.
+//!
+//! A small algebraic data type shared by the concepts demo and the
+//! expression tools built on it (such as the zipper).
+
+use crate::random::Rng;
+use crate::trampoline::{Cont, Trampoline, call};
+
+/// # Enum + Pattern Matching Example
+/// Represents a some arithmetic expressions.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Const(i32),
+    Add(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+}
+
+/// Evaluates an arithmetic expression recursively.
+///
+/// ```
+/// use fp::expr::{Expr, eval};
+///
+/// // (2 + 3) * (7 - 4)
+/// let expr = Expr::Mul(
+///     Box::new(Expr::Add(Box::new(Expr::Const(2)), Box::new(Expr::Const(3)))),
+///     Box::new(Expr::Sub(Box::new(Expr::Const(7)), Box::new(Expr::Const(4)))),
+/// );
+/// assert_eq!(eval(&expr), 15);
+/// ```
+pub fn eval(expr: &Expr) -> i32 {
+    match expr {
+        Expr::Const(n) => *n,
+        Expr::Add(a, b) => eval(a) + eval(b),
+        Expr::Mul(a, b) => eval(a) * eval(b),
+        Expr::Sub(a, b) => eval(a) - eval(b),
+    }
+}
+
+/// Evaluates an arithmetic expression without growing the stack.
+///
+/// Gives the same result as [`eval`], but in continuation-passing style on a
+/// [`Trampoline`], so arbitrarily deep expressions are safe.
+///
+/// ```
+/// use fp::expr::{Expr, eval_tramp};
+///
+/// // 1 + (1 + (1 + ...)), 100,000 levels deep
+/// let deep = (0..100_000).fold(Expr::Const(0), |e, _| Expr::Add(Box::new(Expr::Const(1)), Box::new(e)));
+/// assert_eq!(eval_tramp(&deep), 100_000);
+///
+/// // The derived `Drop` is itself recursive, so a tree this deep is leaked
+/// std::mem::forget(deep);
+/// ```
+pub fn eval_tramp(expr: &Expr) -> i32 {
+    eval_cont(expr, Box::new(Trampoline::Done)).run()
+}
+
+fn eval_cont<'a>(expr: &'a Expr, k: Cont<'a, i32, i32>) -> Trampoline<'a, i32> {
+    let (a, b, op): (&Expr, &Expr, fn(i32, i32) -> i32) = match expr {
+        Expr::Const(n) => return call(k, *n),
+        Expr::Add(a, b) => (a, b, |x, y| x + y),
+        Expr::Mul(a, b) => (a, b, |x, y| x * y),
+        Expr::Sub(a, b) => (a, b, |x, y| x - y),
+    };
+    Trampoline::more(move || {
+        eval_cont(
+            a,
+            Box::new(move |x| eval_cont(b, Box::new(move |y| call(k, op(x, y))))),
+        )
+    })
+}
+
+/// Generates a random expression at most `depth` operators deep, with
+/// constants in `0..10`.
+///
+/// The generator is split between the two operands, so the same `rng`
+/// always yields the same expression. Depths up to 3 always evaluate
+/// within `i32`; deeper products may overflow.
+///
+/// ```
+/// use fp::expr::{eval, random_expr};
+/// use fp::random::Rng;
+///
+/// let expr = random_expr(Rng::new(2025), 3);
+/// assert_eq!(expr, random_expr(Rng::new(2025), 3));
+/// assert_eq!(eval(&expr), eval(&random_expr(Rng::new(2025), 3)));
+/// ```
+pub fn random_expr(rng: Rng, depth: u32) -> Expr {
+    let (choice, rng) = rng.next_below(if depth == 0 { 1 } else { 4 });
+    if choice == 0 {
+        return Expr::Const(rng.next_below(10).0 as i32);
+    }
+    let (left, right) = rng.split();
+    let (a, b) = (
+        Box::new(random_expr(left, depth - 1)),
+        Box::new(random_expr(right, depth - 1)),
+    );
+    match choice {
+        1 => Expr::Add(a, b),
+        2 => Expr::Mul(a, b),
+        _ => Expr::Sub(a, b),
+    }
+}
@@ -0,0 +1,135 @@
+//! C Interface
+//!
+//! Exports the analyzer to C, C++ and Python (`ctypes`/`cffi`) callers,
+//! enabled by the `ffi` feature. A report is an opaque pointer: create it
+//! with `analyze_utf8`, read it with `report_json`, and release both with
+//! their free functions.
+//!
+//! ```c
+//! typedef struct { size_t min_length; uint32_t starts_with; size_t top; } AnalyzeOptions;
+//! typedef struct Report Report;
+//!
+//! Report *analyze_utf8(const uint8_t *ptr, size_t len, const AnalyzeOptions *options);
+//! char *report_json(const Report *report);
+//! void json_free(char *json);
+//! void report_free(Report *report);
+//! ```
+//!
+//! ```
+//! use fp::ffi::{AnalyzeOptions, analyze_utf8, json_free, report_free, report_json};
+//! use std::ffi::CStr;
+//!
+//! let text = "fold map fold";
+//! let options = AnalyzeOptions { min_length: 4, starts_with: 0, top: 1 };
+//! unsafe {
+//!     let report = analyze_utf8(text.as_ptr(), text.len(), &options);
+//!     let json = report_json(report);
+//!     assert_eq!(
+//!         CStr::from_ptr(json).to_str().unwrap(),
+//!         r#"{"total_words":2,"unique_words":1,"reading_seconds":1,"speaking_seconds":1,"top_words":[{"word":"fold","count":2}]}"#
+//!     );
+//!     json_free(json);
+//!     report_free(report);
+//! }
+//! ```
+
+use crate::analysis::{self, DEFAULT_TOP, Filters, Report};
+use std::ffi::{CString, c_char};
+use std::ptr;
+
+/// Analysis options as passed from C; zero means "not set"
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AnalyzeOptions {
+    /// Keep words at least this many grapheme clusters long
+    pub min_length: usize,
+    /// Keep words starting with this Unicode scalar value; the C interface
+    /// takes a single character to keep the struct free of pointers
+    pub starts_with: u32,
+    /// How many of the most frequent words to report; 0 means [`DEFAULT_TOP`]
+    pub top: usize,
+}
+
+impl AnalyzeOptions {
+    fn filters(&self) -> Filters {
+        Filters {
+            min_length: (self.min_length > 0).then_some(self.min_length),
+            starts_with: char::from_u32(self.starts_with)
+                .filter(|&c| c != '\0')
+                .map(String::from),
+            ..Filters::default()
+        }
+    }
+
+    fn top(&self) -> usize {
+        if self.top == 0 { DEFAULT_TOP } else { self.top }
+    }
+}
+
+/// Analyze `len` bytes of UTF-8 text at `ptr`.
+///
+/// `options` may be null for the defaults. Returns null if the text is not
+/// valid UTF-8; otherwise the report must be released with [`report_free`].
+///
+/// # Safety
+/// `ptr` must point to `len` readable bytes, and `options` must be null or
+/// point to a valid [`AnalyzeOptions`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn analyze_utf8(
+    ptr: *const u8,
+    len: usize,
+    options: *const AnalyzeOptions,
+) -> *mut Report {
+    let bytes = if len == 0 {
+        &[][..]
+    } else {
+        unsafe { std::slice::from_raw_parts(ptr, len) }
+    };
+    let options = unsafe { options.as_ref() }.copied().unwrap_or_default();
+    match std::str::from_utf8(bytes) {
+        Ok(text) => Box::into_raw(Box::new(analysis::analyze(
+            text,
+            &options.filters(),
+            options.top(),
+        ))),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// The report as a NUL-terminated JSON string, to be released with
+/// [`json_free`]. Returns null for a null report.
+///
+/// # Safety
+/// `report` must be null or a pointer returned by [`analyze_utf8`] that has
+/// not been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn report_json(report: *const Report) -> *mut c_char {
+    unsafe { report.as_ref() }
+        // JSON escapes control characters, so there is no interior NUL
+        .and_then(|report| CString::new(report.to_json()).ok())
+        .map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// Release a string returned by [`report_json`]; null is ignored.
+///
+/// # Safety
+/// `json` must be null or a pointer returned by [`report_json`] that has not
+/// been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn json_free(json: *mut c_char) {
+    if !json.is_null() {
+        drop(unsafe { CString::from_raw(json) });
+    }
+}
+
+/// Release a report returned by [`analyze_utf8`]; null is ignored.
+///
+/// # Safety
+/// `report` must be null or a pointer returned by [`analyze_utf8`] that has
+/// not been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn report_free(report: *mut Report) {
+    if !report.is_null() {
+        drop(unsafe { Box::from_raw(report) });
+    }
+}
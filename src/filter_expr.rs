@@ -0,0 +1,682 @@
+//! Filter Expression Language
+//!
+//! A tiny language for word filters written on the command line, such as
+//! `len(word) >= 5 && !startswith(word, "un")`. Like [`Expr`](crate::expr::Expr)
+//! it is an algebraic data type with a recursive evaluator, but its values
+//! are integers, strings and booleans, and `word` names the token being
+//! filtered. Expressions are type-checked when parsed, so a filter that
+//! parses evaluates to a boolean for every word.
+//!
+//! Operators, loosest first: `||`, `&&`, comparisons (`== != < <= > >=`),
+//! `+ -`, and prefix `! -`. Functions: `len(s)`, `startswith(s, p)`,
+//! `endswith(s, p)` and `contains(s, p)`; like the command-line filters, the
+//! last three compare [case folded](crate::analysis::fold_case) text, while
+//! `==` stays exact.
+//!
+//! ```
+//! use fp::filter_expr::FilterExpr;
+//! use fp::token_filter::TokenFilter;
+//!
+//! let filter = FilterExpr::parse(r#"len(word) >= 5 && !startswith(word, "un")"#).unwrap();
+//! assert!(filter.keep("functional"));
+//! assert!(!filter.keep("undo"));
+//! assert!(!filter.keep("unfold"));
+//! assert!(!filter.keep("UNFOLDING"));
+//!
+//! assert_eq!(
+//!     FilterExpr::parse("len(word) + 1").unwrap_err(),
+//!     "filter must be a boolean, found an integer"
+//! );
+//!
+//! let deep = format!("{}true{}", "!(".repeat(100), ")".repeat(100));
+//! assert!(FilterExpr::parse(&deep).unwrap_err().contains("nested"));
+//! ```
+
+use crate::analysis::fold_case;
+use crate::token_filter::TokenFilter;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// A value of the filter language
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Int(i64),
+    Str(String),
+    Bool(bool),
+}
+
+/// The type of a [`Value`], known before evaluation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Type {
+    Int,
+    Str,
+    Bool,
+}
+
+/// Binary operators
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinOp {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Sub,
+}
+
+/// Built-in functions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Func {
+    Len,
+    StartsWith,
+    EndsWith,
+    Contains,
+}
+
+/// The syntax tree of a filter expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Ast {
+    Lit(Value),
+    Word,
+    Not(Box<Ast>),
+    Neg(Box<Ast>),
+    Bin(BinOp, Box<Ast>, Box<Ast>),
+    Call(Func, Vec<Ast>),
+}
+
+/// A parsed, type-checked boolean filter over words
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterExpr {
+    source: String,
+    ast: Ast,
+}
+
+impl FilterExpr {
+    /// Parse and type-check `source`, which must describe a boolean.
+    ///
+    /// ```
+    /// use fp::filter_expr::FilterExpr;
+    ///
+    /// let filter = FilterExpr::parse(r#"endswith(word, "ing")"#).unwrap();
+    /// assert!(filter.matches("folding"));
+    /// assert!(FilterExpr::parse("len(word) >").is_err());
+    /// assert_eq!(
+    ///     FilterExpr::parse(r#"len(word) == "five""#).unwrap_err(),
+    ///     "'==' expects an integer, found a string"
+    /// );
+    /// ```
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let ast = Parser::new(source)?.parse()?;
+        match type_of(&ast)? {
+            Type::Bool => Ok(FilterExpr {
+                source: source.to_string(),
+                ast,
+            }),
+            other => Err(format!(
+                "filter must be a boolean, found {}",
+                other.describe()
+            )),
+        }
+    }
+
+    /// Whether `word` satisfies the expression
+    ///
+    /// ```
+    /// use fp::filter_expr::FilterExpr;
+    ///
+    /// let filter = FilterExpr::parse(r#"startswith(word, "re") || len(word) > 8"#).unwrap();
+    /// assert!(filter.matches("reduce"));
+    /// assert!(filter.matches("composition"));
+    /// assert!(!filter.matches("fold"));
+    /// ```
+    pub fn matches(&self, word: &str) -> bool {
+        let value = eval(&self.ast, word);
+        debug_assert!(value.is_ok(), "a type-checked filter failed: {:?}", value);
+        value == Ok(Value::Bool(true))
+    }
+
+    /// The text the expression was parsed from
+    ///
+    /// ```
+    /// use fp::filter_expr::FilterExpr;
+    ///
+    /// let source = "len(word) >= 5";
+    /// assert_eq!(FilterExpr::parse(source).unwrap().source(), source);
+    /// ```
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+impl TokenFilter for FilterExpr {
+    fn keep(&self, token: &str) -> bool {
+        self.matches(token)
+    }
+}
+
+impl Value {
+    fn kind(&self) -> Type {
+        match self {
+            Value::Int(_) => Type::Int,
+            Value::Str(_) => Type::Str,
+            Value::Bool(_) => Type::Bool,
+        }
+    }
+}
+
+impl Type {
+    fn describe(self) -> &'static str {
+        match self {
+            Type::Int => "an integer",
+            Type::Str => "a string",
+            Type::Bool => "a boolean",
+        }
+    }
+}
+
+impl BinOp {
+    const ALL: [BinOp; 10] = [
+        BinOp::Or,
+        BinOp::And,
+        BinOp::Eq,
+        BinOp::Ne,
+        BinOp::Lt,
+        BinOp::Le,
+        BinOp::Gt,
+        BinOp::Ge,
+        BinOp::Add,
+        BinOp::Sub,
+    ];
+
+    fn symbol(self) -> &'static str {
+        match self {
+            BinOp::Or => "||",
+            BinOp::And => "&&",
+            BinOp::Eq => "==",
+            BinOp::Ne => "!=",
+            BinOp::Lt => "<",
+            BinOp::Le => "<=",
+            BinOp::Gt => ">",
+            BinOp::Ge => ">=",
+            BinOp::Add => "+",
+            BinOp::Sub => "-",
+        }
+    }
+
+    fn from_symbol(symbol: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|op| op.symbol() == symbol)
+    }
+}
+
+impl Func {
+    const ALL: [Func; 4] = [Func::Len, Func::StartsWith, Func::EndsWith, Func::Contains];
+
+    fn name(self) -> &'static str {
+        match self {
+            Func::Len => "len",
+            Func::StartsWith => "startswith",
+            Func::EndsWith => "endswith",
+            Func::Contains => "contains",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|func| func.name() == name)
+    }
+
+    /// Parameter types and result type
+    fn signature(self) -> (&'static [Type], Type) {
+        match self {
+            Func::Len => (&[Type::Str], Type::Int),
+            Func::StartsWith | Func::EndsWith | Func::Contains => {
+                (&[Type::Str, Type::Str], Type::Bool)
+            }
+        }
+    }
+}
+
+/// The type of an expression, or why it is ill-typed
+///
+/// ```
+/// use fp::filter_expr::{Ast, BinOp, Func, Type, Value, type_of};
+///
+/// let length = Ast::Call(Func::Len, vec![Ast::Word]);
+/// assert_eq!(type_of(&length), Ok(Type::Int));
+///
+/// let five = Box::new(Ast::Lit(Value::Int(5)));
+/// assert_eq!(type_of(&Ast::Bin(BinOp::Ge, Box::new(length), five.clone())), Ok(Type::Bool));
+/// assert_eq!(
+///     type_of(&Ast::Not(five)).unwrap_err(),
+///     "'!' expects a boolean, found an integer"
+/// );
+/// ```
+pub fn type_of(ast: &Ast) -> Result<Type, String> {
+    let expect = |ast: &Ast, expected: Type, context: &str| {
+        let found = type_of(ast)?;
+        if found == expected {
+            Ok(expected)
+        } else {
+            Err(format!(
+                "{} expects {}, found {}",
+                context,
+                expected.describe(),
+                found.describe()
+            ))
+        }
+    };
+    match ast {
+        Ast::Lit(Value::Int(_)) => Ok(Type::Int),
+        Ast::Lit(Value::Str(_)) | Ast::Word => Ok(Type::Str),
+        Ast::Lit(Value::Bool(_)) => Ok(Type::Bool),
+        Ast::Not(inner) => expect(inner, Type::Bool, "'!'"),
+        Ast::Neg(inner) => expect(inner, Type::Int, "'-'"),
+        Ast::Bin(op, l, r) => {
+            let context = format!("'{}'", op.symbol());
+            match op {
+                BinOp::Or | BinOp::And => {
+                    expect(l, Type::Bool, &context)?;
+                    expect(r, Type::Bool, &context)
+                }
+                BinOp::Add | BinOp::Sub => {
+                    expect(l, Type::Int, &context)?;
+                    expect(r, Type::Int, &context)
+                }
+                _ => {
+                    expect(r, type_of(l)?, &context)?;
+                    Ok(Type::Bool)
+                }
+            }
+        }
+        Ast::Call(func, args) => {
+            let (params, result) = func.signature();
+            if args.len() != params.len() {
+                return Err(format!(
+                    "{} takes {} argument(s), found {}",
+                    func.name(),
+                    params.len(),
+                    args.len()
+                ));
+            }
+            args.iter()
+                .zip(params)
+                .try_for_each(|(arg, &param)| expect(arg, param, func.name()).map(drop))?;
+            Ok(result)
+        }
+    }
+}
+
+/// Evaluates an expression for one word, or says where it is ill-typed
+///
+/// A filter from [`FilterExpr::parse`] is type-checked, so only trees
+/// built by hand can fail, and they fail with the error [`type_of`] gives.
+///
+/// ```
+/// use fp::filter_expr::{Ast, BinOp, Func, Value, eval};
+///
+/// let length_plus_one = Ast::Bin(
+///     BinOp::Add,
+///     Box::new(Ast::Call(Func::Len, vec![Ast::Word])),
+///     Box::new(Ast::Lit(Value::Int(1))),
+/// );
+/// assert_eq!(eval(&length_plus_one, "fold"), Ok(Value::Int(5)));
+///
+/// let not_a_number = Ast::Neg(Box::new(Ast::Word));
+/// assert_eq!(
+///     eval(&not_a_number, "fold").unwrap_err(),
+///     "'-' expects an integer, found a string"
+/// );
+/// assert!(eval(&Ast::Call(Func::Len, vec![]), "fold").is_err());
+/// ```
+pub fn eval(ast: &Ast, word: &str) -> Result<Value, String> {
+    Ok(match ast {
+        Ast::Lit(value) => value.clone(),
+        Ast::Word => Value::Str(word.to_string()),
+        Ast::Not(inner) => Value::Bool(!eval_bool(inner, word, "'!'")?),
+        Ast::Neg(inner) => Value::Int(eval_int(inner, word, "'-'")?.wrapping_neg()),
+        Ast::Bin(op @ (BinOp::Or | BinOp::And), l, r) => {
+            let context = format!("'{}'", op.symbol());
+            let l = eval_bool(l, word, &context)?;
+            // Short-circuits, so an ill-typed right side is only noticed
+            // when it is reached
+            Value::Bool(match op {
+                BinOp::Or => l || eval_bool(r, word, &context)?,
+                _ => l && eval_bool(r, word, &context)?,
+            })
+        }
+        Ast::Bin(op @ (BinOp::Add | BinOp::Sub), l, r) => {
+            let context = format!("'{}'", op.symbol());
+            let (l, r) = (eval_int(l, word, &context)?, eval_int(r, word, &context)?);
+            Value::Int(match op {
+                BinOp::Add => l.wrapping_add(r),
+                _ => l.wrapping_sub(r),
+            })
+        }
+        Ast::Bin(op, l, r) => {
+            let ordering = match (eval(l, word)?, eval(r, word)?) {
+                (Value::Int(a), Value::Int(b)) => a.cmp(&b),
+                (Value::Str(a), Value::Str(b)) => a.cmp(&b),
+                (Value::Bool(a), Value::Bool(b)) => a.cmp(&b),
+                (l, r) => return Err(mismatch(&format!("'{}'", op.symbol()), l.kind(), &r)),
+            };
+            Value::Bool(match op {
+                BinOp::Eq => ordering.is_eq(),
+                BinOp::Ne => ordering.is_ne(),
+                BinOp::Lt => ordering.is_lt(),
+                BinOp::Le => ordering.is_le(),
+                BinOp::Gt => ordering.is_gt(),
+                _ => ordering.is_ge(),
+            })
+        }
+        Ast::Call(func, args) => {
+            let (params, _) = func.signature();
+            if args.len() != params.len() {
+                return Err(format!(
+                    "{} takes {} argument(s), found {}",
+                    func.name(),
+                    params.len(),
+                    args.len()
+                ));
+            }
+            let strs = args
+                .iter()
+                .map(|arg| eval_str(arg, word, func.name()))
+                .collect::<Result<Vec<String>, String>>()?;
+            let folded = |i: usize| fold_case(&strs[i]);
+            match func {
+                Func::Len => Value::Int(strs[0].chars().count() as i64),
+                Func::StartsWith => Value::Bool(folded(0).starts_with(&folded(1))),
+                Func::EndsWith => Value::Bool(folded(0).ends_with(&folded(1))),
+                Func::Contains => Value::Bool(folded(0).contains(&folded(1))),
+            }
+        }
+    })
+}
+
+fn eval_bool(ast: &Ast, word: &str, context: &str) -> Result<bool, String> {
+    match eval(ast, word)? {
+        Value::Bool(b) => Ok(b),
+        other => Err(mismatch(context, Type::Bool, &other)),
+    }
+}
+
+fn eval_int(ast: &Ast, word: &str, context: &str) -> Result<i64, String> {
+    match eval(ast, word)? {
+        Value::Int(n) => Ok(n),
+        other => Err(mismatch(context, Type::Int, &other)),
+    }
+}
+
+fn eval_str(ast: &Ast, word: &str, context: &str) -> Result<String, String> {
+    match eval(ast, word)? {
+        Value::Str(s) => Ok(s),
+        other => Err(mismatch(context, Type::Str, &other)),
+    }
+}
+
+/// The error of `context` given `found` where it expects a value of type
+/// `expected`, worded as [`type_of`] words it
+fn mismatch(context: &str, expected: Type, found: &Value) -> String {
+    format!(
+        "{} expects {}, found {}",
+        context,
+        expected.describe(),
+        found.kind().describe()
+    )
+}
+
+/// Lexical tokens
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Int(i64),
+    Str(String),
+    Ident(String),
+    Op(&'static str),
+    LParen,
+    RParen,
+    Comma,
+}
+
+/// Split `source` into tokens
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    // Two-character operators first, so "<=" is not read as "<"
+    const OPERATORS: [&str; 11] = ["||", "&&", "==", "!=", "<=", ">=", "<", ">", "+", "-", "!"];
+    let mut chars = source.chars().peekable();
+    let mut tokens = Vec::new();
+    while let Some(&c) = chars.peek() {
+        let token = match c {
+            c if c.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            '(' | ')' | ',' => {
+                chars.next();
+                match c {
+                    '(' => Token::LParen,
+                    ')' => Token::RParen,
+                    _ => Token::Comma,
+                }
+            }
+            '"' => Token::Str(string_literal(&mut chars)?),
+            c if c.is_ascii_digit() => {
+                let digits = take_while(&mut chars, |c| c.is_ascii_digit());
+                Token::Int(
+                    digits
+                        .parse()
+                        .map_err(|_| format!("number too large: {}", digits))?,
+                )
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                Token::Ident(take_while(&mut chars, |c| c.is_alphanumeric() || c == '_'))
+            }
+            _ => {
+                let rest: String = chars.clone().take(2).collect();
+                let op = OPERATORS
+                    .into_iter()
+                    .find(|op| rest.starts_with(op))
+                    .ok_or_else(|| format!("unexpected character '{}'", c))?;
+                chars.nth(op.len() - 1);
+                Token::Op(op)
+            }
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+fn take_while(chars: &mut Peekable<Chars>, keep: impl Fn(char) -> bool) -> String {
+    std::iter::from_fn(|| chars.next_if(|&c| keep(c))).collect()
+}
+
+/// A double-quoted string with `\"` and `\\` escapes
+fn string_literal(chars: &mut Peekable<Chars>) -> Result<String, String> {
+    chars.next(); // opening quote
+    let mut literal = String::new();
+    loop {
+        match chars.next() {
+            Some('"') => return Ok(literal),
+            Some('\\') => literal.extend(chars.next()),
+            Some(c) => literal.push(c),
+            None => return Err("unterminated string".to_string()),
+        }
+    }
+}
+
+/// How deeply an expression may nest: parentheses, prefix operators and
+/// operator chains all count. Parsing, type-checking and evaluating
+/// recurse once per level, so this keeps untrusted filters from
+/// overflowing the stack.
+const MAX_DEPTH: usize = 64;
+
+/// Recursive-descent parser, one method per precedence level
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn new(source: &str) -> Result<Self, String> {
+        Ok(Parser {
+            tokens: tokenize(source)?,
+            pos: 0,
+            depth: 0,
+        })
+    }
+
+    fn parse(mut self) -> Result<Ast, String> {
+        let ast = self.or()?;
+        match self.peek() {
+            None => Ok(ast),
+            Some(token) => Err(format!("unexpected {:?}", token)),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    /// Go one level deeper, failing past [`MAX_DEPTH`]
+    fn deeper(&mut self) -> Result<(), String> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(format!(
+                "expression nested more than {} levels deep",
+                MAX_DEPTH
+            ));
+        }
+        Ok(())
+    }
+
+    /// Parse with `parse` one level deeper
+    fn nested(&mut self, parse: fn(&mut Self) -> Result<Ast, String>) -> Result<Ast, String> {
+        let depth = self.depth;
+        self.deeper()?;
+        let ast = parse(self)?;
+        self.depth = depth;
+        Ok(ast)
+    }
+
+    /// Consume the operator if it is one of `ops`
+    fn op(&mut self, ops: &[&str]) -> Option<&'static str> {
+        match self.peek() {
+            Some(Token::Op(op)) if ops.contains(op) => {
+                let op = *op;
+                self.pos += 1;
+                Some(op)
+            }
+            _ => None,
+        }
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), String> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            found => Err(format!("expected {:?}, found {:?}", expected, found)),
+        }
+    }
+
+    /// Consume the binary operator if it is one of `ops`
+    fn bin_op(&mut self, ops: &[BinOp]) -> Option<BinOp> {
+        let symbols: Vec<&str> = ops.iter().map(|op| op.symbol()).collect();
+        self.op(&symbols).and_then(BinOp::from_symbol)
+    }
+
+    /// A left-associative chain of `ops` over `operand`
+    fn chain(
+        &mut self,
+        ops: &[BinOp],
+        operand: fn(&mut Self) -> Result<Ast, String>,
+    ) -> Result<Ast, String> {
+        let depth = self.depth;
+        let mut left = operand(self)?;
+        while let Some(op) = self.bin_op(ops) {
+            // Each operator nests the chain so far one level deeper
+            self.deeper()?;
+            left = Ast::Bin(op, Box::new(left), Box::new(operand(self)?));
+        }
+        self.depth = depth;
+        Ok(left)
+    }
+
+    fn or(&mut self) -> Result<Ast, String> {
+        self.chain(&[BinOp::Or], Self::and)
+    }
+
+    fn and(&mut self) -> Result<Ast, String> {
+        self.chain(&[BinOp::And], Self::comparison)
+    }
+
+    /// Comparisons do not chain: `a < b < c` is an error
+    fn comparison(&mut self) -> Result<Ast, String> {
+        use BinOp::*;
+        let left = self.sum()?;
+        match self.bin_op(&[Eq, Ne, Lt, Le, Gt, Ge]) {
+            Some(op) => Ok(Ast::Bin(op, Box::new(left), Box::new(self.sum()?))),
+            None => Ok(left),
+        }
+    }
+
+    fn sum(&mut self) -> Result<Ast, String> {
+        self.chain(&[BinOp::Add, BinOp::Sub], Self::unary)
+    }
+
+    fn unary(&mut self) -> Result<Ast, String> {
+        match self.op(&["!", "-"]) {
+            Some("!") => Ok(Ast::Not(Box::new(self.nested(Self::unary)?))),
+            Some(_) => Ok(Ast::Neg(Box::new(self.nested(Self::unary)?))),
+            None => self.atom(),
+        }
+    }
+
+    fn atom(&mut self) -> Result<Ast, String> {
+        match self.next() {
+            Some(Token::Int(n)) => Ok(Ast::Lit(Value::Int(n))),
+            Some(Token::Str(s)) => Ok(Ast::Lit(Value::Str(s))),
+            Some(Token::LParen) => {
+                let inner = self.nested(Self::or)?;
+                self.expect(Token::RParen)?;
+                Ok(inner)
+            }
+            Some(Token::Ident(name)) => match name.as_str() {
+                "word" => Ok(Ast::Word),
+                "true" => Ok(Ast::Lit(Value::Bool(true))),
+                "false" => Ok(Ast::Lit(Value::Bool(false))),
+                _ => {
+                    let func =
+                        Func::from_name(&name).ok_or_else(|| format!("unknown name '{}'", name))?;
+                    self.expect(Token::LParen)?;
+                    let args = self.arguments()?;
+                    Ok(Ast::Call(func, args))
+                }
+            },
+            found => Err(format!("expected a value, found {:?}", found)),
+        }
+    }
+
+    /// Comma-separated arguments up to the closing parenthesis
+    fn arguments(&mut self) -> Result<Vec<Ast>, String> {
+        let mut args = Vec::new();
+        if self.peek() == Some(&Token::RParen) {
+            self.pos += 1;
+            return Ok(args);
+        }
+        loop {
+            args.push(self.nested(Self::or)?);
+            match self.next() {
+                Some(Token::Comma) => continue,
+                Some(Token::RParen) => return Ok(args),
+                found => return Err(format!("expected ',' or ')', found {:?}", found)),
+            }
+        }
+    }
+}
@@ -0,0 +1,306 @@
+//! Fuzzy Search
+//!
+//! Finds the words of a vocabulary within a few typos of a query. The
+//! Levenshtein distance counts the fewest single-character insertions,
+//! deletions and substitutions turning one word into another, computed by
+//! dynamic programming one row at a time. Comparing the query with every
+//! word works for small vocabularies; a [`BkTree`] indexes large ones by
+//! distance, and since the distance is a metric, the triangle inequality
+//! lets a search skip every subtree too far from the query to hold a match.
+//!
+//! The same table, read differently, tells whether a word starts with, ends
+//! with or contains a pattern give or take a few edits, which lets filters
+//! tolerate the typos of OCR'd text.
+//!
+//! ```
+//! use fp::fuzzy::BkTree;
+//!
+//! let tree: BkTree<usize> = [("fold", 5), ("foldl", 1), ("filter", 2), ("map", 4)].into_iter().collect();
+//! let matches: Vec<(&str, usize)> = tree.find("folt", 2).into_iter().map(|(word, distance, _)| (word, distance)).collect();
+//! assert_eq!(matches, [("fold", 1), ("foldl", 2)]);
+//! ```
+
+use std::collections::BTreeMap;
+use std::collections::btree_map::Entry;
+
+/// The edit distance `--fuzzy` allows unless `--max-dist` says otherwise
+pub const DEFAULT_MAX_DIST: usize = 2;
+
+/// Characters of a pattern per edit it tolerates; see [`tolerance`]
+pub const CHARS_PER_EDIT: usize = 3;
+
+/// The Levenshtein distance between `a` and `b`, counted in characters
+///
+/// ```
+/// use fp::fuzzy::levenshtein;
+///
+/// assert_eq!(levenshtein("kitten", "sitting"), 3);
+/// assert_eq!(levenshtein("fold", "fold"), 0);
+/// assert_eq!(levenshtein("", "map"), 3);
+/// assert_eq!(levenshtein("café", "cafe"), 1);
+/// ```
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let row = distances(a, b, false);
+    row[row.len() - 1]
+}
+
+/// The distances from `pattern` to every prefix of `text`, shortest prefix
+/// first; with `anywhere`, to every substring ending where the prefix does
+fn distances(pattern: &str, text: &str, anywhere: bool) -> Vec<usize> {
+    let text: Vec<char> = text.chars().collect();
+    // Row i holds the distances from the first i characters of `pattern`;
+    // a match starting anywhere costs nothing to skip to
+    let first: Vec<usize> = if anywhere {
+        vec![0; text.len() + 1]
+    } else {
+        (0..=text.len()).collect()
+    };
+    pattern.chars().enumerate().fold(first, |above, (i, cp)| {
+        text.iter()
+            .enumerate()
+            .fold(vec![i + 1], |mut row, (j, &ct)| {
+                let substitute = above[j] + usize::from(cp != ct);
+                let delete = above[j + 1] + 1;
+                let insert = row[j] + 1;
+                row.push(substitute.min(delete).min(insert));
+                row
+            })
+    })
+}
+
+/// The edits a match of `pattern` may be off by when `max_dist` are
+/// allowed: one per [`CHARS_PER_EDIT`] characters of it, at most
+/// `max_dist`, so that short words such as "of" still match only exactly
+///
+/// ```
+/// use fp::fuzzy::tolerance;
+///
+/// assert_eq!(tolerance("of", 1), 0);
+/// assert_eq!(tolerance("the", 1), 1);
+/// assert_eq!(tolerance("functional", 2), 2);
+/// ```
+pub fn tolerance(pattern: &str, max_dist: usize) -> usize {
+    max_dist.min(pattern.chars().count() / CHARS_PER_EDIT)
+}
+
+/// Whether `word` starts with `prefix` give or take `max_dist` edits
+///
+/// ```
+/// use fp::fuzzy::fuzzy_starts_with;
+///
+/// assert!(fuzzy_starts_with("functional", "fnuc", 2));
+/// assert!(!fuzzy_starts_with("functional", "fnuc", 1));
+/// ```
+pub fn fuzzy_starts_with(word: &str, prefix: &str, max_dist: usize) -> bool {
+    if max_dist == 0 {
+        return word.starts_with(prefix);
+    }
+    distances(prefix, word, false)
+        .into_iter()
+        .any(|distance| distance <= max_dist)
+}
+
+/// Whether `word` ends with `suffix` give or take `max_dist` edits
+///
+/// ```
+/// use fp::fuzzy::fuzzy_ends_with;
+///
+/// assert!(fuzzy_ends_with("running", "img", 1));
+/// assert!(!fuzzy_ends_with("running", "ed", 1));
+/// ```
+pub fn fuzzy_ends_with(word: &str, suffix: &str, max_dist: usize) -> bool {
+    if max_dist == 0 {
+        return word.ends_with(suffix);
+    }
+    let reversed = |s: &str| s.chars().rev().collect::<String>();
+    fuzzy_starts_with(&reversed(word), &reversed(suffix), max_dist)
+}
+
+/// Whether `word` contains `pattern` give or take `max_dist` edits
+///
+/// ```
+/// use fp::fuzzy::fuzzy_contains;
+///
+/// assert!(fuzzy_contains("dysfunctional", "functi0n", 1));
+/// assert!(!fuzzy_contains("dysfunctional", "fractal", 1));
+/// ```
+pub fn fuzzy_contains(word: &str, pattern: &str, max_dist: usize) -> bool {
+    if max_dist == 0 {
+        return word.contains(pattern);
+    }
+    distances(pattern, word, true)
+        .into_iter()
+        .any(|distance| distance <= max_dist)
+}
+
+/// The words of `vocabulary` within `max_dist` edits of `query`, with their
+/// distances, nearest first and then alphabetical; compares every word
+///
+/// ```
+/// use fp::fuzzy::within;
+///
+/// assert_eq!(within("mop", ["map", "fold", "mob", "mop"], 1), [("mop", 0), ("map", 1), ("mob", 1)]);
+/// ```
+pub fn within<'a>(
+    query: &str,
+    vocabulary: impl IntoIterator<Item = &'a str>,
+    max_dist: usize,
+) -> Vec<(&'a str, usize)> {
+    let mut matches: Vec<(&str, usize)> = vocabulary
+        .into_iter()
+        .map(|word| (word, levenshtein(query, word)))
+        .filter(|&(_, distance)| distance <= max_dist)
+        .collect();
+    matches.sort_by(|(a, x), (b, y)| x.cmp(y).then_with(|| a.cmp(b)));
+    matches
+}
+
+/// A Burkhard-Keller tree: a map from words to values indexed by
+/// Levenshtein distance
+///
+/// Each child is keyed by its distance to the parent's word. A word at
+/// distance `d` from a node's word can only match a query within `max_dist`
+/// edits if `d` is within `max_dist` of the query's own distance to the
+/// node, so [`find`](BkTree::find) descends into those children alone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BkTree<V> {
+    root: Option<Node<V>>,
+    len: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Node<V> {
+    word: String,
+    value: V,
+    children: BTreeMap<usize, Node<V>>,
+}
+
+impl<V> Default for BkTree<V> {
+    fn default() -> Self {
+        BkTree { root: None, len: 0 }
+    }
+}
+
+impl<V> BkTree<V> {
+    /// An empty tree
+    ///
+    /// ```
+    /// use fp::fuzzy::BkTree;
+    ///
+    /// let tree: BkTree<usize> = BkTree::new();
+    /// assert!(tree.find("fold", 2).is_empty());
+    /// ```
+    pub fn new() -> Self {
+        BkTree::default()
+    }
+
+    /// How many words the tree holds
+    ///
+    /// ```
+    /// use fp::fuzzy::BkTree;
+    ///
+    /// let mut tree = BkTree::new();
+    /// tree.insert("fold", 1);
+    /// tree.insert("map", 2);
+    /// tree.insert("fold", 3); // replaces, does not add
+    /// assert_eq!(tree.len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the tree holds no words
+    ///
+    /// ```
+    /// use fp::fuzzy::BkTree;
+    ///
+    /// let mut tree = BkTree::new();
+    /// assert!(tree.is_empty());
+    /// tree.insert("fold", ());
+    /// assert!(!tree.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Store `value` under `word`, returning the value it replaces
+    ///
+    /// ```
+    /// use fp::fuzzy::BkTree;
+    ///
+    /// let mut tree = BkTree::new();
+    /// assert_eq!(tree.insert("fold", 1), None);
+    /// assert_eq!(tree.insert("fold", 2), Some(1));
+    /// assert_eq!(tree.len(), 1);
+    /// ```
+    pub fn insert(&mut self, word: &str, value: V) -> Option<V> {
+        let Some(mut node) = self.root.as_mut() else {
+            self.root = Some(Node::leaf(word, value));
+            self.len = 1;
+            return None;
+        };
+        loop {
+            let distance = levenshtein(word, &node.word);
+            if distance == 0 {
+                return Some(std::mem::replace(&mut node.value, value));
+            }
+            match node.children.entry(distance) {
+                Entry::Occupied(child) => node = child.into_mut(),
+                Entry::Vacant(slot) => {
+                    slot.insert(Node::leaf(word, value));
+                    self.len += 1;
+                    return None;
+                }
+            }
+        }
+    }
+
+    /// The words within `max_dist` edits of `query`, with their distances
+    /// and values, nearest first and then alphabetical
+    ///
+    /// ```
+    /// use fp::fuzzy::BkTree;
+    ///
+    /// let mut tree = BkTree::new();
+    /// for (count, word) in ["fold", "food", "map", "folds"].into_iter().enumerate() {
+    ///     tree.insert(word, count);
+    /// }
+    /// assert_eq!(tree.find("fold", 1), [("fold", 0, &0), ("folds", 1, &3), ("food", 1, &1)]);
+    /// assert!(tree.find("filter", 1).is_empty());
+    /// ```
+    pub fn find(&self, query: &str, max_dist: usize) -> Vec<(&str, usize, &V)> {
+        let mut matches = Vec::new();
+        let mut pending: Vec<&Node<V>> = self.root.iter().collect();
+        while let Some(node) = pending.pop() {
+            let distance = levenshtein(query, &node.word);
+            if distance <= max_dist {
+                matches.push((node.word.as_str(), distance, &node.value));
+            }
+            let near = distance.saturating_sub(max_dist)..=distance + max_dist;
+            pending.extend(node.children.range(near).map(|(_, child)| child));
+        }
+        matches.sort_by(|(a, x, _), (b, y, _)| x.cmp(y).then_with(|| a.cmp(b)));
+        matches
+    }
+}
+
+impl<V> Node<V> {
+    fn leaf(word: &str, value: V) -> Self {
+        Node {
+            word: word.to_string(),
+            value,
+            children: BTreeMap::new(),
+        }
+    }
+}
+
+impl<S: AsRef<str>, V> FromIterator<(S, V)> for BkTree<V> {
+    fn from_iter<I: IntoIterator<Item = (S, V)>>(items: I) -> Self {
+        items
+            .into_iter()
+            .fold(BkTree::new(), |mut tree, (word, value)| {
+                tree.insert(word.as_ref(), value);
+                tree
+            })
+    }
+}
@@ -0,0 +1,117 @@
+//! Word Count Goals
+//!
+//! Progress toward a target length, for writers working to a word count
+//! (a 50,000-word novel in a month, a 2,000-word essay), and the pace at
+//! which the words are being written.
+//!
+//! ```
+//! use fp::goal::Progress;
+//!
+//! let progress = Progress::new(12_500, 50_000);
+//! assert_eq!(progress.remaining(), 37_500);
+//! assert_eq!(progress.to_string(), "12500 / 50000 words (25.0%), 37500 to go");
+//! assert_eq!(Progress::new(51_000, 50_000).to_string(), "51000 / 50000 words (102.0%), goal reached");
+//! ```
+
+use std::fmt;
+use std::time::Duration;
+
+/// How far `words` are from a `goal`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// Words written so far
+    pub words: usize,
+    /// Words to reach
+    pub goal: usize,
+}
+
+impl Progress {
+    /// `words` written toward `goal`
+    ///
+    /// ```
+    /// use fp::goal::Progress;
+    ///
+    /// let progress = Progress::new(500, 2_000);
+    /// assert_eq!((progress.words, progress.goal), (500, 2_000));
+    /// ```
+    pub fn new(words: usize, goal: usize) -> Self {
+        Progress { words, goal }
+    }
+
+    /// `words / goal`, above 1 once the goal is passed; a goal of 0 is
+    /// always reached
+    ///
+    /// ```
+    /// use fp::goal::Progress;
+    ///
+    /// assert_eq!(Progress::new(500, 2_000).fraction(), 0.25);
+    /// assert_eq!(Progress::new(3_000, 2_000).fraction(), 1.5);
+    /// assert_eq!(Progress::new(0, 0).fraction(), 1.0);
+    /// ```
+    pub fn fraction(&self) -> f64 {
+        match self.goal {
+            0 => 1.0,
+            goal => self.words as f64 / goal as f64,
+        }
+    }
+
+    /// Words still to write, 0 once the goal is reached
+    ///
+    /// ```
+    /// use fp::goal::Progress;
+    ///
+    /// assert_eq!(Progress::new(500, 2_000).remaining(), 1_500);
+    /// assert_eq!(Progress::new(3_000, 2_000).remaining(), 0);
+    /// ```
+    pub fn remaining(&self) -> usize {
+        self.goal.saturating_sub(self.words)
+    }
+
+    /// Whether at least `goal` words are written
+    ///
+    /// ```
+    /// use fp::goal::Progress;
+    ///
+    /// assert!(!Progress::new(1_999, 2_000).is_reached());
+    /// assert!(Progress::new(2_000, 2_000).is_reached());
+    /// ```
+    pub fn is_reached(&self) -> bool {
+        self.words >= self.goal
+    }
+}
+
+impl fmt::Display for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} / {} words ({:.1}%), ",
+            self.words,
+            self.goal,
+            self.fraction() * 100.0
+        )?;
+        if self.is_reached() {
+            write!(f, "goal reached")
+        } else {
+            write!(f, "{} to go", self.remaining())
+        }
+    }
+}
+
+/// Words written per minute going from `from` to `to` words in `elapsed`;
+/// negative when more was deleted than written, 0 before any time passed
+///
+/// ```
+/// use std::time::Duration;
+/// use fp::goal::words_per_minute;
+///
+/// assert_eq!(words_per_minute(1000, 1300, Duration::from_secs(600)), 30.0);
+/// assert_eq!(words_per_minute(1000, 900, Duration::from_secs(60)), -100.0);
+/// assert_eq!(words_per_minute(1000, 1300, Duration::ZERO), 0.0);
+/// ```
+pub fn words_per_minute(from: usize, to: usize, elapsed: Duration) -> f64 {
+    let minutes = elapsed.as_secs_f64() / 60.0;
+    if minutes == 0.0 {
+        return 0.0;
+    }
+    (to as f64 - from as f64) / minutes
+}
@@ -0,0 +1,57 @@
+//! Hashers for Counting
+//!
+//! Every word counted is hashed at least once, so on large texts the
+//! frequency map's hasher dominates the profile. std's default, SipHash
+//! 1-3, resists collision attacks (HashDoS) but is slow on short keys such
+//! as words. FxHash (from rustc) and aHash are much faster; FxHash is the
+//! simplest and weakest, aHash keeps a random per-map seed. The counting
+//! functions take the hasher as a [`BuildHasher`](std::hash::BuildHasher)
+//! type parameter, and [`HashAlgorithm`] picks one at runtime.
+//!
+//! ```
+//! use fp::analysis::{Filters, borrowed_counts_with_hasher};
+//! use fp::hashing::{AHashBuilder, FxBuilder, HashAlgorithm};
+//!
+//! let text = "fold map\nfold";
+//! let fx = borrowed_counts_with_hasher::<FxBuilder>(text, &Filters::default());
+//! let ahash = borrowed_counts_with_hasher::<AHashBuilder>(text, &Filters::default());
+//! assert_eq!(fx["fold"], 2);
+//! assert_eq!(ahash["fold"], 2);
+//! assert_eq!("fx".parse(), Ok(HashAlgorithm::Fx));
+//! ```
+
+use std::str::FromStr;
+
+/// Builds FxHash hashers: fast, unseeded and not collision resistant
+pub type FxBuilder = rustc_hash::FxBuildHasher;
+
+/// Builds aHash hashers: fast, with a random seed per map
+pub type AHashBuilder = ahash::RandomState;
+
+/// Builds SipHash 1-3 hashers, std's default
+pub type SipBuilder = std::collections::hash_map::RandomState;
+
+/// Which hasher the frequency map uses
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HashAlgorithm {
+    /// std's SipHash 1-3
+    #[default]
+    Sip,
+    /// FxHash, as used by rustc
+    Fx,
+    /// aHash
+    AHash,
+}
+
+impl FromStr for HashAlgorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sip" => Ok(HashAlgorithm::Sip),
+            "fx" => Ok(HashAlgorithm::Fx),
+            "ahash" => Ok(HashAlgorithm::AHash),
+            _ => Err(format!("Unknown hasher '{}', expected ahash, fx or sip", s)),
+        }
+    }
+}
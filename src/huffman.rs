@@ -0,0 +1,242 @@
+//! Huffman Coding
+//!
+//! A Huffman code gives every symbol a string of bits, shorter the more
+//! frequent the symbol, such that no code is the prefix of another and the
+//! bits therefore decode without separators. It is built by repeatedly
+//! merging the two least frequent subtrees into one, until a single tree
+//! remains whose paths are the codes (Huffman, 1952). No prefix code spends
+//! fewer bits on the text it was built from, and it comes within one bit
+//! per symbol of the entropy, the lower bound for any code.
+//!
+//! Symbols can be anything ordered: the characters of a text, or the words
+//! the analyzer counted.
+//!
+//! ```
+//! use fp::huffman::HuffmanCode;
+//!
+//! let text = "abracadabra";
+//! let code = HuffmanCode::from_symbols(text.chars()).unwrap();
+//! let bits = code.encode(text.chars()).unwrap();
+//! // 23 bits, against 88 for the text as bytes
+//! assert_eq!(bits.len(), 23);
+//! assert_eq!(code.decode(&bits).unwrap().into_iter().collect::<String>(), text);
+//! ```
+
+use std::borrow::Borrow;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap};
+
+/// A node of the code tree: a symbol, or the nodes its 0 and 1 bits lead to
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node<S> {
+    Leaf(S),
+    Branch(usize, usize),
+}
+
+/// A Huffman code for a set of symbols
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HuffmanCode<S> {
+    /// The tree, children before parents, the root last
+    nodes: Vec<Node<S>>,
+    codes: BTreeMap<S, Vec<bool>>,
+}
+
+impl<S: Ord + Clone> HuffmanCode<S> {
+    /// The code for symbols occurring as often as `frequencies` say, with
+    /// the counts of repeated symbols added up; none without any symbol of
+    /// positive count
+    ///
+    /// Equally frequent subtrees merge in the order of their symbols, so the
+    /// same counts always give the same code. A lone symbol is coded as a
+    /// single 0 bit.
+    ///
+    /// ```
+    /// use fp::huffman::HuffmanCode;
+    ///
+    /// let code = HuffmanCode::new([("fold", 5), ("map", 2), ("filter", 1)]).unwrap();
+    /// assert_eq!(code.code(&"fold").unwrap().len(), 1);
+    /// assert_eq!(code.code(&"filter").unwrap().len(), 2);
+    /// assert!(HuffmanCode::<&str>::new([]).is_none());
+    /// ```
+    pub fn new(frequencies: impl IntoIterator<Item = (S, usize)>) -> Option<Self> {
+        let counts = frequencies
+            .into_iter()
+            .filter(|&(_, count)| count > 0)
+            .fold(BTreeMap::new(), |mut counts, (symbol, count)| {
+                *counts.entry(symbol).or_insert(0) += count;
+                counts
+            });
+        if counts.is_empty() {
+            return None;
+        }
+        // The heap holds (weight, node) pairs, least weight first; nodes
+        // are numbered in the order they are made, which breaks ties
+        let mut nodes = Vec::new();
+        let mut heap = BinaryHeap::new();
+        for (symbol, count) in counts {
+            heap.push(Reverse((count, nodes.len())));
+            nodes.push(Node::Leaf(symbol));
+        }
+        while let (Some(Reverse((a, zero))), Some(Reverse((b, one)))) = (heap.pop(), heap.pop()) {
+            heap.push(Reverse((a + b, nodes.len())));
+            nodes.push(Node::Branch(zero, one));
+        }
+        let mut codes = BTreeMap::new();
+        assign_codes(&nodes, nodes.len() - 1, Vec::new(), &mut codes);
+        Some(HuffmanCode { nodes, codes })
+    }
+
+    /// The code for `symbols` as they occur, counting them first
+    ///
+    /// ```
+    /// use fp::huffman::HuffmanCode;
+    ///
+    /// let code = HuffmanCode::from_symbols("abracadabra".chars()).unwrap();
+    /// // The most frequent symbol gets the shortest code
+    /// assert_eq!(code.code(&'a').unwrap().len(), 1);
+    /// assert!(HuffmanCode::<char>::from_symbols("".chars()).is_none());
+    /// ```
+    pub fn from_symbols(symbols: impl IntoIterator<Item = S>) -> Option<Self> {
+        Self::new(symbols.into_iter().map(|symbol| (symbol, 1)))
+    }
+
+    /// The bits of `symbol`, if the code has it
+    ///
+    /// ```
+    /// use fp::huffman::HuffmanCode;
+    ///
+    /// let code = HuffmanCode::new([("fold", 2), ("map", 1), ("filter", 1)]).unwrap();
+    /// assert_eq!(code.code(&"fold").map(<[bool]>::len), Some(1));
+    /// assert_eq!(code.code(&"zip"), None);
+    /// ```
+    pub fn code(&self, symbol: &S) -> Option<&[bool]> {
+        self.codes.get(symbol).map(Vec::as_slice)
+    }
+
+    /// How many symbols the code has
+    ///
+    /// ```
+    /// use fp::huffman::HuffmanCode;
+    ///
+    /// assert_eq!(HuffmanCode::from_symbols("fold".chars()).unwrap().len(), 4);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.codes.len()
+    }
+
+    /// Whether the code has no symbols; never true of a built code
+    ///
+    /// ```
+    /// use fp::huffman::HuffmanCode;
+    ///
+    /// assert!(!HuffmanCode::new([('a', 1)]).unwrap().is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.codes.is_empty()
+    }
+
+    /// The bits of `symbols` one after another; none if any symbol is not
+    /// in the code
+    ///
+    /// ```
+    /// use fp::huffman::HuffmanCode;
+    ///
+    /// let code = HuffmanCode::new([('a', 2), ('b', 1), ('c', 1)]).unwrap();
+    /// assert_eq!(code.encode("abc".chars()).map(|bits| bits.len()), Some(5));
+    /// assert_eq!(code.encode("abz".chars()), None);
+    /// ```
+    pub fn encode<I>(&self, symbols: I) -> Option<Vec<bool>>
+    where
+        I: IntoIterator,
+        I::Item: Borrow<S>,
+    {
+        symbols
+            .into_iter()
+            .try_fold(Vec::new(), |mut bits, symbol| {
+                bits.extend(self.codes.get(symbol.borrow())?);
+                Some(bits)
+            })
+    }
+
+    /// The symbols `bits` encode; none if they end partway through a code,
+    /// or hold a 1 where a lone symbol's code is only ever 0
+    ///
+    /// ```
+    /// use fp::huffman::HuffmanCode;
+    ///
+    /// let code = HuffmanCode::new([('a', 3), ('b', 1), ('c', 1)]).unwrap();
+    /// let bits = code.encode(['a', 'b', 'c']).unwrap();
+    /// assert_eq!(code.decode(&bits), Some(vec!['a', 'b', 'c']));
+    /// assert_eq!(code.decode(&bits[..bits.len() - 1]), None);
+    /// ```
+    pub fn decode(&self, bits: &[bool]) -> Option<Vec<S>> {
+        let root = self.nodes.len() - 1;
+        let mut symbols = Vec::new();
+        let mut at = root;
+        for &bit in bits {
+            at = match self.nodes[at] {
+                Node::Branch(zero, one) => {
+                    if bit {
+                        one
+                    } else {
+                        zero
+                    }
+                }
+                Node::Leaf(_) if bit => return None,
+                Node::Leaf(_) => root,
+            };
+            if let Node::Leaf(symbol) = &self.nodes[at] {
+                symbols.push(symbol.clone());
+                at = root;
+            }
+        }
+        (at == root).then_some(symbols)
+    }
+}
+
+/// Record the code of every leaf below node `at`, reached by `path`
+fn assign_codes<S: Ord + Clone>(
+    nodes: &[Node<S>],
+    at: usize,
+    path: Vec<bool>,
+    codes: &mut BTreeMap<S, Vec<bool>>,
+) {
+    match &nodes[at] {
+        Node::Leaf(symbol) if path.is_empty() => {
+            codes.insert(symbol.clone(), vec![false]);
+        }
+        Node::Leaf(symbol) => {
+            codes.insert(symbol.clone(), path);
+        }
+        &Node::Branch(zero, one) => {
+            let mut one_path = path.clone();
+            one_path.push(true);
+            let mut zero_path = path;
+            zero_path.push(false);
+            assign_codes(nodes, zero, zero_path, codes);
+            assign_codes(nodes, one, one_path, codes);
+        }
+    }
+}
+
+/// The Shannon entropy of symbols occurring `counts` times, in bits per
+/// symbol: the fewest bits any code can average on them
+///
+/// ```
+/// use fp::huffman::entropy;
+///
+/// assert_eq!(entropy([1, 1, 1, 1]), 2.0);
+/// assert_eq!(entropy([7]), 0.0);
+/// ```
+pub fn entropy(counts: impl IntoIterator<Item = usize>) -> f64 {
+    let counts: Vec<usize> = counts.into_iter().filter(|&count| count > 0).collect();
+    let total = counts.iter().sum::<usize>() as f64;
+    counts
+        .iter()
+        .map(|&count| {
+            let p = count as f64 / total;
+            -p * p.log2()
+        })
+        .sum::<f64>()
+        .max(0.0)
+}
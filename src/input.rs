@@ -0,0 +1,456 @@
+//! Input Adapters
+//!
+//! Not every file is prose. An adapter turns a file in some format into
+//! the text whose words are counted, before sectioning and tokenizing, so
+//! that markup and metadata do not show up as top words. Plain text passes
+//! through borrowed. CSV and TSV files are parsed as tables, and
+//! [`extract_column`] picks the one column worth analyzing, such as the
+//! free-text "comment" field of a survey export. [`extract_json_field`]
+//! does the same for the messages of a JSON-lines log, and
+//! [`extract_log_field`] for the paths, status codes and user agents of a
+//! web server's access log. The table, JSON and access log adapters are
+//! behind the `csv`, `json` and `regex` features.
+//!
+//! ```
+//! use fp::input::InputFormat;
+//!
+//! let mbox = "From alice@example.com Mon Oct 13 09:00:00 2025\n\
+//!             Subject: Re: folds\n\
+//!             \n\
+//!             Folds are fine.\n\
+//!             On Monday, Bob wrote:\n\
+//!             > Are folds slow?\n";
+//! let extracted = InputFormat::Mbox.extract(mbox);
+//! assert_eq!(extracted.text, "Folds are fine.\n");
+//! assert_eq!(extracted.skipped, 0);
+//! assert_eq!(InputFormat::Text.extract(mbox).text, mbox);
+//! ```
+
+#[cfg(feature = "regex")]
+use regex::Regex;
+use std::borrow::Cow;
+use std::str::FromStr;
+#[cfg(feature = "regex")]
+use std::sync::LazyLock;
+
+/// The format of an input file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InputFormat {
+    /// Plain text, analyzed as it is
+    #[default]
+    Text,
+    /// An mbox mailbox or a single email: headers and quoted replies are
+    /// dropped
+    Mbox,
+    /// Comma-separated values: every field of every row after the header
+    #[cfg(feature = "csv")]
+    Csv,
+    /// Tab-separated values: every field of every row after the header
+    #[cfg(feature = "csv")]
+    Tsv,
+}
+
+impl InputFormat {
+    /// The text to analyze from a file in this format
+    ///
+    /// ```
+    /// use fp::input::InputFormat;
+    /// use std::borrow::Cow;
+    ///
+    /// // Plain text is borrowed as it is
+    /// let text = InputFormat::Text.extract("fold map");
+    /// assert!(matches!(text.text, Cow::Borrowed("fold map")));
+    /// let mail = InputFormat::Mbox.extract("Subject: hi\n\nfold map\n> quoted\n");
+    /// assert_eq!(mail.text, "fold map\n");
+    /// assert_eq!(mail.skipped, 0);
+    /// ```
+    pub fn extract(self, text: &str) -> Extracted<'_> {
+        match self {
+            InputFormat::Text => Extracted {
+                text: Cow::Borrowed(text),
+                skipped: 0,
+            },
+            InputFormat::Mbox => Extracted {
+                text: Cow::Owned(mail_bodies(text)),
+                skipped: 0,
+            },
+            #[cfg(feature = "csv")]
+            InputFormat::Csv | InputFormat::Tsv => {
+                let (fields, skipped) = table_fields(text, self.delimiter(), None);
+                Extracted {
+                    text: Cow::Owned(fields),
+                    skipped,
+                }
+            }
+        }
+    }
+
+    /// The field separator of a table format, a comma unless it is TSV
+    ///
+    /// ```
+    /// # #[cfg(feature = "csv")] {
+    /// use fp::input::InputFormat;
+    ///
+    /// assert_eq!(InputFormat::Csv.delimiter(), b',');
+    /// assert_eq!(InputFormat::Tsv.delimiter(), b'\t');
+    /// # }
+    /// ```
+    pub fn delimiter(self) -> u8 {
+        match self {
+            #[cfg(feature = "csv")]
+            InputFormat::Tsv => b'\t',
+            _ => b',',
+        }
+    }
+}
+
+/// The formats [`InputFormat::from_str`] accepts, as its error lists them;
+/// csv and tsv only exist with the `csv` feature
+#[cfg(feature = "csv")]
+const FORMAT_NAMES: &str = "text, mbox, csv or tsv";
+#[cfg(not(feature = "csv"))]
+const FORMAT_NAMES: &str = "text or mbox";
+
+impl FromStr for InputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(InputFormat::Text),
+            "mbox" => Ok(InputFormat::Mbox),
+            #[cfg(feature = "csv")]
+            "csv" => Ok(InputFormat::Csv),
+            #[cfg(feature = "csv")]
+            "tsv" => Ok(InputFormat::Tsv),
+            _ => Err(format!(
+                "Unknown input format '{}', expected {}",
+                s, FORMAT_NAMES
+            )),
+        }
+    }
+}
+
+/// The text extracted from an input file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Extracted<'a> {
+    /// What is left to analyze, borrowed when nothing was removed
+    pub text: Cow<'a, str>,
+    /// Records that were malformed and left out
+    pub skipped: usize,
+}
+
+/// The bodies of every message, without quoted replies. Headers run from
+/// the start of each message, or of the file, to the first blank line; a
+/// message starts at an mbox `From ` line.
+///
+/// ```
+/// use fp::input::mail_bodies;
+///
+/// let mbox = "From a\nSubject: one\n\nfirst body\n> quoted\nFrom b\nSubject: two\n\nsecond body\n";
+/// assert_eq!(mail_bodies(mbox), "first body\nsecond body\n");
+/// ```
+pub fn mail_bodies(text: &str) -> String {
+    let mut in_headers = true;
+    text.lines()
+        .filter(|line| {
+            if line.starts_with("From ") {
+                in_headers = true;
+                return false;
+            }
+            if in_headers {
+                in_headers = !line.trim().is_empty();
+                return false;
+            }
+            !is_quoted(line)
+        })
+        .fold(String::new(), |mut body, line| {
+            body.push_str(line);
+            body.push('\n');
+            body
+        })
+}
+
+/// A quoted line of a reply (`> ...`) or the attribution before the quote
+/// (`On Monday, Bob wrote:`)
+fn is_quoted(line: &str) -> bool {
+    let line = line.trim();
+    line.starts_with('>') || line.ends_with("wrote:")
+}
+
+/// A column of a table, by its header name or by position
+#[cfg(feature = "csv")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Column {
+    /// The column whose header is this name
+    Name(String),
+    /// The column at this position, counting from 1
+    Position(usize),
+}
+
+/// A number is a position counting from 1; anything else is a header name
+#[cfg(feature = "csv")]
+impl FromStr for Column {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse::<usize>() {
+            Ok(0) => Err("Column positions count from 1".to_string()),
+            Ok(position) => Ok(Column::Position(position)),
+            Err(_) if s.is_empty() => Err("A column needs a name or a position".to_string()),
+            Err(_) => Ok(Column::Name(s.to_string())),
+        }
+    }
+}
+
+/// The values of one column of a table whose first row is its header, one
+/// per line. Rows too short to have the column, or that cannot be parsed,
+/// are skipped and counted. Naming a column the header lacks is an error.
+///
+/// ```
+/// use fp::input::{Column, extract_column};
+///
+/// let survey = "id,comment,score\n1,\"Great, fast\",5\n2\n3,Too slow,2\n";
+/// let comments = extract_column(survey, b',', &Column::Name("comment".into())).unwrap();
+/// assert_eq!(comments.text, "Great, fast\nToo slow\n");
+/// assert_eq!(comments.skipped, 1);
+///
+/// let scores = extract_column(survey, b',', &Column::Position(3)).unwrap();
+/// assert_eq!(scores.text, "5\n2\n");
+/// assert!(extract_column(survey, b',', &Column::Name("email".into())).is_err());
+/// ```
+#[cfg(feature = "csv")]
+pub fn extract_column<'a>(
+    text: &str,
+    delimiter: u8,
+    column: &Column,
+) -> Result<Extracted<'a>, String> {
+    let index = match column {
+        Column::Position(position) => position - 1,
+        Column::Name(name) => {
+            let mut reader = table_reader(text, delimiter);
+            let headers = reader.headers().map_err(|err| err.to_string())?;
+            headers
+                .iter()
+                .position(|header| header == name)
+                .ok_or_else(|| {
+                    let known: Vec<&str> = headers.iter().collect();
+                    format!("No column '{}' in the header ({})", name, known.join(", "))
+                })?
+        }
+    };
+    let (values, skipped) = table_fields(text, delimiter, Some(index));
+    Ok(Extracted {
+        text: Cow::Owned(values),
+        skipped,
+    })
+}
+
+/// A CSV reader over `text` that tolerates rows of different lengths
+#[cfg(feature = "csv")]
+fn table_reader(text: &str, delimiter: u8) -> csv::Reader<&[u8]> {
+    csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .flexible(true)
+        .from_reader(text.as_bytes())
+}
+
+/// The fields of every row after the header, one per line: only the one at
+/// `index` if given, else all of them. Also returns how many rows were
+/// skipped because they failed to parse or lacked that field.
+#[cfg(feature = "csv")]
+fn table_fields(text: &str, delimiter: u8, index: Option<usize>) -> (String, usize) {
+    table_reader(text, delimiter)
+        .records()
+        .fold((String::new(), 0), |(mut out, skipped), row| {
+            let Ok(row) = row else {
+                return (out, skipped + 1);
+            };
+            let fields: Vec<&str> = match index {
+                Some(index) => row.get(index).into_iter().collect(),
+                None => row.iter().collect(),
+            };
+            if index.is_some() && fields.is_empty() {
+                return (out, skipped + 1);
+            }
+            fields.iter().for_each(|field| {
+                out.push_str(field);
+                out.push('\n');
+            });
+            (out, skipped)
+        })
+}
+
+/// The value of one field of every JSON object in a JSON-lines text, one
+/// per line. Dots in `field` descend into nested objects (`request.path`).
+/// Numbers and booleans are kept as written. Lines that are not JSON, or
+/// whose field is missing, null or not a scalar, are skipped and counted;
+/// blank lines are ignored.
+///
+/// ```
+/// use fp::input::extract_json_field;
+///
+/// let log = r#"{"level":"info","message":"Cache warmed"}
+/// {"level":"error","message":"Cache miss","request":{"path":"/home"}}
+/// not json
+/// {"level":"debug"}
+/// "#;
+/// let messages = extract_json_field(log, "message");
+/// assert_eq!(messages.text, "Cache warmed\nCache miss\n");
+/// assert_eq!(messages.skipped, 2);
+/// assert_eq!(extract_json_field(log, "request.path").text, "/home\n");
+/// ```
+#[cfg(feature = "json")]
+pub fn extract_json_field<'a>(text: &str, field: &str) -> Extracted<'a> {
+    line_fields(text, |line| {
+        serde_json::from_str(line)
+            .ok()
+            .and_then(|value| json_scalar(&value, field))
+            .map(Cow::Owned)
+    })
+}
+
+/// The scalar at a dotted `path` in `value`, as text
+#[cfg(feature = "json")]
+fn json_scalar(value: &serde_json::Value, path: &str) -> Option<String> {
+    use serde_json::Value;
+    match path
+        .split('.')
+        .try_fold(value, |value, key| value.get(key))?
+    {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Null | Value::Array(_) | Value::Object(_) => None,
+    }
+}
+
+/// A field of an access log line
+#[cfg(feature = "regex")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogField {
+    /// The requested path, without the method and protocol
+    Path,
+    /// The HTTP status code
+    Status,
+    /// The client's user agent, only in the combined format
+    UserAgent,
+}
+
+#[cfg(feature = "regex")]
+impl LogField {
+    /// Every field, in report order
+    pub const ALL: [LogField; 3] = [LogField::Path, LogField::Status, LogField::UserAgent];
+}
+
+/// One request of an access log in the Common or Combined Log Format, as
+/// written by Apache and nginx
+#[cfg(feature = "regex")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccessEntry<'a> {
+    /// `None` when the request line is not `METHOD PATH PROTOCOL`
+    pub path: Option<&'a str>,
+    /// The three-digit HTTP status code, as written
+    pub status: &'a str,
+    /// `None` in the Common Log Format
+    pub user_agent: Option<&'a str>,
+}
+
+#[cfg(feature = "regex")]
+impl<'a> AccessEntry<'a> {
+    /// The value of `field`, if the line has it
+    ///
+    /// ```
+    /// use fp::input::{LogField, parse_access_line};
+    ///
+    /// let line = r#"10.0.0.1 - - [10/Oct/2025:13:55:36 +0000] "GET /feed HTTP/1.1" 304 0"#;
+    /// let entry = parse_access_line(line).unwrap();
+    /// assert_eq!(entry.get(LogField::Path), Some("/feed"));
+    /// assert_eq!(entry.get(LogField::Status), Some("304"));
+    /// assert_eq!(entry.get(LogField::UserAgent), None);
+    /// ```
+    pub fn get(&self, field: LogField) -> Option<&'a str> {
+        match field {
+            LogField::Path => self.path,
+            LogField::Status => Some(self.status),
+            LogField::UserAgent => self.user_agent,
+        }
+    }
+}
+
+/// `host ident user [time] "request" status bytes`, then `"referer" "agent"`
+/// in the combined format
+#[cfg(feature = "regex")]
+static ACCESS_LINE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^\S+ \S+ \S+ \[[^\]]*\] "([^"]*)" (\d{3}) \S+(?: "[^"]*" "([^"]*)")?"#)
+        .expect("access log pattern is valid")
+});
+
+/// Parse one access log line
+///
+/// ```
+/// use fp::input::parse_access_line;
+///
+/// let line = r#"127.0.0.1 - - [10/Oct/2025:13:55:36 +0000] "GET /index.html HTTP/1.1" 200 2326 "-" "curl/8.5""#;
+/// let entry = parse_access_line(line).unwrap();
+/// assert_eq!(entry.path, Some("/index.html"));
+/// assert_eq!(entry.status, "200");
+/// assert_eq!(entry.user_agent, Some("curl/8.5"));
+/// assert!(parse_access_line("not a log line").is_none());
+/// ```
+#[cfg(feature = "regex")]
+pub fn parse_access_line(line: &str) -> Option<AccessEntry<'_>> {
+    let captures = ACCESS_LINE.captures(line)?;
+    let request = captures.get(1)?.as_str();
+    let path = match request.split(' ').collect::<Vec<_>>().as_slice() {
+        [_method, path, _protocol] => Some(*path),
+        _ => None,
+    };
+    Some(AccessEntry {
+        path,
+        status: captures.get(2)?.as_str(),
+        user_agent: captures.get(3).map(|agent| agent.as_str()),
+    })
+}
+
+/// The value of one field of every access log line, one per line. Lines
+/// that do not parse, or lack the field, are skipped and counted.
+///
+/// ```
+/// use fp::input::{LogField, extract_log_field};
+///
+/// let log = r#"1.2.3.4 - - [10/Oct/2025:13:55:36 +0000] "GET /a HTTP/1.1" 200 5
+/// 1.2.3.4 - - [10/Oct/2025:13:55:37 +0000] "GET /b HTTP/1.1" 404 0
+/// garbage
+/// "#;
+/// let statuses = extract_log_field(log, LogField::Status);
+/// assert_eq!(statuses.text, "200\n404\n");
+/// assert_eq!(statuses.skipped, 1);
+/// assert_eq!(extract_log_field(log, LogField::UserAgent).skipped, 3);
+/// ```
+#[cfg(feature = "regex")]
+pub fn extract_log_field<'a>(text: &str, field: LogField) -> Extracted<'a> {
+    line_fields(text, |line| {
+        parse_access_line(line)?.get(field).map(Cow::Borrowed)
+    })
+}
+
+/// The field `field` finds in each non-blank line of `text`, one per line;
+/// the lines it finds none in are skipped and counted
+#[cfg(any(feature = "json", feature = "regex"))]
+fn line_fields<'a>(text: &str, field: impl Fn(&str) -> Option<Cow<'_, str>>) -> Extracted<'a> {
+    let (values, skipped) = text.lines().filter(|line| !line.trim().is_empty()).fold(
+        (String::new(), 0),
+        |(mut out, skipped), line| match field(line) {
+            Some(value) => {
+                out.push_str(&value);
+                out.push('\n');
+                (out, skipped)
+            }
+            None => (out, skipped + 1),
+        },
+    );
+    Extracted {
+        text: Cow::Owned(values),
+        skipped,
+    }
+}
@@ -0,0 +1,222 @@
+//! Foldable-style extension methods for iterators.
+//!
+//! Haskell's `Foldable` and `Data.List` offer folds and groupings that std's
+//! `Iterator` lacks. `FoldableExt` adds them to every iterator, and
+//! `IteratorExt` adds frequency counting.
+
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+
+/// Extra folds, scans and groupings for any iterator.
+pub trait FoldableExt: Iterator + Sized {
+    /// Folds from the right: `fold_right([a, b, c], z, f) = f(a, f(b, f(c, z)))`.
+    ///
+    /// ```
+    /// use fp::iter_ext::FoldableExt;
+    ///
+    /// let nested = ["a", "b", "c"].into_iter().fold_right(String::from("[]"), |x, acc| format!("({x}:{acc})"));
+    /// assert_eq!(nested, "(a:(b:(c:[])))");
+    /// ```
+    fn fold_right<B, F>(self, init: B, mut f: F) -> B
+    where
+        Self: DoubleEndedIterator,
+        F: FnMut(Self::Item, B) -> B,
+    {
+        self.rev().fold(init, |acc, x| f(x, acc))
+    }
+
+    /// Lazily yields every intermediate accumulator, starting with `init`.
+    ///
+    /// ```
+    /// use fp::iter_ext::FoldableExt;
+    ///
+    /// let running: Vec<i32> = [1, 2, 3].into_iter().scan_left(0, |acc, x| acc + x).collect();
+    /// assert_eq!(running, [0, 1, 3, 6]);
+    /// ```
+    fn scan_left<B, F>(self, init: B, mut f: F) -> impl Iterator<Item = B>
+    where
+        B: Clone,
+        F: FnMut(B, Self::Item) -> B,
+    {
+        std::iter::once(init.clone()).chain(self.scan(init, move |acc, x| {
+            *acc = f(acc.clone(), x);
+            Some(acc.clone())
+        }))
+    }
+
+    /// Lazily places `separator` between consecutive items.
+    ///
+    /// A method of this name is planned for std, so call it with fully
+    /// qualified syntax to avoid the `unstable_name_collisions` lint.
+    ///
+    /// ```
+    /// use fp::iter_ext::FoldableExt;
+    ///
+    /// let words: String = FoldableExt::intersperse(["a", "b", "c"].into_iter(), "-").collect();
+    /// assert_eq!(words, "a-b-c");
+    /// ```
+    fn intersperse(self, separator: Self::Item) -> impl Iterator<Item = Self::Item>
+    where
+        Self::Item: Clone,
+    {
+        self.enumerate().flat_map(move |(i, x)| {
+            let sep = (i > 0).then(|| separator.clone());
+            sep.into_iter().chain(std::iter::once(x))
+        })
+    }
+
+    /// Groups runs of consecutive items sharing the same key.
+    ///
+    /// ```
+    /// use fp::iter_ext::FoldableExt;
+    ///
+    /// let groups = [1, 3, 2, 4, 5].into_iter().group_by_key(|x| x % 2);
+    /// assert_eq!(groups, [(1, vec![1, 3]), (0, vec![2, 4]), (1, vec![5])]);
+    /// ```
+    fn group_by_key<K, F>(self, mut key: F) -> Vec<(K, Vec<Self::Item>)>
+    where
+        K: PartialEq,
+        F: FnMut(&Self::Item) -> K,
+    {
+        self.fold(Vec::new(), |mut groups: Vec<(K, Vec<Self::Item>)>, x| {
+            let k = key(&x);
+            match groups.last_mut() {
+                Some((last, members)) if *last == k => members.push(x),
+                _ => groups.push((k, vec![x])),
+            }
+            groups
+        })
+    }
+
+    /// Splits into chunks, starting a new chunk whenever `same_chunk(prev, next)`
+    /// returns `false`.
+    ///
+    /// ```
+    /// use fp::iter_ext::FoldableExt;
+    ///
+    /// let ascending = [1, 2, 3, 2, 5, 1].into_iter().chunk_while(|a, b| a < b);
+    /// assert_eq!(ascending, [vec![1, 2, 3], vec![2, 5], vec![1]]);
+    /// ```
+    fn chunk_while<F>(self, mut same_chunk: F) -> Vec<Vec<Self::Item>>
+    where
+        F: FnMut(&Self::Item, &Self::Item) -> bool,
+    {
+        self.fold(Vec::new(), |mut chunks: Vec<Vec<Self::Item>>, x| {
+            match chunks.last_mut() {
+                Some(chunk) if chunk.last().is_some_and(|prev| same_chunk(prev, &x)) => chunk.push(x),
+                _ => chunks.push(vec![x]),
+            }
+            chunks
+        })
+    }
+
+    /// Collapses runs of equal items into `(item, run_length)` pairs.
+    ///
+    /// On a sorted token stream this is a word count in one pass:
+    ///
+    /// ```
+    /// use fp::iter_ext::FoldableExt;
+    ///
+    /// let mut words = vec!["to", "be", "or", "not", "to", "be"];
+    /// words.sort();
+    /// let counts = words.into_iter().dedup_count();
+    /// assert_eq!(counts, [("be", 2), ("not", 1), ("or", 1), ("to", 2)]);
+    /// ```
+    fn dedup_count(self) -> Vec<(Self::Item, usize)>
+    where
+        Self::Item: PartialEq,
+    {
+        self.fold(Vec::new(), |mut runs: Vec<(Self::Item, usize)>, x| {
+            match runs.last_mut() {
+                Some((last, n)) if *last == x => *n += 1,
+                _ => runs.push((x, 1)),
+            }
+            runs
+        })
+    }
+}
+
+impl<I: Iterator> FoldableExt for I {}
+
+/// Frequency counting for any iterator.
+pub trait IteratorExt: Iterator + Sized {
+    /// Counts how often each item occurs.
+    ///
+    /// ```
+    /// use fp::iter_ext::IteratorExt;
+    ///
+    /// let counts = "the cat and the hat".split(' ').counts();
+    /// assert_eq!(counts["the"], 2);
+    /// assert_eq!(counts["cat"], 1);
+    /// ```
+    fn counts(self) -> HashMap<Self::Item, usize>
+    where
+        Self::Item: Eq + Hash,
+    {
+        self.counts_with_hasher()
+    }
+
+    /// Counts how often each item occurs, in a map built with the hasher `S`.
+    ///
+    /// ```
+    /// # #[cfg(feature = "fast-hash")] {
+    /// use fp::hashing::FxBuilder;
+    /// use fp::iter_ext::IteratorExt;
+    ///
+    /// let counts = "the cat and the hat".split(' ').counts_with_hasher::<FxBuilder>();
+    /// assert_eq!(counts["the"], 2);
+    /// # }
+    /// ```
+    fn counts_with_hasher<S>(self) -> HashMap<Self::Item, usize, S>
+    where
+        Self::Item: Eq + Hash,
+        S: BuildHasher + Default,
+    {
+        fp_core::tally(self)
+    }
+
+    /// Counts how many items share each key.
+    ///
+    /// ```
+    /// use fp::iter_ext::IteratorExt;
+    ///
+    /// let by_length = ["a", "bb", "cc", "d"].into_iter().counts_by(str::len);
+    /// assert_eq!(by_length[&1], 2);
+    /// assert_eq!(by_length[&2], 2);
+    /// ```
+    fn counts_by<K, F>(self, key: F) -> HashMap<K, usize>
+    where
+        K: Eq + Hash,
+        F: FnMut(Self::Item) -> K,
+    {
+        self.map(key).counts()
+    }
+
+    /// The `n` most frequent items with their counts, most frequent first.
+    /// Ties keep the order in which the items first appeared.
+    ///
+    /// ```
+    /// use fp::iter_ext::IteratorExt;
+    ///
+    /// let top = "b a b c a b".split(' ').most_common(2);
+    /// assert_eq!(top, [("b", 3), ("a", 2)]);
+    /// ```
+    fn most_common(self, n: usize) -> Vec<(Self::Item, usize)>
+    where
+        Self::Item: Eq + Hash,
+    {
+        let mut seen: HashMap<Self::Item, (usize, usize)> = HashMap::new();
+        for (index, item) in self.enumerate() {
+            seen.entry(item).or_insert((index, 0)).1 += 1;
+        }
+        let mut ranked: Vec<_> = seen.into_iter().collect();
+        ranked.sort_by_key(|&(_, (first, count))| (std::cmp::Reverse(count), first));
+        ranked
+            .into_iter()
+            .take(n)
+            .map(|(item, (_, count))| (item, count))
+            .collect()
+    }
+}
+
+impl<I: Iterator> IteratorExt for I {}
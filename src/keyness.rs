@@ -0,0 +1,112 @@
+//! Keyness
+//!
+//! Which words are unusually frequent in a text compared with a reference
+//! corpus, the corpus-linguistics way: Dunning's log-likelihood (G²)
+//! compares a word's share of the text with its share of the reference,
+//! and is large when the difference is both big and well supported by the
+//! counts. Unlike a plain ratio of relative frequencies it does not let one
+//! occurrence of a rare word outrank a word that is common in both.
+//!
+//! ```
+//! use fp::analysis::word_counts;
+//! use fp::keyness::keywords;
+//!
+//! let text = word_counts("the fold and the map and the fold fold fold");
+//! let reference = word_counts("the cat and the dog and the bird sat on the mat");
+//! let keywords = keywords(&text, &reference);
+//! assert_eq!(keywords[0].word, "fold");
+//! assert_eq!((keywords[0].count, keywords[0].reference_count), (4, 0));
+//! assert!(keywords.iter().all(|keyword| keyword.word != "the"));
+//! ```
+
+use std::collections::HashMap;
+
+/// The log-likelihood above which a difference is significant at
+/// p < 0.05 (χ² with one degree of freedom)
+pub const SIGNIFICANT: f64 = 3.84;
+
+/// A word more frequent in the text than in the reference
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keyword {
+    /// The word, as counted
+    pub word: String,
+    /// Occurrences in the text
+    pub count: usize,
+    /// Occurrences in the reference corpus
+    pub reference_count: usize,
+    /// Dunning's G²; the larger, the more characteristic of the text
+    pub log_likelihood: f64,
+}
+
+/// Dunning's log-likelihood of a word occurring `count` times in `total`
+/// words of a text and `reference_count` times in `reference_total` words
+/// of a reference corpus. 0 when its share is the same in both.
+///
+/// ```
+/// use fp::keyness::{SIGNIFICANT, log_likelihood};
+///
+/// assert_eq!(log_likelihood(10, 1000, 10, 1000), 0.0);
+/// assert!(log_likelihood(30, 1000, 10, 1000) > SIGNIFICANT);
+/// assert!(log_likelihood(3, 1000, 1, 1000) < SIGNIFICANT);
+/// ```
+pub fn log_likelihood(
+    count: usize,
+    total: usize,
+    reference_count: usize,
+    reference_total: usize,
+) -> f64 {
+    let (a, b) = (count as f64, reference_count as f64);
+    let (c, d) = (total as f64, reference_total as f64);
+    if a + b == 0.0 || c + d == 0.0 {
+        return 0.0;
+    }
+    let expected = |total: f64| total * (a + b) / (c + d);
+    // A word absent from one side contributes nothing from that side
+    let term = |observed: f64, expected: f64| {
+        if observed == 0.0 {
+            0.0
+        } else {
+            observed * (observed / expected).ln()
+        }
+    };
+    (2.0 * (term(a, expected(c)) + term(b, expected(d)))).max(0.0)
+}
+
+/// The words of `counts` relatively more frequent than in `reference`, most
+/// characteristic first; ties in alphabetical order
+///
+/// ```
+/// use fp::analysis::word_counts;
+/// use fp::keyness::keywords;
+///
+/// let text = word_counts("fold fold fold map the");
+/// let reference = word_counts("the the the map fold");
+/// let words: Vec<String> = keywords(&text, &reference).into_iter().map(|k| k.word).collect();
+/// // "the" is less frequent than in the reference, "map" equally so
+/// assert_eq!(words, ["fold"]);
+/// ```
+pub fn keywords(
+    counts: &HashMap<String, usize>,
+    reference: &HashMap<String, usize>,
+) -> Vec<Keyword> {
+    let total: usize = counts.values().sum();
+    let reference_total: usize = reference.values().sum();
+    let mut keywords: Vec<Keyword> = counts
+        .iter()
+        .map(|(word, &count)| (word, count, reference.get(word).copied().unwrap_or(0)))
+        // Cross-multiplied to compare the two shares without dividing
+        .filter(|&(_, count, reference_count)| count * reference_total > reference_count * total)
+        .map(|(word, count, reference_count)| Keyword {
+            word: word.clone(),
+            count,
+            reference_count,
+            log_likelihood: log_likelihood(count, total, reference_count, reference_total),
+        })
+        .collect();
+    keywords.sort_by(|x, y| {
+        y.log_likelihood
+            .total_cmp(&x.log_likelihood)
+            .then_with(|| x.word.cmp(&y.word))
+    });
+    keywords
+}
@@ -0,0 +1,125 @@
+//! Key Phrase Extraction
+//!
+//! RAKE, Rapid Automatic Keyword Extraction (Rose et al., 2010), finds the
+//! multiword phrases a text is about without any reference corpus. The
+//! text is cut into candidate phrases at punctuation and stop words, so the
+//! candidates are runs of content words such as "linear diophantine
+//! equations". Each word scores its degree, the total length of the
+//! candidates it appears in, over its frequency: words that mostly occur
+//! inside longer phrases score high. A phrase scores the sum of its words'
+//! scores.
+//!
+//! ```
+//! use fp::keywords::rake;
+//! use fp::locale::Locale;
+//!
+//! let text = "Compatibility of systems of linear constraints on the set of natural numbers. \
+//!             Criteria of compatibility of a system of linear Diophantine equations are considered.";
+//! let phrases: Vec<String> = rake(text, Locale::English).into_iter().map(|p| p.phrase).take(3).collect();
+//! assert_eq!(phrases, ["linear diophantine equations", "linear constraints", "natural numbers"]);
+//! ```
+
+use std::collections::HashMap;
+
+use crate::analysis::clean_word_in;
+use crate::locale::Locale;
+
+/// Candidates of more words than this are dropped: without stop words to
+/// break them, they are usually lists or headings rather than phrases
+pub const MAX_PHRASE_WORDS: usize = 4;
+
+/// The characters that end a phrase; hyphens and apostrophes join words
+/// instead
+const DELIMITERS: &[char] = &[
+    '.', ',', ';', ':', '!', '?', '(', ')', '[', ']', '{', '}', '"', '“', '”', '«', '»', '…', '—',
+    '–',
+];
+
+/// A candidate phrase and its RAKE score
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyPhrase {
+    /// The phrase's cleaned words, joined by spaces
+    pub phrase: String,
+    /// The sum of its words' degree over frequency; higher is more key
+    pub score: f64,
+    /// How often the phrase occurs
+    pub count: usize,
+}
+
+/// The candidate phrases of `text` in order: its cleaned words, split at
+/// punctuation and at the stop words of `locale`
+///
+/// ```
+/// use fp::keywords::candidates;
+/// use fp::locale::Locale;
+///
+/// let phrases = candidates("The quick brown fox, and the lazy dog.", Locale::English);
+/// assert_eq!(phrases, [vec!["quick", "brown", "fox"], vec!["lazy", "dog"]]);
+/// ```
+pub fn candidates(text: &str, locale: Locale) -> Vec<Vec<String>> {
+    text.split(DELIMITERS)
+        .flat_map(|fragment| {
+            let words: Vec<String> = fragment
+                .split_whitespace()
+                .map(|word| clean_word_in(word, locale).into_owned())
+                .filter(|word| !word.is_empty())
+                .collect();
+            words
+                .split(|word| locale.is_stopword(word))
+                .filter(|phrase| !phrase.is_empty())
+                .map(<[String]>::to_vec)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// The distinct key phrases of `text`, at most [`MAX_PHRASE_WORDS`] long,
+/// highest score first, ties alphabetical
+///
+/// ```
+/// use fp::keywords::rake;
+/// use fp::locale::Locale;
+///
+/// let phrases = rake("Persistent data structures. The data is shared.", Locale::English);
+/// assert_eq!(phrases[0].phrase, "persistent data structures");
+/// assert_eq!(phrases[0].count, 1);
+/// assert!(phrases.iter().any(|p| p.phrase == "shared"));
+/// ```
+pub fn rake(text: &str, locale: Locale) -> Vec<KeyPhrase> {
+    let phrases: Vec<Vec<String>> = candidates(text, locale)
+        .into_iter()
+        .filter(|phrase| phrase.len() <= MAX_PHRASE_WORDS)
+        .collect();
+    // Degree and frequency of every word, over every occurrence
+    let (degree, frequency) = phrases.iter().fold(
+        (HashMap::new(), HashMap::new()),
+        |(mut degree, mut frequency): (HashMap<&str, usize>, HashMap<&str, usize>), phrase| {
+            for word in phrase {
+                *degree.entry(word.as_str()).or_insert(0) += phrase.len();
+                *frequency.entry(word.as_str()).or_insert(0) += 1;
+            }
+            (degree, frequency)
+        },
+    );
+    let counts = phrases.iter().fold(HashMap::new(), |mut counts, phrase| {
+        *counts.entry(phrase.join(" ")).or_insert(0) += 1;
+        counts
+    });
+    let mut key_phrases: Vec<KeyPhrase> = counts
+        .into_iter()
+        .map(|(phrase, count)| KeyPhrase {
+            score: phrase
+                .split(' ')
+                .map(|word| degree[word] as f64 / frequency[word] as f64)
+                .sum(),
+            phrase,
+            count,
+        })
+        .collect();
+    key_phrases.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.phrase.cmp(&b.phrase))
+    });
+    key_phrases
+}
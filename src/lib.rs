@@ -0,0 +1,149 @@
+//! Functional Programming Library
+//!
+//! Reusable building blocks shared by the `fp_concepts` demo and the
+//! `file_parser` analyzer:
+//! - Word frequency analysis, with async readers and directory walking behind the `async` feature
+//! - OCR cleanup: ligatures, soft hyphens and words broken across lines
+//! - Input adapters that extract the text to analyze (mbox bodies; CSV/TSV columns, JSON-lines fields and access logs behind the `csv`, `json` and `regex` features)
+//! - Splitting documents into sections at delimiter lines (`^From:`, `^#`) behind the `regex` feature
+//! - Frequency list export for other tools: `word count` lists and Hunspell dictionaries
+//! - Storing analyzed documents, words, counts and positions in SQLite behind the `sqlite` feature
+//! - Parquet files of word counts and TF-IDF weights for pandas and polars behind the `parquet` feature
+//! - Report templates (`{{total}}`, `{{#top 10}}{{word}} {{count}}{{/top}}`)
+//! - Keyness: log-likelihood comparison of word frequencies against a reference corpus
+//! - Key phrase extraction with RAKE
+//! - Extractive summaries of the sentences with the most frequent words
+//! - Lexical diversity: type-token ratio, root TTR and MTLD
+//! - Vocabulary trivia: longest and shortest words, palindromes, most distinct letters
+//! - Anagram groups found by sorted-letter signature
+//! - Soundex codes grouping words that sound alike
+//! - Prefix trees (`Trie`) for autocompleting words of the vocabulary
+//! - Fuzzy search within a Levenshtein distance, indexed by a BK-tree
+//! - Near-duplicate detection with word shingles and MinHash, and cosine similarity of documents
+//! - k-means clustering of documents by TF-IDF vectors
+//! - Huffman codes of word or character frequencies, with the bits per symbol they achieve
+//! - Spelling suggestions ranked by edit distance and corpus frequency
+//! - Progress toward a word count goal and writing pace in words per minute
+//! - Terminal bar charts and word-length histograms drawn with Unicode blocks
+//! - A live terminal dashboard of top words, totals and word lengths behind the `tui` feature
+//! - Variadic function composition (`pipe!`, `compose!`, `Pipeline`, `Pipe`, `Compose`)
+//! - Currying and uncurrying for two and three arguments
+//! - Point-free combinators (`identity`, `constant`, `flip`, `tap`, `apply_n`, `fix`)
+//! - Result combinators (`traverse`, `sequence`, `partition_results`, `bimap`)
+//! - Classic exercises as pure functions (`collatz`, `fizzbuzz`, `gcd`)
+//! - Church-encoded numerals and booleans
+//! - A pure, splittable pseudo-random number generator
+//! - Order-k Markov chains generating text from a corpus
+//! - Synthetic Zipf-distributed corpora (`gen_corpus`) for reproducible benchmarks and tests
+//! - Structured logging to stderr as text or JSON, as a `log` backend
+//! - Request counters and latency histograms in the Prometheus text format
+//! - Cancellation tokens with deadlines, keeping partial results as an `Outcome`
+//! - Checkpoints that let long directory scans resume after a crash
+//! - Rate-limited closures (`throttle`, `debounce`)
+//! - Retrying fallible operations with fixed or exponential backoff
+//! - The `Either<L, R>` sum type
+//! - The `Expr` arithmetic expression language
+//! - Error-accumulating `Validated<T, E>` and `NonEmptyVec<T>`
+//! - `Functor` / `Applicative` / `Monad` traits
+//! - `Semigroup` / `Monoid` traits with `mconcat` and `fold_map`
+//! - Effect monads kept pure: `Reader`, `Writer`, `State`, `Io`, and a free-monad `Program`
+//! - Hand-written sequence iterators (`Fib`, `Primes`, `unfold`)
+//! - Grouping into a `HashMap` and the `MultiMap` wrapper
+//! - Slice extensions (`SliceFnExt`: `fmap`, `fold_left`, `zip_with`, ...)
+//! - Disk-backed counting that spills sorted runs and merges them
+//! - Faster hashers (FxHash, aHash) for the frequency map behind the `fast-hash` feature
+//! - Parallel map-reduce over a worker pool, merging with `Monoid`, behind the `parallel` feature
+//! - Foldable-style iterator extensions (`fold_right`, `scan_left`, `dedup_count`, ...)
+//! - Zippers for lists and `Expr` trees
+//! - Optics: `Lens` and `Prism` for immutable nested updates
+//! - Transducers: source- and sink-independent processing pipelines
+//! - Unicode normalization (NFC, NFKC) of words before counting
+//! - Locale-aware casing (Turkish dotted and dotless i) and stop words for eight languages
+//! - Pluggable token filters (`TokenFilter`) chained by name from a `Registry`; the `stem` filter is behind the `stemming` feature
+//! - A typed filter expression language (`len(word) >= 5 && ...`) for custom word filters
+//! - Trampolines and continuation-passing helpers for stack-safe recursion
+//! - Persistent data structures (`PersistentList`, `PVec`, `PMap`, `PTreeMap`, `PTrie`)
+//! - Browser bindings for the analyzer and `Expr` evaluator behind the `wasm` feature
+//! - A C interface to the analyzer behind the `ffi` feature
+//! - A `textfp` Python module behind the `python` feature
+//!
+//! The default `cli` feature turns on everything the `file_parser` binary
+//! uses. Embedding only the counting core takes `default-features = false`;
+//! without `std` at all, the tokenizer and counting fold live in the
+//! `fp-core` crate.
+//!
+//! September 2025
+
+pub mod anagrams;
+pub mod analysis;
+pub mod cancel;
+pub mod checkpoint;
+pub mod church;
+pub mod classic;
+pub mod clustering;
+pub mod combinators;
+pub mod composition;
+pub mod corpus;
+pub mod curiosities;
+pub mod currying;
+#[cfg(feature = "tui")]
+pub mod dashboard;
+pub mod diversity;
+pub mod either;
+pub mod export;
+pub mod expr;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod filter_expr;
+pub mod fuzzy;
+pub mod goal;
+#[cfg(feature = "fast-hash")]
+pub mod hashing;
+pub mod huffman;
+pub mod input;
+pub mod iter_ext;
+pub mod keyness;
+pub mod keywords;
+pub mod locale;
+pub mod logging;
+pub mod markov;
+pub mod metrics;
+pub mod monads;
+pub mod monoid;
+pub mod multimap;
+pub mod normalize;
+pub mod ocr;
+pub mod optics;
+#[cfg(feature = "parallel")]
+pub mod parallel;
+#[cfg(feature = "parquet")]
+pub mod parquet;
+pub mod persistent;
+pub mod phonetic;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod random;
+pub mod results;
+pub mod retry;
+#[cfg(feature = "regex")]
+pub mod sections;
+pub mod sequences;
+pub mod similarity;
+pub mod slice_ext;
+pub mod spelling;
+pub mod spill;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod summary;
+pub mod template;
+pub mod timing;
+pub mod token_filter;
+pub mod trampoline;
+pub mod transducers;
+pub mod trie;
+pub mod typeclasses;
+pub mod validated;
+pub mod viz;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod zipper;
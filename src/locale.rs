@@ -0,0 +1,237 @@
+//! Locales
+//!
+//! Casing and stop words depend on the language of the text. Unicode's
+//! default case mapping sends "I" to "i", which is wrong for Turkish: there
+//! "I" is the capital of dotless "ı" and "İ" the capital of "i", so the
+//! default mapping merges "ılık" with "ilik". A [`Locale`] applies the
+//! language's tailoring and names its bundled stop-word list. Locales are
+//! written as language codes (`tr`) or POSIX names (`tr_TR`, `tr_TR.UTF-8`);
+//! only the language part matters.
+//!
+//! ```
+//! use fp::locale::Locale;
+//!
+//! let turkish: Locale = "tr_TR".parse().unwrap();
+//! assert_eq!(turkish.lowercase("IŞIK İNCİ"), "ışık inci");
+//! assert_eq!(Locale::English.lowercase("IŞIK İNCİ"), "işik i̇nci̇");
+//! assert!(turkish.is_stopword("ve"));
+//! assert!("de_DE.UTF-8".parse::<Locale>().unwrap().is_stopword("und"));
+//! ```
+
+use crate::analysis::fold_case;
+use std::str::FromStr;
+
+/// A language whose casing rules and stop words the analyzer knows
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Locale {
+    #[default]
+    English,
+    German,
+    French,
+    Spanish,
+    Italian,
+    Portuguese,
+    Dutch,
+    Turkish,
+}
+
+impl Locale {
+    /// Every supported locale
+    pub const ALL: [Locale; 8] = [
+        Locale::English,
+        Locale::German,
+        Locale::French,
+        Locale::Spanish,
+        Locale::Italian,
+        Locale::Portuguese,
+        Locale::Dutch,
+        Locale::Turkish,
+    ];
+
+    /// The ISO 639-1 code of the language
+    ///
+    /// ```
+    /// use fp::locale::Locale;
+    ///
+    /// assert_eq!(Locale::German.code(), "de");
+    /// assert_eq!("de_AT.UTF-8".parse::<Locale>().unwrap().code(), "de");
+    /// ```
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::English => "en",
+            Locale::German => "de",
+            Locale::French => "fr",
+            Locale::Spanish => "es",
+            Locale::Italian => "it",
+            Locale::Portuguese => "pt",
+            Locale::Dutch => "nl",
+            Locale::Turkish => "tr",
+        }
+    }
+
+    /// Whether the language tailors Unicode's default case mapping
+    ///
+    /// ```
+    /// use fp::locale::Locale;
+    ///
+    /// assert!(Locale::Turkish.tailors_case());
+    /// assert!(!Locale::French.tailors_case());
+    /// ```
+    pub fn tailors_case(self) -> bool {
+        self == Locale::Turkish
+    }
+
+    /// Lowercase `s` by the language's rules. In Turkish "I" becomes "ı",
+    /// and "İ" (or "I" followed by a combining dot above) becomes "i".
+    ///
+    /// ```
+    /// use fp::locale::Locale;
+    ///
+    /// assert_eq!(Locale::Turkish.lowercase("IRMAK İLE"), "ırmak ile");
+    /// assert_eq!(Locale::English.lowercase("IRMAK İLE"), "irmak i\u{307}le");
+    /// ```
+    pub fn lowercase(self, s: &str) -> String {
+        if !self.tailors_case() {
+            return s.to_lowercase();
+        }
+        let mut out = String::with_capacity(s.len());
+        let mut chars = s.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                'İ' => out.push('i'),
+                'I' if chars.next_if_eq(&'\u{307}').is_some() => out.push('i'),
+                'I' => out.push('ı'),
+                _ => out.extend(c.to_lowercase()),
+            }
+        }
+        out
+    }
+
+    /// Case fold `s` for caseless matching: [`fold_case`] with the
+    /// language's tailoring, so Turkish keeps "ı" and "i" apart.
+    ///
+    /// ```
+    /// use fp::locale::Locale;
+    ///
+    /// assert_eq!(Locale::Turkish.fold_case("DİYARBAKIR"), "diyarbakır");
+    /// assert_ne!(Locale::Turkish.fold_case("I"), Locale::Turkish.fold_case("i"));
+    /// assert_eq!(Locale::German.fold_case("STRASSE"), Locale::German.fold_case("Straße"));
+    /// ```
+    pub fn fold_case(self, s: &str) -> String {
+        if self.tailors_case() {
+            fold_case(&self.lowercase(s))
+        } else {
+            fold_case(s)
+        }
+    }
+
+    /// The bundled stop-word list, lowercase and without punctuation as
+    /// cleaned words are
+    ///
+    /// ```
+    /// use fp::locale::Locale;
+    ///
+    /// assert!(Locale::English.stopwords().contains(&"the"));
+    /// assert!(Locale::French.stopwords().contains(&"les"));
+    /// ```
+    pub fn stopwords(self) -> &'static [&'static str] {
+        match self {
+            Locale::English => ENGLISH,
+            Locale::German => GERMAN,
+            Locale::French => FRENCH,
+            Locale::Spanish => SPANISH,
+            Locale::Italian => ITALIAN,
+            Locale::Portuguese => PORTUGUESE,
+            Locale::Dutch => DUTCH,
+            Locale::Turkish => TURKISH,
+        }
+    }
+
+    /// Whether `word` is on the language's stop-word list
+    ///
+    /// ```
+    /// use fp::locale::Locale;
+    ///
+    /// assert!(Locale::English.is_stopword("the"));
+    /// assert!(!Locale::English.is_stopword("fold"));
+    /// assert!(Locale::German.is_stopword("und"));
+    /// ```
+    pub fn is_stopword(self, word: &str) -> bool {
+        self.stopwords().contains(&word)
+    }
+}
+
+impl FromStr for Locale {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let language = s.split(['_', '-', '.', '@']).next().unwrap_or(s);
+        Locale::ALL
+            .into_iter()
+            .find(|locale| locale.code().eq_ignore_ascii_case(language))
+            .ok_or_else(|| {
+                let codes: Vec<&str> = Locale::ALL.iter().map(|locale| locale.code()).collect();
+                format!(
+                    "Unknown locale '{}', expected one of {}",
+                    s,
+                    codes.join(", ")
+                )
+            })
+    }
+}
+
+const ENGLISH: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "for", "from", "had", "has", "have",
+    "he", "her", "his", "i", "in", "is", "it", "its", "of", "on", "or", "she", "so", "that", "the",
+    "their", "they", "this", "to", "was", "we", "were", "which", "will", "with", "you",
+];
+
+const GERMAN: &[&str] = &[
+    "aber", "als", "am", "an", "auch", "auf", "aus", "bei", "bin", "bis", "das", "dass", "dem",
+    "den", "der", "des", "die", "du", "ein", "eine", "einem", "einen", "einer", "eines", "er",
+    "es", "für", "hat", "ich", "ihr", "im", "in", "ist", "ja", "mit", "nach", "nicht", "noch",
+    "nur", "oder", "sich", "sie", "sind", "so", "um", "und", "uns", "von", "war", "wie", "wir",
+    "zu", "zum", "zur",
+];
+
+const FRENCH: &[&str] = &[
+    "a", "au", "aux", "avec", "ce", "ces", "dans", "de", "des", "du", "elle", "en", "est", "et",
+    "été", "être", "il", "ils", "je", "la", "le", "les", "leur", "lui", "ma", "mais", "me", "mes",
+    "moi", "mon", "ne", "nous", "on", "ou", "par", "pas", "pour", "que", "qui", "sa", "se", "ses",
+    "son", "sur", "ta", "te", "tes", "toi", "ton", "tu", "un", "une", "vos", "votre", "vous",
+];
+
+const SPANISH: &[&str] = &[
+    "a", "al", "algo", "como", "con", "de", "del", "el", "ella", "ellos", "en", "es", "esta",
+    "este", "fue", "ha", "la", "las", "le", "lo", "los", "más", "me", "mi", "muy", "no", "nos",
+    "o", "para", "pero", "por", "que", "se", "si", "sin", "su", "sus", "también", "te", "tu", "un",
+    "una", "y", "ya", "yo",
+];
+
+const ITALIAN: &[&str] = &[
+    "a", "ai", "al", "alla", "anche", "che", "chi", "come", "con", "da", "dal", "dei", "del",
+    "della", "di", "e", "è", "gli", "ha", "i", "il", "in", "io", "la", "le", "lo", "lui", "ma",
+    "mi", "ne", "nel", "nella", "noi", "non", "o", "per", "più", "quella", "questo", "se", "si",
+    "sono", "su", "tu", "un", "una", "uno",
+];
+
+const PORTUGUESE: &[&str] = &[
+    "a", "ao", "as", "com", "como", "da", "das", "de", "do", "dos", "e", "é", "ela", "ele", "em",
+    "era", "eu", "foi", "isso", "já", "mais", "mas", "me", "meu", "na", "não", "nas", "no", "nos",
+    "o", "os", "ou", "para", "pela", "pelo", "por", "que", "se", "sem", "seu", "sua", "são",
+    "também", "um", "uma",
+];
+
+const DUTCH: &[&str] = &[
+    "aan", "al", "als", "bij", "dan", "dat", "de", "die", "dit", "door", "een", "en", "er", "het",
+    "hij", "hoe", "ik", "in", "is", "je", "maar", "met", "naar", "niet", "nog", "of", "om", "ook",
+    "op", "over", "te", "tot", "uit", "van", "voor", "was", "wat", "we", "wel", "wij", "zal", "ze",
+    "zich", "zij", "zijn",
+];
+
+const TURKISH: &[&str] = &[
+    "acaba", "ama", "ancak", "bazı", "ben", "bir", "biz", "bu", "da", "daha", "de", "diye", "en",
+    "gibi", "hem", "hep", "her", "hiç", "için", "ile", "ise", "kadar", "ki", "kim", "mı", "mi",
+    "mu", "mü", "ne", "neden", "o", "olan", "olarak", "onlar", "sen", "siz", "şey", "şu", "tüm",
+    "ve", "veya", "ya", "yani",
+];
@@ -0,0 +1,186 @@
+//! Structured Logging
+//!
+//! A small [`log`] backend shared by the binaries. Records are written to
+//! standard error, one per line, with a UTC timestamp and level, either as
+//! readable text or as JSON objects for log aggregation. Formatting is a pure
+//! function of the record, so it is tested without touching stderr.
+//!
+//! ```
+//! use fp::logging::{Format, format_record};
+//! use log::Level;
+//! use std::time::{Duration, UNIX_EPOCH};
+//!
+//! let at = UNIX_EPOCH + Duration::from_millis(1_757_000_000_250);
+//! assert_eq!(
+//!     format_record(Format::Text, at, Level::Warn, "file_parser", "file vanished"),
+//!     "2025-09-04T15:33:20.250Z WARN  file vanished"
+//! );
+//! assert_eq!(
+//!     format_record(Format::Json, at, Level::Error, "serve", "bad \"request\""),
+//!     r#"{"timestamp":"2025-09-04T15:33:20.250Z","level":"ERROR","target":"serve","message":"bad \"request\""}"#
+//! );
+//! ```
+
+use crate::analysis::json_string;
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// How each record is written
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    /// `timestamp LEVEL message`
+    #[default]
+    Text,
+    /// One JSON object per line
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("Unknown log format '{}', expected text or json", s)),
+        }
+    }
+}
+
+/// A logger writing records at or above `level` to stderr in `format`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Logger {
+    /// The least severe level written
+    pub level: LevelFilter,
+    /// Text or JSON
+    pub format: Format,
+}
+
+impl Default for Logger {
+    fn default() -> Self {
+        Logger {
+            level: LevelFilter::Info,
+            format: Format::Text,
+        }
+    }
+}
+
+impl Logger {
+    /// Install this logger as the process-wide `log` backend.
+    ///
+    /// ```
+    /// use fp::logging::{Format, Logger};
+    /// use log::LevelFilter;
+    ///
+    /// let logger = Logger { level: LevelFilter::Warn, format: Format::Json };
+    /// assert!(logger.init().is_ok());
+    /// assert_eq!(log::max_level(), LevelFilter::Warn);
+    /// assert!(Logger::default().init().is_err(), "only one logger per process");
+    /// ```
+    pub fn init(self) -> Result<(), SetLoggerError> {
+        log::set_max_level(self.level);
+        log::set_boxed_logger(Box::new(self))
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!(
+                "{}",
+                format_record(
+                    self.format,
+                    SystemTime::now(),
+                    record.level(),
+                    record.target(),
+                    &record.args().to_string(),
+                )
+            );
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Render one log record as a line of text or JSON.
+///
+/// ```
+/// use fp::logging::{Format, format_record};
+/// use log::Level;
+/// use std::time::UNIX_EPOCH;
+///
+/// assert_eq!(
+///     format_record(Format::Text, UNIX_EPOCH, Level::Info, "serve", "listening"),
+///     "1970-01-01T00:00:00.000Z INFO  listening"
+/// );
+/// // Only JSON keeps the target
+/// assert!(format_record(Format::Json, UNIX_EPOCH, Level::Info, "serve", "listening")
+///     .contains(r#""target":"serve""#));
+/// ```
+pub fn format_record(
+    format: Format,
+    time: SystemTime,
+    level: Level,
+    target: &str,
+    message: &str,
+) -> String {
+    match format {
+        Format::Text => format!("{} {:<5} {}", timestamp(time), level, message),
+        Format::Json => format!(
+            r#"{{"timestamp":"{}","level":"{}","target":{},"message":{}}}"#,
+            timestamp(time),
+            level,
+            json_string(target),
+            json_string(message)
+        ),
+    }
+}
+
+/// An RFC 3339 UTC timestamp with milliseconds.
+///
+/// ```
+/// use std::time::UNIX_EPOCH;
+///
+/// assert_eq!(fp::logging::timestamp(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+/// ```
+pub fn timestamp(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    let (hour, minute, second) = (secs / 3600 % 24, secs / 60 % 60, secs % 60);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        hour,
+        minute,
+        second,
+        since_epoch.subsec_millis()
+    )
+}
+
+/// The proleptic Gregorian date `days` after 1970-01-01 (Howard Hinnant's
+/// `civil_from_days`), counting in 400-year eras of 146,097 days
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153; // March = 0
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
@@ -0,0 +1,143 @@
+//! Markov-Chain Text Generation
+//!
+//! An order-k Markov model remembers, for every run of k consecutive words
+//! in a text, the words that followed it. Generating text starts from one
+//! such run and keeps drawing a follower of the last k words, so the result
+//! reads locally like the source while wandering freely across it. Words
+//! that followed a run more often are drawn more often. The randomness comes
+//! from the pure [`Rng`], so a seed always gives the same text.
+//!
+//! ```
+//! use fp::markov::Markov;
+//! use fp::random::Rng;
+//!
+//! let text: Vec<&str> = "the cat sat on the mat and the cat ran".split(' ').collect();
+//! let model = Markov::new(&text, 1);
+//! let (words, _) = model.generate(6, Rng::new(7));
+//! assert_eq!(words.len(), 6);
+//! assert!(words.iter().all(|word| text.contains(&word.as_str())));
+//! // Same seed, same text
+//! assert_eq!(model.generate(6, Rng::new(7)).0, words);
+//! ```
+
+use std::collections::HashMap;
+
+use crate::random::Rng;
+
+/// The order `generate` uses unless told otherwise
+pub const DEFAULT_ORDER: usize = 2;
+
+/// An order-k model of which words follow each run of k words
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Markov {
+    order: usize,
+    /// The followers of every run, repeated as often as they followed it
+    followers: HashMap<Vec<String>, Vec<String>>,
+    /// Every run in the order of the text, to start from
+    states: Vec<Vec<String>>,
+}
+
+impl Markov {
+    /// The model of `tokens` with runs of `order` words; an order of 0 is
+    /// taken as 1
+    ///
+    /// An order longer than the text leaves the model empty.
+    ///
+    /// ```
+    /// use fp::markov::Markov;
+    ///
+    /// assert!(Markov::new(&["fold", "map"], usize::MAX).is_empty());
+    /// ```
+    pub fn new<S: AsRef<str>>(tokens: &[S], order: usize) -> Self {
+        let order = order.max(1);
+        let tokens: Vec<String> = tokens.iter().map(|t| t.as_ref().to_string()).collect();
+        // No text is long enough for a run one word longer than usize::MAX
+        let followers = match order.checked_add(1) {
+            Some(window) => tokens.windows(window).fold(
+                HashMap::new(),
+                |mut followers: HashMap<Vec<String>, Vec<String>>, window| {
+                    followers
+                        .entry(window[..order].to_vec())
+                        .or_default()
+                        .push(window[order].clone());
+                    followers
+                },
+            ),
+            None => HashMap::new(),
+        };
+        let states = tokens.windows(order).map(<[String]>::to_vec).collect();
+        Markov {
+            order,
+            followers,
+            states,
+        }
+    }
+
+    /// How many words each run holds
+    ///
+    /// ```
+    /// use fp::markov::Markov;
+    ///
+    /// assert_eq!(Markov::new(&["fold", "map", "filter"], 2).order(), 2);
+    /// ```
+    pub fn order(&self) -> usize {
+        self.order
+    }
+
+    /// Whether the text had fewer words than the order, leaving nothing to
+    /// generate from
+    ///
+    /// ```
+    /// use fp::markov::Markov;
+    ///
+    /// assert!(Markov::new(&["fold"], 2).is_empty());
+    /// assert!(!Markov::new(&["fold", "map"], 2).is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    /// `words` words of generated text and the generator to continue with;
+    /// none if the model is empty
+    ///
+    /// Starts from a random run of the text, and jumps to another random run
+    /// wherever the current one has no follower.
+    ///
+    /// ```
+    /// use fp::markov::Markov;
+    /// use fp::random::Rng;
+    ///
+    /// let model = Markov::new(&["fold", "map", "fold", "map"], 2);
+    /// assert_eq!(model.generate(3, Rng::new(1)).0.len(), 3);
+    /// assert!(Markov::new(&["fold"], 2).generate(3, Rng::new(1)).0.is_empty());
+    /// ```
+    pub fn generate(&self, words: usize, rng: Rng) -> (Vec<String>, Rng) {
+        if self.is_empty() {
+            return (Vec::new(), rng);
+        }
+        let (start, mut rng) = self.random_state(rng);
+        let mut text: Vec<String> = start.into_iter().take(words).collect();
+        while text.len() < words {
+            let run = &text[text.len() - self.order..];
+            match self.followers.get(run) {
+                Some(next) => {
+                    let (i, next_rng) = rng.next_below(next.len() as u64);
+                    text.push(next[i as usize].clone());
+                    rng = next_rng;
+                }
+                None => {
+                    let (state, next_rng) = self.random_state(rng);
+                    text.extend(state.into_iter().take(words - text.len()));
+                    rng = next_rng;
+                }
+            }
+        }
+        (text, rng)
+    }
+
+    /// A run drawn uniformly from the text's
+    fn random_state(&self, rng: Rng) -> (Vec<String>, Rng) {
+        let (i, rng) = rng.next_below(self.states.len() as u64);
+        (self.states[i as usize].clone(), rng)
+    }
+}
@@ -0,0 +1,287 @@
+//! Service Metrics
+//!
+//! What the word statistics service has done since it started, rendered in
+//! the Prometheus text exposition format for a scraper to collect: requests
+//! answered by path and status, bytes of text analyzed, words counted, and
+//! a histogram of how long requests took. Counters only ever grow; rates
+//! are the scraper's business.
+//!
+//! ```
+//! use fp::metrics::Metrics;
+//!
+//! let mut metrics = Metrics::new();
+//! metrics.record_analysis(11, 2);
+//! metrics.record_request("/analyze", 200, 0.003);
+//! let text = metrics.render();
+//! assert!(text.contains("fp_requests_total{path=\"/analyze\",status=\"200\"} 1\n"));
+//! assert!(text.contains("fp_analyzed_bytes_total 11\n"));
+//! assert!(text.contains("fp_request_duration_seconds_bucket{le=\"0.005\"} 1\n"));
+//! ```
+
+use std::collections::BTreeMap;
+
+/// Upper bounds of the latency buckets, in seconds
+pub const LATENCY_BUCKETS: &[f64] = &[
+    0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0,
+];
+
+/// The content type of [`Metrics::render`]'s text
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Observations counted into buckets by upper bound
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    bounds: Vec<f64>,
+    /// Observations per bucket, not cumulative; the last one is above every
+    /// bound
+    buckets: Vec<u64>,
+    sum: f64,
+}
+
+impl Histogram {
+    /// An empty histogram with buckets up to each of `bounds`, ascending
+    ///
+    /// ```
+    /// use fp::metrics::Histogram;
+    ///
+    /// let histogram = Histogram::new(&[0.1, 1.0]);
+    /// assert_eq!(histogram.count(), 0);
+    /// assert_eq!(histogram.sum(), 0.0);
+    /// ```
+    pub fn new(bounds: &[f64]) -> Self {
+        Histogram {
+            bounds: bounds.to_vec(),
+            buckets: vec![0; bounds.len() + 1],
+            sum: 0.0,
+        }
+    }
+
+    /// Count `value` in the first bucket whose bound it does not exceed
+    ///
+    /// ```
+    /// use fp::metrics::Histogram;
+    ///
+    /// let mut histogram = Histogram::new(&[0.1, 1.0]);
+    /// histogram.observe(1.0); // a value on a bound is in that bucket
+    /// assert!(histogram.render("h").contains("h_bucket{le=\"1\"} 1\n"));
+    /// ```
+    pub fn observe(&mut self, value: f64) {
+        let bucket = self.bounds.partition_point(|&bound| bound < value);
+        self.buckets[bucket] += 1;
+        self.sum += value;
+    }
+
+    /// How many values were observed
+    ///
+    /// ```
+    /// use fp::metrics::Histogram;
+    ///
+    /// let mut histogram = Histogram::new(&[1.0]);
+    /// histogram.observe(0.5);
+    /// histogram.observe(7.0); // above every bound, still counted
+    /// assert_eq!(histogram.count(), 2);
+    /// ```
+    pub fn count(&self) -> u64 {
+        self.buckets.iter().sum()
+    }
+
+    /// The sum of the values observed
+    ///
+    /// ```
+    /// use fp::metrics::Histogram;
+    ///
+    /// let mut histogram = Histogram::new(&[1.0]);
+    /// histogram.observe(0.25);
+    /// histogram.observe(2.5);
+    /// assert_eq!(histogram.sum(), 2.75);
+    /// ```
+    pub fn sum(&self) -> f64 {
+        self.sum
+    }
+
+    /// The `_bucket`, `_sum` and `_count` lines of the histogram `name`,
+    /// each bucket counting the values up to its bound
+    ///
+    /// ```
+    /// use fp::metrics::Histogram;
+    ///
+    /// let mut histogram = Histogram::new(&[0.1, 1.0]);
+    /// histogram.observe(0.5);
+    /// histogram.observe(2.0);
+    /// assert_eq!(
+    ///     histogram.render("latency"),
+    ///     "latency_bucket{le=\"0.1\"} 0\nlatency_bucket{le=\"1\"} 1\nlatency_bucket{le=\"+Inf\"} 2\n\
+    ///      latency_sum 2.5\nlatency_count 2\n"
+    /// );
+    /// ```
+    pub fn render(&self, name: &str) -> String {
+        let bounds = self
+            .bounds
+            .iter()
+            .map(f64::to_string)
+            .chain(std::iter::once("+Inf".to_string()));
+        let (buckets, count) = bounds.zip(&self.buckets).fold(
+            (String::new(), 0),
+            |(out, cumulative), (bound, count)| {
+                let cumulative = cumulative + count;
+                let line = format!("{}_bucket{{le=\"{}\"}} {}\n", name, bound, cumulative);
+                (out + &line, cumulative)
+            },
+        );
+        format!(
+            "{}{}_sum {}\n{}_count {}\n",
+            buckets, name, self.sum, name, count
+        )
+    }
+}
+
+/// The counters and latency histogram of the service
+#[derive(Debug, Clone, PartialEq)]
+pub struct Metrics {
+    /// Requests answered, by path and status
+    requests: BTreeMap<(String, u16), u64>,
+    analyzed_bytes: u64,
+    tokens: u64,
+    latency: Histogram,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Metrics {
+    /// No requests yet, latencies bucketed by [`LATENCY_BUCKETS`]
+    ///
+    /// ```
+    /// use fp::metrics::Metrics;
+    ///
+    /// let metrics = Metrics::new();
+    /// assert_eq!(metrics.requests(), 0);
+    /// assert!(metrics.render().contains("fp_tokens_total 0\n"));
+    /// ```
+    pub fn new() -> Self {
+        Metrics {
+            requests: BTreeMap::new(),
+            analyzed_bytes: 0,
+            tokens: 0,
+            latency: Histogram::new(LATENCY_BUCKETS),
+        }
+    }
+
+    /// Count a request to `path` answered with `status` after `seconds`
+    ///
+    /// ```
+    /// use fp::metrics::Metrics;
+    ///
+    /// let mut metrics = Metrics::new();
+    /// metrics.record_request("/analyze", 200, 0.01);
+    /// metrics.record_request("/analyze", 413, 0.002);
+    /// let text = metrics.render();
+    /// assert!(text.contains("fp_requests_total{path=\"/analyze\",status=\"413\"} 1\n"));
+    /// assert!(text.contains("fp_request_duration_seconds_count 2\n"));
+    /// ```
+    pub fn record_request(&mut self, path: &str, status: u16, seconds: f64) {
+        *self.requests.entry((path.to_string(), status)).or_insert(0) += 1;
+        self.latency.observe(seconds);
+    }
+
+    /// Count `bytes` of text analyzed into `tokens` words
+    ///
+    /// ```
+    /// use fp::metrics::Metrics;
+    ///
+    /// let mut metrics = Metrics::new();
+    /// metrics.record_analysis(11, 2);
+    /// metrics.record_analysis(4, 1);
+    /// assert!(metrics.render().contains("fp_tokens_total 3\n"));
+    /// ```
+    pub fn record_analysis(&mut self, bytes: usize, tokens: usize) {
+        self.analyzed_bytes += bytes as u64;
+        self.tokens += tokens as u64;
+    }
+
+    /// How many requests were answered
+    ///
+    /// ```
+    /// use fp::metrics::Metrics;
+    ///
+    /// let mut metrics = Metrics::new();
+    /// metrics.record_request("/analyze", 200, 0.01);
+    /// metrics.record_request("/metrics", 200, 0.001);
+    /// assert_eq!(metrics.requests(), 2);
+    /// ```
+    pub fn requests(&self) -> u64 {
+        self.requests.values().sum()
+    }
+
+    /// Every metric in the Prometheus text format, each with its `# HELP`
+    /// and `# TYPE` lines
+    ///
+    /// ```
+    /// use fp::metrics::Metrics;
+    ///
+    /// let mut metrics = Metrics::new();
+    /// metrics.record_request("/say \"hi\"", 404, 0.001);
+    /// let text = metrics.render();
+    /// assert!(text.starts_with("# HELP fp_requests_total "));
+    /// assert!(text.contains("# TYPE fp_request_duration_seconds histogram\n"));
+    /// // Label values are escaped
+    /// assert!(text.contains("path=\"/say \\\"hi\\\"\""));
+    /// ```
+    pub fn render(&self) -> String {
+        let requests: String = self
+            .requests
+            .iter()
+            .map(|((path, status), count)| {
+                format!(
+                    "fp_requests_total{{path=\"{}\",status=\"{}\"}} {}\n",
+                    label_value(path),
+                    status,
+                    count
+                )
+            })
+            .collect();
+        [
+            header(
+                "fp_requests_total",
+                "counter",
+                "HTTP requests answered, by path and status.",
+            ),
+            requests,
+            header(
+                "fp_analyzed_bytes_total",
+                "counter",
+                "Bytes of text analyzed.",
+            ),
+            format!("fp_analyzed_bytes_total {}\n", self.analyzed_bytes),
+            header(
+                "fp_tokens_total",
+                "counter",
+                "Words counted in the text analyzed.",
+            ),
+            format!("fp_tokens_total {}\n", self.tokens),
+            header(
+                "fp_request_duration_seconds",
+                "histogram",
+                "Time taken to answer a request.",
+            ),
+            self.latency.render("fp_request_duration_seconds"),
+        ]
+        .concat()
+    }
+}
+
+/// The `# HELP` and `# TYPE` lines of the metric `name`
+fn header(name: &str, kind: &str, help: &str) -> String {
+    format!("# HELP {} {}\n# TYPE {} {}\n", name, help, name, kind)
+}
+
+/// `value` escaped for a label: backslashes, quotes and newlines
+fn label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -0,0 +1,18 @@
+//! Monads for effects that stay pure.
+//!
+//! Each type here describes a computation with an extra capability (reading
+//! configuration, writing a log, threading state, performing IO) as an
+//! ordinary value, so code that needs the capability can still be a plain
+//! function of its inputs.
+
+pub mod free;
+pub mod io;
+pub mod reader;
+pub mod state;
+pub mod writer;
+
+pub use free::Program;
+pub use io::Io;
+pub use reader::Reader;
+pub use state::State;
+pub use writer::Writer;
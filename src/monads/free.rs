@@ -0,0 +1,389 @@
+//! A free-monad-style program of CLI effects with swappable interpreters.
+//!
+//! A [`Program`] is a tree of [`Command`]s (read a file, write output, log a
+//! message), each holding the rest of the program as a continuation. Building
+//! one performs nothing, and different interpreters give it different
+//! meanings: [`Program::into_io`] turns it into real effects, while
+//! [`InMemory`] runs it against a fake file system and records what it wrote,
+//! so a whole command-line flow can be checked without touching the disk.
+//!
+//! ```
+//! use fp::monads::free::{InMemory, Program};
+//!
+//! let shout = |path: &str| {
+//!     Program::log(format!("reading {}", path)).and_then({
+//!         let path = path.to_string();
+//!         move |_| Program::read_file(path)
+//!     })
+//!     .and_then(|contents| match contents {
+//!         Ok(text) => Program::write_output(text.to_uppercase()).map(|_| 0),
+//!         Err(err) => Program::log(err.to_string()).map(|_| 1),
+//!     })
+//! };
+//!
+//! let mut world = InMemory::with_files([("a.txt", "hi there")]);
+//! assert_eq!(world.run(shout("a.txt")), 0);
+//! assert_eq!(world.output, ["HI THERE"]);
+//! assert_eq!(world.log, ["reading a.txt"]);
+//!
+//! let mut empty = InMemory::default();
+//! assert_eq!(empty.run(shout("missing.txt")), 1);
+//! assert!(empty.output.is_empty());
+//! ```
+
+use std::collections::HashMap;
+use std::io;
+
+use super::Io;
+
+/// How severe a line of diagnostics is, the most severe first.
+///
+/// The real interpreter hands it to the `log` facade at the matching
+/// level; programs themselves do not depend on `log`.
+///
+/// ```
+/// use fp::monads::free::Level;
+///
+/// assert!(Level::Error < Level::Warn);
+/// assert_eq!(log::Level::from(Level::Warn), log::Level::Warn);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    /// The program failed
+    Error,
+    /// Something went wrong, but the program carries on
+    Warn,
+    /// What the program is doing
+    Info,
+    /// Detail for whoever is debugging it
+    Debug,
+    /// Every step
+    Trace,
+}
+
+impl From<Level> for log::Level {
+    fn from(level: Level) -> Self {
+        match level {
+            Level::Error => log::Level::Error,
+            Level::Warn => log::Level::Warn,
+            Level::Info => log::Level::Info,
+            Level::Debug => log::Level::Debug,
+            Level::Trace => log::Level::Trace,
+        }
+    }
+}
+
+/// One effect, followed by the rest of the program.
+pub enum Command<'a, Next> {
+    /// Read a whole file and continue with its contents.
+    ReadFile(String, Box<dyn FnOnce(io::Result<String>) -> Next + 'a>),
+    /// Write a line of output.
+    WriteOutput(String, Next),
+    /// Write a line of diagnostics at a severity level.
+    Log(Level, String, Next),
+}
+
+/// A description of effects ending in a value of type `A`.
+pub enum Program<'a, A> {
+    /// Finished with a value.
+    Done(A),
+    /// One more command to interpret.
+    Step(Box<Command<'a, Program<'a, A>>>),
+}
+
+impl<'a, A: 'a> Program<'a, A> {
+    /// A program with no effects.
+    ///
+    /// ```
+    /// use fp::monads::free::{InMemory, Program};
+    ///
+    /// let mut world = InMemory::default();
+    /// assert_eq!(world.run(Program::pure(42)), 42);
+    /// assert!(world.output.is_empty() && world.log.is_empty());
+    /// ```
+    pub fn pure(value: A) -> Self {
+        Program::Done(value)
+    }
+
+    /// A program starting with `command`.
+    ///
+    /// ```
+    /// use fp::monads::free::{Command, InMemory, Program};
+    ///
+    /// let program = Program::step(Command::WriteOutput("hi".to_string(), Program::pure(1)));
+    /// let mut world = InMemory::default();
+    /// assert_eq!(world.run(program), 1);
+    /// assert_eq!(world.output, ["hi"]);
+    /// ```
+    pub fn step(command: Command<'a, Self>) -> Self {
+        Program::Step(Box::new(command))
+    }
+
+    /// Runs `f`'s program after this one, feeding it this one's result.
+    ///
+    /// The output and log steps up to the end or the next file read are
+    /// peeled off in a loop and chained back in front of the rest, so a
+    /// program of any length is extended without recursing once per step.
+    ///
+    /// ```
+    /// use fp::monads::free::{InMemory, Program};
+    ///
+    /// let program = Program::read_file("notes.txt").and_then(|contents| {
+    ///     let words = contents.map_or(0, |text| text.split_whitespace().count());
+    ///     Program::write_output(format!("{} words", words)).map(move |_| words)
+    /// });
+    /// let mut world = InMemory::with_files([("notes.txt", "fold map fold")]);
+    /// assert_eq!(world.run(program), 3);
+    /// assert_eq!(world.output, ["3 words"]);
+    /// ```
+    pub fn and_then<B: 'a, F: FnOnce(A) -> Program<'a, B> + 'a>(self, f: F) -> Program<'a, B> {
+        let mut steps = Vec::new();
+        let mut program = self;
+        let rest = loop {
+            let command = match program {
+                Program::Done(a) => break f(a),
+                Program::Step(command) => *command,
+            };
+            program = match command {
+                Command::ReadFile(path, k) => {
+                    break Program::step(Command::ReadFile(
+                        path,
+                        Box::new(move |contents| k(contents).and_then(f)),
+                    ));
+                }
+                Command::WriteOutput(line, next) => {
+                    steps.push((None, line));
+                    next
+                }
+                Command::Log(level, line, next) => {
+                    steps.push((Some(level), line));
+                    next
+                }
+            };
+        };
+        steps
+            .into_iter()
+            .rev()
+            .fold(rest, |next, (level, line)| match level {
+                Some(level) => Program::step(Command::Log(level, line, next)),
+                None => Program::step(Command::WriteOutput(line, next)),
+            })
+    }
+
+    /// Runs this program, discards its result, then runs `next`.
+    ///
+    /// ```
+    /// use fp::monads::free::{InMemory, Program};
+    ///
+    /// let program = Program::write_output("one").then(Program::write_output("two"));
+    /// let mut world = InMemory::default();
+    /// world.run(program);
+    /// assert_eq!(world.output, ["one", "two"]);
+    /// ```
+    pub fn then<B: 'a>(self, next: Program<'a, B>) -> Program<'a, B> {
+        self.and_then(|_| next)
+    }
+
+    /// Transforms the final value.
+    ///
+    /// ```
+    /// use fp::monads::free::{InMemory, Program};
+    ///
+    /// let program = Program::write_output("counted").map(|_| 3).map(|n| n * 2);
+    /// let mut world = InMemory::default();
+    /// assert_eq!(world.run(program), 6);
+    /// assert_eq!(world.output, ["counted"]);
+    /// ```
+    pub fn map<B: 'a, F: FnOnce(A) -> B + 'a>(self, f: F) -> Program<'a, B> {
+        self.and_then(|a| Program::Done(f(a)))
+    }
+
+    /// Logs each of `lines` at info level, then runs this program.
+    ///
+    /// The log commands are chained in front of the program one step each,
+    /// where folding [`then`](Program::then) over the lines would nest a
+    /// `then` per line for every later step to unwind.
+    ///
+    /// ```
+    /// use fp::monads::free::{InMemory, Program};
+    ///
+    /// let lines: Vec<String> = (0..100_000).map(|i| i.to_string()).collect();
+    /// let program = Program::write_output("done").after_logs(lines);
+    /// let mut world = InMemory::default();
+    /// world.run(program.then(Program::write_output("after")));
+    /// assert_eq!(world.log.len(), 100_000);
+    /// assert_eq!(world.log[0], "0");
+    /// assert_eq!(world.output, ["done", "after"]);
+    /// ```
+    pub fn after_logs(self, lines: Vec<String>) -> Self {
+        lines.into_iter().rev().fold(self, |next, line| {
+            Program::step(Command::Log(Level::Info, line, next))
+        })
+    }
+
+    /// The real interpreter: translates every command into an [`Io`] action.
+    ///
+    /// Log commands go through the `log` facade, so they appear once a logger
+    /// such as [`Logger`](crate::logging::Logger) is installed.
+    ///
+    /// ```
+    /// use fp::monads::free::Program;
+    ///
+    /// let io = Program::read_file("/nonexistent/fp").map(|contents| contents.is_ok());
+    /// assert!(!io.into_io().run());
+    /// ```
+    pub fn into_io(self) -> Io<'a, A> {
+        // One action interpreting the commands in a loop, as nesting an
+        // `Io::and_then` per command would nest their runs as deep
+        Io::new(move || {
+            let mut program = self;
+            loop {
+                let command = match program {
+                    Program::Done(a) => return a,
+                    Program::Step(command) => *command,
+                };
+                program = match command {
+                    Command::ReadFile(path, k) => k(Io::read_file(path).run()),
+                    Command::WriteOutput(line, next) => {
+                        Io::println(line).run();
+                        next
+                    }
+                    Command::Log(level, line, next) => {
+                        Io::log(level.into(), line).run();
+                        next
+                    }
+                };
+            }
+        })
+    }
+}
+
+impl<'a> Program<'a, io::Result<String>> {
+    /// Reads a whole file.
+    ///
+    /// ```
+    /// use fp::monads::free::{InMemory, Program};
+    ///
+    /// let mut world = InMemory::with_files([("a.txt", "fold")]);
+    /// assert_eq!(world.run(Program::read_file("a.txt")).unwrap(), "fold");
+    /// assert!(world.run(Program::read_file("b.txt")).is_err());
+    /// ```
+    pub fn read_file<S: Into<String>>(path: S) -> Self {
+        Program::step(Command::ReadFile(path.into(), Box::new(Program::Done)))
+    }
+}
+
+impl<'a> Program<'a, ()> {
+    /// Writes a line of output.
+    ///
+    /// ```
+    /// use fp::monads::free::{InMemory, Program};
+    ///
+    /// let mut world = InMemory::default();
+    /// world.run(Program::write_output("Total words: 3"));
+    /// assert_eq!(world.output, ["Total words: 3"]);
+    /// assert!(world.log.is_empty());
+    /// ```
+    pub fn write_output<S: Into<String>>(line: S) -> Self {
+        Program::step(Command::WriteOutput(line.into(), Program::Done(())))
+    }
+
+    /// Writes a line of diagnostics at info level.
+    ///
+    /// ```
+    /// use fp::monads::free::{InMemory, Program};
+    ///
+    /// let mut world = InMemory::default();
+    /// world.run(Program::log("line 1: 3 words"));
+    /// assert_eq!(world.log, ["line 1: 3 words"]);
+    /// assert!(world.output.is_empty());
+    /// ```
+    pub fn log<S: Into<String>>(line: S) -> Self {
+        Program::log_at(Level::Info, line)
+    }
+
+    /// Writes a line of diagnostics at `level`.
+    ///
+    /// ```
+    /// use fp::monads::free::{InMemory, Level, Program};
+    ///
+    /// let mut world = InMemory::default();
+    /// world.run(Program::log_at(Level::Warn, "stopped early"));
+    /// assert_eq!(world.log, ["stopped early"]);
+    /// ```
+    pub fn log_at<S: Into<String>>(level: Level, line: S) -> Self {
+        Program::step(Command::Log(level, line.into(), Program::Done(())))
+    }
+}
+
+/// The test interpreter: an in-memory file system and captured output.
+#[derive(Debug, Default)]
+pub struct InMemory {
+    /// Files the program can read, by path.
+    pub files: HashMap<String, String>,
+    /// Lines written with `WriteOutput`.
+    pub output: Vec<String>,
+    /// Lines written with `Log`.
+    pub log: Vec<String>,
+}
+
+impl InMemory {
+    /// An interpreter whose file system holds `files`.
+    ///
+    /// ```
+    /// use fp::monads::free::{InMemory, Program};
+    ///
+    /// let mut world = InMemory::with_files([("a.txt", "fold"), ("b.txt", "map")]);
+    /// let both = Program::read_file("a.txt")
+    ///     .and_then(|a| Program::read_file("b.txt").map(move |b| a.unwrap() + &b.unwrap()));
+    /// assert_eq!(world.run(both), "foldmap");
+    /// ```
+    pub fn with_files<P: Into<String>, C: Into<String>>(
+        files: impl IntoIterator<Item = (P, C)>,
+    ) -> Self {
+        InMemory {
+            files: files
+                .into_iter()
+                .map(|(path, contents)| (path.into(), contents.into()))
+                .collect(),
+            ..InMemory::default()
+        }
+    }
+
+    /// Interprets `program`, recording its output, and returns its result.
+    ///
+    /// ```
+    /// use fp::monads::free::{InMemory, Program};
+    ///
+    /// let program = Program::log("starting")
+    ///     .then(Program::write_output("report"))
+    ///     .map(|_| 0);
+    /// let mut world = InMemory::default();
+    /// assert_eq!(world.run(program), 0);
+    /// assert_eq!((world.output.len(), world.log.len()), (1, 1));
+    /// ```
+    pub fn run<A>(&mut self, program: Program<'_, A>) -> A {
+        let mut program = program;
+        loop {
+            let command = match program {
+                Program::Done(a) => return a,
+                Program::Step(command) => *command,
+            };
+            program = match command {
+                Command::ReadFile(path, k) => k(self
+                    .files
+                    .get(&path)
+                    .cloned()
+                    .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))),
+                Command::WriteOutput(line, next) => {
+                    self.output.push(line);
+                    next
+                }
+                Command::Log(_, line, next) => {
+                    self.log.push(line);
+                    next
+                }
+            }
+        }
+    }
+}
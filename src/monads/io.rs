@@ -0,0 +1,192 @@
+//! The IO monad: effects described as values.
+//!
+//! An `Io<A>` is a recipe for an effect that produces an `A`. Building one
+//! (with [`Io::read_file`], [`Io::println`], [`Io::map`], [`Io::and_then`], ...)
+//! does nothing; only [`Io::run`] performs the effects, in order. A program
+//! written this way is a pure function returning a description of what it
+//! would do, and the single call to `run` in `main` is the edge where the
+//! description meets the world.
+//!
+//! ```
+//! use fp::monads::Io;
+//!
+//! let greet = |name: String| Io::println(format!("Hello, {}!", name)).map(move |_| name.len());
+//!
+//! // Nothing has been printed yet: `program` is just a value
+//! let program = Io::pure("Ada".to_string()).and_then(greet);
+//! assert_eq!(program.run(), 3);
+//! ```
+
+use log::Level;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// A deferred effect producing an `A`.
+pub struct Io<'a, A> {
+    effect: Box<dyn FnOnce() -> A + 'a>,
+}
+
+impl<'a, A: 'a> Io<'a, A> {
+    /// Wraps an effectful function without calling it.
+    ///
+    /// ```
+    /// use fp::monads::Io;
+    /// use std::cell::Cell;
+    ///
+    /// let ran = Cell::new(false);
+    /// let io = Io::new(|| ran.set(true));
+    /// assert!(!ran.get());
+    /// io.run();
+    /// assert!(ran.get());
+    /// ```
+    pub fn new<F: FnOnce() -> A + 'a>(f: F) -> Self {
+        Io {
+            effect: Box::new(f),
+        }
+    }
+
+    /// An action that performs no effect and returns `value`.
+    ///
+    /// ```
+    /// use fp::monads::Io;
+    ///
+    /// assert_eq!(Io::pure(42).run(), 42);
+    /// ```
+    pub fn pure(value: A) -> Self {
+        Io::new(move || value)
+    }
+
+    /// Performs the described effects. Call this once, at the edge of the program.
+    ///
+    /// ```
+    /// use fp::monads::Io;
+    /// use std::cell::Cell;
+    ///
+    /// let count = Cell::new(0);
+    /// let io = Io::new(|| count.set(count.get() + 1));
+    /// assert_eq!(count.get(), 0, "describing the action performs nothing");
+    /// io.run();
+    /// assert_eq!(count.get(), 1);
+    /// ```
+    pub fn run(self) -> A {
+        (self.effect)()
+    }
+
+    /// Transforms the result once the action has run.
+    ///
+    /// ```
+    /// use fp::monads::Io;
+    ///
+    /// let words = Io::pure("fold map fold").map(|text| text.split_whitespace().count());
+    /// assert_eq!(words.run(), 3);
+    /// ```
+    pub fn map<B: 'a, F: FnOnce(A) -> B + 'a>(self, f: F) -> Io<'a, B> {
+        Io::new(move || f(self.run()))
+    }
+
+    /// Runs this action, then the action `f` builds from its result.
+    ///
+    /// ```
+    /// use fp::monads::Io;
+    ///
+    /// let path = std::env::temp_dir().join("fp_io_and_then.txt");
+    /// std::fs::write(&path, "fold map").unwrap();
+    /// let words = Io::read_file(&path).and_then(|contents| {
+    ///     let count = contents.map_or(0, |text| text.split_whitespace().count());
+    ///     Io::println(format!("{} words", count)).map(move |_| count)
+    /// });
+    /// assert_eq!(words.run(), 2);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn and_then<B: 'a, F: FnOnce(A) -> Io<'a, B> + 'a>(self, f: F) -> Io<'a, B> {
+        Io::new(move || f(self.run()).run())
+    }
+
+    /// Runs this action, discards its result, then runs `next`.
+    ///
+    /// ```
+    /// use fp::monads::Io;
+    ///
+    /// assert_eq!(Io::println("first").then(Io::pure(2)).run(), 2);
+    /// ```
+    pub fn then<B: 'a>(self, next: Io<'a, B>) -> Io<'a, B> {
+        self.and_then(|_| next)
+    }
+}
+
+impl<'a> Io<'a, io::Result<String>> {
+    /// Reads a whole file into a `String`.
+    ///
+    /// ```
+    /// use fp::monads::Io;
+    ///
+    /// let path = std::env::temp_dir().join("fp_io_read_file.txt");
+    /// std::fs::write(&path, "fold").unwrap();
+    /// assert_eq!(Io::read_file(&path).run().unwrap(), "fold");
+    /// assert!(Io::read_file("/nonexistent/fp").run().is_err());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn read_file<P: Into<PathBuf>>(path: P) -> Self {
+        let path = path.into();
+        Io::new(move || fs::read_to_string(path))
+    }
+}
+
+impl<'a> Io<'a, ()> {
+    /// Prints a line to standard output.
+    ///
+    /// ```
+    /// use fp::monads::Io;
+    ///
+    /// // Nothing is printed until the action is run
+    /// let greeting = Io::println("hello");
+    /// greeting.run();
+    /// ```
+    pub fn println<S: Into<String>>(line: S) -> Self {
+        let line = line.into();
+        Io::new(move || println!("{}", line))
+    }
+
+    /// Prints a line to standard error.
+    ///
+    /// ```
+    /// use fp::monads::Io;
+    ///
+    /// let warn = Io::eprintln("warning: no words").map(|_| 1);
+    /// assert_eq!(warn.run(), 1);
+    /// ```
+    pub fn eprintln<S: Into<String>>(line: S) -> Self {
+        let line = line.into();
+        Io::new(move || eprintln!("{}", line))
+    }
+
+    /// Emits a message through the `log` facade at `level`.
+    ///
+    /// ```
+    /// use fp::monads::Io;
+    /// use log::Level;
+    ///
+    /// // Without a logger installed the message is dropped
+    /// Io::log(Level::Info, "counted 3 words").run();
+    /// ```
+    pub fn log<S: Into<String>>(level: Level, message: S) -> Self {
+        let message = message.into();
+        Io::new(move || log::log!(level, "{}", message))
+    }
+}
+
+impl<'a> Io<'a, Vec<String>> {
+    /// Reads the process's command-line arguments.
+    ///
+    /// ```
+    /// use fp::monads::Io;
+    ///
+    /// let program_name = Io::args().map(|args| args.into_iter().next());
+    /// assert!(program_name.run().is_some());
+    /// ```
+    pub fn args() -> Self {
+        Io::new(|| env::args().collect())
+    }
+}
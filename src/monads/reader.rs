@@ -0,0 +1,146 @@
+//! The Reader monad: computations that depend on a shared environment.
+//!
+//! A `Reader<Env, A>` is a function `&Env -> A` wrapped as a value. Functions
+//! that need configuration return a `Reader` instead of taking `&Config`, so
+//! the dependency shows up in their type and whole pipelines can be assembled
+//! before any environment exists. The same pipeline can then be run against
+//! as many environments as needed, which makes trying several
+//! configurations in a test a one-liner.
+//!
+//! ```
+//! use fp::monads::Reader;
+//!
+//! struct Config {
+//!     shout: bool,
+//!     suffix: &'static str,
+//! }
+//!
+//! fn render(word: &str) -> Reader<'_, Config, String> {
+//!     Reader::asks(|c: &Config| c.shout)
+//!         .map(move |shout| if shout { word.to_uppercase() } else { word.to_string() })
+//!         .zip(Reader::asks(|c: &Config| c.suffix))
+//!         .map(|(text, suffix)| text + suffix)
+//! }
+//!
+//! let greeting = render("hello");
+//! assert_eq!(greeting.run(&Config { shout: false, suffix: "." }), "hello.");
+//! assert_eq!(greeting.run(&Config { shout: true, suffix: "!" }), "HELLO!");
+//! ```
+
+/// A computation that reads an environment `Env` to produce an `A`.
+pub struct Reader<'a, Env, A> {
+    run: Box<dyn Fn(&Env) -> A + 'a>,
+}
+
+impl<'a, Env: 'a, A: 'a> Reader<'a, Env, A> {
+    /// Wraps a function of the environment.
+    ///
+    /// ```
+    /// use fp::monads::Reader;
+    ///
+    /// let double = Reader::new(|n: &i32| n * 2);
+    /// assert_eq!(double.run(&21), 42);
+    /// ```
+    pub fn new<F: Fn(&Env) -> A + 'a>(f: F) -> Self {
+        Reader { run: Box::new(f) }
+    }
+
+    /// Reads one part of the environment.
+    ///
+    /// ```
+    /// use fp::monads::Reader;
+    ///
+    /// struct Config {
+    ///     top: usize,
+    /// }
+    ///
+    /// let top = Reader::asks(|config: &Config| config.top);
+    /// assert_eq!(top.run(&Config { top: 10 }), 10);
+    /// ```
+    pub fn asks<F: Fn(&Env) -> A + 'a>(f: F) -> Self {
+        Reader::new(f)
+    }
+
+    /// Produces `value` regardless of the environment.
+    ///
+    /// ```
+    /// use fp::monads::Reader;
+    ///
+    /// let always = Reader::<'_, String, _>::pure(7);
+    /// assert_eq!(always.run(&"ignored".to_string()), 7);
+    /// ```
+    pub fn pure(value: A) -> Self
+    where
+        A: Clone,
+    {
+        Reader::new(move |_| value.clone())
+    }
+
+    /// Supplies the environment and runs the computation.
+    ///
+    /// ```
+    /// use fp::monads::Reader;
+    ///
+    /// // The same computation, run against two environments
+    /// let greet = Reader::new(|name: &&str| format!("hello {}", name));
+    /// assert_eq!(greet.run(&"fold"), "hello fold");
+    /// assert_eq!(greet.run(&"map"), "hello map");
+    /// ```
+    pub fn run(&self, env: &Env) -> A {
+        (self.run)(env)
+    }
+
+    /// Transforms the result.
+    ///
+    /// ```
+    /// use fp::monads::Reader;
+    ///
+    /// let length = Reader::new(|word: &String| word.clone()).map(|word| word.len());
+    /// assert_eq!(length.run(&"fold".to_string()), 4);
+    /// ```
+    pub fn map<B: 'a, F: Fn(A) -> B + 'a>(self, f: F) -> Reader<'a, Env, B> {
+        Reader::new(move |env| f(self.run(env)))
+    }
+
+    /// Chooses the next computation from this one's result, in the same environment.
+    ///
+    /// ```
+    /// use fp::monads::Reader;
+    ///
+    /// let limit = Reader::asks(|max: &usize| *max);
+    /// let words = limit.and_then(|n| Reader::new(move |max: &usize| format!("{} of {}", n.min(3), max)));
+    /// assert_eq!(words.run(&10), "3 of 10");
+    /// ```
+    pub fn and_then<B: 'a, F: Fn(A) -> Reader<'a, Env, B> + 'a>(self, f: F) -> Reader<'a, Env, B> {
+        Reader::new(move |env| f(self.run(env)).run(env))
+    }
+
+    /// Runs both computations in the same environment and pairs their results.
+    ///
+    /// ```
+    /// use fp::monads::Reader;
+    ///
+    /// let both = Reader::asks(|n: &i32| n + 1).zip(Reader::asks(|n: &i32| n * 2));
+    /// assert_eq!(both.run(&5), (6, 10));
+    /// ```
+    pub fn zip<B: 'a>(self, other: Reader<'a, Env, B>) -> Reader<'a, Env, (A, B)> {
+        Reader::new(move |env| (self.run(env), other.run(env)))
+    }
+
+    /// Adapts the computation to a larger environment it can be found in.
+    ///
+    /// ```
+    /// use fp::monads::Reader;
+    ///
+    /// struct App {
+    ///     name: String,
+    /// }
+    ///
+    /// let greeting = Reader::new(|name: &String| format!("hello {}", name));
+    /// let in_app = greeting.local(|app: &App| &app.name);
+    /// assert_eq!(in_app.run(&App { name: "fp".to_string() }), "hello fp");
+    /// ```
+    pub fn local<Outer: 'a, F: Fn(&Outer) -> &Env + 'a>(self, f: F) -> Reader<'a, Outer, A> {
+        Reader::new(move |outer| self.run(f(outer)))
+    }
+}
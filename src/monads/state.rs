@@ -0,0 +1,180 @@
+//! The State monad: computations that thread a state value through.
+//!
+//! A `State<S, A>` is a function `S -> (A, S)` wrapped as a value. Steps are
+//! chained with [`State::and_then`], which hands each step the state left by
+//! the previous one, so code that would otherwise take a `&mut` counter or
+//! cursor can be written as a pipeline of pure functions and run once at
+//! the end.
+//!
+//! A tokenizer that tracks its byte position without mutable parameters:
+//!
+//! ```
+//! use fp::monads::State;
+//!
+//! /// Reads the next whitespace-separated token and its offset, if any.
+//! fn next_token<'a>(text: &'a str) -> State<'a, usize, Option<(usize, &'a str)>> {
+//!     State::new(move |pos: usize| {
+//!         let rest = &text[pos..];
+//!         let start = pos + (rest.len() - rest.trim_start().len());
+//!         let len = text[start..].find(char::is_whitespace).unwrap_or(text.len() - start);
+//!         match len {
+//!             0 => (None, start),
+//!             _ => (Some((start, &text[start..start + len])), start + len),
+//!         }
+//!     })
+//! }
+//!
+//! fn tokens<'a>(text: &'a str) -> State<'a, usize, Vec<(usize, &'a str)>> {
+//!     next_token(text).and_then(move |token| match token {
+//!         None => State::pure(Vec::new()),
+//!         Some(t) => tokens(text).map(move |mut rest| {
+//!             rest.insert(0, t);
+//!             rest
+//!         }),
+//!     })
+//! }
+//!
+//! let (found, end) = tokens("  to be  or").run(0);
+//! assert_eq!(found, [(2, "to"), (5, "be"), (9, "or")]);
+//! assert_eq!(end, 11);
+//! ```
+//!
+//! `State` wraps a boxed closure, so unlike the other monads it has no
+//! [`Functor`](crate::typeclasses::Functor) instance: the trait cannot
+//! require the mapped function to outlive the computation.
+
+/// A computation that reads and updates a state `S` and produces an `A`.
+pub struct State<'a, S, A> {
+    run: Box<dyn FnOnce(S) -> (A, S) + 'a>,
+}
+
+impl<'a, S: 'a, A: 'a> State<'a, S, A> {
+    /// Wraps a state transition.
+    ///
+    /// ```
+    /// use fp::monads::State;
+    ///
+    /// let next_id = State::new(|id: u32| (format!("word-{}", id), id + 1));
+    /// assert_eq!(next_id.run(3), ("word-3".to_string(), 4));
+    /// ```
+    pub fn new<F: FnOnce(S) -> (A, S) + 'a>(f: F) -> Self {
+        State { run: Box::new(f) }
+    }
+
+    /// Produces `value` without touching the state.
+    ///
+    /// ```
+    /// use fp::monads::State;
+    ///
+    /// assert_eq!(State::pure("fold").run(9), ("fold", 9));
+    /// ```
+    pub fn pure(value: A) -> Self {
+        State::new(move |s| (value, s))
+    }
+
+    /// Runs the computation from `initial`, returning the result and final state.
+    ///
+    /// ```
+    /// use fp::monads::State;
+    ///
+    /// let count = State::new(|seen: usize| ("fold", seen + 1));
+    /// assert_eq!(count.run(0), ("fold", 1));
+    /// ```
+    pub fn run(self, initial: S) -> (A, S) {
+        (self.run)(initial)
+    }
+
+    /// Runs the computation and keeps only the result.
+    ///
+    /// ```
+    /// use fp::monads::State;
+    ///
+    /// let next_id = State::new(|id: u32| (id, id + 1));
+    /// assert_eq!(next_id.eval(7), 7);
+    /// ```
+    pub fn eval(self, initial: S) -> A {
+        self.run(initial).0
+    }
+
+    /// Runs the computation and keeps only the final state.
+    ///
+    /// ```
+    /// use fp::monads::State;
+    ///
+    /// let next_id = State::new(|id: u32| (id, id + 1));
+    /// assert_eq!(next_id.exec(7), 8);
+    /// ```
+    pub fn exec(self, initial: S) -> S {
+        self.run(initial).1
+    }
+
+    /// Transforms the result.
+    ///
+    /// ```
+    /// use fp::monads::State;
+    ///
+    /// let next_id = State::new(|id: u32| (id, id + 1)).map(|id| id * 100);
+    /// assert_eq!(next_id.run(2), (200, 3));
+    /// ```
+    pub fn map<B: 'a, F: FnOnce(A) -> B + 'a>(self, f: F) -> State<'a, S, B> {
+        State::new(move |s| {
+            let (a, s) = self.run(s);
+            (f(a), s)
+        })
+    }
+
+    /// Runs `f`'s computation on the state this one leaves behind.
+    ///
+    /// ```
+    /// use fp::monads::State;
+    ///
+    /// let next_id = || State::new(|id: u32| (id, id + 1));
+    /// let pair = next_id().and_then(move |a| next_id().map(move |b| (a, b)));
+    /// assert_eq!(pair.run(10), ((10, 11), 12));
+    /// ```
+    pub fn and_then<B: 'a, F: FnOnce(A) -> State<'a, S, B> + 'a>(self, f: F) -> State<'a, S, B> {
+        State::new(move |s| {
+            let (a, s) = self.run(s);
+            f(a).run(s)
+        })
+    }
+}
+
+impl<'a, S: Clone + 'a> State<'a, S, S> {
+    /// Reads the current state.
+    ///
+    /// ```
+    /// use fp::monads::State;
+    ///
+    /// let doubled = State::get().map(|n: i32| n * 2);
+    /// assert_eq!(doubled.run(5), (10, 5));
+    /// ```
+    pub fn get() -> Self {
+        State::new(|s: S| (s.clone(), s))
+    }
+}
+
+impl<'a, S: 'a> State<'a, S, ()> {
+    /// Replaces the state.
+    ///
+    /// ```
+    /// use fp::monads::State;
+    ///
+    /// assert_eq!(State::put(3).run(0), ((), 3));
+    /// ```
+    pub fn put(state: S) -> Self {
+        State::new(move |_| ((), state))
+    }
+
+    /// Updates the state with `f`.
+    ///
+    /// ```
+    /// use fp::monads::State;
+    ///
+    /// let count_twice = State::modify(|n: u32| n + 1).and_then(|_| State::modify(|n| n + 1));
+    /// assert_eq!(count_twice.exec(0), 2);
+    /// ```
+    pub fn modify<F: FnOnce(S) -> S + 'a>(f: F) -> Self {
+        State::new(move |s| ((), f(s)))
+    }
+}
@@ -0,0 +1,187 @@
+//! The Writer monad: a value paired with an accumulated log.
+//!
+//! Instead of printing as it goes, a computation returns what it would have
+//! printed alongside its result. Chaining with [`Writer::and_then`] combines
+//! the logs through their [`Monoid`] instance, and the caller decides what
+//! to do with the final log.
+//!
+//! ```
+//! use fp::monads::Writer;
+//!
+//! fn halve(n: i32) -> Writer<i32, Vec<String>> {
+//!     Writer::new(n / 2, vec![format!("halved {}", n)])
+//! }
+//!
+//! let (value, log) = Writer::pure(40).and_then(halve).and_then(halve).run();
+//! assert_eq!(value, 10);
+//! assert_eq!(log, ["halved 40", "halved 20"]);
+//! ```
+
+use crate::monoid::{Monoid, Semigroup};
+use crate::typeclasses::{Applicative, Functor, Monad};
+
+/// A value of type `T` together with a log of type `W`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Writer<T, W> {
+    value: T,
+    log: W,
+}
+
+impl<T, W> Writer<T, W> {
+    /// Pairs a value with a log.
+    ///
+    /// ```
+    /// use fp::monads::Writer;
+    ///
+    /// let counted = Writer::new(3, vec!["line 1: 3 words"]);
+    /// assert_eq!(counted.run(), (3, vec!["line 1: 3 words"]));
+    /// ```
+    pub fn new(value: T, log: W) -> Self {
+        Writer { value, log }
+    }
+
+    /// The value, without the log.
+    ///
+    /// ```
+    /// use fp::monads::Writer;
+    ///
+    /// assert_eq!(Writer::new(3, vec!["three"]).value(), &3);
+    /// ```
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// The log so far.
+    ///
+    /// ```
+    /// use fp::monads::Writer;
+    ///
+    /// assert_eq!(Writer::new(3, vec!["three"]).log(), &["three"]);
+    /// ```
+    pub fn log(&self) -> &W {
+        &self.log
+    }
+
+    /// Splits into the value and the log.
+    ///
+    /// ```
+    /// use fp::monads::Writer;
+    ///
+    /// let (value, log) = Writer::new("fold", String::from("read fold; ")).run();
+    /// assert_eq!((value, log.as_str()), ("fold", "read fold; "));
+    /// ```
+    pub fn run(self) -> (T, W) {
+        (self.value, self.log)
+    }
+
+    /// Transforms the value, keeping the log.
+    ///
+    /// ```
+    /// use fp::monads::Writer;
+    ///
+    /// let w = Writer::new(2, vec!["two"]).map(|n| n * 10);
+    /// assert_eq!(w.run(), (20, vec!["two"]));
+    /// ```
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Writer<U, W> {
+        Writer::new(f(self.value), self.log)
+    }
+}
+
+impl<T, W: Monoid> Writer<T, W> {
+    /// A value with an empty log.
+    ///
+    /// ```
+    /// use fp::monads::Writer;
+    ///
+    /// let w: Writer<i32, Vec<String>> = Writer::pure(7);
+    /// assert_eq!(w.value(), &7);
+    /// assert!(w.log().is_empty());
+    /// ```
+    pub fn pure(value: T) -> Self {
+        Writer::new(value, W::empty())
+    }
+
+    /// Runs `f` on the value and appends its log to this one.
+    ///
+    /// ```
+    /// use fp::monads::Writer;
+    ///
+    /// let words = |line: &'static str| {
+    ///     let n = line.split_whitespace().count();
+    ///     Writer::new(n, vec![format!("{}: {}", line, n)])
+    /// };
+    /// let total = words("fold map").and_then(|a| words("fold").map(move |b| a + b));
+    /// assert_eq!(total.run(), (3, vec!["fold map: 2".to_string(), "fold: 1".to_string()]));
+    /// ```
+    pub fn and_then<U, F: FnOnce(T) -> Writer<U, W>>(self, f: F) -> Writer<U, W> {
+        let next = f(self.value);
+        Writer::new(next.value, self.log.combine(next.log))
+    }
+
+    /// Appends `entry` to the log.
+    ///
+    /// ```
+    /// use fp::monads::Writer;
+    ///
+    /// let w = Writer::pure(1).and_tell(vec!["one"]).and_tell(vec!["done"]);
+    /// assert_eq!(w.log(), &["one", "done"]);
+    /// ```
+    pub fn and_tell(self, entry: W) -> Self {
+        Writer::new(self.value, self.log.combine(entry))
+    }
+}
+
+impl<W> Writer<(), W> {
+    /// A computation that only writes to the log.
+    ///
+    /// ```
+    /// use fp::monads::Writer;
+    ///
+    /// let (value, log) = Writer::tell(vec!["started"]).and_then(|()| Writer::new(5, vec!["five"])).run();
+    /// assert_eq!(value, 5);
+    /// assert_eq!(log, ["started", "five"]);
+    /// ```
+    pub fn tell(log: W) -> Self {
+        Writer::new((), log)
+    }
+}
+
+/// Writers combine pointwise, so [`fold_map`](crate::monoid::fold_map) can
+/// merge many logged results at once.
+impl<T: Semigroup, W: Semigroup> Semigroup for Writer<T, W> {
+    fn combine(self, other: Self) -> Self {
+        Writer::new(self.value.combine(other.value), self.log.combine(other.log))
+    }
+}
+
+impl<T: Monoid, W: Monoid> Monoid for Writer<T, W> {
+    fn empty() -> Self {
+        Writer::new(T::empty(), W::empty())
+    }
+}
+
+impl<T, W> Functor for Writer<T, W> {
+    type Inner = T;
+    type Wrapped<B> = Writer<B, W>;
+
+    fn fmap<B, F: FnMut(T) -> B>(self, f: F) -> Writer<B, W> {
+        self.map(f)
+    }
+}
+
+impl<T, W: Monoid> Applicative for Writer<T, W> {
+    fn pure(value: T) -> Self {
+        Writer::pure(value)
+    }
+
+    fn ap<B, F: FnMut(T) -> B>(self, fs: Writer<F, W>) -> Writer<B, W> {
+        let (mut f, log) = fs.run();
+        Writer::new(f(self.value), self.log.combine(log))
+    }
+}
+
+impl<T, W: Monoid> Monad for Writer<T, W> {
+    fn bind<B, F: FnMut(T) -> Writer<B, W>>(self, f: F) -> Writer<B, W> {
+        self.and_then(f)
+    }
+}
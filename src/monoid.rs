@@ -0,0 +1,188 @@
+//! Semigroup and Monoid traits.
+//!
+//! A `Semigroup` can combine two values into one (associatively); a `Monoid`
+//! also has an `empty` value that changes nothing when combined. Together
+//! they turn "merge many partial results" into a single generic fold, which
+//! is how the analyzer merges word counts.
+//!
+//! Plain integers and floats combine by addition (the counting monoid), so a
+//! `HashMap<String, usize>` of word counts is a monoid as-is. Use the
+//! [`Sum`] and [`Product`] wrappers to pick an operation explicitly.
+
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+
+/// A type with an associative `combine` operation.
+pub trait Semigroup {
+    /// Combines two values: `a.combine(b).combine(c) == a.combine(b.combine(c))`.
+    fn combine(self, other: Self) -> Self;
+}
+
+/// A semigroup with an identity element.
+pub trait Monoid: Semigroup {
+    /// The identity: `x.combine(empty()) == x == empty().combine(x)`.
+    fn empty() -> Self;
+}
+
+/// Combines numbers by addition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Sum<T>(pub T);
+
+/// Combines numbers by multiplication.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Product<T>(pub T);
+
+macro_rules! numeric_monoid {
+    ($($t:ty => $zero:expr, $one:expr);* $(;)?) => {
+        $(
+            impl Semigroup for $t {
+                fn combine(self, other: Self) -> Self {
+                    self + other
+                }
+            }
+
+            impl Monoid for $t {
+                fn empty() -> Self {
+                    $zero
+                }
+            }
+
+            impl Semigroup for Sum<$t> {
+                fn combine(self, other: Self) -> Self {
+                    Sum(self.0 + other.0)
+                }
+            }
+
+            impl Monoid for Sum<$t> {
+                fn empty() -> Self {
+                    Sum($zero)
+                }
+            }
+
+            impl Semigroup for Product<$t> {
+                fn combine(self, other: Self) -> Self {
+                    Product(self.0 * other.0)
+                }
+            }
+
+            impl Monoid for Product<$t> {
+                fn empty() -> Self {
+                    Product($one)
+                }
+            }
+        )*
+    };
+}
+
+numeric_monoid! {
+    u8 => 0, 1; u16 => 0, 1; u32 => 0, 1; u64 => 0, 1; u128 => 0, 1; usize => 0, 1;
+    i8 => 0, 1; i16 => 0, 1; i32 => 0, 1; i64 => 0, 1; i128 => 0, 1; isize => 0, 1;
+    f32 => 0.0, 1.0; f64 => 0.0, 1.0;
+}
+
+impl Semigroup for String {
+    fn combine(mut self, other: Self) -> Self {
+        self.push_str(&other);
+        self
+    }
+}
+
+impl Monoid for String {
+    fn empty() -> Self {
+        String::new()
+    }
+}
+
+impl<T> Semigroup for Vec<T> {
+    fn combine(mut self, other: Self) -> Self {
+        self.extend(other);
+        self
+    }
+}
+
+impl<T> Monoid for Vec<T> {
+    fn empty() -> Self {
+        Vec::new()
+    }
+}
+
+/// `None` is the identity; two `Some`s combine their contents.
+impl<T: Semigroup> Semigroup for Option<T> {
+    fn combine(self, other: Self) -> Self {
+        match (self, other) {
+            (Some(a), Some(b)) => Some(a.combine(b)),
+            (a, None) => a,
+            (None, b) => b,
+        }
+    }
+}
+
+impl<T: Semigroup> Monoid for Option<T> {
+    fn empty() -> Self {
+        None
+    }
+}
+
+/// Maps merge key-wise, combining the values of keys present in both.
+///
+/// ```
+/// use std::collections::HashMap;
+/// use fp::monoid::Semigroup;
+///
+/// let a = HashMap::from([("fp", 2), ("rust", 1)]);
+/// let b = HashMap::from([("fp", 3), ("iter", 4)]);
+/// let merged = a.combine(b);
+/// assert_eq!(merged, HashMap::from([("fp", 5), ("rust", 1), ("iter", 4)]));
+/// ```
+impl<K: Eq + Hash, V: Semigroup, S: BuildHasher> Semigroup for HashMap<K, V, S> {
+    fn combine(mut self, other: Self) -> Self {
+        for (key, value) in other {
+            let merged = match self.remove(&key) {
+                Some(existing) => existing.combine(value),
+                None => value,
+            };
+            self.insert(key, merged);
+        }
+        self
+    }
+}
+
+impl<K: Eq + Hash, V: Semigroup, S: BuildHasher + Default> Monoid for HashMap<K, V, S> {
+    fn empty() -> Self {
+        HashMap::default()
+    }
+}
+
+/// Combines every value, returning `empty()` for no values.
+///
+/// ```
+/// use fp::monoid::{mconcat, Product};
+///
+/// assert_eq!(mconcat(vec![1, 2, 3]), 6);
+/// assert_eq!(mconcat(vec![Product(2), Product(5)]), Product(10));
+/// assert_eq!(mconcat(vec!["a".to_string(), "b".to_string()]), "ab");
+/// ```
+pub fn mconcat<M, I>(items: I) -> M
+where
+    M: Monoid,
+    I: IntoIterator<Item = M>,
+{
+    items.into_iter().fold(M::empty(), M::combine)
+}
+
+/// Maps every item into a monoid and combines the results.
+///
+/// ```
+/// use fp::monoid::{fold_map, Sum};
+///
+/// let total_len = fold_map(["fold", "map"], |w| Sum(w.len()));
+/// assert_eq!(total_len, Sum(7));
+/// ```
+pub fn fold_map<T, M, I, F>(items: I, f: F) -> M
+where
+    M: Monoid,
+    I: IntoIterator<Item = T>,
+    F: FnMut(T) -> M,
+{
+    mconcat(items.into_iter().map(f))
+}
@@ -0,0 +1,209 @@
+//! Grouping by key and a map with many values per key.
+//!
+//! [`group_by_key`] collects *all* items sharing a key, wherever they appear
+//! (unlike [`FoldableExt::group_by_key`](crate::iter_ext::FoldableExt::group_by_key),
+//! which only groups consecutive runs). [`MultiMap`] wraps the resulting
+//! `HashMap<K, Vec<V>>` with the handful of operations that shape needs.
+
+use std::collections::HashMap;
+use std::collections::hash_map;
+use std::hash::Hash;
+
+/// Groups items by the key `f` computes, keeping each group in input order.
+///
+/// ```
+/// use fp::multimap::group_by_key;
+///
+/// let by_parity = group_by_key(1..=6, |n| n % 2 == 0);
+/// assert_eq!(by_parity[&true], [2, 4, 6]);
+/// assert_eq!(by_parity[&false], [1, 3, 5]);
+/// ```
+pub fn group_by_key<I, K, F>(items: I, mut f: F) -> HashMap<K, Vec<I::Item>>
+where
+    I: IntoIterator,
+    K: Eq + Hash,
+    F: FnMut(&I::Item) -> K,
+{
+    items.into_iter().fold(HashMap::new(), |mut groups, item| {
+        groups.entry(f(&item)).or_insert_with(Vec::new).push(item);
+        groups
+    })
+}
+
+/// A map from each key to all the values inserted under it.
+///
+/// ```
+/// use fp::multimap::MultiMap;
+///
+/// let words = ["apple", "avocado", "banana"];
+/// let by_initial = MultiMap::group_by(words, |w| w.chars().next());
+/// assert_eq!(by_initial.get(&Some('a')), ["apple", "avocado"]);
+/// assert_eq!(by_initial.get(&Some('c')), [] as [&str; 0]);
+/// assert_eq!((by_initial.len(), by_initial.values_len()), (2, 3));
+/// ```
+#[derive(Debug, Clone)]
+pub struct MultiMap<K, V> {
+    groups: HashMap<K, Vec<V>>,
+}
+
+impl<K: Eq + Hash, V> MultiMap<K, V> {
+    /// An empty multimap.
+    ///
+    /// ```
+    /// use fp::multimap::MultiMap;
+    ///
+    /// let empty: MultiMap<char, &str> = MultiMap::new();
+    /// assert!(empty.is_empty());
+    /// ```
+    pub fn new() -> Self {
+        MultiMap {
+            groups: HashMap::new(),
+        }
+    }
+
+    /// Groups `items` by the key `f` computes.
+    ///
+    /// ```
+    /// use fp::multimap::MultiMap;
+    ///
+    /// let by_len = MultiMap::group_by(["fold", "map", "scan", "zip"], |w| w.len());
+    /// assert_eq!(by_len.get(&4), ["fold", "scan"]);
+    /// assert_eq!(by_len.get(&3), ["map", "zip"]);
+    /// ```
+    pub fn group_by<I, F>(items: I, f: F) -> Self
+    where
+        I: IntoIterator<Item = V>,
+        F: FnMut(&V) -> K,
+    {
+        MultiMap {
+            groups: group_by_key(items, f),
+        }
+    }
+
+    /// Adds `value` under `key`, after any values already there.
+    ///
+    /// ```
+    /// use fp::multimap::MultiMap;
+    ///
+    /// let mut tags = MultiMap::new();
+    /// tags.insert("rust", "fold");
+    /// tags.insert("rust", "map");
+    /// assert_eq!(tags.get(&"rust"), ["fold", "map"]);
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) {
+        self.groups.entry(key).or_default().push(value);
+    }
+
+    /// All values under `key`, empty if there are none.
+    ///
+    /// ```
+    /// use fp::multimap::MultiMap;
+    ///
+    /// let by_parity = MultiMap::group_by([1, 2, 3], |n| n % 2);
+    /// assert_eq!(by_parity.get(&1), [1, 3]);
+    /// assert_eq!(by_parity.get(&7), [] as [i32; 0]);
+    /// ```
+    pub fn get(&self, key: &K) -> &[V] {
+        self.groups.get(key).map_or(&[], Vec::as_slice)
+    }
+
+    /// Whether any value is stored under `key`.
+    ///
+    /// ```
+    /// use fp::multimap::MultiMap;
+    ///
+    /// let by_len = MultiMap::group_by(["fold", "map"], |w| w.len());
+    /// assert!(by_len.contains_key(&4));
+    /// assert!(!by_len.contains_key(&5));
+    /// ```
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.groups.contains_key(key)
+    }
+
+    /// Number of distinct keys.
+    ///
+    /// ```
+    /// use fp::multimap::MultiMap;
+    ///
+    /// let by_parity = MultiMap::group_by([1, 2, 3, 5], |n| n % 2);
+    /// assert_eq!(by_parity.len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// Whether there are no keys.
+    ///
+    /// ```
+    /// use fp::multimap::MultiMap;
+    ///
+    /// assert!(MultiMap::group_by(Vec::<i32>::new(), |n| n % 2).is_empty());
+    /// assert!(!MultiMap::group_by([1], |n| n % 2).is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Number of values across all keys.
+    ///
+    /// ```
+    /// use fp::multimap::MultiMap;
+    ///
+    /// let by_parity = MultiMap::group_by([1, 2, 3, 5], |n| n % 2);
+    /// assert_eq!(by_parity.values_len(), 4);
+    /// ```
+    pub fn values_len(&self) -> usize {
+        self.groups.values().map(Vec::len).sum()
+    }
+
+    /// Every key with its values, in no particular order.
+    ///
+    /// ```
+    /// use fp::multimap::MultiMap;
+    ///
+    /// let by_parity = MultiMap::group_by([1, 2, 3], |n| n % 2);
+    /// let mut sizes: Vec<(i32, usize)> = by_parity.iter().map(|(k, vs)| (*k, vs.len())).collect();
+    /// sizes.sort();
+    /// assert_eq!(sizes, [(0, 1), (1, 2)]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &[V])> {
+        self.groups.iter().map(|(k, vs)| (k, vs.as_slice()))
+    }
+
+    /// Unwraps into the underlying `HashMap`.
+    ///
+    /// ```
+    /// use fp::multimap::MultiMap;
+    /// use std::collections::HashMap;
+    ///
+    /// let groups = MultiMap::group_by(["ant", "bee", "asp"], |w| w.chars().next()).into_inner();
+    /// assert_eq!(groups, HashMap::from([(Some('a'), vec!["ant", "asp"]), (Some('b'), vec!["bee"])]));
+    /// ```
+    pub fn into_inner(self) -> HashMap<K, Vec<V>> {
+        self.groups
+    }
+}
+
+impl<K: Eq + Hash, V> Default for MultiMap<K, V> {
+    fn default() -> Self {
+        MultiMap::new()
+    }
+}
+
+impl<K: Eq + Hash, V> FromIterator<(K, V)> for MultiMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        iter.into_iter().fold(MultiMap::new(), |mut map, (k, v)| {
+            map.insert(k, v);
+            map
+        })
+    }
+}
+
+impl<K, V> IntoIterator for MultiMap<K, V> {
+    type Item = (K, Vec<V>);
+    type IntoIter = hash_map::IntoIter<K, Vec<V>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.groups.into_iter()
+    }
+}
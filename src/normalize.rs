@@ -0,0 +1,102 @@
+//! Unicode Normalization
+//!
+//! The same word can be encoded in several ways: "café" is either a single
+//! precomposed `é` or an `e` followed by a combining acute accent, depending
+//! on the keyboard or input method that produced it. Normalizing every word
+//! before it is cleaned makes such words count as one. NFC composes
+//! canonically equivalent sequences; NFKC also folds compatibility forms
+//! such as ligatures and full-width letters. Stripping accents goes further
+//! and merges "café" with "cafe".
+//!
+//! ```
+//! use fp::normalize::Normalization;
+//!
+//! let precomposed = "caf\u{e9}";
+//! let decomposed = "cafe\u{301}";
+//! assert_ne!(precomposed, decomposed);
+//! assert_eq!(Normalization::Nfc.apply(decomposed), precomposed);
+//! assert_eq!(Normalization::Nfkc.apply("\u{fb01}ne"), "fine");
+//! assert_eq!(Normalization::None.apply(decomposed), decomposed);
+//! assert_eq!(fp::normalize::strip_accents(precomposed), "cafe");
+//! ```
+
+use std::borrow::Cow;
+use std::str::FromStr;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::{UnicodeNormalization, is_nfc, is_nfkc};
+
+/// Which Unicode normalization form words are brought to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Normalization {
+    /// Leave words as they are
+    #[default]
+    None,
+    /// Canonical composition
+    Nfc,
+    /// Compatibility composition
+    Nfkc,
+}
+
+impl Normalization {
+    /// `text` in this normalization form, borrowed when it already is.
+    ///
+    /// ```
+    /// use fp::normalize::Normalization;
+    /// use std::borrow::Cow;
+    ///
+    /// // Full-width letters are compatibility forms, kept by NFC
+    /// assert_eq!(Normalization::Nfc.apply("\u{ff26}old"), "\u{ff26}old");
+    /// assert_eq!(Normalization::Nfkc.apply("\u{ff26}old"), "Fold");
+    /// // Nothing to do, nothing allocated
+    /// assert!(matches!(Normalization::Nfkc.apply("fold"), Cow::Borrowed("fold")));
+    /// ```
+    pub fn apply(self, text: &str) -> Cow<'_, str> {
+        match self {
+            Normalization::None => Cow::Borrowed(text),
+            Normalization::Nfc if is_nfc(text) => Cow::Borrowed(text),
+            Normalization::Nfc => Cow::Owned(text.nfc().collect()),
+            Normalization::Nfkc if is_nfkc(text) => Cow::Borrowed(text),
+            Normalization::Nfkc => Cow::Owned(text.nfkc().collect()),
+        }
+    }
+}
+
+impl FromStr for Normalization {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Normalization::None),
+            "nfc" => Ok(Normalization::Nfc),
+            "nfkc" => Ok(Normalization::Nfkc),
+            _ => Err(format!(
+                "Unknown normalization '{}', expected nfc, nfkc or none",
+                s
+            )),
+        }
+    }
+}
+
+/// Remove diacritics: decompose canonically (NFD), drop the combining marks
+/// and recompose what is left (NFC). Letters without a decomposition, such
+/// as "ø" or "ł", are kept.
+///
+/// ```
+/// use fp::normalize::strip_accents;
+///
+/// assert_eq!(strip_accents("Ångström"), "Angstrom");
+/// assert_eq!(strip_accents("cafe\u{301}"), "cafe");
+/// assert_eq!(strip_accents("smørrebrød"), "smørrebrød");
+/// assert_eq!(strip_accents("한국어"), "한국어");
+/// ```
+pub fn strip_accents(text: &str) -> Cow<'_, str> {
+    if text.is_ascii() {
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(
+        text.nfd()
+            .filter(|&c| !is_combining_mark(c))
+            .nfc()
+            .collect(),
+    )
+}
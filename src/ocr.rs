@@ -0,0 +1,203 @@
+//! OCR Cleanup
+//!
+//! Text recognized from scanned pages carries the typesetting of the page
+//! into the words: ligatures come out as single characters ("ﬁle" for
+//! "file"), soft hyphens mark where words may break, and words broken at
+//! the end of a line stay broken ("func-" and "tional"). Each of these
+//! turns one word into several distinct ones and skews every count, so
+//! [`clean_ocr`] undoes them before the text is tokenized.
+//!
+//! Broken words are as common in plain-text books, which are hard-wrapped,
+//! so rejoining them also works alone, and on a stream of lines through
+//! [`RejoinHyphens`].
+//!
+//! ```
+//! use fp::ocr::clean_ocr;
+//!
+//! let scanned = "The ﬁrst func-\ntional pro\u{ad}gram\n";
+//! assert_eq!(clean_ocr(scanned), "The first\nfunctional program\n");
+//! ```
+
+use std::borrow::Cow;
+use std::convert::Infallible;
+use std::iter::Peekable;
+use std::mem;
+
+/// The soft hyphen, an invisible mark of where a word may break
+pub const SOFT_HYPHEN: char = '\u{ad}';
+
+/// The Latin ligatures of Unicode's Alphabetic Presentation Forms and the
+/// letters they stand for
+pub const LIGATURES: &[(char, &str)] = &[
+    ('ﬀ', "ff"),
+    ('ﬁ', "fi"),
+    ('ﬂ', "fl"),
+    ('ﬃ', "ffi"),
+    ('ﬄ', "ffl"),
+    ('ﬅ', "st"),
+    ('ﬆ', "st"),
+];
+
+/// The hyphens a word can be broken at the end of a line with
+const LINE_END_HYPHENS: &[char] = &['-', '‐', SOFT_HYPHEN];
+
+/// `text` with ligatures spelled out, soft hyphens removed, and words
+/// broken across lines rejoined; borrowed if there was nothing to clean
+///
+/// ```
+/// use fp::ocr::clean_ocr;
+/// use std::borrow::Cow;
+///
+/// assert_eq!(clean_ocr("the ﬂoor"), "the floor");
+/// // A rejoined word moves down to the line it ends on
+/// assert_eq!(clean_ocr("re-\nturn"), "\nreturn");
+/// // A hyphen before a capital starts a new word, not a broken one
+/// assert_eq!(clean_ocr("Haskell-\nCurry"), "Haskell-\nCurry");
+/// assert!(matches!(clean_ocr("clean text"), Cow::Borrowed(_)));
+/// ```
+pub fn clean_ocr(text: &str) -> Cow<'_, str> {
+    match rejoin_hyphenated(text) {
+        Cow::Borrowed(text) => clean_characters(text),
+        Cow::Owned(text) => Cow::Owned(clean_characters(&text).into_owned()),
+    }
+}
+
+/// `text` with ligatures spelled out and soft hyphens removed, the part of
+/// [`clean_ocr`] that works line by line, to follow [`RejoinHyphens`] on a
+/// stream; borrowed if there was nothing to clean
+///
+/// ```
+/// use fp::ocr::{RejoinHyphens, clean_characters};
+///
+/// let lines = ["The ﬁrst func\u{ad}", "tional pro\u{ad}gram"].map(|line| Ok::<_, ()>(line.to_string()));
+/// let cleaned: Vec<String> = RejoinHyphens::new(lines.into_iter())
+///     .map(|line| clean_characters(&line.unwrap()).into_owned())
+///     .collect();
+/// assert_eq!(cleaned, ["The first", "functional program"]);
+/// ```
+pub fn clean_characters(text: &str) -> Cow<'_, str> {
+    let artifact = |c: char| c == SOFT_HYPHEN || LIGATURES.iter().any(|&(l, _)| l == c);
+    if !text.contains(artifact) {
+        return Cow::Borrowed(text);
+    }
+    let cleaned = text
+        .chars()
+        .fold(String::with_capacity(text.len()), |mut out, c| {
+            match LIGATURES.iter().find(|&&(l, _)| l == c) {
+                Some((_, letters)) => out.push_str(letters),
+                None if c == SOFT_HYPHEN => {}
+                None => out.push(c),
+            }
+            out
+        });
+    Cow::Owned(cleaned)
+}
+
+/// `text` with every word broken across a line by a hyphen rejoined, as
+/// [`RejoinHyphens`] does; borrowed if no word was broken
+///
+/// ```
+/// use fp::ocr::rejoin_hyphenated;
+///
+/// assert_eq!(rejoin_hyphenated("a func-\n  tional fold"), "a\nfunctional fold");
+/// // A dash before a capital or a number is left alone
+/// assert_eq!(rejoin_hyphenated("pages 3-\n5 and Fold-\nMap"), "pages 3-\n5 and Fold-\nMap");
+/// ```
+pub fn rejoin_hyphenated(text: &str) -> Cow<'_, str> {
+    let lines: Vec<&str> = text.lines().collect();
+    let broken = lines
+        .windows(2)
+        .any(|pair| broken_word(pair[0], pair[1]).is_some());
+    if !broken {
+        return Cow::Borrowed(text);
+    }
+    let Ok(rejoined) = RejoinHyphens::new(
+        lines
+            .into_iter()
+            .map(|line| Ok::<_, Infallible>(line.to_string())),
+    )
+    .collect::<Result<Vec<String>, _>>();
+    let ending = if text.ends_with('\n') { "\n" } else { "" };
+    Cow::Owned(rejoined.join("\n") + ending)
+}
+
+/// Lines with every word broken across two of them by a hyphen rejoined on
+/// the second, so the number of lines stays the same; reads one line ahead
+///
+/// A line ending in a hyphen after a letter breaks a word if the next line
+/// starts with a lowercase letter. A compound broken at its own hyphen,
+/// "well-" and "known", is rejoined as "wellknown": the line alone cannot
+/// tell the two apart, and broken words are far more common.
+///
+/// The lines are results, as a reader's are, and an error passes through
+/// as it is; a plain iterator of lines can wrap them in `Ok`.
+///
+/// ```
+/// use fp::ocr::RejoinHyphens;
+/// use std::io::{BufRead, Cursor};
+///
+/// let book = Cursor::new("It was a func-\ntional\nfold-\nright after all");
+/// let lines: Vec<String> = RejoinHyphens::new(book.lines()).collect::<Result<_, _>>().unwrap();
+/// assert_eq!(lines, ["It was a", "functional", "", "foldright after all"]);
+/// ```
+pub struct RejoinHyphens<I: Iterator> {
+    lines: Peekable<I>,
+    /// The first half of a word broken at the end of the last line
+    carried: String,
+}
+
+impl<I: Iterator> RejoinHyphens<I> {
+    /// Rejoin the words broken across `lines`
+    ///
+    /// ```
+    /// use fp::ocr::RejoinHyphens;
+    /// use std::convert::Infallible;
+    ///
+    /// let lines = ["fold-", "ing maps"].map(|line| Ok::<_, Infallible>(line.to_string()));
+    /// let joined: Vec<String> = RejoinHyphens::new(lines.into_iter()).map(Result::unwrap).collect();
+    /// assert_eq!(joined, ["", "folding maps"]);
+    /// ```
+    pub fn new(lines: I) -> Self {
+        RejoinHyphens {
+            lines: lines.peekable(),
+            carried: String::new(),
+        }
+    }
+}
+
+impl<I, E> Iterator for RejoinHyphens<I>
+where
+    I: Iterator<Item = Result<String, E>>,
+{
+    type Item = Result<String, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = match self.lines.next()? {
+            Ok(line) if self.carried.is_empty() => line,
+            Ok(line) => format!("{}{}", mem::take(&mut self.carried), line.trim_start()),
+            Err(err) => return Some(Err(err)),
+        };
+        if let Some(Ok(next)) = self.lines.peek()
+            && let Some((kept, fragment)) = broken_word(&line, next)
+        {
+            self.carried = fragment.to_string();
+            return Some(Ok(kept.to_string()));
+        }
+        Some(Ok(line))
+    }
+}
+
+/// The part of `line` before a word broken at its end, and that word's
+/// first half without the hyphen, if `next` continues the word
+fn broken_word<'a>(line: &'a str, next: &str) -> Option<(&'a str, &'a str)> {
+    let stem = line.trim_end().strip_suffix(LINE_END_HYPHENS)?;
+    let start = stem
+        .char_indices()
+        .rev()
+        .find(|(_, c)| c.is_whitespace())
+        .map_or(0, |(i, c)| i + c.len_utf8());
+    let fragment = &stem[start..];
+    let continues = next.trim_start().starts_with(char::is_lowercase);
+    (fragment.ends_with(char::is_alphabetic) && continues)
+        .then(|| (stem[..start].trim_end(), fragment))
+}
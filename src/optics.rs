@@ -0,0 +1,314 @@
+//! Lenses and prisms for immutable updates.
+//!
+//! A [`Lens`] focuses on a part that is always present (a struct field); a
+//! [`Prism`] focuses on a part that may be absent (one enum variant). Both
+//! compose, so a deep update such as "set `config.filters.min_length`" is a
+//! single call that returns a new value instead of mutating in place.
+
+use std::rc::Rc;
+
+use crate::expr::Expr;
+
+type Getter<S, A> = Rc<dyn Fn(&S) -> &A>;
+type Setter<S, A> = Rc<dyn Fn(S, A) -> S>;
+
+/// A getter/setter pair focusing on an `A` inside an `S`.
+pub struct Lens<S, A> {
+    getter: Getter<S, A>,
+    setter: Setter<S, A>,
+}
+
+impl<S: 'static, A: 'static> Lens<S, A> {
+    /// Builds a lens from a getter and a setter.
+    ///
+    /// ```
+    /// use fp::optics::Lens;
+    ///
+    /// struct Word {
+    ///     text: String,
+    ///     count: usize,
+    /// }
+    ///
+    /// let count = Lens::new(|w: &Word| &w.count, |w: Word, count| Word { count, ..w });
+    /// let word = count.set(Word { text: "fold".to_string(), count: 1 }, 2);
+    /// assert_eq!((word.text.as_str(), word.count), ("fold", 2));
+    /// ```
+    pub fn new<G, St>(get: G, set: St) -> Self
+    where
+        G: Fn(&S) -> &A + 'static,
+        St: Fn(S, A) -> S + 'static,
+    {
+        Self {
+            getter: Rc::new(get),
+            setter: Rc::new(set),
+        }
+    }
+
+    /// Reads the focus.
+    ///
+    /// ```
+    /// use fp::optics::Lens;
+    ///
+    /// let first = Lens::new(|pair: &(i32, i32)| &pair.0, |pair: (i32, i32), a| (a, pair.1));
+    /// assert_eq!(*first.get(&(1, 2)), 1);
+    /// ```
+    pub fn get<'s>(&self, s: &'s S) -> &'s A {
+        (self.getter)(s)
+    }
+
+    /// Returns `s` with the focus replaced by `a`.
+    ///
+    /// ```
+    /// use fp::optics::Lens;
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// struct Point {
+    ///     x: i32,
+    ///     y: i32,
+    /// }
+    ///
+    /// let x = Lens::new(|p: &Point| &p.x, |p: Point, x| Point { x, ..p });
+    /// assert_eq!(x.set(Point { x: 1, y: 2 }, 5), Point { x: 5, y: 2 });
+    /// ```
+    pub fn set(&self, s: S, a: A) -> S {
+        (self.setter)(s, a)
+    }
+
+    /// Returns `s` with the focus transformed by `f`.
+    ///
+    /// ```
+    /// use fp::optics::Lens;
+    ///
+    /// let first = Lens::new(|pair: &(i32, i32)| &pair.0, |pair: (i32, i32), a| (a, pair.1));
+    /// assert_eq!(first.modify((1, 2), |a| a + 10), (11, 2));
+    /// ```
+    pub fn modify<F: FnOnce(A) -> A>(&self, s: S, f: F) -> S
+    where
+        A: Clone,
+    {
+        let a = f(self.get(&s).clone());
+        self.set(s, a)
+    }
+
+    /// Composes with a lens into the focus, reaching one level deeper.
+    ///
+    /// ```
+    /// use fp::lens;
+    ///
+    /// #[derive(Clone, Debug, PartialEq)]
+    /// struct Inner { n: u32 }
+    /// #[derive(Clone, Debug, PartialEq)]
+    /// struct Outer { inner: Inner, label: &'static str }
+    ///
+    /// let n = lens!(Outer, inner).then(&lens!(Inner, n));
+    /// let before = Outer { inner: Inner { n: 1 }, label: "x" };
+    /// let after = n.modify(before.clone(), |n| n + 41);
+    ///
+    /// assert_eq!(*n.get(&after), 42);
+    /// assert_eq!(before.inner.n, 1);
+    /// assert_eq!(after.label, "x");
+    /// ```
+    pub fn then<B: 'static>(&self, inner: &Lens<A, B>) -> Lens<S, B>
+    where
+        A: Clone,
+    {
+        let (outer_get, outer_set) = (self.getter.clone(), self.setter.clone());
+        let (inner_get, inner_set) = (inner.getter.clone(), inner.setter.clone());
+        let getter: Getter<S, B> = {
+            let outer_get = outer_get.clone();
+            Rc::new(move |s| inner_get(outer_get(s)))
+        };
+        let setter: Setter<S, B> = Rc::new(move |s, b| {
+            let a = inner_set(outer_get(&s).clone(), b);
+            outer_set(s, a)
+        });
+        Lens { getter, setter }
+    }
+}
+
+impl<S, A> Clone for Lens<S, A> {
+    fn clone(&self) -> Self {
+        Self {
+            getter: self.getter.clone(),
+            setter: self.setter.clone(),
+        }
+    }
+}
+
+/// Builds a [`Lens`] onto a named struct field: `lens!(Config, min_length)`.
+#[macro_export]
+macro_rules! lens {
+    ($S:ty, $field:ident) => {
+        $crate::optics::Lens::new(
+            |s: &$S| &s.$field,
+            |mut s: $S, value| {
+                s.$field = value;
+                s
+            },
+        )
+    };
+}
+
+type Preview<S, A> = Rc<dyn Fn(&S) -> Option<A>>;
+type Review<S, A> = Rc<dyn Fn(A) -> S>;
+
+/// A partial focus: matches one case of a sum type.
+pub struct Prism<S, A> {
+    preview: Preview<S, A>,
+    review: Review<S, A>,
+}
+
+impl<S: 'static, A: 'static> Prism<S, A> {
+    /// Builds a prism from a matcher and a constructor.
+    ///
+    /// ```
+    /// use fp::optics::Prism;
+    ///
+    /// let ok = Prism::new(|r: &Result<u32, String>| r.as_ref().ok().copied(), Ok);
+    /// assert_eq!(ok.preview(&Ok(3)), Some(3));
+    /// assert_eq!(ok.preview(&Err("no".to_string())), None);
+    /// assert_eq!(ok.review(4), Ok(4));
+    /// ```
+    pub fn new<P, R>(preview: P, review: R) -> Self
+    where
+        P: Fn(&S) -> Option<A> + 'static,
+        R: Fn(A) -> S + 'static,
+    {
+        Self {
+            preview: Rc::new(preview),
+            review: Rc::new(review),
+        }
+    }
+
+    /// Extracts the focus if `s` is the matching case.
+    ///
+    /// ```
+    /// use fp::expr::Expr;
+    /// use fp::optics::expr_const;
+    ///
+    /// assert_eq!(expr_const().preview(&Expr::Const(4)), Some(4));
+    /// assert_eq!(expr_const().preview(&Expr::Add(Box::new(Expr::Const(4)), Box::new(Expr::Const(0)))), None);
+    /// ```
+    pub fn preview(&self, s: &S) -> Option<A> {
+        (self.preview)(s)
+    }
+
+    /// Builds an `S` of the matching case.
+    ///
+    /// ```
+    /// use fp::expr::Expr;
+    /// use fp::optics::expr_mul;
+    ///
+    /// let product = expr_mul().review((Expr::Const(2), Expr::Const(3)));
+    /// assert_eq!(product, Expr::Mul(Box::new(Expr::Const(2)), Box::new(Expr::Const(3))));
+    /// ```
+    pub fn review(&self, a: A) -> S {
+        (self.review)(a)
+    }
+
+    /// Transforms the focus if `s` matches; otherwise returns `s` unchanged.
+    ///
+    /// ```
+    /// use fp::optics::Prism;
+    ///
+    /// let some = Prism::new(|o: &Option<i32>| *o, Some);
+    /// assert_eq!(some.modify(Some(2), |n| n * 10), Some(20));
+    /// assert_eq!(some.modify(None, |n| n * 10), None);
+    /// ```
+    pub fn modify<F: FnOnce(A) -> A>(&self, s: S, f: F) -> S {
+        match self.preview(&s) {
+            Some(a) => self.review(f(a)),
+            None => s,
+        }
+    }
+}
+
+impl<S, A> Clone for Prism<S, A> {
+    fn clone(&self) -> Self {
+        Self {
+            preview: self.preview.clone(),
+            review: self.review.clone(),
+        }
+    }
+}
+
+/// Prism onto `Expr::Const`.
+///
+/// ```
+/// use fp::expr::Expr;
+/// use fp::optics::expr_const;
+///
+/// assert_eq!(expr_const().modify(Expr::Const(4), |n| n * 10), Expr::Const(40));
+/// ```
+pub fn expr_const() -> Prism<Expr, i32> {
+    Prism::new(
+        |e| match e {
+            Expr::Const(n) => Some(*n),
+            _ => None,
+        },
+        Expr::Const,
+    )
+}
+
+/// Prism onto the operands of `Expr::Add`.
+///
+/// ```
+/// use fp::expr::{eval, Expr};
+/// use fp::optics::expr_add;
+///
+/// let sum = Expr::Add(Box::new(Expr::Const(2)), Box::new(Expr::Const(3)));
+/// let swapped = expr_add().modify(sum, |(l, r)| (r, l));
+/// assert_eq!(swapped, Expr::Add(Box::new(Expr::Const(3)), Box::new(Expr::Const(2))));
+///
+/// // A non-matching case passes through untouched.
+/// assert_eq!(expr_add().modify(Expr::Const(7), |(l, r)| (r, l)), Expr::Const(7));
+/// ```
+pub fn expr_add() -> Prism<Expr, (Expr, Expr)> {
+    Prism::new(
+        |e| match e {
+            Expr::Add(l, r) => Some((*l.clone(), *r.clone())),
+            _ => None,
+        },
+        |(l, r)| Expr::Add(Box::new(l), Box::new(r)),
+    )
+}
+
+/// Prism onto the operands of `Expr::Mul`.
+///
+/// ```
+/// use fp::expr::{Expr, eval};
+/// use fp::optics::expr_mul;
+///
+/// let product = expr_mul().review((Expr::Const(6), Expr::Const(7)));
+/// assert_eq!(eval(&product), 42);
+/// assert!(expr_mul().preview(&Expr::Const(42)).is_none());
+/// ```
+pub fn expr_mul() -> Prism<Expr, (Expr, Expr)> {
+    Prism::new(
+        |e| match e {
+            Expr::Mul(l, r) => Some((*l.clone(), *r.clone())),
+            _ => None,
+        },
+        |(l, r)| Expr::Mul(Box::new(l), Box::new(r)),
+    )
+}
+
+/// Prism onto the operands of `Expr::Sub`.
+///
+/// ```
+/// use fp::expr::{Expr, eval};
+/// use fp::optics::expr_sub;
+///
+/// let difference = Expr::Sub(Box::new(Expr::Const(5)), Box::new(Expr::Const(8)));
+/// let flipped = expr_sub().modify(difference, |(l, r)| (r, l));
+/// assert_eq!(eval(&flipped), 3);
+/// ```
+pub fn expr_sub() -> Prism<Expr, (Expr, Expr)> {
+    Prism::new(
+        |e| match e {
+            Expr::Sub(l, r) => Some((*l.clone(), *r.clone())),
+            _ => None,
+        },
+        |(l, r)| Expr::Sub(Box::new(l), Box::new(r)),
+    )
+}
@@ -0,0 +1,100 @@
+//! Parallel map-reduce over a worker pool.
+//!
+//! A reader thread cuts the input into chunks and feeds them into a bounded
+//! channel; `workers` threads take chunks, map every item to a [`Monoid`] and
+//! combine them; the calling thread merges the partial results. Partials are
+//! merged in chunk order, so the result equals the sequential
+//! [`fold_map`](crate::monoid::fold_map) for every monoid, commutative or
+//! not, and for every chunk size.
+//!
+//! ```
+//! use fp::monoid::fold_map;
+//! use fp::parallel::map_reduce;
+//!
+//! let words: Vec<String> = (0..1000).map(|i| format!("w{} ", i % 7)).collect();
+//! let sequential = fold_map(words.iter(), |w| w.clone());
+//!
+//! // String concatenation is not commutative, yet any chunking gives the same answer
+//! for chunk_size in [1, 3, 64, 999, 5000] {
+//!     for workers in [1, 2, 8] {
+//!         assert_eq!(map_reduce(words.iter(), chunk_size, workers, |w| w.clone()), sequential);
+//!     }
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::monoid::{Monoid, fold_map};
+
+/// Maps every item with `f` and combines the results, using `workers`
+/// threads over chunks of `chunk_size` items. Both are treated as at least 1.
+///
+/// ```
+/// use fp::iter_ext::IteratorExt;
+/// use fp::parallel::map_reduce;
+///
+/// let text = "a b\nb c\nc c";
+/// let counts = map_reduce(text.lines(), 1, 3, |line| line.split_whitespace().counts());
+/// assert_eq!((counts["a"], counts["b"], counts["c"]), (1, 2, 3));
+/// ```
+pub fn map_reduce<I, M, F>(items: I, chunk_size: usize, workers: usize, f: F) -> M
+where
+    I: IntoIterator,
+    I::IntoIter: Send,
+    I::Item: Send,
+    M: Monoid + Send,
+    F: Fn(I::Item) -> M + Sync,
+{
+    let chunk_size = chunk_size.max(1);
+    let workers = workers.max(1);
+    let (chunk_tx, chunk_rx) = mpsc::sync_channel::<(usize, Vec<I::Item>)>(workers * 2);
+    let chunk_rx = Arc::new(Mutex::new(chunk_rx));
+    let (partial_tx, partial_rx) = mpsc::channel::<(usize, M)>();
+    let items = items.into_iter();
+    let f = &f;
+
+    thread::scope(|scope| {
+        // Reader: cut the input into numbered chunks
+        scope.spawn(move || {
+            let mut items = items;
+            for index in 0.. {
+                let chunk: Vec<_> = items.by_ref().take(chunk_size).collect();
+                if chunk.is_empty() || chunk_tx.send((index, chunk)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        // Workers: map and combine one chunk at a time
+        for _ in 0..workers {
+            let chunk_rx = Arc::clone(&chunk_rx);
+            let partial_tx = partial_tx.clone();
+            scope.spawn(move || {
+                loop {
+                    let next = chunk_rx.lock().map(|rx| rx.recv());
+                    let Ok(Ok((index, chunk))) = next else { break };
+                    if partial_tx.send((index, fold_map(chunk, f))).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(partial_tx);
+
+        // Reducer: merge partials in chunk order as they become contiguous
+        let mut pending = BTreeMap::new();
+        let mut next = 0;
+        let mut result = M::empty();
+        for (index, partial) in partial_rx {
+            pending.insert(index, partial);
+            while let Some(partial) = pending.remove(&next) {
+                result = result.combine(partial);
+                next += 1;
+            }
+        }
+        result
+    })
+}
@@ -0,0 +1,150 @@
+//! Parquet Output
+//!
+//! Word counts and TF-IDF weights as Parquet files, the columnar format
+//! pandas (`read_parquet`) and polars load directly, with typed columns
+//! instead of text to parse. Each table is written as one row group,
+//! uncompressed.
+//!
+//! The TF-IDF matrix is stored in long form, one `document, word, tf_idf`
+//! row per word of each document, since a column per word would not scale
+//! with the vocabulary; `pivot` turns it back into a matrix.
+//!
+//! ```
+//! use fp::analysis::word_counts;
+//! use fp::parquet::frequency_table;
+//!
+//! let bytes = frequency_table(&word_counts("fold map fold")).unwrap();
+//! // Parquet files start and end with the magic bytes
+//! assert!(bytes.starts_with(b"PAR1") && bytes.ends_with(b"PAR1"));
+//! ```
+
+use crate::clustering::Vector;
+use ::parquet::data_type::{ByteArray, ByteArrayType, DoubleType, Int64Type};
+use ::parquet::errors::{ParquetError, Result};
+use ::parquet::file::properties::WriterProperties;
+use ::parquet::file::writer::SerializedFileWriter;
+use ::parquet::schema::parser::parse_message_type;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// The schema of [`frequency_table`]
+pub const FREQUENCY_SCHEMA: &str =
+    "message word_counts { REQUIRED BYTE_ARRAY word (UTF8); REQUIRED INT64 count; }";
+
+/// The schema of [`tf_idf_table`]
+pub const TF_IDF_SCHEMA: &str = "message tf_idf { REQUIRED BYTE_ARRAY document (UTF8); REQUIRED BYTE_ARRAY word (UTF8); REQUIRED DOUBLE tf_idf; }";
+
+/// The values of one column, in the order of the schema's fields
+enum Column {
+    Text(Vec<ByteArray>),
+    Int(Vec<i64>),
+    Double(Vec<f64>),
+}
+
+/// A Parquet file of `word, count` rows, the most frequent word first, ties
+/// alphabetical
+///
+/// ```
+/// use fp::analysis::word_counts;
+/// use fp::parquet::frequency_table;
+/// use parquet::file::reader::{FileReader, SerializedFileReader};
+/// use parquet::record::RowAccessor;
+///
+/// let path = std::env::temp_dir().join("fp-doctest-word-counts.parquet");
+/// std::fs::write(&path, frequency_table(&word_counts("fold map fold")).unwrap()).unwrap();
+/// let reader = SerializedFileReader::new(std::fs::File::open(&path).unwrap()).unwrap();
+/// let rows: Vec<(String, i64)> = reader
+///     .get_row_iter(None)
+///     .unwrap()
+///     .map(|row| {
+///         let row = row.unwrap();
+///         (row.get_string(0).unwrap().clone(), row.get_long(1).unwrap())
+///     })
+///     .collect();
+/// assert_eq!(rows, [("fold".to_string(), 2), ("map".to_string(), 1)]);
+/// ```
+pub fn frequency_table(counts: &HashMap<String, usize>) -> Result<Vec<u8>> {
+    let mut entries: Vec<(&String, &usize)> = counts.iter().collect();
+    entries.sort_by(|(a, x), (b, y)| y.cmp(x).then_with(|| a.cmp(b)));
+    let (words, counts): (Vec<ByteArray>, Vec<i64>) = entries
+        .into_iter()
+        .map(|(word, &count)| (ByteArray::from(word.as_str()), count as i64))
+        .unzip();
+    write_table(
+        FREQUENCY_SCHEMA,
+        vec![Column::Text(words), Column::Int(counts)],
+    )
+}
+
+/// A Parquet file of `document, word, tf_idf` rows: for each document in
+/// order, named by `documents`, the weights of `vectors` alphabetically
+///
+/// ```
+/// use fp::analysis::word_counts;
+/// use fp::clustering::tf_idf;
+/// use fp::parquet::tf_idf_table;
+///
+/// let vectors = tf_idf(&[word_counts("fold map"), word_counts("fold filter")]);
+/// assert!(tf_idf_table(&["a.txt", "b.txt"], &vectors).is_ok());
+/// assert!(tf_idf_table(&["a.txt"], &vectors).is_err());
+/// ```
+pub fn tf_idf_table<S: AsRef<str>>(documents: &[S], vectors: &[Vector]) -> Result<Vec<u8>> {
+    if documents.len() != vectors.len() {
+        return Err(ParquetError::General(format!(
+            "{} document names for {} vectors",
+            documents.len(),
+            vectors.len()
+        )));
+    }
+    let mut names = Vec::new();
+    let mut words = Vec::new();
+    let mut weights = Vec::new();
+    for (document, vector) in documents.iter().zip(vectors) {
+        let mut entries: Vec<(&String, &f64)> = vector.iter().collect();
+        entries.sort_by_key(|&(word, _)| word);
+        for (word, &weight) in entries {
+            names.push(ByteArray::from(document.as_ref()));
+            words.push(ByteArray::from(word.as_str()));
+            weights.push(weight);
+        }
+    }
+    write_table(
+        TF_IDF_SCHEMA,
+        vec![
+            Column::Text(names),
+            Column::Text(words),
+            Column::Double(weights),
+        ],
+    )
+}
+
+/// A Parquet file of a single row group with `columns` under `schema`
+fn write_table(schema: &str, columns: Vec<Column>) -> Result<Vec<u8>> {
+    let schema = Arc::new(parse_message_type(schema)?);
+    let properties = Arc::new(WriterProperties::builder().build());
+    let mut bytes = Vec::new();
+    let mut writer = SerializedFileWriter::new(&mut bytes, schema, properties)?;
+    let mut row_group = writer.next_row_group()?;
+    for column in columns {
+        let Some(mut column_writer) = row_group.next_column()? else {
+            return Err(ParquetError::General(
+                "more columns than the schema has".to_string(),
+            ));
+        };
+        match column {
+            Column::Text(values) => column_writer
+                .typed::<ByteArrayType>()
+                .write_batch(&values, None, None)?,
+            Column::Int(values) => column_writer
+                .typed::<Int64Type>()
+                .write_batch(&values, None, None)?,
+            Column::Double(values) => column_writer
+                .typed::<DoubleType>()
+                .write_batch(&values, None, None)?,
+        };
+        column_writer.close()?;
+    }
+    row_group.close()?;
+    writer.close()?;
+    Ok(bytes)
+}
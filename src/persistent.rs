@@ -0,0 +1,18 @@
+//! Persistent (immutable) data structures.
+//!
+//! "Updating" a persistent structure returns a new version and leaves the old
+//! one intact. Versions share unchanged parts through reference counting, so
+//! keeping history is cheap. Nodes are `Arc`-shared so versions can be sent
+//! across threads.
+
+pub mod list;
+pub mod map;
+pub mod tree;
+pub mod trie;
+pub mod vector;
+
+pub use list::PersistentList;
+pub use map::PMap;
+pub use tree::PTreeMap;
+pub use trie::PTrie;
+pub use vector::PVec;
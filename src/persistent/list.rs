@@ -0,0 +1,286 @@
+//! Persistent singly linked list.
+
+use std::fmt;
+use std::sync::Arc;
+
+type Link<T> = Option<Arc<Node<T>>>;
+
+struct Node<T> {
+    value: T,
+    next: Link<T>,
+}
+
+/// An immutable cons list with O(1) `cons`, `head` and `tail`.
+///
+/// Every list that was built from another one shares that list's nodes:
+///
+/// ```
+/// use fp::persistent::PersistentList;
+///
+/// let base: PersistentList<i32> = [2, 3].into_iter().collect();
+/// let a = base.cons(1);
+/// let b = base.cons(10);
+///
+/// assert_eq!(a.iter().copied().collect::<Vec<_>>(), [1, 2, 3]);
+/// assert_eq!(b.iter().copied().collect::<Vec<_>>(), [10, 2, 3]);
+/// // Both new lists point at the very same tail nodes as `base`.
+/// assert!(a.tail().unwrap().ptr_eq(&base));
+/// assert!(b.tail().unwrap().ptr_eq(&base));
+/// // And `base` itself is unchanged.
+/// assert_eq!(base.len(), 2);
+/// ```
+pub struct PersistentList<T> {
+    head: Link<T>,
+    len: usize,
+}
+
+impl<T> PersistentList<T> {
+    /// The empty list.
+    ///
+    /// ```
+    /// use fp::persistent::PersistentList;
+    ///
+    /// let xs: PersistentList<i32> = PersistentList::new();
+    /// assert_eq!(xs.len(), 0);
+    /// ```
+    pub fn new() -> Self {
+        Self { head: None, len: 0 }
+    }
+
+    /// Returns a new list with `value` in front of this one.
+    ///
+    /// ```
+    /// use fp::persistent::PersistentList;
+    ///
+    /// let xs = PersistentList::new().cons(2);
+    /// let ys = xs.cons(1);
+    /// assert_eq!(ys.iter().copied().collect::<Vec<_>>(), [1, 2]);
+    /// assert_eq!(xs.len(), 1, "the old list is unchanged");
+    /// ```
+    pub fn cons(&self, value: T) -> Self {
+        Self {
+            head: Some(Arc::new(Node {
+                value,
+                next: self.head.clone(),
+            })),
+            len: self.len + 1,
+        }
+    }
+
+    /// The first element.
+    ///
+    /// ```
+    /// use fp::persistent::PersistentList;
+    ///
+    /// let xs = PersistentList::new().cons(2).cons(1);
+    /// assert_eq!(xs.head(), Some(&1));
+    /// assert_eq!(PersistentList::<i32>::new().head(), None);
+    /// ```
+    pub fn head(&self) -> Option<&T> {
+        self.head.as_ref().map(|node| &node.value)
+    }
+
+    /// Everything after the first element, or `None` for the empty list.
+    ///
+    /// ```
+    /// use fp::persistent::PersistentList;
+    ///
+    /// let xs = PersistentList::new().cons(2).cons(1);
+    /// assert_eq!(xs.tail().unwrap().head(), Some(&2));
+    /// assert!(PersistentList::<i32>::new().tail().is_none());
+    /// ```
+    pub fn tail(&self) -> Option<Self> {
+        self.head.as_ref().map(|node| Self {
+            head: node.next.clone(),
+            len: self.len - 1,
+        })
+    }
+
+    /// Number of elements.
+    ///
+    /// ```
+    /// use fp::persistent::PersistentList;
+    ///
+    /// assert_eq!(PersistentList::new().cons('b').cons('a').len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the list has no elements.
+    ///
+    /// ```
+    /// use fp::persistent::PersistentList;
+    ///
+    /// let xs = PersistentList::new().cons(1);
+    /// assert!(!xs.is_empty());
+    /// assert!(xs.tail().unwrap().is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.head.is_none()
+    }
+
+    /// Returns `true` if both lists start at the same shared node.
+    ///
+    /// ```
+    /// use fp::persistent::PersistentList;
+    ///
+    /// let xs = PersistentList::new().cons(1);
+    /// assert!(xs.ptr_eq(&xs.clone()));
+    /// // Equal elements in separate nodes are not shared
+    /// assert!(!xs.ptr_eq(&PersistentList::new().cons(1)));
+    /// ```
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        match (&self.head, &other.head) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    /// Iterates over references to the elements, front to back.
+    ///
+    /// ```
+    /// use fp::persistent::PersistentList;
+    ///
+    /// let xs = PersistentList::new().cons(3).cons(2).cons(1);
+    /// assert_eq!(xs.iter().map(|x| x * 10).collect::<Vec<_>>(), [10, 20, 30]);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            next: self.head.as_deref(),
+        }
+    }
+
+    /// Folds the elements front to back.
+    ///
+    /// ```
+    /// use fp::persistent::PersistentList;
+    ///
+    /// let xs: PersistentList<&str> = ["f", "o", "l", "d"].into_iter().collect();
+    /// assert_eq!(xs.fold(String::new(), |acc, s| acc + s), "fold");
+    /// ```
+    pub fn fold<B, F: FnMut(B, &T) -> B>(&self, init: B, f: F) -> B {
+        self.iter().fold(init, f)
+    }
+
+    /// Returns a new list with `f` applied to every element.
+    ///
+    /// ```
+    /// use fp::persistent::PersistentList;
+    ///
+    /// let xs: PersistentList<i32> = (1..=3).collect();
+    /// let doubled = xs.map(|x| x * 2);
+    /// assert_eq!(doubled.fold(0, |acc, x| acc + x), 12);
+    /// ```
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> PersistentList<U> {
+        self.iter().map(f).collect()
+    }
+
+    /// Returns a new list with only the elements matching `keep`.
+    ///
+    /// ```
+    /// use fp::persistent::PersistentList;
+    ///
+    /// let xs: PersistentList<i32> = (1..=6).collect();
+    /// let evens = xs.filter(|x| x % 2 == 0);
+    /// assert_eq!(evens.iter().copied().collect::<Vec<_>>(), [2, 4, 6]);
+    /// assert_eq!(xs.len(), 6);
+    /// ```
+    pub fn filter<F: FnMut(&T) -> bool>(&self, mut keep: F) -> Self
+    where
+        T: Clone,
+    {
+        self.iter().filter(|x| keep(x)).cloned().collect()
+    }
+
+    /// Returns the list in reverse order.
+    ///
+    /// ```
+    /// use fp::persistent::PersistentList;
+    ///
+    /// let xs: PersistentList<char> = "abc".chars().collect();
+    /// assert_eq!(xs.reverse().iter().collect::<String>(), "cba");
+    /// ```
+    pub fn reverse(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.fold(Self::new(), |acc, x| acc.cons(x.clone()))
+    }
+}
+
+impl<T> Default for PersistentList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Cloning a list copies one pointer; the nodes are shared.
+impl<T> Clone for PersistentList<T> {
+    fn clone(&self) -> Self {
+        Self {
+            head: self.head.clone(),
+            len: self.len,
+        }
+    }
+}
+
+/// Drops nodes iteratively so that long lists cannot overflow the stack.
+impl<T> Drop for PersistentList<T> {
+    fn drop(&mut self) {
+        let mut link = self.head.take();
+        while let Some(node) = link {
+            match Arc::try_unwrap(node) {
+                Ok(mut node) => link = node.next.take(),
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+impl<T> FromIterator<T> for PersistentList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let items: Vec<T> = iter.into_iter().collect();
+        items.into_iter().rev().fold(Self::new(), |list, x| list.cons(x))
+    }
+}
+
+impl<T: PartialEq> PartialEq for PersistentList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for PersistentList<T> {}
+
+impl<T: fmt::Debug> fmt::Debug for PersistentList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// Borrowing iterator over a [`PersistentList`].
+pub struct Iter<'a, T> {
+    next: Option<&'a Node<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.next.map(|node| {
+            self.next = node.next.as_deref();
+            &node.value
+        })
+    }
+}
+
+impl<'a, T> IntoIterator for &'a PersistentList<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
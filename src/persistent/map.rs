@@ -0,0 +1,444 @@
+//! Persistent hash map (hash array mapped trie).
+
+use std::borrow::Borrow;
+use std::fmt;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::Arc;
+
+const BITS: u32 = 5;
+const MASK: u64 = (1 << BITS) - 1;
+
+enum Node<K, V> {
+    /// Entries whose keys share the same full 64-bit hash (usually just one).
+    Leaf { hash: u64, entries: Vec<(K, V)> },
+    /// Up to 32 children, stored densely; `bitmap` says which slots are used.
+    Branch {
+        bitmap: u32,
+        children: Vec<Arc<Node<K, V>>>,
+    },
+}
+
+/// An immutable hash map with O(log32 n) `insert`, `remove` and `get`.
+///
+/// The key's hash is consumed 5 bits per level to choose among 32 slots, and
+/// each branch only stores the slots that are occupied. An update copies the
+/// handful of nodes on one root-to-leaf path and shares the rest, so cloning
+/// the map is O(1) and old versions stay valid. That makes it cheap to keep a
+/// snapshot of a word-count map while counting carries on:
+///
+/// ```
+/// use fp::persistent::PMap;
+///
+/// let words = "the cat and the hat and the bat".split_whitespace();
+/// let (counts, snapshots) = words.enumerate().fold(
+///     (PMap::new(), Vec::new()),
+///     |(counts, mut snapshots), (i, word)| {
+///         let counts = counts.update(word, |n| n.copied().unwrap_or(0) + 1);
+///         if i % 3 == 2 {
+///             snapshots.push(counts.clone()); // O(1)
+///         }
+///         (counts, snapshots)
+///     },
+/// );
+///
+/// assert_eq!(counts.get("the"), Some(&3));
+/// assert_eq!(snapshots[0].get("the"), Some(&1));
+/// assert_eq!(snapshots[1].get("the"), Some(&2));
+/// assert_eq!(snapshots[1].get("bat"), None);
+/// ```
+pub struct PMap<K, V> {
+    root: Arc<Node<K, V>>,
+    len: usize,
+}
+
+fn hash_of<Q: Hash + ?Sized>(key: &Q) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn slot(hash: u64, shift: u32) -> u32 {
+    ((hash >> shift) & MASK) as u32
+}
+
+/// Position of `bit` among the occupied slots of `bitmap`.
+fn index(bitmap: u32, bit: u32) -> usize {
+    (bitmap & (bit - 1)).count_ones() as usize
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> PMap<K, V> {
+    /// The empty map.
+    ///
+    /// ```
+    /// use fp::persistent::PMap;
+    ///
+    /// let empty: PMap<&str, usize> = PMap::new();
+    /// assert_eq!(empty.get("fold"), None);
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            root: Arc::new(Node::Branch {
+                bitmap: 0,
+                children: Vec::new(),
+            }),
+            len: 0,
+        }
+    }
+
+    /// Number of entries.
+    ///
+    /// ```
+    /// use fp::persistent::PMap;
+    ///
+    /// let counts = PMap::new().insert("fold", 1).insert("map", 1).insert("fold", 2);
+    /// assert_eq!(counts.len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the map has no entries.
+    ///
+    /// ```
+    /// use fp::persistent::PMap;
+    ///
+    /// let empty: PMap<&str, i32> = PMap::new();
+    /// assert!(empty.is_empty());
+    /// assert!(!empty.insert("fold", 1).is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The value stored for `key`.
+    ///
+    /// ```
+    /// use fp::persistent::PMap;
+    ///
+    /// let counts: PMap<String, usize> = [("fold".to_string(), 2)].into_iter().collect();
+    /// assert_eq!(counts.get("fold"), Some(&2));
+    /// assert_eq!(counts.get("map"), None);
+    /// ```
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        let hash = hash_of(key);
+        let mut node = self.root.as_ref();
+        let mut shift = 0;
+        loop {
+            match node {
+                Node::Leaf { hash: h, entries } => {
+                    return (*h == hash)
+                        .then(|| entries.iter().find(|(k, _)| k.borrow() == key))
+                        .flatten()
+                        .map(|(_, v)| v);
+                }
+                Node::Branch { bitmap, children } => {
+                    let bit = 1 << slot(hash, shift);
+                    if bitmap & bit == 0 {
+                        return None;
+                    }
+                    node = &children[index(*bitmap, bit)];
+                    shift += BITS;
+                }
+            }
+        }
+    }
+
+    /// Returns `true` if `key` is present.
+    ///
+    /// ```
+    /// use fp::persistent::PMap;
+    ///
+    /// let counts: PMap<String, usize> = [("fold".to_string(), 2)].into_iter().collect();
+    /// assert!(counts.contains_key("fold"));
+    /// assert!(!counts.contains_key("map"));
+    /// ```
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+
+    /// Returns a new map with `key` set to `value`.
+    ///
+    /// ```
+    /// use fp::persistent::PMap;
+    ///
+    /// let before = PMap::new().insert("fold", 1);
+    /// let after = before.insert("fold", 2);
+    /// assert_eq!(after.get("fold"), Some(&2));
+    /// assert_eq!(before.get("fold"), Some(&1), "the old map is unchanged");
+    /// ```
+    pub fn insert(&self, key: K, value: V) -> Self {
+        let hash = hash_of(&key);
+        let (root, added) = insert_into(&self.root, 0, hash, key, value);
+        Self {
+            root: Arc::new(root),
+            len: self.len + usize::from(added),
+        }
+    }
+
+    /// Returns a new map where `key` maps to `f(current value)`.
+    ///
+    /// ```
+    /// use fp::persistent::PMap;
+    ///
+    /// let counts = ["fold", "map", "fold"]
+    ///     .into_iter()
+    ///     .fold(PMap::<_, usize>::new(), |counts, word| counts.update(word, |n| n.map_or(1, |n| n + 1)));
+    /// assert_eq!(counts.get("fold"), Some(&2));
+    /// ```
+    pub fn update<F: FnOnce(Option<&V>) -> V>(&self, key: K, f: F) -> Self {
+        let value = f(self.get(&key));
+        self.insert(key, value)
+    }
+
+    /// Returns a new map without `key`.
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use fp::persistent::PMap;
+    ///
+    /// // Mirror a mutable HashMap through thousands of inserts and removals.
+    /// let (pmap, hmap) = (0..5000u64).fold((PMap::new(), HashMap::new()), |(p, mut h), i| {
+    ///     let key = i * 7919 % 1000;
+    ///     if i % 3 == 0 {
+    ///         h.remove(&key);
+    ///         (p.remove(&key), h)
+    ///     } else {
+    ///         h.insert(key, i);
+    ///         (p.insert(key, i), h)
+    ///     }
+    /// });
+    /// assert_eq!(pmap.len(), hmap.len());
+    /// assert!(hmap.iter().all(|(k, v)| pmap.get(k) == Some(v)));
+    /// assert_eq!(pmap.iter().count(), hmap.len());
+    /// ```
+    pub fn remove<Q>(&self, key: &Q) -> Self
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        match remove_from(&self.root, 0, hash_of(key), key) {
+            None => self.clone(),
+            Some(root) => Self {
+                root: root.unwrap_or_else(|| Self::new().root),
+                len: self.len - 1,
+            },
+        }
+    }
+
+    /// Iterates over the entries in an unspecified order.
+    ///
+    /// ```
+    /// use fp::persistent::PMap;
+    ///
+    /// let counts: PMap<&str, usize> = [("fold", 2), ("map", 1)].into_iter().collect();
+    /// let mut entries: Vec<_> = counts.iter().collect();
+    /// entries.sort();
+    /// assert_eq!(entries, [(&"fold", &2), (&"map", &1)]);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            stack: vec![self.root.as_ref()],
+            leaf: [].iter(),
+        }
+    }
+}
+
+/// Returns the updated node and whether a new key was added.
+fn insert_into<K: Eq + Clone, V: Clone>(
+    node: &Arc<Node<K, V>>,
+    shift: u32,
+    hash: u64,
+    key: K,
+    value: V,
+) -> (Node<K, V>, bool) {
+    match node.as_ref() {
+        Node::Leaf { hash: h, entries } if *h == hash => {
+            let mut entries = entries.clone();
+            let added = match entries.iter_mut().find(|(k, _)| *k == key) {
+                Some(entry) => {
+                    entry.1 = value;
+                    false
+                }
+                None => {
+                    entries.push((key, value));
+                    true
+                }
+            };
+            (Node::Leaf { hash, entries }, added)
+        }
+        Node::Leaf { hash: h, .. } => {
+            let leaf = Node::Leaf {
+                hash,
+                entries: vec![(key, value)],
+            };
+            (split(node.clone(), *h, leaf, hash, shift), true)
+        }
+        Node::Branch { bitmap, children } => {
+            let bit = 1 << slot(hash, shift);
+            let i = index(*bitmap, bit);
+            let mut children = children.clone();
+            let added = if bitmap & bit != 0 {
+                let (child, added) = insert_into(&children[i], shift + BITS, hash, key, value);
+                children[i] = Arc::new(child);
+                added
+            } else {
+                let leaf = Node::Leaf {
+                    hash,
+                    entries: vec![(key, value)],
+                };
+                children.insert(i, Arc::new(leaf));
+                true
+            };
+            let bitmap = bitmap | bit;
+            (Node::Branch { bitmap, children }, added)
+        }
+    }
+}
+
+/// Builds the branches needed to hold two leaves with different hashes.
+fn split<K, V>(
+    a: Arc<Node<K, V>>,
+    hash_a: u64,
+    b: Node<K, V>,
+    hash_b: u64,
+    shift: u32,
+) -> Node<K, V> {
+    let (slot_a, slot_b) = (slot(hash_a, shift), slot(hash_b, shift));
+    if slot_a == slot_b {
+        Node::Branch {
+            bitmap: 1 << slot_a,
+            children: vec![Arc::new(split(a, hash_a, b, hash_b, shift + BITS))],
+        }
+    } else {
+        let b = Arc::new(b);
+        let children = if slot_a < slot_b {
+            vec![a, b]
+        } else {
+            vec![b, a]
+        };
+        Node::Branch {
+            bitmap: (1 << slot_a) | (1 << slot_b),
+            children,
+        }
+    }
+}
+
+/// `None` if the key was absent; otherwise the replacement node, which is
+/// itself `None` when the subtree became empty.
+fn remove_from<K, V, Q>(
+    node: &Node<K, V>,
+    shift: u32,
+    hash: u64,
+    key: &Q,
+) -> Option<Option<Arc<Node<K, V>>>>
+where
+    K: Borrow<Q> + Clone,
+    V: Clone,
+    Q: Eq + ?Sized,
+{
+    match node {
+        Node::Leaf { hash: h, entries } => {
+            if *h != hash || !entries.iter().any(|(k, _)| k.borrow() == key) {
+                return None;
+            }
+            let entries: Vec<_> = entries
+                .iter()
+                .filter(|(k, _)| k.borrow() != key)
+                .cloned()
+                .collect();
+            Some((!entries.is_empty()).then(|| Arc::new(Node::Leaf { hash, entries })))
+        }
+        Node::Branch { bitmap, children } => {
+            let bit = 1 << slot(hash, shift);
+            if bitmap & bit == 0 {
+                return None;
+            }
+            let i = index(*bitmap, bit);
+            let replacement = remove_from(&children[i], shift + BITS, hash, key)?;
+            let mut children = children.clone();
+            let bitmap = match replacement {
+                Some(child) => {
+                    children[i] = child;
+                    *bitmap
+                }
+                None => {
+                    children.remove(i);
+                    bitmap & !bit
+                }
+            };
+            // Collapse a branch that only wraps a single leaf.
+            match children.as_slice() {
+                [] => Some(None),
+                [only] if matches!(only.as_ref(), Node::Leaf { .. }) => Some(Some(only.clone())),
+                _ => Some(Some(Arc::new(Node::Branch { bitmap, children }))),
+            }
+        }
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Default for PMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Cloning copies one pointer; the trie is shared.
+impl<K, V> Clone for PMap<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            root: self.root.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> FromIterator<(K, V)> for PMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        iter.into_iter()
+            .fold(Self::new(), |map, (k, v)| map.insert(k, v))
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone + PartialEq> PartialEq for PMap<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().all(|(k, v)| other.get(k) == Some(v))
+    }
+}
+
+impl<K: Eq + Hash + Clone + fmt::Debug, V: Clone + fmt::Debug> fmt::Debug for PMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// Borrowing iterator over a [`PMap`].
+pub struct Iter<'a, K, V> {
+    stack: Vec<&'a Node<K, V>>,
+    leaf: std::slice::Iter<'a, (K, V)>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((k, v)) = self.leaf.next() {
+                return Some((k, v));
+            }
+            match self.stack.pop()? {
+                Node::Leaf { entries, .. } => self.leaf = entries.iter(),
+                Node::Branch { children, .. } => {
+                    self.stack.extend(children.iter().map(Arc::as_ref))
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,506 @@
+//! Persistent sorted map (AVL tree).
+
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::sync::Arc;
+
+type Link<K, V> = Option<Arc<Node<K, V>>>;
+
+struct Node<K, V> {
+    key: K,
+    value: V,
+    height: u32,
+    left: Link<K, V>,
+    right: Link<K, V>,
+}
+
+/// An immutable map kept sorted by key, with O(log n) `insert`, `remove`
+/// and `get`.
+///
+/// The tree is an AVL tree: sibling subtrees never differ in height by more
+/// than one. Updates rebuild only the nodes on the search path (rotating
+/// them back into balance as needed) and share every other subtree.
+///
+/// ```
+/// use fp::persistent::PTreeMap;
+///
+/// let v1: PTreeMap<&str, u32> = [("pear", 1), ("apple", 3), ("fig", 2)].into_iter().collect();
+/// let v2 = v1.insert("banana", 5).remove("pear");
+///
+/// assert_eq!(v1.keys().copied().collect::<Vec<_>>(), ["apple", "fig", "pear"]);
+/// assert_eq!(v2.keys().copied().collect::<Vec<_>>(), ["apple", "banana", "fig"]);
+/// assert_eq!(v2.fold(0, |acc, _, count| acc + count), 10);
+///
+/// // Stays balanced under sorted insertion, the worst case for a plain BST.
+/// let big: PTreeMap<u32, ()> = (0..10_000).map(|i| (i, ())).collect();
+/// assert!(big.height() <= 15);
+/// ```
+pub struct PTreeMap<K, V> {
+    root: Link<K, V>,
+    len: usize,
+}
+
+fn height<K, V>(link: &Link<K, V>) -> u32 {
+    link.as_ref().map_or(0, |node| node.height)
+}
+
+fn node<K, V>(key: K, value: V, left: Link<K, V>, right: Link<K, V>) -> Arc<Node<K, V>> {
+    Arc::new(Node {
+        height: 1 + height(&left).max(height(&right)),
+        key,
+        value,
+        left,
+        right,
+    })
+}
+
+/// Builds a node from parts, rotating once or twice if the subtree heights
+/// differ by two.
+fn balance<K: Clone, V: Clone>(
+    key: K,
+    value: V,
+    left: Link<K, V>,
+    right: Link<K, V>,
+) -> Arc<Node<K, V>> {
+    let (hl, hr) = (height(&left), height(&right));
+    if hl > hr + 1 {
+        let l = left.expect("left-heavy tree has a left child");
+        if height(&l.left) >= height(&l.right) {
+            // Single right rotation.
+            let new_right = node(key, value, l.right.clone(), right);
+            node(
+                l.key.clone(),
+                l.value.clone(),
+                l.left.clone(),
+                Some(new_right),
+            )
+        } else {
+            // Left-right double rotation.
+            let lr = l.right.as_ref().expect("inner grandchild exists");
+            let new_left = node(
+                l.key.clone(),
+                l.value.clone(),
+                l.left.clone(),
+                lr.left.clone(),
+            );
+            let new_right = node(key, value, lr.right.clone(), right);
+            node(
+                lr.key.clone(),
+                lr.value.clone(),
+                Some(new_left),
+                Some(new_right),
+            )
+        }
+    } else if hr > hl + 1 {
+        let r = right.expect("right-heavy tree has a right child");
+        if height(&r.right) >= height(&r.left) {
+            // Single left rotation.
+            let new_left = node(key, value, left, r.left.clone());
+            node(
+                r.key.clone(),
+                r.value.clone(),
+                Some(new_left),
+                r.right.clone(),
+            )
+        } else {
+            // Right-left double rotation.
+            let rl = r.left.as_ref().expect("inner grandchild exists");
+            let new_left = node(key, value, left, rl.left.clone());
+            let new_right = node(
+                r.key.clone(),
+                r.value.clone(),
+                rl.right.clone(),
+                r.right.clone(),
+            );
+            node(
+                rl.key.clone(),
+                rl.value.clone(),
+                Some(new_left),
+                Some(new_right),
+            )
+        }
+    } else {
+        node(key, value, left, right)
+    }
+}
+
+/// Returns the new subtree and whether a new key was added.
+fn insert_into<K: Ord + Clone, V: Clone>(
+    link: &Link<K, V>,
+    key: K,
+    value: V,
+) -> (Arc<Node<K, V>>, bool) {
+    let Some(n) = link else {
+        return (node(key, value, None, None), true);
+    };
+    match key.cmp(&n.key) {
+        Ordering::Less => {
+            let (left, added) = insert_into(&n.left, key, value);
+            (
+                balance(n.key.clone(), n.value.clone(), Some(left), n.right.clone()),
+                added,
+            )
+        }
+        Ordering::Greater => {
+            let (right, added) = insert_into(&n.right, key, value);
+            (
+                balance(n.key.clone(), n.value.clone(), n.left.clone(), Some(right)),
+                added,
+            )
+        }
+        Ordering::Equal => (node(key, value, n.left.clone(), n.right.clone()), false),
+    }
+}
+
+/// Removes the smallest entry, returning it and the remaining subtree.
+fn remove_min<K: Clone, V: Clone>(n: &Node<K, V>) -> (K, V, Link<K, V>) {
+    match &n.left {
+        None => (n.key.clone(), n.value.clone(), n.right.clone()),
+        Some(left) => {
+            let (k, v, rest) = remove_min(left);
+            (
+                k,
+                v,
+                Some(balance(
+                    n.key.clone(),
+                    n.value.clone(),
+                    rest,
+                    n.right.clone(),
+                )),
+            )
+        }
+    }
+}
+
+/// `None` if the key was absent, otherwise the new subtree.
+fn remove_from<K, V, Q>(link: &Link<K, V>, key: &Q) -> Option<Link<K, V>>
+where
+    K: Borrow<Q> + Clone,
+    V: Clone,
+    Q: Ord + ?Sized,
+{
+    let n = link.as_ref()?;
+    Some(match key.cmp(n.key.borrow()) {
+        Ordering::Less => {
+            let left = remove_from(&n.left, key)?;
+            Some(balance(
+                n.key.clone(),
+                n.value.clone(),
+                left,
+                n.right.clone(),
+            ))
+        }
+        Ordering::Greater => {
+            let right = remove_from(&n.right, key)?;
+            Some(balance(
+                n.key.clone(),
+                n.value.clone(),
+                n.left.clone(),
+                right,
+            ))
+        }
+        Ordering::Equal => match (&n.left, &n.right) {
+            (None, right) => right.clone(),
+            (left, None) => left.clone(),
+            (left, Some(right)) => {
+                let (k, v, rest) = remove_min(right);
+                Some(balance(k, v, left.clone(), rest))
+            }
+        },
+    })
+}
+
+impl<K: Ord + Clone, V: Clone> PTreeMap<K, V> {
+    /// The empty map.
+    ///
+    /// ```
+    /// use fp::persistent::PTreeMap;
+    ///
+    /// let empty: PTreeMap<&str, usize> = PTreeMap::new();
+    /// assert_eq!(empty.len(), 0);
+    /// ```
+    pub fn new() -> Self {
+        Self { root: None, len: 0 }
+    }
+
+    /// Number of entries.
+    ///
+    /// ```
+    /// use fp::persistent::PTreeMap;
+    ///
+    /// let tree = PTreeMap::new().insert("fold", 1).insert("map", 1).insert("fold", 2);
+    /// assert_eq!(tree.len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the map has no entries.
+    ///
+    /// ```
+    /// use fp::persistent::PTreeMap;
+    ///
+    /// let empty: PTreeMap<u32, u32> = PTreeMap::new();
+    /// assert!(empty.is_empty());
+    /// assert!(empty.insert(1, 1).remove(&1).is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Height of the tree (0 when empty).
+    ///
+    /// ```
+    /// use fp::persistent::PTreeMap;
+    ///
+    /// // Kept balanced: 1,000 keys inserted in order are only a few levels deep
+    /// let tree = (0..1000).fold(PTreeMap::new(), |tree, i| tree.insert(i, ()));
+    /// assert!(tree.height() <= 14);
+    /// assert_eq!(PTreeMap::<u32, ()>::new().height(), 0);
+    /// ```
+    pub fn height(&self) -> u32 {
+        height(&self.root)
+    }
+
+    /// The value stored for `key`.
+    ///
+    /// ```
+    /// use fp::persistent::PTreeMap;
+    ///
+    /// let tree: PTreeMap<&str, usize> = [("fold", 2), ("map", 1)].into_iter().collect();
+    /// assert_eq!(tree.get("fold"), Some(&2));
+    /// assert_eq!(tree.get("filter"), None);
+    /// ```
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let mut link = &self.root;
+        while let Some(n) = link {
+            link = match key.cmp(n.key.borrow()) {
+                Ordering::Less => &n.left,
+                Ordering::Greater => &n.right,
+                Ordering::Equal => return Some(&n.value),
+            };
+        }
+        None
+    }
+
+    /// Returns `true` if `key` is present.
+    ///
+    /// ```
+    /// use fp::persistent::PTreeMap;
+    ///
+    /// let tree: PTreeMap<String, ()> = [("fold".to_string(), ())].into_iter().collect();
+    /// assert!(tree.contains_key("fold"));
+    /// ```
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.get(key).is_some()
+    }
+
+    /// Returns a new map with `key` set to `value`.
+    ///
+    /// ```
+    /// use fp::persistent::PTreeMap;
+    ///
+    /// let before = PTreeMap::new().insert("fold", 1);
+    /// let after = before.insert("fold", 2);
+    /// assert_eq!(after.get("fold"), Some(&2));
+    /// assert_eq!(before.get("fold"), Some(&1), "the old map is unchanged");
+    /// ```
+    pub fn insert(&self, key: K, value: V) -> Self {
+        let (root, added) = insert_into(&self.root, key, value);
+        Self {
+            root: Some(root),
+            len: self.len + usize::from(added),
+        }
+    }
+
+    /// Returns a new map where `key` maps to `f(current value)`.
+    ///
+    /// ```
+    /// use fp::persistent::PTreeMap;
+    ///
+    /// let counts = ["fold", "map", "fold"]
+    ///     .into_iter()
+    ///     .fold(PTreeMap::<_, usize>::new(), |counts, word| counts.update(word, |n| n.map_or(1, |n| n + 1)));
+    /// assert_eq!(counts.get("fold"), Some(&2));
+    /// assert_eq!(counts.get("map"), Some(&1));
+    /// ```
+    pub fn update<F: FnOnce(Option<&V>) -> V>(&self, key: K, f: F) -> Self {
+        let value = f(self.get(&key));
+        self.insert(key, value)
+    }
+
+    /// Returns a new map without `key`.
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use fp::persistent::PTreeMap;
+    ///
+    /// let (tree, btree) = (0..5000u32).fold((PTreeMap::new(), BTreeMap::new()), |(t, mut b), i| {
+    ///     let key = i * 7919 % 1000;
+    ///     if i % 3 == 0 {
+    ///         b.remove(&key);
+    ///         (t.remove(&key), b)
+    ///     } else {
+    ///         b.insert(key, i);
+    ///         (t.insert(key, i), b)
+    ///     }
+    /// });
+    /// assert_eq!(tree.len(), btree.len());
+    /// assert!(tree.iter().eq(btree.iter()));
+    /// assert!(tree.height() <= 14);
+    /// ```
+    pub fn remove<Q>(&self, key: &Q) -> Self
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        match remove_from(&self.root, key) {
+            None => self.clone(),
+            Some(root) => Self {
+                root,
+                len: self.len - 1,
+            },
+        }
+    }
+
+    /// Iterates over the entries in ascending key order.
+    ///
+    /// ```
+    /// use fp::persistent::PTreeMap;
+    ///
+    /// let tree: PTreeMap<&str, usize> = [("map", 1), ("fold", 2)].into_iter().collect();
+    /// assert_eq!(tree.iter().collect::<Vec<_>>(), [(&"fold", &2), (&"map", &1)]);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        let mut iter = Iter { stack: Vec::new() };
+        iter.push_left(&self.root);
+        iter
+    }
+
+    /// Iterates over the keys in ascending order.
+    ///
+    /// ```
+    /// use fp::persistent::PTreeMap;
+    ///
+    /// let tree: PTreeMap<u32, ()> = [(3, ()), (1, ()), (2, ())].into_iter().collect();
+    /// assert_eq!(tree.keys().copied().collect::<Vec<_>>(), [1, 2, 3]);
+    /// ```
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.iter().map(|(k, _)| k)
+    }
+
+    /// Returns a new map with `f` applied to every value.
+    ///
+    /// ```
+    /// use fp::persistent::PTreeMap;
+    ///
+    /// let tree: PTreeMap<&str, usize> = [("fold", 2), ("map", 1)].into_iter().collect();
+    /// let shouted = tree.map(|word, count| word.to_uppercase().repeat(*count));
+    /// assert_eq!(shouted.get("fold"), Some(&"FOLDFOLD".to_string()));
+    /// ```
+    pub fn map<U: Clone, F: FnMut(&K, &V) -> U>(&self, mut f: F) -> PTreeMap<K, U> {
+        self.iter().map(|(k, v)| (k.clone(), f(k, v))).collect()
+    }
+
+    /// Folds the entries in ascending key order.
+    ///
+    /// ```
+    /// use fp::persistent::PTreeMap;
+    ///
+    /// let tree: PTreeMap<&str, usize> = [("map", 1), ("fold", 2)].into_iter().collect();
+    /// let listed = tree.fold(String::new(), |out, word, count| format!("{}{}={} ", out, word, count));
+    /// assert_eq!(listed, "fold=2 map=1 ");
+    /// ```
+    pub fn fold<B, F: FnMut(B, &K, &V) -> B>(&self, init: B, mut f: F) -> B {
+        self.iter().fold(init, |acc, (k, v)| f(acc, k, v))
+    }
+}
+
+impl<K: Ord + Clone, V: Clone> Default for PTreeMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Cloning copies one pointer; the tree is shared.
+impl<K, V> Clone for PTreeMap<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            root: self.root.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<K, V> PTreeMap<K, V> {
+    /// Empties the map, returning the values of the nodes no other version
+    /// shares; shared subtrees are left to the versions still holding them.
+    ///
+    /// Lets a value that holds maps of its own be dropped level by level
+    /// rather than recursively.
+    pub(crate) fn take_unshared_values(&mut self) -> Vec<V> {
+        let mut values = Vec::new();
+        let mut nodes: Vec<Arc<Node<K, V>>> = self.root.take().into_iter().collect();
+        self.len = 0;
+        while let Some(node) = nodes.pop() {
+            if let Ok(node) = Arc::try_unwrap(node) {
+                values.push(node.value);
+                nodes.extend(node.left);
+                nodes.extend(node.right);
+            }
+        }
+        values
+    }
+}
+
+impl<K: Ord + Clone, V: Clone> FromIterator<(K, V)> for PTreeMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        iter.into_iter()
+            .fold(Self::new(), |map, (k, v)| map.insert(k, v))
+    }
+}
+
+impl<K: Ord + Clone, V: Clone + PartialEq> PartialEq for PTreeMap<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<K: Ord + Clone + fmt::Debug, V: Clone + fmt::Debug> fmt::Debug for PTreeMap<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// In-order iterator over a [`PTreeMap`].
+pub struct Iter<'a, K, V> {
+    stack: Vec<&'a Node<K, V>>,
+}
+
+impl<'a, K, V> Iter<'a, K, V> {
+    fn push_left(&mut self, mut link: &'a Link<K, V>) {
+        while let Some(n) = link {
+            self.stack.push(n);
+            link = &n.left;
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let n = self.stack.pop()?;
+        self.push_left(&n.right);
+        Some((&n.key, &n.value))
+    }
+}
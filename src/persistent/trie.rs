@@ -0,0 +1,248 @@
+//! Persistent prefix tree.
+
+use std::fmt;
+use std::sync::Arc;
+
+use super::PTreeMap;
+
+/// An immutable [`Trie`](crate::trie::Trie): `insert` returns a new version
+/// and leaves the old one intact.
+///
+/// Each node keeps its children in a [`PTreeMap`] keyed by character, so an
+/// insert rebuilds only the nodes along the word's path and shares every
+/// other subtree between versions. Keeping a vocabulary index for every
+/// version of a watched file costs little more than the words that changed.
+///
+/// ```
+/// use fp::persistent::PTrie;
+///
+/// let v1: PTrie<usize> = [("fold", 5), ("filter", 2)].into_iter().collect();
+/// let v2 = v1.insert("foldl", 1).insert("fold", 6);
+///
+/// assert_eq!(v1.words_with_prefix("fo"), [("fold".to_string(), &5)]);
+/// assert_eq!(v2.words_with_prefix("fo"), [("fold".to_string(), &6), ("foldl".to_string(), &1)]);
+/// assert_eq!((v1.len(), v2.len()), (2, 3));
+/// ```
+pub struct PTrie<V> {
+    /// The value of the word ending here, if one does
+    value: Option<Arc<V>>,
+    children: PTreeMap<char, PTrie<V>>,
+    /// Words in this subtree
+    len: usize,
+}
+
+impl<V> PTrie<V> {
+    /// The empty trie
+    ///
+    /// ```
+    /// use fp::persistent::PTrie;
+    ///
+    /// let trie: PTrie<usize> = PTrie::new();
+    /// assert!(trie.words_with_prefix("").is_empty());
+    /// ```
+    pub fn new() -> Self {
+        PTrie {
+            value: None,
+            children: PTreeMap::new(),
+            len: 0,
+        }
+    }
+
+    /// How many words the trie holds
+    ///
+    /// ```
+    /// use fp::persistent::PTrie;
+    ///
+    /// // A word that is a prefix of another counts as a word of its own
+    /// let trie = PTrie::new().insert("fold", 1).insert("folds", 2).insert("fold", 3);
+    /// assert_eq!(trie.len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the trie holds no words
+    ///
+    /// ```
+    /// use fp::persistent::PTrie;
+    ///
+    /// let empty: PTrie<()> = PTrie::new();
+    /// assert!(empty.is_empty());
+    /// assert!(!empty.insert("", ()).is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns a new trie with `value` stored under `word`.
+    ///
+    /// The path is copied bottom-up in a loop, so a word of any length
+    /// fits on the stack.
+    ///
+    /// ```
+    /// use fp::persistent::PTrie;
+    ///
+    /// let long = "a".repeat(300_000);
+    /// let trie = PTrie::new().insert(&long, 1).insert("ab", 2);
+    /// assert_eq!(trie.len(), 2);
+    /// assert_eq!(trie.words_with_prefix("a")[0].0.len(), 300_000);
+    /// ```
+    pub fn insert(&self, word: &str, value: V) -> Self {
+        // The existing nodes along the word's path, as far as it exists
+        let mut path = vec![Some(self)];
+        for c in word.chars() {
+            let next = path
+                .last()
+                .copied()
+                .flatten()
+                .and_then(|node| node.children.get(&c));
+            path.push(next);
+        }
+        let mut node = match path.pop().flatten() {
+            Some(old) => PTrie {
+                len: old.len + usize::from(old.value.is_none()),
+                value: Some(Arc::new(value)),
+                children: old.children.clone(),
+            },
+            None => PTrie {
+                len: 1,
+                value: Some(Arc::new(value)),
+                children: PTreeMap::new(),
+            },
+        };
+        for (c, parent) in word.chars().rev().zip(path.into_iter().rev()) {
+            node = match parent {
+                Some(parent) => {
+                    let old = parent.children.get(&c).map_or(0, PTrie::len);
+                    PTrie {
+                        len: parent.len - old + node.len,
+                        value: parent.value.clone(),
+                        children: parent.children.insert(c, node),
+                    }
+                }
+                None => PTrie {
+                    len: node.len,
+                    value: None,
+                    children: PTreeMap::new().insert(c, node),
+                },
+            };
+        }
+        node
+    }
+
+    /// The value stored under exactly `word`
+    ///
+    /// ```
+    /// use fp::persistent::PTrie;
+    ///
+    /// let trie = PTrie::new().insert("fold", 2);
+    /// assert_eq!(trie.get("fold"), Some(&2));
+    /// assert_eq!(trie.get("fol"), None);
+    /// ```
+    pub fn get(&self, word: &str) -> Option<&V> {
+        self.node(word)?.value.as_deref()
+    }
+
+    /// Whether `word` itself is in the trie, not just as a prefix
+    ///
+    /// ```
+    /// use fp::persistent::PTrie;
+    ///
+    /// let trie = PTrie::new().insert("fold", ());
+    /// assert!(trie.contains("fold"));
+    /// assert!(!trie.contains("fo"));
+    /// ```
+    pub fn contains(&self, word: &str) -> bool {
+        self.get(word).is_some()
+    }
+
+    /// Every word starting with `prefix`, itself included, in alphabetical
+    /// order of characters, with its value
+    ///
+    /// ```
+    /// use fp::persistent::PTrie;
+    ///
+    /// let trie = PTrie::new().insert("map", 1).insert("fold", 2).insert("filter", 3);
+    /// let words: Vec<(String, &i32)> = trie.words_with_prefix("f");
+    /// assert_eq!(words, [("filter".to_string(), &3), ("fold".to_string(), &2)]);
+    /// ```
+    pub fn words_with_prefix(&self, prefix: &str) -> Vec<(String, &V)> {
+        let mut words = Vec::new();
+        if let Some(node) = self.node(prefix) {
+            node.collect(prefix, &mut words);
+        }
+        words
+    }
+
+    /// The node of `prefix`, if any word starts with it
+    fn node(&self, prefix: &str) -> Option<&PTrie<V>> {
+        prefix
+            .chars()
+            .try_fold(self, |node, c| node.children.get(&c))
+    }
+
+    /// Push the words below this node, spelled from `prefix`, onto `words`
+    fn collect<'a>(&'a self, prefix: &str, words: &mut Vec<(String, &'a V)>) {
+        let mut spelled = prefix.to_string();
+        // Nodes still to visit, the next on top, with the length of their
+        // parent's spelling and the character that leads to them
+        let mut stack = vec![(spelled.len(), None, self)];
+        while let Some((parent, c, node)) = stack.pop() {
+            spelled.truncate(parent);
+            spelled.extend(c);
+            if let Some(value) = &node.value {
+                words.push((spelled.clone(), value));
+            }
+            let len = spelled.len();
+            let children: Vec<_> = node.children.iter().collect();
+            stack.extend(
+                children
+                    .into_iter()
+                    .rev()
+                    .map(|(&c, child)| (len, Some(c), child)),
+            );
+        }
+    }
+}
+
+/// Dropped level by level, as a long word's chain of nodes would overflow
+/// the stack dropping itself recursively; nodes other versions still share
+/// are left to them
+impl<V> Drop for PTrie<V> {
+    fn drop(&mut self) {
+        let mut nodes = self.children.take_unshared_values();
+        while let Some(mut node) = nodes.pop() {
+            nodes.append(&mut node.children.take_unshared_values());
+        }
+    }
+}
+
+impl<V> Default for PTrie<V> {
+    fn default() -> Self {
+        PTrie::new()
+    }
+}
+
+impl<V> Clone for PTrie<V> {
+    fn clone(&self) -> Self {
+        PTrie {
+            value: self.value.clone(),
+            children: self.children.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<S: AsRef<str>, V> FromIterator<(S, V)> for PTrie<V> {
+    fn from_iter<I: IntoIterator<Item = (S, V)>>(items: I) -> Self {
+        items.into_iter().fold(PTrie::new(), |trie, (word, value)| {
+            trie.insert(word.as_ref(), value)
+        })
+    }
+}
+
+impl<V: fmt::Debug> fmt::Debug for PTrie<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.words_with_prefix("")).finish()
+    }
+}
@@ -0,0 +1,289 @@
+//! Persistent vector backed by a bit-partitioned trie.
+
+use std::fmt;
+use std::sync::Arc;
+
+const BITS: usize = 5;
+const WIDTH: usize = 1 << BITS;
+const MASK: usize = WIDTH - 1;
+
+enum Node<T> {
+    Branch(Vec<Arc<Node<T>>>),
+    Leaf(Vec<T>),
+}
+
+/// An immutable vector with O(log n) `push`, `get` and `update`.
+///
+/// Elements live in the leaves of a trie where every node has up to 32
+/// children, so an index is split into 5-bit chunks that pick the child at
+/// each level. `push` and `update` copy only the nodes on the path to the
+/// touched leaf (at most `log32(n)` of them); every other node is shared
+/// with the previous version.
+///
+/// ```
+/// use fp::persistent::PVec;
+///
+/// let v1: PVec<i32> = (0..100).collect();
+/// let v2 = v1.update(50, -1).unwrap();
+/// let v3 = v2.push(100);
+///
+/// assert_eq!(v1.get(50), Some(&50));
+/// assert_eq!(v2.get(50), Some(&-1));
+/// assert_eq!((v1.len(), v2.len(), v3.len()), (100, 100, 101));
+/// assert_eq!(v3.get(100), Some(&100));
+///
+/// // Deeper tries behave exactly like a `Vec`.
+/// let big: PVec<usize> = (0..5000).collect();
+/// assert!(big.iter().copied().eq(0..5000));
+/// assert_eq!(big.update(4096, 0).unwrap().fold(0, |acc, x| acc + x), (0..5000).sum::<usize>() - 4096);
+/// ```
+pub struct PVec<T> {
+    len: usize,
+    shift: usize,
+    root: Arc<Node<T>>,
+}
+
+impl<T: Clone> PVec<T> {
+    /// The empty vector.
+    ///
+    /// ```
+    /// use fp::persistent::PVec;
+    ///
+    /// let v = PVec::new().push("fold");
+    /// assert_eq!(v.get(0), Some(&"fold"));
+    /// ```
+    pub fn new() -> Self {
+        Self {
+            len: 0,
+            shift: 0,
+            root: Arc::new(Node::Leaf(Vec::new())),
+        }
+    }
+
+    /// Number of elements.
+    ///
+    /// ```
+    /// use fp::persistent::PVec;
+    ///
+    /// let v: PVec<u32> = (0..1000).collect();
+    /// assert_eq!(v.len(), 1000);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the vector has no elements.
+    ///
+    /// ```
+    /// use fp::persistent::PVec;
+    ///
+    /// let empty: PVec<i32> = PVec::new();
+    /// assert!(empty.is_empty());
+    /// assert!(!empty.push(1).is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The element at `index`, if in bounds.
+    ///
+    /// ```
+    /// use fp::persistent::PVec;
+    ///
+    /// let v: PVec<u32> = (0..100).collect();
+    /// assert_eq!(v.get(42), Some(&42));
+    /// assert_eq!(v.get(100), None);
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&T> {
+        (index < self.len).then(|| &self.leaf(index)[index & MASK])
+    }
+
+    /// Returns a new vector with `value` appended.
+    ///
+    /// ```
+    /// use fp::persistent::PVec;
+    ///
+    /// let v = PVec::new().push("fold");
+    /// let w = v.push("map");
+    /// assert_eq!((v.len(), w.len()), (1, 2), "the old vector is unchanged");
+    /// assert_eq!(w.get(1), Some(&"map"));
+    /// ```
+    pub fn push(&self, value: T) -> Self {
+        let capacity = 1 << (self.shift + BITS);
+        if self.len == capacity {
+            // The trie is full: grow a new root one level up.
+            let root = Node::Branch(vec![
+                self.root.clone(),
+                Arc::new(new_path(self.shift, value)),
+            ]);
+            Self {
+                len: self.len + 1,
+                shift: self.shift + BITS,
+                root: Arc::new(root),
+            }
+        } else {
+            Self {
+                len: self.len + 1,
+                shift: self.shift,
+                root: Arc::new(push_into(&self.root, self.shift, self.len, value)),
+            }
+        }
+    }
+
+    /// Returns a new vector with the element at `index` replaced, or `None`
+    /// if `index` is out of bounds.
+    ///
+    /// ```
+    /// use fp::persistent::PVec;
+    ///
+    /// let v: PVec<u32> = (0..10).collect();
+    /// let w = v.update(3, 30).unwrap();
+    /// assert_eq!((v.get(3), w.get(3)), (Some(&3), Some(&30)));
+    /// assert!(v.update(10, 0).is_none());
+    /// ```
+    pub fn update(&self, index: usize, value: T) -> Option<Self> {
+        (index < self.len).then(|| Self {
+            len: self.len,
+            shift: self.shift,
+            root: Arc::new(update_in(&self.root, self.shift, index, value)),
+        })
+    }
+
+    /// Iterates over the elements in order.
+    ///
+    /// ```
+    /// use fp::persistent::PVec;
+    ///
+    /// let v: PVec<char> = "fold".chars().collect();
+    /// assert_eq!(v.iter().rev().collect::<String>(), "dlof");
+    /// ```
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> {
+        (0..self.len)
+            .step_by(WIDTH)
+            .flat_map(move |start| self.leaf(start).iter())
+    }
+
+    /// Returns a new vector with `f` applied to every element.
+    ///
+    /// ```
+    /// use fp::persistent::PVec;
+    ///
+    /// let v: PVec<i32> = (1..=100).collect();
+    /// let squares = v.map(|x| x * x);
+    /// assert_eq!(squares.get(99), Some(&10_000));
+    /// assert_eq!(v.get(99), Some(&100));
+    /// ```
+    pub fn map<U: Clone, F: FnMut(&T) -> U>(&self, f: F) -> PVec<U> {
+        self.iter().map(f).collect()
+    }
+
+    /// Folds the elements in order.
+    ///
+    /// ```
+    /// use fp::persistent::PVec;
+    ///
+    /// let v: PVec<u64> = (1..=100).collect();
+    /// assert_eq!(v.fold(0, |sum, x| sum + x), 5050);
+    /// ```
+    pub fn fold<B, F: FnMut(B, &T) -> B>(&self, init: B, f: F) -> B {
+        self.iter().fold(init, f)
+    }
+
+    /// The leaf holding `index`.
+    fn leaf(&self, index: usize) -> &[T] {
+        let mut node = self.root.as_ref();
+        let mut level = self.shift;
+        loop {
+            match node {
+                Node::Branch(children) => {
+                    node = &children[(index >> level) & MASK];
+                    level -= BITS;
+                }
+                Node::Leaf(values) => return values,
+            }
+        }
+    }
+}
+
+/// A chain of single-child branches ending in a leaf holding `value`.
+fn new_path<T>(level: usize, value: T) -> Node<T> {
+    if level == 0 {
+        Node::Leaf(vec![value])
+    } else {
+        Node::Branch(vec![Arc::new(new_path(level - BITS, value))])
+    }
+}
+
+/// Copies the path to position `index` and appends `value` there.
+fn push_into<T: Clone>(node: &Node<T>, level: usize, index: usize, value: T) -> Node<T> {
+    match node {
+        Node::Leaf(values) => {
+            let mut values = values.clone();
+            values.push(value);
+            Node::Leaf(values)
+        }
+        Node::Branch(children) => {
+            let slot = (index >> level) & MASK;
+            let mut children = children.clone();
+            match children.get(slot) {
+                Some(child) => {
+                    children[slot] = Arc::new(push_into(child, level - BITS, index, value))
+                }
+                None => children.push(Arc::new(new_path(level - BITS, value))),
+            }
+            Node::Branch(children)
+        }
+    }
+}
+
+/// Copies the path to position `index` and replaces the value there.
+fn update_in<T: Clone>(node: &Node<T>, level: usize, index: usize, value: T) -> Node<T> {
+    match node {
+        Node::Leaf(values) => {
+            let mut values = values.clone();
+            values[index & MASK] = value;
+            Node::Leaf(values)
+        }
+        Node::Branch(children) => {
+            let slot = (index >> level) & MASK;
+            let mut children = children.clone();
+            children[slot] = Arc::new(update_in(&children[slot], level - BITS, index, value));
+            Node::Branch(children)
+        }
+    }
+}
+
+impl<T: Clone> Default for PVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Cloning copies one pointer; the trie is shared.
+impl<T> Clone for PVec<T> {
+    fn clone(&self) -> Self {
+        Self {
+            len: self.len,
+            shift: self.shift,
+            root: self.root.clone(),
+        }
+    }
+}
+
+impl<T: Clone> FromIterator<T> for PVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        iter.into_iter().fold(Self::new(), |v, x| v.push(x))
+    }
+}
+
+impl<T: Clone + PartialEq> PartialEq for PVec<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Clone + fmt::Debug> fmt::Debug for PVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
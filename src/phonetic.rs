@@ -0,0 +1,105 @@
+//! Phonetic Codes
+//!
+//! Soundex (Russell and Odell, 1918) gives words that sound alike in English
+//! the same four-character code: the first letter, then digits for the
+//! consonant sounds that follow, so "smith" and "smyth" both become S530.
+//! Grouping a vocabulary by code, with [`MultiMap::group_by`], surfaces
+//! spelling variants and misspelled names when cleaning data.
+//!
+//! ```
+//! use fp::phonetic::phonetic_groups;
+//!
+//! let groups = phonetic_groups(["smith", "fold", "smyth", "robert", "rupert", "map", "2024"]);
+//! assert_eq!(groups, [
+//!     ("R163".to_string(), vec!["robert", "rupert"]),
+//!     ("S530".to_string(), vec!["smith", "smyth"]),
+//! ]);
+//! ```
+
+use crate::multimap::MultiMap;
+
+/// The length of a Soundex code
+const SOUNDEX_LEN: usize = 4;
+
+/// The Soundex code of `word`, or `None` if it has no ASCII letters to code
+///
+/// Letters coded alike count once when adjacent or separated only by h or
+/// w; a vowel between them makes them count twice. Other characters are
+/// skipped.
+///
+/// ```
+/// use fp::phonetic::soundex;
+///
+/// assert_eq!(soundex("Robert").as_deref(), Some("R163"));
+/// assert_eq!(soundex("Ashcraft").as_deref(), Some("A261"));
+/// assert_eq!(soundex("Tymczak").as_deref(), Some("T522"));
+/// assert_eq!(soundex("Pfister").as_deref(), Some("P236"));
+/// assert_eq!(soundex("lee").as_deref(), Some("L000"));
+/// assert_eq!(soundex("404"), None);
+/// ```
+pub fn soundex(word: &str) -> Option<String> {
+    let mut letters = word
+        .chars()
+        .filter(char::is_ascii_alphabetic)
+        .map(|c| c.to_ascii_lowercase());
+    let first = letters.next()?;
+    let (mut code, _) = letters.fold(
+        (first.to_ascii_uppercase().to_string(), digit(first)),
+        |(mut code, last), c| match digit(c) {
+            // h and w neither count nor separate
+            None if matches!(c, 'h' | 'w') => (code, last),
+            Some(d) if Some(d) != last => {
+                code.push(d);
+                (code, Some(d))
+            }
+            d => (code, d),
+        },
+    );
+    code.truncate(SOUNDEX_LEN);
+    while code.len() < SOUNDEX_LEN {
+        code.push('0');
+    }
+    Some(code)
+}
+
+/// The Soundex digit of a lowercase ASCII letter; `None` for vowels, y, h
+/// and w
+fn digit(c: char) -> Option<char> {
+    match c {
+        'b' | 'f' | 'p' | 'v' => Some('1'),
+        'c' | 'g' | 'j' | 'k' | 'q' | 's' | 'x' | 'z' => Some('2'),
+        'd' | 't' => Some('3'),
+        'l' => Some('4'),
+        'm' | 'n' => Some('5'),
+        'r' => Some('6'),
+        _ => None,
+    }
+}
+
+/// The groups of two or more distinct words in `words` sharing a Soundex
+/// code, each in alphabetical order with its code; the largest groups
+/// first, then by code
+///
+/// ```
+/// use fp::phonetic::phonetic_groups;
+///
+/// let groups = phonetic_groups(["catherine", "kathryn", "fold", "foaled", "fold"]);
+/// assert_eq!(groups, [
+///     ("F430".to_string(), vec!["foaled", "fold"]),
+/// ]);
+/// ```
+pub fn phonetic_groups<'a>(
+    words: impl IntoIterator<Item = &'a str>,
+) -> Vec<(String, Vec<&'a str>)> {
+    let mut words: Vec<&str> = words.into_iter().collect();
+    words.sort_unstable();
+    words.dedup();
+    let mut groups: Vec<(String, Vec<&str>)> = MultiMap::group_by(words, |word| soundex(word))
+        .into_iter()
+        // Words without ASCII letters, such as numbers, have no code
+        .filter_map(|(code, group)| code.map(|code| (code, group)))
+        .filter(|(_, group)| group.len() > 1)
+        .collect();
+    groups.sort_by(|(a, x), (b, y)| y.len().cmp(&x.len()).then_with(|| a.cmp(b)));
+    groups
+}
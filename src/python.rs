@@ -0,0 +1,82 @@
+//! Python Bindings
+//!
+//! A `textfp` extension module built with pyo3 when the `python` feature is
+//! on (`maturin develop` picks it up from `pyproject.toml`). It exposes the
+//! library's tokenizer and counter to notebooks without a subprocess.
+//!
+//! ```python
+//! import textfp
+//!
+//! textfp.analyze("The cat, the hat.", min_length=3, top=1)
+//! # {'total_words': 4, 'unique_words': 3, 'reading_seconds': 2, 'speaking_seconds': 2,
+//! #  'top_words': [('the', 2)], 'counts': {'the': 2, 'cat': 1, 'hat': 1}}
+//! ```
+
+use crate::analysis::{self, DEFAULT_TOP, Filters, Report};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+/// Count the words of `text` that pass the filters.
+///
+/// `length_mode` (`"bytes"`, `"chars"` or `"graphemes"`) sets how
+/// `min_length` and `max_length` are measured, and `normalize` (`"nfc"`,
+/// `"nfkc"` or `"none"`) the Unicode form words are brought to first;
+/// `strip_accents` then merges "café" with "cafe". `fuzziness` lets
+/// `starts_with`, `ends_with` and `contains` match up to that many edits
+/// off. `locale` (`"tr_TR"`, `"de"`, ...) sets the casing rules. Returns a
+/// dict with `total_words`, `unique_words`, `reading_seconds` and
+/// `speaking_seconds`, the `top` most frequent words as `(word, count)`
+/// tuples, and every word's count under `counts`.
+#[pyfunction]
+#[allow(clippy::too_many_arguments)] // keyword arguments on the Python side
+#[pyo3(signature = (text, min_length=None, max_length=None, starts_with=None, ends_with=None, contains=None, fuzziness=0, top=DEFAULT_TOP, length_mode="graphemes", normalize="none", strip_accents=false, locale=None))]
+fn analyze<'py>(
+    py: Python<'py>,
+    text: &str,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    starts_with: Option<String>,
+    ends_with: Option<String>,
+    contains: Option<String>,
+    fuzziness: usize,
+    top: usize,
+    length_mode: &str,
+    normalize: &str,
+    strip_accents: bool,
+    locale: Option<&str>,
+) -> PyResult<Bound<'py, PyDict>> {
+    let filters = Filters {
+        min_length,
+        max_length,
+        starts_with,
+        ends_with,
+        contains,
+        fuzziness,
+        length_mode: length_mode.parse().map_err(PyValueError::new_err)?,
+        normalization: normalize.parse().map_err(PyValueError::new_err)?,
+        strip_accents,
+        locale: locale
+            .map(str::parse)
+            .transpose()
+            .map_err(PyValueError::new_err)?,
+    };
+    let counts = analysis::filtered_counts(text, &filters);
+    let report = Report::new(&counts, top);
+
+    let dict = PyDict::new(py);
+    dict.set_item("total_words", report.total_words)?;
+    dict.set_item("unique_words", report.unique_words)?;
+    dict.set_item("reading_seconds", report.reading_time.as_secs())?;
+    dict.set_item("speaking_seconds", report.speaking_time.as_secs())?;
+    dict.set_item("top_words", report.top_words)?;
+    dict.set_item("counts", counts)?;
+    Ok(dict)
+}
+
+/// The `textfp` Python module
+#[pymodule]
+#[pyo3(name = "textfp")]
+fn textfp(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(analyze, m)?)
+}
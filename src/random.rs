@@ -0,0 +1,166 @@
+//! A pure, splittable pseudo-random number generator.
+//!
+//! [`Rng`] is an immutable value: drawing a number returns the number *and*
+//! the generator to use next, so the same seed always gives the same
+//! sequence and no global state is involved. [`Rng::split`] derives two
+//! independent generators, which lets separate parts of a computation draw
+//! numbers without threading one generator through both.
+//!
+//! The algorithm is SplitMix64, as used by Java's `SplittableRandom`. It is
+//! fast and statistically solid, but not cryptographically secure.
+//!
+//! ```
+//! use fp::random::Rng;
+//!
+//! let rng = Rng::new(42);
+//! let (a, next) = rng.next();
+//! let (b, _) = next.next();
+//! assert_ne!(a, b);
+//!
+//! // Same seed, same numbers
+//! assert_eq!(Rng::new(42).next().0, a);
+//!
+//! // Dice rolls, reproducibly
+//! let (rolls, _) = (0..5).fold((Vec::new(), Rng::new(7)), |(mut rolls, rng), _| {
+//!     let (roll, rng) = rng.next_below(6);
+//!     rolls.push(roll + 1);
+//!     (rolls, rng)
+//! });
+//! assert!(rolls.iter().all(|r| (1..=6).contains(r)));
+//!
+//! // Split generators produce unrelated streams
+//! let (left, right) = Rng::new(7).split();
+//! assert_ne!(left.iter().take(3).collect::<Vec<_>>(), right.iter().take(3).collect::<Vec<_>>());
+//! ```
+
+const GOLDEN_GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// An immutable SplitMix64 generator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rng {
+    seed: u64,
+    gamma: u64,
+}
+
+impl Rng {
+    /// A generator determined entirely by `seed`.
+    ///
+    /// ```
+    /// use fp::random::Rng;
+    ///
+    /// assert_eq!(Rng::new(1), Rng::new(1));
+    /// assert_ne!(Rng::new(1).next().0, Rng::new(2).next().0);
+    /// ```
+    pub fn new(seed: u64) -> Self {
+        Rng {
+            seed,
+            gamma: GOLDEN_GAMMA,
+        }
+    }
+
+    /// The next 64 random bits and the generator to continue with.
+    ///
+    /// ```
+    /// use fp::random::Rng;
+    ///
+    /// let rng = Rng::new(2025);
+    /// let (a, rest) = rng.next();
+    /// assert_eq!(rng.next().0, a, "the generator itself is unchanged");
+    /// assert_ne!(rest.next().0, a);
+    /// ```
+    pub fn next(self) -> (u64, Rng) {
+        let seed = self.seed.wrapping_add(self.gamma);
+        (mix64(seed), Rng { seed, ..self })
+    }
+
+    /// A number in `0..bound` without modulo bias. `bound` must not be zero.
+    ///
+    /// ```
+    /// use fp::random::Rng;
+    ///
+    /// let (roll, _) = Rng::new(7).next_below(6);
+    /// assert!(roll < 6);
+    /// assert_eq!(Rng::new(7).next_below(1).0, 0);
+    /// ```
+    pub fn next_below(self, bound: u64) -> (u64, Rng) {
+        assert!(bound > 0, "next_below needs a positive bound");
+        // Reject the few values that would make the low residues more likely
+        let threshold = bound.wrapping_neg() % bound;
+        let (bits, rng) = self.next();
+        if bits >= threshold {
+            (bits % bound, rng)
+        } else {
+            rng.next_below(bound)
+        }
+    }
+
+    /// A float in `[0, 1)`.
+    ///
+    /// ```
+    /// use fp::random::Rng;
+    ///
+    /// let (x, rng) = Rng::new(7).next_f64();
+    /// assert!((0.0..1.0).contains(&x));
+    /// assert_eq!(Rng::new(7).next_f64().0, x);
+    /// assert_ne!(rng.next_f64().0, x);
+    /// ```
+    pub fn next_f64(self) -> (f64, Rng) {
+        let (bits, rng) = self.next();
+        ((bits >> 11) as f64 / (1u64 << 53) as f64, rng)
+    }
+
+    /// Two independent generators derived from this one.
+    ///
+    /// ```
+    /// use fp::random::Rng;
+    ///
+    /// let (a, b) = Rng::new(7).split();
+    /// assert_ne!(a.next().0, b.next().0);
+    /// assert_eq!(Rng::new(7).split(), (a, b), "splitting is deterministic too");
+    /// ```
+    pub fn split(self) -> (Rng, Rng) {
+        let (seed_bits, rng) = self.next_seed();
+        let (gamma_bits, rng) = rng.next_seed();
+        let child = Rng {
+            seed: mix64(seed_bits),
+            gamma: mix_gamma(gamma_bits),
+        };
+        (rng, child)
+    }
+
+    /// An endless iterator of the numbers this generator produces.
+    ///
+    /// ```
+    /// use fp::random::Rng;
+    ///
+    /// let first: Vec<u64> = Rng::new(7).iter().take(3).collect();
+    /// assert_eq!(first[0], Rng::new(7).next().0);
+    /// assert_eq!(first.len(), 3);
+    /// ```
+    pub fn iter(self) -> impl Iterator<Item = u64> {
+        std::iter::successors(Some(self.next()), |&(_, rng)| Some(rng.next())).map(|(n, _)| n)
+    }
+
+    fn next_seed(self) -> (u64, Rng) {
+        let seed = self.seed.wrapping_add(self.gamma);
+        (seed, Rng { seed, ..self })
+    }
+}
+
+fn mix64(z: u64) -> u64 {
+    let z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    let z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+fn mix_gamma(z: u64) -> u64 {
+    let z = (z ^ (z >> 33)).wrapping_mul(0xff51_afd7_ed55_8ccd);
+    let z = (z ^ (z >> 33)).wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    let z = (z ^ (z >> 33)) | 1;
+    // Gammas with too few bit transitions produce poor sequences
+    if (z ^ (z >> 1)).count_ones() < 24 {
+        z ^ 0xaaaa_aaaa_aaaa_aaaa
+    } else {
+        z
+    }
+}
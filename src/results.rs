@@ -0,0 +1,118 @@
+//! Result-focused combinators.
+//!
+//! Extension traits for working with many `Result`s at once: short-circuiting
+//! (`traverse`, `sequence`) or collecting every failure (`partition_results`,
+//! `sequence_all`), plus `bimap` for transforming both sides of one `Result`.
+
+/// Extension methods for a single `Result`.
+pub trait ResultExt<T, E> {
+    /// Maps the success value with `f` and the error with `g`.
+    ///
+    /// ```
+    /// use fp::results::ResultExt;
+    ///
+    /// let parsed = "42".parse::<u32>().bimap(|n| n * 2, |e| e.to_string());
+    /// assert_eq!(parsed, Ok(84));
+    ///
+    /// let failed = "x".parse::<u32>().bimap(|n| n * 2, |_| "not a number");
+    /// assert_eq!(failed, Err("not a number"));
+    /// ```
+    fn bimap<U, E2, F, G>(self, f: F, g: G) -> Result<U, E2>
+    where
+        F: FnOnce(T) -> U,
+        G: FnOnce(E) -> E2;
+}
+
+impl<T, E> ResultExt<T, E> for Result<T, E> {
+    fn bimap<U, E2, F, G>(self, f: F, g: G) -> Result<U, E2>
+    where
+        F: FnOnce(T) -> U,
+        G: FnOnce(E) -> E2,
+    {
+        match self {
+            Ok(t) => Ok(f(t)),
+            Err(e) => Err(g(e)),
+        }
+    }
+}
+
+/// Extension methods for iterators that produce or feed `Result`s.
+pub trait ResultIterExt: Iterator + Sized {
+    /// Applies a fallible function to every item, stopping at the first error.
+    ///
+    /// ```
+    /// use fp::results::ResultIterExt;
+    ///
+    /// let ok = ["1", "2", "3"].into_iter().traverse(str::parse::<i32>);
+    /// assert_eq!(ok, Ok(vec![1, 2, 3]));
+    ///
+    /// let err = ["1", "x", "y"].into_iter().traverse(str::parse::<i32>);
+    /// assert!(err.is_err());
+    /// ```
+    fn traverse<U, E, F>(self, f: F) -> Result<Vec<U>, E>
+    where
+        F: FnMut(Self::Item) -> Result<U, E>,
+    {
+        self.map(f).collect()
+    }
+
+    /// Turns an iterator of `Result<T, E>` into `Result<Vec<T>, E>`,
+    /// returning the first error encountered.
+    ///
+    /// ```
+    /// use fp::results::ResultIterExt;
+    ///
+    /// let all: Result<Vec<i32>, &str> = vec![Ok(1), Ok(2)].into_iter().sequence();
+    /// assert_eq!(all, Ok(vec![1, 2]));
+    ///
+    /// let first: Result<Vec<i32>, &str> = vec![Ok(1), Err("a"), Err("b")].into_iter().sequence();
+    /// assert_eq!(first, Err("a"));
+    /// ```
+    fn sequence<T, E>(self) -> Result<Vec<T>, E>
+    where
+        Self: Iterator<Item = Result<T, E>>,
+    {
+        self.collect()
+    }
+
+    /// Splits an iterator of results into its successes and its failures,
+    /// preserving order within each side.
+    ///
+    /// ```
+    /// use fp::results::ResultIterExt;
+    ///
+    /// let (oks, errs) = vec![Ok(1), Err("a"), Ok(3), Err("b")].into_iter().partition_results();
+    /// assert_eq!(oks, [1, 3]);
+    /// assert_eq!(errs, ["a", "b"]);
+    /// ```
+    fn partition_results<T, E>(self) -> (Vec<T>, Vec<E>)
+    where
+        Self: Iterator<Item = Result<T, E>>,
+    {
+        self.fold((Vec::new(), Vec::new()), |(mut oks, mut errs), item| {
+            match item {
+                Ok(t) => oks.push(t),
+                Err(e) => errs.push(e),
+            }
+            (oks, errs)
+        })
+    }
+
+    /// Like `sequence`, but collects every error instead of only the first.
+    ///
+    /// ```
+    /// use fp::results::ResultIterExt;
+    ///
+    /// let all: Result<Vec<i32>, Vec<&str>> = vec![Ok(1), Err("a"), Err("b")].into_iter().sequence_all();
+    /// assert_eq!(all, Err(vec!["a", "b"]));
+    /// ```
+    fn sequence_all<T, E>(self) -> Result<Vec<T>, Vec<E>>
+    where
+        Self: Iterator<Item = Result<T, E>>,
+    {
+        let (oks, errs) = self.partition_results();
+        if errs.is_empty() { Ok(oks) } else { Err(errs) }
+    }
+}
+
+impl<I: Iterator> ResultIterExt for I {}
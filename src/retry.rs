@@ -0,0 +1,111 @@
+//! Retrying fallible operations with backoff.
+//!
+//! [`retry`] calls an operation until it succeeds or runs out of attempts,
+//! waiting between attempts according to a [`Backoff`] strategy. The result
+//! is the first `Ok`, or the last error if every attempt failed.
+//! [`retry_with`] takes the sleeping function as a parameter, so the waiting
+//! can be observed (or skipped) in tests.
+
+use std::thread;
+use std::time::Duration;
+
+/// How long to wait before each retry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backoff {
+    /// The same delay every time.
+    Fixed(Duration),
+    /// `initial`, then doubling after every failure, never more than `max`.
+    Exponential { initial: Duration, max: Duration },
+}
+
+impl Backoff {
+    /// The delay after the `failures`-th failed attempt (counting from 1).
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use fp::retry::Backoff;
+    ///
+    /// let ms = Duration::from_millis;
+    /// let backoff = Backoff::Exponential { initial: ms(100), max: ms(500) };
+    /// let delays: Vec<_> = (1..=4).map(|n| backoff.delay(n)).collect();
+    /// assert_eq!(delays, [ms(100), ms(200), ms(400), ms(500)]);
+    /// ```
+    pub fn delay(&self, failures: u32) -> Duration {
+        match *self {
+            Backoff::Fixed(delay) => delay,
+            Backoff::Exponential { initial, max } => {
+                let factor = 2u32.saturating_pow(failures.saturating_sub(1));
+                initial.saturating_mul(factor).min(max)
+            }
+        }
+    }
+}
+
+/// Calls `f` up to `attempts` times (at least once), sleeping between
+/// failures, and returns the first success or the last error.
+///
+/// ```
+/// use fp::retry::{Backoff, retry};
+/// use std::time::Duration;
+///
+/// let mut attempts = 0;
+/// let result: Result<u32, &str> = retry(3, Backoff::Fixed(Duration::ZERO), || {
+///     attempts += 1;
+///     if attempts < 2 { Err("busy") } else { Ok(attempts) }
+/// });
+/// assert_eq!(result, Ok(2));
+///
+/// let failing: Result<(), &str> = retry(2, Backoff::Fixed(Duration::ZERO), || Err("down"));
+/// assert_eq!(failing, Err("down"));
+/// ```
+pub fn retry<T, E, F>(attempts: u32, backoff: Backoff, f: F) -> Result<T, E>
+where
+    F: FnMut() -> Result<T, E>,
+{
+    retry_with(attempts, backoff, f, thread::sleep)
+}
+
+/// Like [`retry`], but waits by calling `sleep` instead of blocking the thread.
+///
+/// ```
+/// use std::time::Duration;
+/// use fp::retry::{retry_with, Backoff};
+///
+/// let mut calls = 0;
+/// let mut waited = Vec::new();
+/// let flaky = || {
+///     calls += 1;
+///     if calls < 3 { Err(format!("failure {}", calls)) } else { Ok(calls) }
+/// };
+/// let backoff = Backoff::Fixed(Duration::from_secs(1));
+///
+/// assert_eq!(retry_with(5, backoff, flaky, |d| waited.push(d)), Ok(3));
+/// assert_eq!(waited, [Duration::from_secs(1); 2]);
+///
+/// let always_failing = || Err::<(), _>("down");
+/// assert_eq!(retry_with(2, backoff, always_failing, |_| {}), Err("down"));
+/// ```
+pub fn retry_with<T, E, F, S>(
+    attempts: u32,
+    backoff: Backoff,
+    mut f: F,
+    mut sleep: S,
+) -> Result<T, E>
+where
+    F: FnMut() -> Result<T, E>,
+    S: FnMut(Duration),
+{
+    let mut failures = 0;
+    loop {
+        match f() {
+            Ok(value) => return Ok(value),
+            Err(err) => {
+                failures += 1;
+                if failures >= attempts.max(1) {
+                    return Err(err);
+                }
+                sleep(backoff.delay(failures));
+            }
+        }
+    }
+}
@@ -0,0 +1,75 @@
+//! Sectioning
+//!
+//! A layer above the tokenizer that partitions a document before its words
+//! are counted. Every line matching a delimiter pattern starts a new
+//! section: `^From ` splits an mbox archive into messages, `^#` splits
+//! Markdown at its headings. Text before the first delimiter forms an
+//! untitled preamble. Sections borrow from the document and, taken together,
+//! cover all of it, so their counts combine into the document's counts.
+//!
+//! ```
+//! use fp::sections::split_sections;
+//! use regex::Regex;
+//!
+//! let markdown = "intro\n# Monads\nbind\n# Monoids\nfold\nmap\n";
+//! let sections = split_sections(markdown, &Regex::new("^#").unwrap());
+//! let titles: Vec<Option<&str>> = sections.iter().map(|s| s.title).collect();
+//! assert_eq!(titles, [None, Some("# Monads"), Some("# Monoids")]);
+//! assert_eq!(sections[2].text, "# Monoids\nfold\nmap\n");
+//! ```
+
+use regex::Regex;
+
+/// A contiguous part of a document, from one delimiter line to the next
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Section<'a> {
+    /// The delimiter line that starts the section, `None` for the preamble
+    pub title: Option<&'a str>,
+    /// The section's text, delimiter line included
+    pub text: &'a str,
+}
+
+/// Split `text` before every line that `delimiter` matches. An empty
+/// preamble is left out.
+///
+/// ```
+/// use fp::sections::split_sections;
+/// use regex::Regex;
+///
+/// let delimiter = Regex::new("^From ").unwrap();
+/// let mbox = "From a\nhello\nFrom b\nbye\n";
+/// let messages = split_sections(mbox, &delimiter);
+/// // No text before the first message, so no preamble
+/// assert_eq!(messages.len(), 2);
+/// assert_eq!(messages[1].title, Some("From b"));
+/// assert_eq!(split_sections("no delimiters\n", &delimiter)[0].title, None);
+/// ```
+pub fn split_sections<'a>(text: &'a str, delimiter: &Regex) -> Vec<Section<'a>> {
+    let mut sections = Vec::new();
+    let mut current = Section {
+        title: None,
+        text: "",
+    };
+    let mut start = 0;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let bare = line.trim_end_matches(['\n', '\r']);
+        if delimiter.is_match(bare) {
+            current.text = &text[start..offset];
+            if current.title.is_some() || !current.text.is_empty() {
+                sections.push(current);
+            }
+            current = Section {
+                title: Some(bare),
+                text: "",
+            };
+            start = offset;
+        }
+        offset += line.len();
+    }
+    current.text = &text[start..];
+    if current.title.is_some() || !current.text.is_empty() {
+        sections.push(current);
+    }
+    sections
+}
@@ -0,0 +1,162 @@
+//! Hand-written iterators for classic sequences.
+//!
+//! Each type implements [`Iterator`] directly (state in a struct, one step
+//! per `next` call) rather than by chaining std adapters. All of them are
+//! lazy, so infinite sequences are fine as long as the consumer stops.
+
+use std::collections::HashMap;
+
+/// The Fibonacci numbers `0, 1, 1, 2, 3, 5, ...`, ending before `u64` overflows.
+///
+/// ```
+/// use fp::sequences::Fib;
+///
+/// assert_eq!(Fib::new().take(8).collect::<Vec<_>>(), [0, 1, 1, 2, 3, 5, 8, 13]);
+/// assert_eq!(Fib::new().count(), 94);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Fib {
+    current: Option<u64>,
+    next: Option<u64>,
+}
+
+impl Fib {
+    /// Starts at 0.
+    ///
+    /// ```
+    /// use fp::sequences::Fib;
+    ///
+    /// let mut fib = Fib::new();
+    /// assert_eq!((fib.next(), fib.next(), fib.next()), (Some(0), Some(1), Some(1)));
+    /// ```
+    pub fn new() -> Self {
+        Fib {
+            current: Some(0),
+            next: Some(1),
+        }
+    }
+}
+
+impl Default for Fib {
+    fn default() -> Self {
+        Fib::new()
+    }
+}
+
+impl Iterator for Fib {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        let current = self.current?;
+        let next = self.next;
+        self.current = next;
+        self.next = next.and_then(|n| n.checked_add(current));
+        Some(current)
+    }
+}
+
+/// The prime numbers, by an incremental sieve of Eratosthenes.
+///
+/// Instead of sieving a fixed range, each found prime `p` is scheduled to
+/// cross off `p * p`; when a scheduled number comes up, its primes move on
+/// to their next multiples. Memory grows with the number of primes found,
+/// not with the range searched.
+///
+/// ```
+/// use fp::sequences::Primes;
+///
+/// assert_eq!(Primes::new().take(10).collect::<Vec<_>>(), [2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+/// assert_eq!(Primes::new().nth(999), Some(7919));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Primes {
+    candidate: u64,
+    composites: HashMap<u64, Vec<u64>>,
+}
+
+impl Primes {
+    /// Starts at 2.
+    ///
+    /// ```
+    /// use fp::sequences::Primes;
+    ///
+    /// let mut primes = Primes::new();
+    /// assert_eq!((primes.next(), primes.next(), primes.next()), (Some(2), Some(3), Some(5)));
+    /// ```
+    pub fn new() -> Self {
+        Primes {
+            candidate: 2,
+            composites: HashMap::new(),
+        }
+    }
+}
+
+impl Default for Primes {
+    fn default() -> Self {
+        Primes::new()
+    }
+}
+
+impl Iterator for Primes {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        loop {
+            let n = self.candidate;
+            self.candidate = n.checked_add(1)?;
+            match self.composites.remove(&n) {
+                Some(factors) => {
+                    for p in factors {
+                        if let Some(next) = n.checked_add(p) {
+                            self.composites.entry(next).or_default().push(p);
+                        }
+                    }
+                }
+                None => {
+                    if let Some(square) = n.checked_mul(n) {
+                        self.composites.insert(square, vec![n]);
+                    }
+                    return Some(n);
+                }
+            }
+        }
+    }
+}
+
+/// The dual of a fold: builds a sequence from a seed.
+///
+/// `f` gets the current state and returns the next item with the next
+/// state, or `None` to stop.
+///
+/// ```
+/// use fp::sequences::unfold;
+///
+/// let powers: Vec<u32> = unfold(1, |n| (n <= 100).then(|| (n, n * 2))).collect();
+/// assert_eq!(powers, [1, 2, 4, 8, 16, 32, 64]);
+///
+/// let digits: Vec<u32> = unfold(2025, |n| (n > 0).then(|| (n % 10, n / 10))).collect();
+/// assert_eq!(digits, [5, 2, 0, 2]);
+/// ```
+pub fn unfold<S, A, F: FnMut(S) -> Option<(A, S)>>(seed: S, f: F) -> Unfold<S, F> {
+    Unfold {
+        state: Some(seed),
+        f,
+    }
+}
+
+/// Iterator returned by [`unfold`].
+#[derive(Debug, Clone)]
+pub struct Unfold<S, F> {
+    state: Option<S>,
+    f: F,
+}
+
+impl<S, A, F: FnMut(S) -> Option<(A, S)>> Iterator for Unfold<S, F> {
+    type Item = A;
+
+    fn next(&mut self) -> Option<A> {
+        let (item, state) = (self.f)(self.state.take()?)?;
+        self.state = Some(state);
+        Some(item)
+    }
+}
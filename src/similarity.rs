@@ -0,0 +1,242 @@
+//! Document Similarity
+//!
+//! Two documents are near-duplicates when they share most of their
+//! shingles, the runs of [`SHINGLE_WORDS`] consecutive words: their Jaccard
+//! similarity, shared shingles over all shingles, is close to 1. Comparing
+//! shingle sets directly is slow for many long documents, so each one is
+//! reduced to a [`MinHash`] signature of [`SIGNATURE_LEN`] numbers whose
+//! agreement estimates the Jaccard similarity (Broder, 1997). Documents
+//! whose estimate reaches a threshold are linked, and the linked groups are
+//! the clusters of near-duplicates.
+//!
+//! For how alike documents are overall rather than whether one is a copy,
+//! [`cosine`] compares their word frequencies as vectors, and
+//! [`similarity_matrix`] does so for every pair.
+//!
+//! ```
+//! use fp::similarity::{MinHash, NEAR_DUPLICATE, near_duplicates, shingles};
+//!
+//! let documents = [
+//!     "the quick brown fox jumps over the lazy dog by the river bank today",
+//!     "fold and map and filter are the bread and butter of functional code",
+//!     "the quick brown fox jumps over the lazy dog by the river bank tonight",
+//! ];
+//! let signatures: Vec<MinHash> = documents
+//!     .iter()
+//!     .map(|doc| MinHash::new(&shingles(&doc.split(' ').collect::<Vec<_>>(), 3)))
+//!     .collect();
+//! assert_eq!(near_duplicates(&signatures, NEAR_DUPLICATE), [vec![0, 2]]);
+//! ```
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+use crate::random::Rng;
+
+/// Words per shingle
+pub const SHINGLE_WORDS: usize = 3;
+
+/// Numbers per [`MinHash`] signature; the estimate's standard error is
+/// about `1 / sqrt(SIGNATURE_LEN)`
+pub const SIGNATURE_LEN: usize = 128;
+
+/// The estimated similarity from which documents count as near-duplicates
+pub const NEAR_DUPLICATE: f64 = 0.8;
+
+/// Seeds the hash functions, so that signatures are comparable across runs
+const SEED: u64 = 0x5eed;
+
+/// The hashes of every run of `k` consecutive `words`; a document shorter
+/// than `k` words is one shingle, and an empty one has none
+///
+/// ```
+/// use fp::similarity::shingles;
+///
+/// assert_eq!(shingles(&["a", "b", "c", "a", "b", "c"], 3).len(), 3);
+/// assert_eq!(shingles(&["a", "b"], 3).len(), 1);
+/// assert!(shingles::<&str>(&[], 3).is_empty());
+/// ```
+pub fn shingles<S: AsRef<str>>(words: &[S], k: usize) -> HashSet<u64> {
+    let hash = |run: &[S]| {
+        let mut hasher = DefaultHasher::new();
+        run.iter().for_each(|word| word.as_ref().hash(&mut hasher));
+        hasher.finish()
+    };
+    match words.len() {
+        0 => HashSet::new(),
+        n if n < k => HashSet::from([hash(words)]),
+        _ => words.windows(k.max(1)).map(hash).collect(),
+    }
+}
+
+/// Shared shingles over all shingles; two empty documents are identical
+///
+/// ```
+/// use fp::similarity::jaccard;
+/// use std::collections::HashSet;
+///
+/// let a = HashSet::from([1, 2, 3]);
+/// let b = HashSet::from([2, 3, 4]);
+/// assert_eq!(jaccard(&a, &b), 0.5);
+/// ```
+pub fn jaccard(a: &HashSet<u64>, b: &HashSet<u64>) -> f64 {
+    let union = a.union(b).count();
+    match union {
+        0 => 1.0,
+        n => a.intersection(b).count() as f64 / n as f64,
+    }
+}
+
+/// A MinHash signature: for each of [`SIGNATURE_LEN`] hash functions, the
+/// least hash of any shingle
+///
+/// Two sets give the same least hash with a probability equal to their
+/// Jaccard similarity, so the fraction of agreeing positions estimates it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinHash {
+    mins: Vec<u64>,
+}
+
+impl MinHash {
+    /// The signature of a document's `shingles`
+    ///
+    /// ```
+    /// use fp::similarity::{MinHash, shingles};
+    ///
+    /// let a = MinHash::new(&shingles(&["fold", "map", "filter", "scan"], 2));
+    /// let b = MinHash::new(&shingles(&["fold", "map", "filter", "scan"], 2));
+    /// // The same shingles always give the same signature
+    /// assert_eq!(a, b);
+    /// ```
+    pub fn new(shingles: &HashSet<u64>) -> Self {
+        // Each function is a random odd multiplier plus an offset, a
+        // permutation of u64, drawn from the same seed every time
+        let mins = hash_functions()
+            .map(|(a, b)| {
+                shingles
+                    .iter()
+                    .map(|&shingle| shingle.wrapping_mul(a).wrapping_add(b))
+                    .min()
+                    .unwrap_or(u64::MAX)
+            })
+            .collect();
+        MinHash { mins }
+    }
+
+    /// The estimated Jaccard similarity of the documents behind two
+    /// signatures
+    ///
+    /// ```
+    /// use fp::similarity::{MinHash, jaccard};
+    /// use std::collections::HashSet;
+    ///
+    /// let a: HashSet<u64> = (0..100).collect();
+    /// let b: HashSet<u64> = (20..120).collect();
+    /// let estimate = MinHash::new(&a).similarity(&MinHash::new(&b));
+    /// assert!((estimate - jaccard(&a, &b)).abs() < 0.15);
+    /// assert_eq!(MinHash::new(&a).similarity(&MinHash::new(&a)), 1.0);
+    /// ```
+    pub fn similarity(&self, other: &MinHash) -> f64 {
+        let agreeing = self
+            .mins
+            .iter()
+            .zip(&other.mins)
+            .filter(|(a, b)| a == b)
+            .count();
+        agreeing as f64 / self.mins.len() as f64
+    }
+}
+
+/// The [`SIGNATURE_LEN`] multiplier and offset pairs of the hash functions
+fn hash_functions() -> impl Iterator<Item = (u64, u64)> {
+    let mut numbers = Rng::new(SEED).iter();
+    std::iter::from_fn(move || Some((numbers.next()? | 1, numbers.next()?))).take(SIGNATURE_LEN)
+}
+
+/// The clusters of documents linked by an estimated similarity of at least
+/// `threshold`, as indices into `signatures`: each cluster in ascending
+/// order, clusters ordered by their first document, singletons left out
+///
+/// Linking is transitive, so a chain of revisions forms one cluster even
+/// if its first and last versions differ more than the threshold allows.
+///
+/// ```
+/// use fp::similarity::{MinHash, near_duplicates, shingles};
+///
+/// let text = |s: &str| MinHash::new(&shingles(&s.split(' ').collect::<Vec<_>>(), 2));
+/// let signatures = [
+///     text("the quick brown fox jumps over the lazy dog"),
+///     text("a completely different sentence about folds and maps"),
+///     text("the quick brown fox jumps over the lazy dog"),
+/// ];
+/// assert_eq!(near_duplicates(&signatures, 0.9), [vec![0, 2]]);
+/// ```
+pub fn near_duplicates(signatures: &[MinHash], threshold: f64) -> Vec<Vec<usize>> {
+    // Union-find: every document points toward its cluster's root
+    let mut parent: Vec<usize> = (0..signatures.len()).collect();
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+    for i in 0..signatures.len() {
+        for j in i + 1..signatures.len() {
+            if signatures[i].similarity(&signatures[j]) >= threshold {
+                let (a, b) = (root(&mut parent, i), root(&mut parent, j));
+                parent[a.max(b)] = a.min(b);
+            }
+        }
+    }
+    let mut clusters: Vec<Vec<usize>> = vec![Vec::new(); signatures.len()];
+    for i in 0..signatures.len() {
+        let r = root(&mut parent, i);
+        clusters[r].push(i);
+    }
+    clusters.retain(|cluster| cluster.len() > 1);
+    clusters
+}
+
+/// The cosine similarity of two term frequency vectors: 1 when the words
+/// occur in the same proportions, 0 when none are shared or either is empty
+///
+/// ```
+/// use fp::analysis::word_counts;
+/// use fp::similarity::cosine;
+///
+/// let a = word_counts("fold map fold");
+/// assert!((cosine(&a, &word_counts("map fold fold fold map fold")) - 1.0).abs() < 1e-9);
+/// assert_eq!(cosine(&a, &word_counts("filter")), 0.0);
+/// assert!((cosine(&word_counts("fold map"), &word_counts("fold filter")) - 0.5).abs() < 1e-9);
+/// ```
+pub fn cosine(a: &HashMap<String, usize>, b: &HashMap<String, usize>) -> f64 {
+    let dot: f64 = a
+        .iter()
+        .filter_map(|(word, &x)| b.get(word).map(|&y| x as f64 * y as f64))
+        .sum();
+    let norm =
+        |v: &HashMap<String, usize>| v.values().map(|&x| (x as f64).powi(2)).sum::<f64>().sqrt();
+    let norms = norm(a) * norm(b);
+    if norms == 0.0 { 0.0 } else { dot / norms }
+}
+
+/// The [`cosine`] similarity of every pair of `documents`: row `i`, column
+/// `j` compares document `i` with document `j`
+///
+/// ```
+/// use fp::analysis::word_counts;
+/// use fp::similarity::similarity_matrix;
+///
+/// let documents = [word_counts("fold map"), word_counts("fold filter")];
+/// let matrix = similarity_matrix(&documents);
+/// assert!((matrix[0][1] - 0.5).abs() < 1e-9);
+/// assert_eq!(matrix[0][1], matrix[1][0]);
+/// ```
+pub fn similarity_matrix(documents: &[HashMap<String, usize>]) -> Vec<Vec<f64>> {
+    documents
+        .iter()
+        .map(|a| documents.iter().map(|b| cosine(a, b)).collect())
+        .collect()
+}
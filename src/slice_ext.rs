@@ -0,0 +1,79 @@
+//! Functional helpers on slices.
+//!
+//! [`SliceFnExt`] is an extension trait: it adds methods to the built-in
+//! slice type `[T]`, so they are available on arrays, `Vec`s and slices
+//! alike once the trait is imported. Every method borrows the slice and
+//! leaves it untouched.
+
+/// Mapping, folding and splitting for slices.
+pub trait SliceFnExt<T> {
+    /// Applies `f` to every element, collecting the results.
+    ///
+    /// ```
+    /// use fp::slice_ext::SliceFnExt;
+    ///
+    /// assert_eq!([1, 2, 3].fmap(|x| x * x), [1, 4, 9]);
+    /// ```
+    fn fmap<U, F: FnMut(&T) -> U>(&self, f: F) -> Vec<U>;
+
+    /// Keeps the `Some` results of `f`.
+    ///
+    /// ```
+    /// use fp::slice_ext::SliceFnExt;
+    ///
+    /// let numbers = ["1", "two", "3"].filter_map_collect(|s| s.parse::<i32>().ok());
+    /// assert_eq!(numbers, [1, 3]);
+    /// ```
+    fn filter_map_collect<U, F: FnMut(&T) -> Option<U>>(&self, f: F) -> Vec<U>;
+
+    /// Combines the elements from left to right, starting from `init`.
+    ///
+    /// ```
+    /// use fp::slice_ext::SliceFnExt;
+    ///
+    /// let digits = [1, 2, 3].fold_left(0, |acc, d| acc * 10 + d);
+    /// assert_eq!(digits, 123);
+    /// ```
+    fn fold_left<B, F: FnMut(B, &T) -> B>(&self, init: B, f: F) -> B;
+
+    /// Combines elements pairwise with `other`, stopping at the shorter one.
+    ///
+    /// ```
+    /// use fp::slice_ext::SliceFnExt;
+    ///
+    /// assert_eq!([1, 2, 3].zip_with(&[10, 20], |a, b| a + b), [11, 22]);
+    /// ```
+    fn zip_with<U, V, F: FnMut(&T, &U) -> V>(&self, other: &[U], f: F) -> Vec<V>;
+
+    /// Splits the elements into those matching `pred` and the rest.
+    ///
+    /// ```
+    /// use fp::slice_ext::SliceFnExt;
+    ///
+    /// let (even, odd) = [1, 2, 3, 4].partition_by(|x| x % 2 == 0);
+    /// assert_eq!((even, odd), (vec![&2, &4], vec![&1, &3]));
+    /// ```
+    fn partition_by<F: FnMut(&T) -> bool>(&self, pred: F) -> (Vec<&T>, Vec<&T>);
+}
+
+impl<T> SliceFnExt<T> for [T] {
+    fn fmap<U, F: FnMut(&T) -> U>(&self, f: F) -> Vec<U> {
+        self.iter().map(f).collect()
+    }
+
+    fn filter_map_collect<U, F: FnMut(&T) -> Option<U>>(&self, f: F) -> Vec<U> {
+        self.iter().filter_map(f).collect()
+    }
+
+    fn fold_left<B, F: FnMut(B, &T) -> B>(&self, init: B, f: F) -> B {
+        self.iter().fold(init, f)
+    }
+
+    fn zip_with<U, V, F: FnMut(&T, &U) -> V>(&self, other: &[U], mut f: F) -> Vec<V> {
+        self.iter().zip(other).map(|(a, b)| f(a, b)).collect()
+    }
+
+    fn partition_by<F: FnMut(&T) -> bool>(&self, mut pred: F) -> (Vec<&T>, Vec<&T>) {
+        self.iter().partition(|x| pred(x))
+    }
+}
@@ -0,0 +1,65 @@
+//! Spelling Suggestions
+//!
+//! A spelling corrector in the manner of Norvig (2007): the likeliest
+//! intended word is the known word fewest edits away from what was typed,
+//! and among equally near ones the most frequent in the corpus. A word the
+//! corpus knows is its own best suggestion. Rather than generating every
+//! edit of the word and looking each up, the candidates come from a
+//! [`BkTree`] of the vocabulary, which finds them by Levenshtein distance.
+//!
+//! ```
+//! use fp::fuzzy::BkTree;
+//! use fp::spelling::suggest;
+//!
+//! let tree: BkTree<usize> = [("fold", 5), ("food", 9), ("folds", 1), ("map", 4)].into_iter().collect();
+//! let words: Vec<String> = suggest(&tree, "fokd", 2).into_iter().map(|s| s.word).collect();
+//! // "fold" and "food" are both one edit away; "food" is more frequent
+//! assert_eq!(words, ["food", "fold", "folds"]);
+//! ```
+
+use crate::fuzzy::BkTree;
+
+/// How many suggestions `suggest` prints
+pub const SUGGESTIONS: usize = 5;
+
+/// A known word that `word` may have been meant as
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    /// The known word
+    pub word: String,
+    /// Edits from the word typed; 0 if it is known
+    pub distance: usize,
+    /// How often the corpus has the word
+    pub count: usize,
+}
+
+/// The known words within `max_dist` edits of `word`, fewest edits first,
+/// then most frequent, then alphabetical
+///
+/// ```
+/// use fp::fuzzy::BkTree;
+/// use fp::spelling::suggest;
+///
+/// let tree: BkTree<usize> = [("fold", 5), ("food", 9)].into_iter().collect();
+/// // A known word comes first, however rare
+/// assert_eq!(suggest(&tree, "fold", 1)[0].distance, 0);
+/// assert!(suggest(&tree, "zzzz", 1).is_empty());
+/// ```
+pub fn suggest(tree: &BkTree<usize>, word: &str, max_dist: usize) -> Vec<Suggestion> {
+    let mut suggestions: Vec<Suggestion> = tree
+        .find(word, max_dist)
+        .into_iter()
+        .map(|(word, distance, &count)| Suggestion {
+            word: word.to_string(),
+            distance,
+            count,
+        })
+        .collect();
+    suggestions.sort_by(|a, b| {
+        a.distance
+            .cmp(&b.distance)
+            .then_with(|| b.count.cmp(&a.count))
+            .then_with(|| a.word.cmp(&b.word))
+    });
+    suggestions
+}
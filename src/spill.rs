@@ -0,0 +1,272 @@
+//! Disk-Backed Counting
+//!
+//! For corpora whose vocabulary does not fit in memory, a [`Spill`] keeps
+//! at most a fixed number of distinct words in its `HashMap`. When the map
+//! grows past that, it is written to a temporary file as a run sorted by
+//! word and cleared. [`Spill::finish`] then merges the runs in one pass (an
+//! external k-way merge), summing the counts of equal words. The merged
+//! counts come out in alphabetical order and are never held in memory all at
+//! once.
+//!
+//! ```
+//! use fp::analysis::word_counts;
+//! use fp::spill::Spill;
+//!
+//! let dir = std::env::temp_dir();
+//! let mut spill = Spill::new(&dir, 2).unwrap();
+//! spill.add(word_counts("the cat")).unwrap();
+//! spill.add(word_counts("the hat")).unwrap();
+//! spill.add(word_counts("a cat")).unwrap();
+//! assert_eq!(spill.runs(), 1); // "the cat" and "the hat" made three words
+//!
+//! let merged: Vec<(String, usize)> = spill.finish().unwrap().map(Result::unwrap).collect();
+//! assert_eq!(
+//!     merged,
+//!     [("a", 1), ("cat", 2), ("hat", 1), ("the", 2)].map(|(w, n)| (w.to_string(), n))
+//! );
+//! ```
+
+use crate::monoid::Semigroup;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Lines, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// How many distinct words are kept in memory before a run is written,
+/// unless told otherwise
+pub const DEFAULT_RUN_ENTRIES: usize = 100_000;
+
+/// Distinguishes the spill directories of one process
+static NEXT_SPILL: AtomicUsize = AtomicUsize::new(0);
+
+/// Counts that spill to sorted run files once they exceed `run_entries`
+/// distinct words
+pub struct Spill {
+    dir: PathBuf,
+    run_entries: usize,
+    counts: HashMap<String, usize>,
+    runs: Vec<PathBuf>,
+}
+
+impl Spill {
+    /// Spill into a fresh directory inside `parent`, removed when the
+    /// merged counts are dropped.
+    ///
+    /// ```
+    /// use fp::spill::Spill;
+    ///
+    /// let spill = Spill::new(&std::env::temp_dir(), 1000).unwrap();
+    /// assert_eq!(spill.runs(), 0);
+    /// assert_eq!(spill.finish().unwrap().count(), 0);
+    /// ```
+    pub fn new(parent: &Path, run_entries: usize) -> io::Result<Self> {
+        let dir = parent.join(format!(
+            "fp-spill-{}-{}",
+            process::id(),
+            NEXT_SPILL.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&dir)?;
+        Ok(Spill {
+            dir,
+            run_entries: run_entries.max(1),
+            counts: HashMap::new(),
+            runs: Vec::new(),
+        })
+    }
+
+    /// Merge partial counts in, writing a run if the vocabulary outgrew
+    /// `run_entries`.
+    ///
+    /// Any word can be counted, even one with line breaks in it:
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use fp::spill::Spill;
+    ///
+    /// let words = ["two\nlines", "back\\slash", "cr\r", "tab\tbed"];
+    /// let mut spill = Spill::new(&std::env::temp_dir(), 1).unwrap();
+    /// for word in words {
+    ///     spill.add(HashMap::from([(word.to_string(), 2)])).unwrap();
+    /// }
+    /// let mut merged: Vec<String> = spill.finish().unwrap().map(|entry| entry.unwrap().0).collect();
+    /// merged.sort();
+    /// let mut expected = words.map(String::from);
+    /// expected.sort();
+    /// assert_eq!(merged, expected);
+    /// ```
+    pub fn add(&mut self, counts: HashMap<String, usize>) -> io::Result<()> {
+        self.counts = std::mem::take(&mut self.counts).combine(counts);
+        if self.counts.len() > self.run_entries {
+            self.write_run()?;
+        }
+        Ok(())
+    }
+
+    /// How many runs have been written so far
+    ///
+    /// ```
+    /// use fp::analysis::word_counts;
+    /// use fp::spill::Spill;
+    ///
+    /// let mut spill = Spill::new(&std::env::temp_dir(), 1).unwrap();
+    /// spill.add(word_counts("fold")).unwrap();
+    /// assert_eq!(spill.runs(), 0);
+    /// spill.add(word_counts("map")).unwrap();
+    /// assert_eq!(spill.runs(), 1);
+    /// ```
+    pub fn runs(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// Write what is left in memory and merge every run.
+    ///
+    /// ```
+    /// use fp::analysis::word_counts;
+    /// use fp::spill::Spill;
+    ///
+    /// let mut spill = Spill::new(&std::env::temp_dir(), 1).unwrap();
+    /// spill.add(word_counts("map fold")).unwrap();
+    /// spill.add(word_counts("map")).unwrap();
+    /// let merged: Vec<(String, usize)> = spill.finish().unwrap().map(Result::unwrap).collect();
+    /// assert_eq!(merged, [("fold".to_string(), 1), ("map".to_string(), 2)]);
+    /// ```
+    pub fn finish(mut self) -> io::Result<Merged> {
+        if !self.counts.is_empty() {
+            self.write_run()?;
+        }
+        let mut runs = Vec::with_capacity(self.runs.len());
+        let mut heap = BinaryHeap::new();
+        for (index, path) in self.runs.iter().enumerate() {
+            let mut lines = BufReader::new(File::open(path)?).lines();
+            if let Some(entry) = next_entry(&mut lines)? {
+                heap.push(Reverse((entry, index)));
+            }
+            runs.push(lines);
+        }
+        Ok(Merged {
+            dir: std::mem::take(&mut self.dir),
+            runs,
+            heap,
+        })
+    }
+
+    /// Write the in-memory counts as a run sorted by word: `word\tcount`
+    /// lines, with the word [escaped](escape) to keep to its line
+    fn write_run(&mut self) -> io::Result<()> {
+        let path = self.dir.join(format!("run-{}.tsv", self.runs.len()));
+        let mut sorted: Vec<(String, usize)> =
+            std::mem::take(&mut self.counts).into_iter().collect();
+        sorted.sort_unstable();
+        let mut out = BufWriter::new(File::create(&path)?);
+        for (word, count) in sorted {
+            writeln!(out, "{}\t{}", escape(&word), count)?;
+        }
+        out.flush()?;
+        self.runs.push(path);
+        Ok(())
+    }
+}
+
+/// A spill dropped before [`Spill::finish`] removes its runs
+impl Drop for Spill {
+    fn drop(&mut self) {
+        if !self.dir.as_os_str().is_empty() {
+            let _ = fs::remove_dir_all(&self.dir);
+        }
+    }
+}
+
+/// The merged counts of every run, in alphabetical order
+pub struct Merged {
+    dir: PathBuf,
+    runs: Vec<Lines<BufReader<File>>>,
+    heap: BinaryHeap<Reverse<((String, usize), usize)>>,
+}
+
+impl Merged {
+    /// Take the smallest entry and refill the heap from its run
+    fn pop(&mut self) -> io::Result<Option<(String, usize)>> {
+        let Some(Reverse((entry, index))) = self.heap.pop() else {
+            return Ok(None);
+        };
+        if let Some(next) = next_entry(&mut self.runs[index])? {
+            self.heap.push(Reverse((next, index)));
+        }
+        Ok(Some(entry))
+    }
+}
+
+impl Iterator for Merged {
+    type Item = io::Result<(String, usize)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (word, mut count) = match self.pop() {
+            Ok(entry) => entry?,
+            Err(err) => return Some(Err(err)),
+        };
+        // Equal words from different runs are adjacent in the heap order
+        while self
+            .heap
+            .peek()
+            .is_some_and(|Reverse(((next, _), _))| *next == word)
+        {
+            match self.pop() {
+                Ok(Some((_, more))) => count += more,
+                Ok(None) => break,
+                Err(err) => return Some(Err(err)),
+            }
+        }
+        Some(Ok((word, count)))
+    }
+}
+
+impl Drop for Merged {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// `word` with backslashes, line feeds and carriage returns written as
+/// `\\`, `\n` and `\r`, so that a word cannot break or end its line of a run
+fn escape(word: &str) -> String {
+    word.replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+/// The word [`escape`] wrote, or none if it is not escaped that way
+fn unescape(escaped: &str) -> Option<String> {
+    let mut word = String::with_capacity(escaped.len());
+    let mut chars = escaped.chars();
+    while let Some(c) = chars.next() {
+        word.push(match c {
+            '\\' => match chars.next()? {
+                '\\' => '\\',
+                'n' => '\n',
+                'r' => '\r',
+                _ => return None,
+            },
+            c => c,
+        });
+    }
+    Some(word)
+}
+
+/// Parse the next `word\tcount` line of a run
+fn next_entry(lines: &mut Lines<BufReader<File>>) -> io::Result<Option<(String, usize)>> {
+    let Some(line) = lines.next().transpose()? else {
+        return Ok(None);
+    };
+    line.rsplit_once('\t')
+        .and_then(|(word, count)| Some((unescape(word)?, count.parse().ok()?)))
+        .map(Some)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("bad spill line '{}'", line),
+            )
+        })
+}
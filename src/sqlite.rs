@@ -0,0 +1,194 @@
+//! SQLite Output
+//!
+//! The words of analyzed documents stored in a small relational schema, so
+//! that many runs accumulate in one database and can be queried with SQL:
+//!
+//! - `documents`: one row per analyzed document, with its path, when it was
+//!   analyzed, and how many words it had
+//! - `words`: every distinct word any document had, once
+//! - `counts`: how often each document has each of its words
+//! - `positions`: where in each document each word occurs, counting words
+//!   from 0
+//!
+//! Analyzing the same file again adds a new document rather than replacing
+//! the old one, so the database keeps the history of a file as it changes.
+//!
+//! ```
+//! use fp::sqlite::Database;
+//!
+//! let mut db = Database::open_in_memory().unwrap();
+//! db.add_document("a.txt", &["fold", "map", "fold"]).unwrap();
+//! db.add_document("b.txt", &["fold", "filter"]).unwrap();
+//! let total: usize = db
+//!     .connection()
+//!     .query_row(
+//!         "SELECT SUM(count) FROM counts JOIN words ON words.id = word_id WHERE word = 'fold'",
+//!         [],
+//!         |row| row.get(0),
+//!     )
+//!     .unwrap();
+//! assert_eq!(total, 3);
+//! ```
+
+use rusqlite::{Connection, Result, params};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// The tables, created if the database does not have them yet
+pub const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS documents (
+    id INTEGER PRIMARY KEY,
+    path TEXT NOT NULL,
+    analyzed_at TEXT NOT NULL DEFAULT (datetime('now')),
+    total_words INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS words (
+    id INTEGER PRIMARY KEY,
+    word TEXT NOT NULL UNIQUE
+);
+CREATE TABLE IF NOT EXISTS counts (
+    document_id INTEGER NOT NULL REFERENCES documents(id),
+    word_id INTEGER NOT NULL REFERENCES words(id),
+    count INTEGER NOT NULL,
+    PRIMARY KEY (document_id, word_id)
+);
+CREATE TABLE IF NOT EXISTS positions (
+    document_id INTEGER NOT NULL REFERENCES documents(id),
+    word_id INTEGER NOT NULL REFERENCES words(id),
+    position INTEGER NOT NULL,
+    PRIMARY KEY (document_id, position)
+);
+";
+
+/// A database of analyzed documents
+pub struct Database {
+    connection: Connection,
+}
+
+impl Database {
+    /// The database at `path`, created along with the schema if it does not
+    /// exist yet
+    ///
+    /// ```
+    /// use fp::sqlite::Database;
+    ///
+    /// let path = std::env::temp_dir().join("fp-doctest-open.sqlite");
+    /// # let _ = std::fs::remove_file(&path);
+    /// Database::open(&path).unwrap().add_document("a.txt", &["fold"]).unwrap();
+    /// // Reopened, the database still has the document
+    /// let db = Database::open(&path).unwrap();
+    /// let documents: i64 = db
+    ///     .connection()
+    ///     .query_row("SELECT COUNT(*) FROM documents", [], |row| row.get(0))
+    ///     .unwrap();
+    /// assert_eq!(documents, 1);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::with_schema(Connection::open(path)?)
+    }
+
+    /// A database that lives only as long as the value
+    ///
+    /// ```
+    /// use fp::sqlite::Database;
+    ///
+    /// let db = Database::open_in_memory().unwrap();
+    /// let words: i64 = db
+    ///     .connection()
+    ///     .query_row("SELECT COUNT(*) FROM words", [], |row| row.get(0))
+    ///     .unwrap();
+    /// assert_eq!(words, 0);
+    /// ```
+    pub fn open_in_memory() -> Result<Self> {
+        Self::with_schema(Connection::open_in_memory()?)
+    }
+
+    fn with_schema(connection: Connection) -> Result<Self> {
+        connection.execute_batch(SCHEMA)?;
+        Ok(Database { connection })
+    }
+
+    /// Store a document with its words in order, all or nothing, and return
+    /// its id
+    ///
+    /// ```
+    /// use fp::sqlite::Database;
+    ///
+    /// let mut db = Database::open_in_memory().unwrap();
+    /// let id = db.add_document("a.txt", &["fold", "map", "fold"]).unwrap();
+    /// let positions: Vec<usize> = db
+    ///     .connection()
+    ///     .prepare(
+    ///         "SELECT position FROM positions JOIN words ON words.id = word_id
+    ///          WHERE document_id = ?1 AND word = 'fold' ORDER BY position",
+    ///     )
+    ///     .unwrap()
+    ///     .query_map([id], |row| row.get(0))
+    ///     .unwrap()
+    ///     .collect::<Result<_, _>>()
+    ///     .unwrap();
+    /// assert_eq!(positions, [0, 2]);
+    /// ```
+    pub fn add_document<S: AsRef<str>>(&mut self, path: &str, words: &[S]) -> Result<i64> {
+        let tx = self.connection.transaction()?;
+        tx.execute(
+            "INSERT INTO documents (path, total_words) VALUES (?1, ?2)",
+            params![path, words.len()],
+        )?;
+        let document = tx.last_insert_rowid();
+        {
+            let mut insert_word =
+                tx.prepare_cached("INSERT OR IGNORE INTO words (word) VALUES (?1)")?;
+            let mut word_id = tx.prepare_cached("SELECT id FROM words WHERE word = ?1")?;
+            let mut insert_position = tx.prepare_cached(
+                "INSERT INTO positions (document_id, word_id, position) VALUES (?1, ?2, ?3)",
+            )?;
+            // The id and count of every word of the document
+            let mut ids: HashMap<&str, (i64, usize)> = HashMap::new();
+            for (position, word) in words.iter().enumerate() {
+                let word = word.as_ref();
+                let id = match ids.get_mut(word) {
+                    Some((id, count)) => {
+                        *count += 1;
+                        *id
+                    }
+                    None => {
+                        insert_word.execute([word])?;
+                        let id: i64 = word_id.query_row([word], |row| row.get(0))?;
+                        ids.insert(word, (id, 1));
+                        id
+                    }
+                };
+                insert_position.execute(params![document, id, position])?;
+            }
+            let mut insert_count = tx.prepare_cached(
+                "INSERT INTO counts (document_id, word_id, count) VALUES (?1, ?2, ?3)",
+            )?;
+            for (id, count) in ids.into_values() {
+                insert_count.execute(params![document, id, count])?;
+            }
+        }
+        tx.commit()?;
+        Ok(document)
+    }
+
+    /// The connection, to query the stored documents
+    ///
+    /// ```
+    /// use fp::sqlite::Database;
+    ///
+    /// let mut db = Database::open_in_memory().unwrap();
+    /// db.add_document("a.txt", &["fold", "map"]).unwrap();
+    /// let total: usize = db
+    ///     .connection()
+    ///     .query_row("SELECT total_words FROM documents WHERE path = 'a.txt'", [], |row| {
+    ///         row.get(0)
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(total, 2);
+    /// ```
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+}
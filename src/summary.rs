@@ -0,0 +1,82 @@
+//! Extractive Summaries
+//!
+//! Summarizes a text by picking its most representative sentences rather
+//! than writing new ones (Luhn, 1958). Sentences are found by the Unicode
+//! sentence boundary rules, each is scored by how frequent its words are in
+//! the whole text, stop words aside, and the best are kept in the order
+//! they appear, so the summary still reads in sequence.
+//!
+//! ```
+//! use fp::analysis::word_counts;
+//! use fp::locale::Locale;
+//! use fp::summary::{frequency_score, summarize};
+//!
+//! let text = "Fold combines a list. The weather was nice. Fold and map are everywhere in functional code. Lunch was late.";
+//! let counts = word_counts(text);
+//! let summary = summarize(text, 2, |sentence| frequency_score(sentence, &counts, Locale::English));
+//! assert_eq!(summary, ["Fold combines a list.", "Fold and map are everywhere in functional code."]);
+//! ```
+
+use std::collections::HashMap;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::analysis::clean_word_in;
+use crate::locale::Locale;
+
+/// The sentences of `text`, trimmed, by the Unicode sentence boundary rules
+///
+/// ```
+/// use fp::summary::sentences;
+///
+/// assert_eq!(sentences("Fold it. Map it!  Done?"), ["Fold it.", "Map it!", "Done?"]);
+/// ```
+pub fn sentences(text: &str) -> Vec<&str> {
+    text.unicode_sentences()
+        .map(str::trim)
+        .filter(|sentence| !sentence.is_empty())
+        .collect()
+}
+
+/// The `n` sentences of `text` that `score` rates highest, in the order
+/// they appear; of equally rated sentences, the earlier are kept
+///
+/// ```
+/// use fp::summary::summarize;
+///
+/// let text = "Folds are everywhere. It rained. Folds reduce a list.";
+/// let by_folds = |sentence: &str| sentence.matches("Folds").count() as f64;
+/// assert_eq!(summarize(text, 2, by_folds), ["Folds are everywhere.", "Folds reduce a list."]);
+/// ```
+pub fn summarize(text: &str, n: usize, score: impl Fn(&str) -> f64) -> Vec<&str> {
+    let sentences = sentences(text);
+    let mut ranked: Vec<(usize, f64)> = sentences
+        .iter()
+        .map(|sentence| score(sentence))
+        .enumerate()
+        .collect();
+    ranked.sort_by(|(i, x), (j, y)| y.total_cmp(x).then_with(|| i.cmp(j)));
+    let mut kept: Vec<usize> = ranked.into_iter().take(n).map(|(i, _)| i).collect();
+    kept.sort_unstable();
+    kept.into_iter().map(|i| sentences[i]).collect()
+}
+
+/// The total count in `counts` of the words of `sentence`, cleaned with
+/// the casing of `locale`, leaving out its stop words
+///
+/// ```
+/// use fp::analysis::word_counts;
+/// use fp::locale::Locale;
+/// use fp::summary::frequency_score;
+///
+/// let counts = word_counts("fold fold map the the the");
+/// assert_eq!(frequency_score("The fold, the map.", &counts, Locale::English), 3.0);
+/// ```
+pub fn frequency_score(sentence: &str, counts: &HashMap<String, usize>, locale: Locale) -> f64 {
+    sentence
+        .split_whitespace()
+        .map(|word| clean_word_in(word, locale))
+        .filter(|word| !locale.is_stopword(word))
+        .filter_map(|word| counts.get(word.as_ref()))
+        .sum::<usize>() as f64
+}
@@ -0,0 +1,259 @@
+//! Report Templates
+//!
+//! A small template language for rendering word counts exactly as a team
+//! wants them. `{{name}}` inserts a variable; `{{#top N}}...{{/top}}`
+//! repeats its body for the N most frequent words (ties alphabetical), and
+//! `{{#top}}` uses [`DEFAULT_TOP`]. Everywhere: `total` (words counted),
+//! `unique` (distinct words), and `reading_time` and `speaking_time` (as
+//! `3 min 20 s`). Inside `top`: `word`, `count` and `rank`
+//! (from 1). Templates are checked when parsed, so an unknown variable or
+//! an unclosed block is reported before any file is read. A block tag
+//! followed directly by a newline swallows it, so blocks can sit on lines
+//! of their own.
+//!
+//! ```
+//! use fp::analysis::word_counts;
+//! use fp::template::Template;
+//!
+//! let template = Template::parse("{{total}} words\n{{#top 2}}\n{{rank}}. {{word}} {{count}}\n{{/top}}").unwrap();
+//! let counts = word_counts("the cat and the hat and the end");
+//! assert_eq!(template.render(&counts), "8 words\n1. the 3\n2. and 2\n");
+//!
+//! assert_eq!(
+//!     Template::parse("{{word}}").unwrap_err(),
+//!     "{{word}} is only defined inside {{#top}}"
+//! );
+//! ```
+
+use crate::analysis::{DEFAULT_TOP, Speeds, format_duration};
+use std::collections::HashMap;
+
+/// A parsed template, ready to render any number of counts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    source: String,
+    nodes: Vec<Node>,
+}
+
+/// A piece of a template
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    Text(String),
+    Var(Var),
+    /// The body, once per most frequent word
+    Top(usize, Vec<Node>),
+}
+
+/// The variables a template can use
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Var {
+    Total,
+    Unique,
+    ReadingTime,
+    SpeakingTime,
+    Word,
+    Count,
+    Rank,
+}
+
+impl Template {
+    /// Parse and check `source`.
+    ///
+    /// ```
+    /// use fp::template::Template;
+    ///
+    /// assert!(Template::parse("{{#top 3}}{{word}}\n{{/top}}").is_ok());
+    /// assert_eq!(Template::parse("{{/top}}").unwrap_err(), "{{/top}} without an opening {{#top}}");
+    /// ```
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut rest = source;
+        let (nodes, closed) = parse_nodes(&mut rest, false)?;
+        match closed {
+            true => Err("{{/top}} without an opening {{#top}}".to_string()),
+            false => Ok(Template {
+                source: source.to_string(),
+                nodes,
+            }),
+        }
+    }
+
+    /// The text the template was parsed from
+    ///
+    /// ```
+    /// use fp::template::Template;
+    ///
+    /// assert_eq!(Template::parse("{{total}} words").unwrap().source(), "{{total}} words");
+    /// ```
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Fill the template in with `counts`, estimating times at the default
+    /// [`Speeds`]
+    ///
+    /// ```
+    /// use fp::analysis::word_counts;
+    /// use fp::template::Template;
+    ///
+    /// let template = Template::parse("{{unique}} of {{total}}: {{#top 1}}{{word}}{{/top}}").unwrap();
+    /// assert_eq!(template.render(&word_counts("fold map fold")), "2 of 3: fold");
+    /// ```
+    pub fn render(&self, counts: &HashMap<String, usize>) -> String {
+        self.render_with(counts, Speeds::default())
+    }
+
+    /// Fill the template in with `counts`, estimating times at `speeds`
+    ///
+    /// ```
+    /// use fp::analysis::{Speeds, word_counts};
+    /// use fp::template::Template;
+    ///
+    /// let template = Template::parse("{{reading_time}} to read, {{speaking_time}} to say").unwrap();
+    /// let speeds = Speeds { reading: 2, speaking: 1 };
+    /// assert_eq!(template.render_with(&word_counts("fold map"), speeds), "1 min to read, 2 min to say");
+    /// ```
+    pub fn render_with(&self, counts: &HashMap<String, usize>, speeds: Speeds) -> String {
+        let mut ranked: Vec<(&String, usize)> =
+            counts.iter().map(|(word, &count)| (word, count)).collect();
+        ranked.sort_by(|(a, x), (b, y)| y.cmp(x).then_with(|| a.cmp(b)));
+        let total = counts.values().sum();
+        let totals = Totals {
+            total,
+            unique: counts.len(),
+            reading_time: format_duration(speeds.reading_time(total)),
+            speaking_time: format_duration(speeds.speaking_time(total)),
+            ranked,
+        };
+        let mut out = String::new();
+        render_nodes(&self.nodes, &totals, None, &mut out);
+        out
+    }
+}
+
+/// Parse nodes until the end of the input or, inside a block, its
+/// `{{/top}}`. Returns whether a closing tag ended the nodes.
+fn parse_nodes(rest: &mut &str, in_top: bool) -> Result<(Vec<Node>, bool), String> {
+    let mut nodes = Vec::new();
+    while !rest.is_empty() {
+        let Some(open) = rest.find("{{") else {
+            nodes.push(Node::Text(rest.to_string()));
+            *rest = "";
+            break;
+        };
+        if open > 0 {
+            nodes.push(Node::Text(rest[..open].to_string()));
+        }
+        let close = rest[open..]
+            .find("}}")
+            .ok_or_else(|| "Unclosed {{ in template".to_string())?;
+        let tag = rest[open + 2..open + close].trim();
+        *rest = &rest[open + close + 2..];
+        if let Some(block) = tag.strip_prefix('#') {
+            if in_top {
+                return Err("{{#top}} blocks cannot be nested".to_string());
+            }
+            let n = parse_top(block)?;
+            skip_newline(rest);
+            let (body, closed) = parse_nodes(rest, true)?;
+            if !closed {
+                return Err("{{#top}} is never closed with {{/top}}".to_string());
+            }
+            nodes.push(Node::Top(n, body));
+        } else if let Some(name) = tag.strip_prefix('/') {
+            if name.trim() != "top" {
+                return Err(format!("Unknown closing tag {{{{/{}}}}}", name.trim()));
+            }
+            skip_newline(rest);
+            return Ok((nodes, true));
+        } else {
+            nodes.push(Node::Var(parse_var(tag, in_top)?));
+        }
+    }
+    Ok((nodes, false))
+}
+
+/// The count of a `#top N` tag, [`DEFAULT_TOP`] without one
+fn parse_top(block: &str) -> Result<usize, String> {
+    let mut words = block.split_whitespace();
+    match (words.next(), words.next(), words.next()) {
+        (Some("top"), None, None) => Ok(DEFAULT_TOP),
+        (Some("top"), Some(n), None) => n
+            .parse()
+            .map_err(|_| format!("Invalid count '{}' in {{{{#top}}}}", n)),
+        _ => Err(format!(
+            "Unknown block {{{{#{}}}}}, expected top",
+            block.trim()
+        )),
+    }
+}
+
+/// Resolve a variable name, checking that it is defined where it is used
+fn parse_var(name: &str, in_top: bool) -> Result<Var, String> {
+    let var = match name {
+        "total" => Var::Total,
+        "unique" => Var::Unique,
+        "reading_time" => Var::ReadingTime,
+        "speaking_time" => Var::SpeakingTime,
+        "word" => Var::Word,
+        "count" => Var::Count,
+        "rank" => Var::Rank,
+        _ => return Err(format!("Unknown template variable {{{{{}}}}}", name)),
+    };
+    match var {
+        Var::Word | Var::Count | Var::Rank if !in_top => Err(format!(
+            "{{{{{}}}}} is only defined inside {{{{#top}}}}",
+            name
+        )),
+        _ => Ok(var),
+    }
+}
+
+/// Drop the newline right after a block tag
+fn skip_newline(rest: &mut &str) {
+    if let Some(after) = rest
+        .strip_prefix("\r\n")
+        .or_else(|| rest.strip_prefix('\n'))
+    {
+        *rest = after;
+    }
+}
+
+/// What the variables are rendered from
+struct Totals<'a> {
+    total: usize,
+    unique: usize,
+    reading_time: String,
+    speaking_time: String,
+    /// Most frequent first, ties alphabetical
+    ranked: Vec<(&'a String, usize)>,
+}
+
+/// Render `nodes`; `entry` is the rank and word of the current `top` row
+fn render_nodes(
+    nodes: &[Node],
+    totals: &Totals,
+    entry: Option<(usize, &str, usize)>,
+    out: &mut String,
+) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Var(var) => match (var, entry) {
+                (Var::Total, _) => out.push_str(&totals.total.to_string()),
+                (Var::Unique, _) => out.push_str(&totals.unique.to_string()),
+                (Var::ReadingTime, _) => out.push_str(&totals.reading_time),
+                (Var::SpeakingTime, _) => out.push_str(&totals.speaking_time),
+                (Var::Word, Some((_, word, _))) => out.push_str(word),
+                (Var::Count, Some((_, _, count))) => out.push_str(&count.to_string()),
+                (Var::Rank, Some((rank, _, _))) => out.push_str(&rank.to_string()),
+                // Rejected by the parser outside of `top`
+                (Var::Word | Var::Count | Var::Rank, None) => {}
+            },
+            Node::Top(n, body) => {
+                for (i, (word, count)) in totals.ranked.iter().take(*n).enumerate() {
+                    render_nodes(body, totals, Some((i + 1, word, *count)), out);
+                }
+            }
+        }
+    }
+}
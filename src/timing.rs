@@ -0,0 +1,134 @@
+//! Rate-limiting wrappers for closures.
+//!
+//! [`throttle`] lets a function run at most once per interval and drops the
+//! calls in between. [`debounce`] waits for calls to stop: it remembers the
+//! latest call and only runs it once no new call has arrived for a while.
+//! Both keep their bookkeeping in `Cell`/`RefCell`, so the wrappers can be
+//! shared and called through `&self` like plain functions.
+
+use std::cell::{Cell, RefCell};
+use std::time::{Duration, Instant};
+
+/// Wraps `f` so it runs at most once per `interval`.
+///
+/// Calls within `interval` of the last run are skipped and return `None`.
+///
+/// ```
+/// use std::time::Duration;
+/// use fp::timing::throttle;
+///
+/// let save = throttle(|n: i32| n * 10, Duration::from_secs(60));
+/// assert_eq!(save(1), Some(10));
+/// assert_eq!(save(2), None); // too soon
+/// ```
+pub fn throttle<A, B, F: Fn(A) -> B>(f: F, interval: Duration) -> impl Fn(A) -> Option<B> {
+    let last_run: Cell<Option<Instant>> = Cell::new(None);
+    move |a| {
+        let now = Instant::now();
+        let ready = last_run
+            .get()
+            .is_none_or(|last| now.duration_since(last) >= interval);
+        ready.then(|| {
+            last_run.set(Some(now));
+            f(a)
+        })
+    }
+}
+
+/// Wraps `f` so it only runs after calls have been quiet for `delay`.
+///
+/// [`Debounced::call`] records a call without running it;
+/// [`Debounced::flush`] runs the latest recorded call once `delay` has passed
+/// since it was made. Bursts of calls therefore collapse into one run.
+///
+/// ```
+/// use std::thread;
+/// use std::time::Duration;
+/// use fp::timing::debounce;
+///
+/// let render = debounce(|version: u32| format!("rendered v{}", version), Duration::from_millis(20));
+/// for version in 1..=5 {
+///     render.call(version);
+/// }
+/// assert_eq!(render.flush(), None); // still settling
+///
+/// thread::sleep(Duration::from_millis(30));
+/// assert_eq!(render.flush(), Some("rendered v5".to_string()));
+/// assert_eq!(render.flush(), None); // nothing new
+/// ```
+pub fn debounce<A, B, F: Fn(A) -> B>(f: F, delay: Duration) -> Debounced<A, F> {
+    Debounced {
+        f,
+        delay,
+        pending: RefCell::new(None),
+    }
+}
+
+/// A debounced function; see [`debounce`].
+pub struct Debounced<A, F> {
+    f: F,
+    delay: Duration,
+    pending: RefCell<Option<(A, Instant)>>,
+}
+
+impl<A, F> Debounced<A, F> {
+    /// Records a call, replacing any earlier one that has not run yet.
+    ///
+    /// ```
+    /// use fp::timing::debounce;
+    /// use std::time::Duration;
+    ///
+    /// let save = debounce(|n: u32| n * 10, Duration::ZERO);
+    /// save.call(1);
+    /// save.call(2); // replaces the first call
+    /// assert_eq!(save.flush(), Some(20));
+    /// ```
+    pub fn call(&self, a: A) {
+        *self.pending.borrow_mut() = Some((a, Instant::now()));
+    }
+
+    /// Whether a call is waiting to run.
+    ///
+    /// ```
+    /// use fp::timing::debounce;
+    /// use std::time::Duration;
+    ///
+    /// let save = debounce(|n: u32| n, Duration::ZERO);
+    /// assert!(!save.is_pending());
+    /// save.call(1);
+    /// assert!(save.is_pending());
+    /// assert_eq!(save.flush(), Some(1));
+    /// assert!(!save.is_pending());
+    /// ```
+    pub fn is_pending(&self) -> bool {
+        self.pending.borrow().is_some()
+    }
+
+    /// Runs the waiting call if it has been quiet for the delay.
+    ///
+    /// ```
+    /// use fp::timing::debounce;
+    /// use std::time::Duration;
+    ///
+    /// let save = debounce(|n: u32| n, Duration::from_secs(60));
+    /// assert_eq!(save.flush(), None); // nothing called
+    /// save.call(1);
+    /// assert_eq!(save.flush(), None); // not quiet for long enough
+    /// assert!(save.is_pending());
+    /// ```
+    pub fn flush<B>(&self) -> Option<B>
+    where
+        F: Fn(A) -> B,
+    {
+        let settled = self
+            .pending
+            .borrow()
+            .as_ref()
+            .is_some_and(|(_, at)| at.elapsed() >= self.delay);
+        if !settled {
+            return None;
+        }
+        let (a, _) = self.pending.borrow_mut().take()?;
+        Some((self.f)(a))
+    }
+}
@@ -0,0 +1,351 @@
+//! Token Filter Plugins
+//!
+//! A [`TokenFilter`] decides whether a token is kept and may rewrite it.
+//! Filters are looked up by name in a [`Registry`] and chained in order, so
+//! a pipeline can come from configuration (`lowercase,stem,stopwords:en`).
+//! The text after `:` is passed to the filter's factory as its argument.
+//! `stopwords:de` drops the German stop words, `lowercase:tr` lowercases by
+//! Turkish rules; [`Registry::for_locale`] makes a locale the default for
+//! both, and [`Registry::tolerant`] lets stop words match despite typos.
+//! Crates embedding the analyzer register their own filters alongside the
+//! built-in ones.
+//!
+//! ```
+//! use fp::token_filter::{Registry, TokenFilter};
+//!
+//! struct NoDigits;
+//!
+//! impl TokenFilter for NoDigits {
+//!     fn keep(&self, token: &str) -> bool {
+//!         !token.chars().any(|c| c.is_ascii_digit())
+//!     }
+//! }
+//!
+//! # #[cfg(feature = "stemming")] {
+//! let mut registry = Registry::default();
+//! registry.register("no-digits", |_| Ok(Box::new(NoDigits)));
+//!
+//! let chain = registry.chain(["lowercase", "stopwords:en", "stem", "no-digits"]).into_result().unwrap();
+//! let kept: Vec<String> = ["The", "Cats", "were", "Jumping", "42nd"]
+//!     .into_iter()
+//!     .filter_map(|t| chain.map(t.to_string()))
+//!     .collect();
+//! assert_eq!(kept, ["cat", "jump"]);
+//!
+//! let errors = registry.chain(["stem", "shout", "stopwords:xx"]).into_result().err().unwrap();
+//! assert_eq!(errors.len(), 2);
+//! # }
+//! ```
+
+use crate::fuzzy::tolerance;
+use crate::locale::Locale;
+use crate::validated::Validated;
+use std::collections::HashMap;
+
+/// A step in the token pipeline: keeps, drops or rewrites a token.
+///
+/// Implement [`keep`](TokenFilter::keep) for a pure predicate, or
+/// [`map`](TokenFilter::map) to rewrite tokens too.
+pub trait TokenFilter: Send + Sync {
+    /// Whether the token passes this filter
+    fn keep(&self, _token: &str) -> bool {
+        true
+    }
+
+    /// The token to pass on, or `None` to drop it
+    fn map(&self, token: String) -> Option<String> {
+        self.keep(&token).then_some(token)
+    }
+}
+
+/// Builds a filter from the argument after `:` in its spec, if any
+pub type Factory = Box<dyn Fn(Option<&str>) -> Result<Box<dyn TokenFilter>, String> + Send + Sync>;
+
+/// Filters by name, ready to be chained from configuration
+pub struct Registry {
+    factories: HashMap<String, Factory>,
+}
+
+impl Registry {
+    /// A registry without any filters
+    ///
+    /// ```
+    /// use fp::token_filter::Registry;
+    ///
+    /// assert_eq!(Registry::empty().build("lowercase").err().unwrap(), "Unknown token filter 'lowercase'");
+    /// ```
+    pub fn empty() -> Self {
+        Registry {
+            factories: HashMap::new(),
+        }
+    }
+
+    /// Make a filter available under `name`, replacing any previous one
+    ///
+    /// ```
+    /// use fp::token_filter::{Registry, TokenFilter};
+    ///
+    /// struct MinLength(usize);
+    ///
+    /// impl TokenFilter for MinLength {
+    ///     fn keep(&self, token: &str) -> bool {
+    ///         token.chars().count() >= self.0
+    ///     }
+    /// }
+    ///
+    /// let mut registry = Registry::empty();
+    /// registry.register("min", |argument| {
+    ///     let n = argument.unwrap_or("3").parse().map_err(|_| "min expects a number".to_string())?;
+    ///     Ok(Box::new(MinLength(n)))
+    /// });
+    /// assert!(!registry.build("min:5").unwrap().keep("fold"));
+    /// assert!(registry.build("min").unwrap().keep("fold"));
+    /// ```
+    pub fn register<F>(&mut self, name: &str, factory: F)
+    where
+        F: Fn(Option<&str>) -> Result<Box<dyn TokenFilter>, String> + Send + Sync + 'static,
+    {
+        self.factories.insert(name.to_string(), Box::new(factory));
+    }
+
+    /// Build the filter for one spec, `name` or `name:argument`
+    ///
+    /// ```
+    /// use fp::token_filter::Registry;
+    ///
+    /// let stopwords = Registry::default().build("stopwords:de").unwrap();
+    /// assert_eq!(stopwords.map("und".to_string()), None);
+    /// assert_eq!(stopwords.map("fold".to_string()), Some("fold".to_string()));
+    /// assert!(Registry::default().build("stopwords:xx").is_err());
+    /// ```
+    pub fn build(&self, spec: &str) -> Result<Box<dyn TokenFilter>, String> {
+        let (name, argument) = match spec.split_once(':') {
+            Some((name, argument)) => (name, Some(argument)),
+            None => (spec, None),
+        };
+        let factory = self
+            .factories
+            .get(name)
+            .ok_or_else(|| format!("Unknown token filter '{}'", name))?;
+        factory(argument)
+    }
+
+    /// Build every spec into one chain, reporting all invalid specs at once
+    ///
+    /// ```
+    /// use fp::token_filter::{Registry, TokenFilter};
+    ///
+    /// let chain = Registry::default().chain(["lowercase", "stopwords:en"]).into_result().unwrap();
+    /// assert_eq!(chain.map("The".to_string()), None);
+    /// assert_eq!(chain.map("Fold".to_string()), Some("fold".to_string()));
+    ///
+    /// let errors = Registry::default().chain(["shout", "stopwords:xx"]).into_result().err().unwrap();
+    /// assert_eq!(errors.len(), 2);
+    /// ```
+    pub fn chain<'a, I>(&self, specs: I) -> Validated<FilterChain, String>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let specs: Vec<&str> = specs.into_iter().collect();
+        specs
+            .iter()
+            .map(|spec| self.build(spec).into())
+            .collect::<Validated<Vec<_>, String>>()
+            .map(|filters| FilterChain {
+                filters,
+                specs: specs.iter().map(|spec| spec.to_string()).collect(),
+            })
+    }
+}
+
+impl Registry {
+    /// The built-in filters with `locale` as the language of `lowercase`
+    /// and `stopwords` when their spec names none.
+    ///
+    /// ```
+    /// use fp::locale::Locale;
+    /// use fp::token_filter::{Registry, TokenFilter};
+    ///
+    /// let chain = Registry::for_locale(Locale::Turkish)
+    ///     .chain(["lowercase", "stopwords"])
+    ///     .into_result()
+    ///     .unwrap();
+    /// let kept: Vec<String> = ["Ve", "KIRMIZI", "İpek"]
+    ///     .into_iter()
+    ///     .filter_map(|t| chain.map(t.to_string()))
+    ///     .collect();
+    /// assert_eq!(kept, ["kırmızı", "ipek"]);
+    /// ```
+    pub fn for_locale(locale: Locale) -> Self {
+        Registry::tolerant(locale, 0)
+    }
+
+    /// The built-in filters for `locale`, with `stopwords` also dropping
+    /// words within `max_dist` substituted letters of a stop word, as
+    /// [`tolerance`] allows for each, to catch the misspelled stop words of
+    /// OCR'd text
+    ///
+    /// Only substitutions count: with an inserted or deleted letter, "hand"
+    /// would be "and" and "form" would be "for".
+    ///
+    /// ```
+    /// use fp::locale::Locale;
+    /// use fp::token_filter::{Registry, TokenFilter};
+    ///
+    /// let stopwords = Registry::tolerant(Locale::English, 1).build("stopwords").unwrap();
+    /// assert!(!stopwords.keep("tbe"));
+    /// assert!(stopwords.keep("fold"));
+    /// // Too short to tolerate an edit: "ox" is not "of"
+    /// assert!(stopwords.keep("ox"));
+    /// assert!(stopwords.keep("hand"));
+    /// assert!(stopwords.keep("form"));
+    /// ```
+    pub fn tolerant(locale: Locale, max_dist: usize) -> Self {
+        let mut registry = Registry::empty();
+        registry.register("lowercase", move |language| {
+            let locale = language.map_or(Ok(locale), str::parse)?;
+            Ok(Box::new(LowercaseIn(locale)))
+        });
+        #[cfg(feature = "stemming")]
+        registry.register("stem", |_| Ok(Box::new(Stem)));
+        registry.register("stopwords", move |language| {
+            let locale = language.map_or(Ok(locale), |language| {
+                language
+                    .parse()
+                    .map_err(|_| format!("No stopword list for '{}'", language))
+            })?;
+            Ok(Box::new(Stopwords {
+                words: locale.stopwords(),
+                max_dist,
+            }))
+        });
+        registry
+    }
+}
+
+/// The built-in filters: `lowercase`, `stem` (with the `stemming` feature)
+/// and `stopwords`, English unless a language follows the `:`
+impl Default for Registry {
+    fn default() -> Self {
+        Registry::for_locale(Locale::English)
+    }
+}
+
+/// Filters applied one after another; a token dropped by one is gone
+#[derive(Default)]
+pub struct FilterChain {
+    filters: Vec<Box<dyn TokenFilter>>,
+    specs: Vec<String>,
+}
+
+impl FilterChain {
+    /// The specs the chain was built from, in order
+    ///
+    /// ```
+    /// use fp::token_filter::Registry;
+    ///
+    /// let chain = Registry::default().chain(["lowercase", "stopwords:fr"]).into_result().unwrap();
+    /// assert_eq!(chain.specs(), ["lowercase", "stopwords:fr"]);
+    /// ```
+    pub fn specs(&self) -> &[String] {
+        &self.specs
+    }
+
+    /// Whether the chain has no filters and passes every token unchanged
+    ///
+    /// ```
+    /// use fp::token_filter::{FilterChain, Registry};
+    ///
+    /// assert!(FilterChain::default().is_empty());
+    /// assert!(!Registry::default().chain(["lowercase"]).into_result().unwrap().is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.filters.is_empty()
+    }
+}
+
+impl TokenFilter for FilterChain {
+    fn map(&self, token: String) -> Option<String> {
+        self.filters
+            .iter()
+            .try_fold(token, |token, filter| filter.map(token))
+    }
+}
+
+/// Lowercases every token
+pub struct Lowercase;
+
+impl TokenFilter for Lowercase {
+    fn map(&self, token: String) -> Option<String> {
+        Some(token.to_lowercase())
+    }
+}
+
+/// Lowercases every token by the casing rules of a locale
+pub struct LowercaseIn(pub Locale);
+
+impl TokenFilter for LowercaseIn {
+    fn map(&self, token: String) -> Option<String> {
+        Some(self.0.lowercase(&token))
+    }
+}
+
+/// Strips common English suffixes, so "jumping" and "jumped" count as "jump"
+#[cfg(feature = "stemming")]
+pub struct Stem;
+
+#[cfg(feature = "stemming")]
+impl TokenFilter for Stem {
+    fn map(&self, token: String) -> Option<String> {
+        Some(stem(&token))
+    }
+}
+
+/// A light suffix stripper; keeps at least three characters of the stem
+#[cfg(feature = "stemming")]
+fn stem(word: &str) -> String {
+    const RULES: [(&str, &str); 7] = [
+        ("sses", "ss"),
+        ("ies", "y"),
+        ("ing", ""),
+        ("ed", ""),
+        ("ly", ""),
+        ("es", ""),
+        ("s", ""),
+    ];
+    RULES
+        .iter()
+        .find_map(|(suffix, replacement)| {
+            let stem = word.strip_suffix(suffix)?;
+            let long_enough = stem.chars().count() >= 3;
+            let keeps_double_s = *suffix == "s" && stem.ends_with('s');
+            (long_enough && !keeps_double_s).then(|| format!("{}{}", stem, replacement))
+        })
+        .unwrap_or_else(|| word.to_string())
+}
+
+/// Drops the words of a stopword list, and with a `max_dist` the words of
+/// the same length within that many substitutions of one
+pub struct Stopwords {
+    words: &'static [&'static str],
+    max_dist: usize,
+}
+
+impl TokenFilter for Stopwords {
+    fn keep(&self, token: &str) -> bool {
+        if self.max_dist == 0 {
+            return !self.words.contains(&token);
+        }
+        let length = token.chars().count();
+        !self.words.iter().any(|word| {
+            // The lengths alone rule most stop words out
+            word.chars().count() == length
+                && substitutions(word, token) <= tolerance(word, self.max_dist)
+        })
+    }
+}
+
+/// How many letters differ between two words of the same length
+fn substitutions(a: &str, b: &str) -> usize {
+    a.chars().zip(b.chars()).filter(|(x, y)| x != y).count()
+}
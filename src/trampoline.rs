@@ -0,0 +1,115 @@
+//! Trampolines for stack-safe recursion.
+//!
+//! Deep recursion in Rust can overflow the stack. A function written to
+//! return a [`Trampoline`] instead of calling itself hands back either its
+//! answer (`Done`) or a thunk for the next step (`More`), and [`Trampoline::run`]
+//! drives the steps in a loop, so the stack never grows. Combined with
+//! continuation-passing style ([`Cont`], [`call`]) this works even for
+//! recursion that is not a tail call, such as evaluating both sides of a tree.
+//!
+//! ```
+//! use fp::trampoline::Trampoline;
+//!
+//! fn is_even(n: u64) -> Trampoline<'static, bool> {
+//!     if n == 0 { Trampoline::Done(true) } else { Trampoline::more(move || is_odd(n - 1)) }
+//! }
+//!
+//! fn is_odd(n: u64) -> Trampoline<'static, bool> {
+//!     if n == 0 { Trampoline::Done(false) } else { Trampoline::more(move || is_even(n - 1)) }
+//! }
+//!
+//! assert!(is_even(1_000_000).run());
+//! ```
+
+/// A computation that is either finished or has another step to take.
+pub enum Trampoline<'a, T> {
+    /// The final result.
+    Done(T),
+    /// The next step.
+    More(Box<dyn FnOnce() -> Trampoline<'a, T> + 'a>),
+}
+
+impl<'a, T> Trampoline<'a, T> {
+    /// Defers `step` until the trampoline is run.
+    ///
+    /// ```
+    /// use fp::trampoline::Trampoline;
+    ///
+    /// let step = Trampoline::more(|| Trampoline::Done(42));
+    /// assert!(!step.is_done());
+    /// assert_eq!(step.run(), 42);
+    /// ```
+    pub fn more<F: FnOnce() -> Trampoline<'a, T> + 'a>(step: F) -> Self {
+        Trampoline::More(Box::new(step))
+    }
+
+    /// Whether the result is available.
+    ///
+    /// ```
+    /// use fp::trampoline::Trampoline;
+    ///
+    /// assert!(Trampoline::Done(1).is_done());
+    /// assert!(!Trampoline::more(|| Trampoline::Done(1)).is_done());
+    /// ```
+    pub fn is_done(&self) -> bool {
+        matches!(self, Trampoline::Done(_))
+    }
+
+    /// Takes a single step. A finished trampoline is returned unchanged.
+    ///
+    /// ```
+    /// use fp::trampoline::Trampoline;
+    ///
+    /// let two_steps = Trampoline::more(|| Trampoline::more(|| Trampoline::Done("landed")));
+    /// let one_left = two_steps.bounce();
+    /// assert!(!one_left.is_done());
+    /// assert!(one_left.bounce().is_done());
+    /// ```
+    pub fn bounce(self) -> Self {
+        match self {
+            Trampoline::Done(value) => Trampoline::Done(value),
+            Trampoline::More(step) => step(),
+        }
+    }
+
+    /// Takes steps until the result is available.
+    ///
+    /// ```
+    /// use fp::trampoline::Trampoline;
+    ///
+    /// fn sum_to(n: u64, acc: u64) -> Trampoline<'static, u64> {
+    ///     match n {
+    ///         0 => Trampoline::Done(acc),
+    ///         _ => Trampoline::more(move || sum_to(n - 1, acc + n)),
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(sum_to(100_000, 0).run(), 5_000_050_000);
+    /// ```
+    pub fn run(self) -> T {
+        let mut current = self;
+        loop {
+            match current {
+                Trampoline::Done(value) => return value,
+                Trampoline::More(step) => current = step(),
+            }
+        }
+    }
+}
+
+/// A continuation: what to do with an intermediate result `A`.
+pub type Cont<'a, A, R> = Box<dyn FnOnce(A) -> Trampoline<'a, R> + 'a>;
+
+/// Passes `value` to `k` on the next bounce rather than on the current stack.
+///
+/// ```
+/// use fp::trampoline::{Cont, Trampoline, call};
+///
+/// let double: Cont<'_, i32, i32> = Box::new(|n| Trampoline::Done(n * 2));
+/// let pending = call(double, 21);
+/// assert!(!pending.is_done());
+/// assert_eq!(pending.run(), 42);
+/// ```
+pub fn call<'a, A: 'a, R: 'a>(k: Cont<'a, A, R>, value: A) -> Trampoline<'a, R> {
+    Trampoline::more(move || k(value))
+}
@@ -0,0 +1,391 @@
+//! Transducers: composable reducers.
+//!
+//! A *reducer* folds items into an accumulator one at a time. A *transducer*
+//! turns one reducer into another (mapping, filtering or stopping early), so
+//! a processing pipeline can be written once and then driven by any source
+//! (a slice, the lines of a file, a channel) into any sink (a `Vec`, a count
+//! map, a custom fold) without building intermediate collections.
+//!
+//! ```
+//! use std::sync::mpsc;
+//! use fp::transducers::{filter, map, take, transduce, Collecting, TransducerExt};
+//!
+//! let pipeline = || map(|x: i32| x * x).then(filter(|x: &i32| x % 2 == 1)).then(take(3));
+//!
+//! // Source: a slice
+//! assert_eq!(transduce(pipeline(), Collecting, Vec::new(), [1, 2, 3, 4, 5, 6, 7, 8]), [1, 9, 25]);
+//!
+//! // Source: a channel, reduced into a sum instead of a Vec
+//! let (tx, rx) = mpsc::channel();
+//! (1..=100).for_each(|i| tx.send(i).unwrap());
+//! drop(tx);
+//! let sum = transduce(pipeline(), fp::transducers::reducer(|acc: i32, x| acc + x), 0, rx);
+//! assert_eq!(sum, 1 + 9 + 25);
+//! ```
+
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::ops::ControlFlow;
+
+/// Folds items of type `T` into an accumulator, possibly stopping early.
+pub trait Reducer<T> {
+    /// The accumulator type.
+    type Acc;
+
+    /// Feeds one item. `Break` means no further items are wanted.
+    fn step(&mut self, acc: Self::Acc, item: T) -> ControlFlow<Self::Acc, Self::Acc>;
+}
+
+/// Transforms a reducer of `Out`s into a reducer of `In`s.
+pub trait Transducer<In> {
+    /// Item type handed to the wrapped reducer.
+    type Out;
+    /// The resulting reducer.
+    type Reducer<R: Reducer<Self::Out>>: Reducer<In, Acc = R::Acc>;
+
+    /// Wraps `r`.
+    fn transform<R: Reducer<Self::Out>>(self, r: R) -> Self::Reducer<R>;
+}
+
+/// Runs every item from `items` through `xform` into `reducer`.
+///
+/// ```
+/// use fp::transducers::{Counting, map, transduce};
+/// use std::collections::HashMap;
+///
+/// let counts: HashMap<String, usize> =
+///     transduce(map(|w: &str| w.to_lowercase()), Counting, HashMap::new(), ["Fold", "map", "fold"]);
+/// assert_eq!(counts["fold"], 2);
+/// ```
+pub fn transduce<T, X, R, I>(xform: X, reducer: R, init: R::Acc, items: I) -> R::Acc
+where
+    X: Transducer<T>,
+    R: Reducer<X::Out>,
+    I: IntoIterator<Item = T>,
+{
+    let mut r = xform.transform(reducer);
+    match items.into_iter().try_fold(init, |acc, x| r.step(acc, x)) {
+        ControlFlow::Continue(acc) | ControlFlow::Break(acc) => acc,
+    }
+}
+
+/// Lazily runs `items` through `xform`, yielding its output as an iterator.
+///
+/// This is the pull-based counterpart of [`transduce`]: the transducer stack
+/// stays the same, and any iterator adapter can consume the result.
+///
+/// ```
+/// use fp::transducers::{filter, flat_map, sequence, TransducerExt};
+///
+/// let words = flat_map(str::split_whitespace).then(filter(|w: &&str| w.len() > 2));
+/// let long: Vec<&str> = sequence(words, ["to be", "or not"]).collect();
+/// assert_eq!(long, ["not"]);
+/// ```
+pub fn sequence<T, X, I>(xform: X, items: I) -> Sequence<X::Reducer<Buffering>, I::IntoIter, X::Out>
+where
+    X: Transducer<T>,
+    I: IntoIterator<Item = T>,
+{
+    Sequence {
+        reducer: xform.transform(Buffering),
+        source: items.into_iter(),
+        buffer: VecDeque::new(),
+        done: false,
+    }
+}
+
+/// Iterator returned by [`sequence`].
+pub struct Sequence<R, I, O> {
+    reducer: R,
+    source: I,
+    buffer: VecDeque<O>,
+    done: bool,
+}
+
+impl<R, I, O> Iterator for Sequence<R, I, O>
+where
+    I: Iterator,
+    R: Reducer<I::Item, Acc = VecDeque<O>>,
+{
+    type Item = O;
+
+    fn next(&mut self) -> Option<O> {
+        loop {
+            if let Some(out) = self.buffer.pop_front() {
+                return Some(out);
+            }
+            if self.done {
+                return None;
+            }
+            let Some(item) = self.source.next() else {
+                self.done = true;
+                continue;
+            };
+            self.buffer = match self.reducer.step(std::mem::take(&mut self.buffer), item) {
+                ControlFlow::Continue(buffer) => buffer,
+                ControlFlow::Break(buffer) => {
+                    self.done = true;
+                    buffer
+                }
+            };
+        }
+    }
+}
+
+/// Chaining for transducers.
+pub trait TransducerExt<In>: Transducer<In> + Sized {
+    /// Feeds this transducer's output into `next`.
+    fn then<X: Transducer<Self::Out>>(self, next: X) -> Comp<Self, X> {
+        Comp(self, next)
+    }
+}
+
+impl<In, X: Transducer<In>> TransducerExt<In> for X {}
+
+/// Two transducers applied in sequence.
+pub struct Comp<A, B>(A, B);
+
+impl<In, A, B> Transducer<In> for Comp<A, B>
+where
+    A: Transducer<In>,
+    B: Transducer<A::Out>,
+{
+    type Out = B::Out;
+    type Reducer<R: Reducer<B::Out>> = A::Reducer<B::Reducer<R>>;
+
+    fn transform<R: Reducer<B::Out>>(self, r: R) -> Self::Reducer<R> {
+        self.0.transform(self.1.transform(r))
+    }
+}
+
+/// A reducer built from a plain fold function.
+pub struct FnReducer<F, A> {
+    f: F,
+    _acc: PhantomData<fn(A) -> A>,
+}
+
+/// Turns `f(acc, item) -> acc` into a reducer.
+///
+/// ```
+/// use fp::transducers::{filter, reducer, transduce};
+///
+/// let longest = reducer(|acc: usize, w: &str| acc.max(w.len()));
+/// assert_eq!(transduce(filter(|w: &&str| w.starts_with('f')), longest, 0, ["fold", "filter", "map"]), 6);
+/// ```
+pub fn reducer<A, T, F: FnMut(A, T) -> A>(f: F) -> FnReducer<F, A> {
+    FnReducer {
+        f,
+        _acc: PhantomData,
+    }
+}
+
+impl<T, A, F: FnMut(A, T) -> A> Reducer<T> for FnReducer<F, A> {
+    type Acc = A;
+
+    fn step(&mut self, acc: A, item: T) -> ControlFlow<A, A> {
+        ControlFlow::Continue((self.f)(acc, item))
+    }
+}
+
+/// Sink that pushes every item onto a `Vec`.
+pub struct Collecting;
+
+impl<T> Reducer<T> for Collecting {
+    type Acc = Vec<T>;
+
+    fn step(&mut self, mut acc: Vec<T>, item: T) -> ControlFlow<Vec<T>, Vec<T>> {
+        acc.push(item);
+        ControlFlow::Continue(acc)
+    }
+}
+
+/// Sink that queues items for [`sequence`] to hand out.
+pub struct Buffering;
+
+impl<T> Reducer<T> for Buffering {
+    type Acc = VecDeque<T>;
+
+    fn step(&mut self, mut acc: VecDeque<T>, item: T) -> ControlFlow<VecDeque<T>, VecDeque<T>> {
+        acc.push_back(item);
+        ControlFlow::Continue(acc)
+    }
+}
+
+/// Sink that counts occurrences of each item.
+pub struct Counting;
+
+impl<T: Eq + Hash> Reducer<T> for Counting {
+    type Acc = HashMap<T, usize>;
+
+    fn step(&mut self, mut acc: Self::Acc, item: T) -> ControlFlow<Self::Acc, Self::Acc> {
+        *acc.entry(item).or_insert(0) += 1;
+        ControlFlow::Continue(acc)
+    }
+}
+
+/// Applies `f` to every item.
+pub struct Mapping<F>(F);
+
+/// Transducer applying `f` to every item.
+///
+/// ```
+/// use fp::transducers::{Collecting, map, transduce};
+///
+/// assert_eq!(transduce(map(|x: i32| x + 1), Collecting, Vec::new(), [1, 2, 3]), [2, 3, 4]);
+/// ```
+pub fn map<F>(f: F) -> Mapping<F> {
+    Mapping(f)
+}
+
+/// Reducer produced by [`Mapping`].
+pub struct MapReducer<F, R>(F, R);
+
+impl<In, U, F: FnMut(In) -> U> Transducer<In> for Mapping<F> {
+    type Out = U;
+    type Reducer<R: Reducer<U>> = MapReducer<F, R>;
+
+    fn transform<R: Reducer<U>>(self, r: R) -> MapReducer<F, R> {
+        MapReducer(self.0, r)
+    }
+}
+
+impl<In, U, F: FnMut(In) -> U, R: Reducer<U>> Reducer<In> for MapReducer<F, R> {
+    type Acc = R::Acc;
+
+    fn step(&mut self, acc: R::Acc, item: In) -> ControlFlow<R::Acc, R::Acc> {
+        self.1.step(acc, (self.0)(item))
+    }
+}
+
+/// Keeps items matching a predicate.
+pub struct Filtering<P>(P);
+
+/// Transducer keeping only items for which `pred` returns `true`.
+///
+/// ```
+/// use fp::transducers::{Collecting, filter, transduce};
+///
+/// let long = transduce(filter(|w: &&str| w.len() > 3), Collecting, Vec::new(), ["fold", "map", "filter"]);
+/// assert_eq!(long, ["fold", "filter"]);
+/// ```
+pub fn filter<P>(pred: P) -> Filtering<P> {
+    Filtering(pred)
+}
+
+/// Reducer produced by [`Filtering`].
+pub struct FilterReducer<P, R>(P, R);
+
+impl<In, P: FnMut(&In) -> bool> Transducer<In> for Filtering<P> {
+    type Out = In;
+    type Reducer<R: Reducer<In>> = FilterReducer<P, R>;
+
+    fn transform<R: Reducer<In>>(self, r: R) -> FilterReducer<P, R> {
+        FilterReducer(self.0, r)
+    }
+}
+
+impl<In, P: FnMut(&In) -> bool, R: Reducer<In>> Reducer<In> for FilterReducer<P, R> {
+    type Acc = R::Acc;
+
+    fn step(&mut self, acc: R::Acc, item: In) -> ControlFlow<R::Acc, R::Acc> {
+        if (self.0)(&item) {
+            self.1.step(acc, item)
+        } else {
+            ControlFlow::Continue(acc)
+        }
+    }
+}
+
+/// Expands every item into zero or more items.
+pub struct FlatMapping<F>(F);
+
+/// Transducer replacing every item with the items of `f(item)`.
+///
+/// ```
+/// use fp::transducers::{Collecting, flat_map, transduce};
+///
+/// let words = transduce(flat_map(str::split_whitespace), Collecting, Vec::new(), ["fold map", "", "filter"]);
+/// assert_eq!(words, ["fold", "map", "filter"]);
+/// ```
+pub fn flat_map<F>(f: F) -> FlatMapping<F> {
+    FlatMapping(f)
+}
+
+/// Reducer produced by [`FlatMapping`].
+pub struct FlatMapReducer<F, R>(F, R);
+
+impl<In, I: IntoIterator, F: FnMut(In) -> I> Transducer<In> for FlatMapping<F> {
+    type Out = I::Item;
+    type Reducer<R: Reducer<I::Item>> = FlatMapReducer<F, R>;
+
+    fn transform<R: Reducer<I::Item>>(self, r: R) -> FlatMapReducer<F, R> {
+        FlatMapReducer(self.0, r)
+    }
+}
+
+impl<In, I, F, R> Reducer<In> for FlatMapReducer<F, R>
+where
+    I: IntoIterator,
+    F: FnMut(In) -> I,
+    R: Reducer<I::Item>,
+{
+    type Acc = R::Acc;
+
+    fn step(&mut self, acc: R::Acc, item: In) -> ControlFlow<R::Acc, R::Acc> {
+        let inner = &mut self.1;
+        (self.0)(item)
+            .into_iter()
+            .try_fold(acc, |acc, x| inner.step(acc, x))
+    }
+}
+
+/// Passes on the first `n` items, then stops the reduction.
+pub struct Taking(usize);
+
+/// Transducer stopping after `n` items.
+///
+/// ```
+/// use fp::transducers::{Collecting, take, transduce};
+///
+/// // The source is infinite; the reduction stops after the third item
+/// assert_eq!(transduce(take(3), Collecting, Vec::new(), 1..), [1, 2, 3]);
+/// ```
+pub fn take(n: usize) -> Taking {
+    Taking(n)
+}
+
+/// Reducer produced by [`Taking`].
+pub struct TakeReducer<R> {
+    remaining: usize,
+    inner: R,
+}
+
+impl<In> Transducer<In> for Taking {
+    type Out = In;
+    type Reducer<R: Reducer<In>> = TakeReducer<R>;
+
+    fn transform<R: Reducer<In>>(self, r: R) -> TakeReducer<R> {
+        TakeReducer {
+            remaining: self.0,
+            inner: r,
+        }
+    }
+}
+
+impl<In, R: Reducer<In>> Reducer<In> for TakeReducer<R> {
+    type Acc = R::Acc;
+
+    fn step(&mut self, acc: R::Acc, item: In) -> ControlFlow<R::Acc, R::Acc> {
+        if self.remaining == 0 {
+            return ControlFlow::Break(acc);
+        }
+        self.remaining -= 1;
+        let acc = self.inner.step(acc, item)?;
+        if self.remaining == 0 {
+            ControlFlow::Break(acc)
+        } else {
+            ControlFlow::Continue(acc)
+        }
+    }
+}
@@ -0,0 +1,238 @@
+//! Prefix Trees
+//!
+//! A trie stores words character by character, one node per prefix, so the
+//! words starting with a prefix are exactly the ones below the prefix's
+//! node: finding them costs the length of the prefix plus the number of
+//! matches, however large the vocabulary. That makes it the index behind
+//! autocompletion (`--complete`) and the `startswith` command of watch mode.
+//! [`PTrie`](crate::persistent::PTrie) is the persistent variant.
+//!
+//! ```
+//! use fp::trie::Trie;
+//!
+//! let trie: Trie<usize> = [("fold", 5), ("foldl", 1), ("filter", 2), ("map", 4)].into_iter().collect();
+//! assert_eq!(trie.words_with_prefix("fo"), [("fold".to_string(), &5), ("foldl".to_string(), &1)]);
+//! assert_eq!(trie.get("map"), Some(&4));
+//! assert!(!trie.contains("fol"));
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::mem;
+
+/// A map from words to values, indexed by prefix
+pub struct Trie<V> {
+    /// The value of the word ending here, if one does
+    value: Option<V>,
+    children: BTreeMap<char, Trie<V>>,
+    /// Words in this subtree
+    len: usize,
+}
+
+impl<V> Default for Trie<V> {
+    fn default() -> Self {
+        Trie {
+            value: None,
+            children: BTreeMap::new(),
+            len: 0,
+        }
+    }
+}
+
+impl<V> Trie<V> {
+    /// The empty trie
+    ///
+    /// ```
+    /// use fp::trie::Trie;
+    ///
+    /// let trie: Trie<usize> = Trie::new();
+    /// assert!(trie.words_with_prefix("").is_empty());
+    /// ```
+    pub fn new() -> Self {
+        Trie::default()
+    }
+
+    /// How many words the trie holds
+    ///
+    /// ```
+    /// use fp::trie::Trie;
+    ///
+    /// // A word that is a prefix of another counts as a word of its own
+    /// let trie: Trie<usize> = [("fold", 1), ("folds", 2)].into_iter().collect();
+    /// assert_eq!(trie.len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the trie holds no words
+    ///
+    /// ```
+    /// use fp::trie::Trie;
+    ///
+    /// let mut trie = Trie::new();
+    /// assert!(trie.is_empty());
+    /// trie.insert("", ());
+    /// assert!(!trie.is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Store `value` under `word`, returning the value it replaces
+    ///
+    /// ```
+    /// use fp::trie::Trie;
+    ///
+    /// let mut trie = Trie::new();
+    /// assert_eq!(trie.insert("fold", 1), None);
+    /// assert_eq!(trie.insert("fold", 2), Some(1));
+    /// assert_eq!(trie.len(), 1);
+    /// ```
+    pub fn insert(&mut self, word: &str, value: V) -> Option<V> {
+        // Every node on the way down holds one more word if this one is new
+        let added = usize::from(!self.contains(word));
+        let mut node = self;
+        for c in word.chars() {
+            node.len += added;
+            node = node.children.entry(c).or_default();
+        }
+        node.len += added;
+        node.value.replace(value)
+    }
+
+    /// The value stored under exactly `word`
+    ///
+    /// ```
+    /// use fp::trie::Trie;
+    ///
+    /// let trie: Trie<usize> = [("fold", 2)].into_iter().collect();
+    /// assert_eq!(trie.get("fold"), Some(&2));
+    /// assert_eq!(trie.get("fol"), None);
+    /// ```
+    pub fn get(&self, word: &str) -> Option<&V> {
+        self.node(word)?.value.as_ref()
+    }
+
+    /// Whether `word` itself is in the trie, not just as a prefix
+    ///
+    /// ```
+    /// use fp::trie::Trie;
+    ///
+    /// let trie: Trie<()> = [("fold", ())].into_iter().collect();
+    /// assert!(trie.contains("fold"));
+    /// assert!(!trie.contains("fo"));
+    /// ```
+    pub fn contains(&self, word: &str) -> bool {
+        self.get(word).is_some()
+    }
+
+    /// Every word starting with `prefix`, itself included, in alphabetical
+    /// order of characters, with its value
+    ///
+    /// The trie is walked with loops rather than recursion, so a word as
+    /// long as a whole file does not overflow the stack.
+    ///
+    /// ```
+    /// use fp::trie::Trie;
+    ///
+    /// let long = "a".repeat(300_000);
+    /// let trie: Trie<usize> = [(long.as_str(), 1), ("ab", 2)].into_iter().collect();
+    /// let words = trie.words_with_prefix("a");
+    /// assert_eq!(words.len(), 2);
+    /// assert_eq!(words[0].0.len(), 300_000);
+    /// ```
+    pub fn words_with_prefix(&self, prefix: &str) -> Vec<(String, &V)> {
+        let mut words = Vec::new();
+        if let Some(node) = self.node(prefix) {
+            node.collect(prefix, &mut words);
+        }
+        words
+    }
+
+    /// The node of `prefix`, if any word starts with it
+    fn node(&self, prefix: &str) -> Option<&Trie<V>> {
+        prefix
+            .chars()
+            .try_fold(self, |node, c| node.children.get(&c))
+    }
+
+    /// Push the words below this node, spelled from `prefix`, onto `words`
+    fn collect<'a>(&'a self, prefix: &str, words: &mut Vec<(String, &'a V)>) {
+        let mut spelled = prefix.to_string();
+        // Nodes still to visit, the next on top, with the length of their
+        // parent's spelling and the character that leads to them
+        let mut stack = vec![(spelled.len(), None, self)];
+        while let Some((parent, c, node)) = stack.pop() {
+            spelled.truncate(parent);
+            spelled.extend(c);
+            if let Some(value) = &node.value {
+                words.push((spelled.clone(), value));
+            }
+            let len = spelled.len();
+            stack.extend(
+                node.children
+                    .iter()
+                    .rev()
+                    .map(|(&c, child)| (len, Some(c), child)),
+            );
+        }
+    }
+}
+
+/// Dropped node by node, as a long word's chain of nodes would overflow the
+/// stack dropping itself recursively
+impl<V> Drop for Trie<V> {
+    fn drop(&mut self) {
+        let mut nodes: Vec<Trie<V>> = mem::take(&mut self.children).into_values().collect();
+        while let Some(mut node) = nodes.pop() {
+            nodes.extend(mem::take(&mut node.children).into_values());
+        }
+    }
+}
+
+/// Cloned word by word, for the same reason
+///
+/// ```
+/// use fp::trie::Trie;
+///
+/// let long = "a".repeat(300_000);
+/// let trie: Trie<usize> = [(long.as_str(), 1), ("ab", 2)].into_iter().collect();
+/// let copy = trie.clone();
+/// assert_eq!(copy, trie);
+/// assert!(format!("{:?}", copy).ends_with("\"ab\": 2}"));
+/// ```
+impl<V: Clone> Clone for Trie<V> {
+    fn clone(&self) -> Self {
+        self.words_with_prefix("")
+            .into_iter()
+            .map(|(word, value)| (word, value.clone()))
+            .collect()
+    }
+}
+
+/// Equal when they hold the same words with the same values
+impl<V: PartialEq> PartialEq for Trie<V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.words_with_prefix("") == other.words_with_prefix("")
+    }
+}
+
+impl<V: Eq> Eq for Trie<V> {}
+
+impl<V: fmt::Debug> fmt::Debug for Trie<V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.words_with_prefix("")).finish()
+    }
+}
+
+impl<S: AsRef<str>, V> FromIterator<(S, V)> for Trie<V> {
+    fn from_iter<I: IntoIterator<Item = (S, V)>>(items: I) -> Self {
+        items
+            .into_iter()
+            .fold(Trie::new(), |mut trie, (word, value)| {
+                trie.insert(word.as_ref(), value);
+                trie
+            })
+    }
+}
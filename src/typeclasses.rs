@@ -0,0 +1,209 @@
+//! Functor / Applicative / Monad traits.
+//!
+//! Rust has no higher-kinded types, so "the same container with a different
+//! element type" is encoded with a generic associated type: `Option<A>`
+//! declares `type Wrapped<B> = Option<B>`. This is enough to write the
+//! classic signatures and state their laws for concrete types.
+//!
+//! Instances: `Option`, `Result`, `Vec`, [`Either`] (right-biased),
+//! [`Validated`] and the types in [`monads`](crate::monads). `Validated` is an `Applicative` but deliberately not a
+//! `Monad`: a lawful `bind` must stop at the first error, which contradicts
+//! the error accumulation that `ap` provides.
+//!
+//! # Laws
+//!
+//! ```
+//! use fp::typeclasses::{Applicative, Functor, Monad};
+//!
+//! // Functor identity: fmap(id) == id
+//! assert_eq!(Some(3).fmap(|x| x), Some(3));
+//! assert_eq!(vec![1, 2].fmap(|x| x), vec![1, 2]);
+//!
+//! // Functor composition: fmap(g . f) == fmap(f).fmap(g)
+//! let f = |x: i32| x + 1;
+//! let g = |x: i32| x * 2;
+//! assert_eq!(vec![1, 2, 3].fmap(|x| g(f(x))), vec![1, 2, 3].fmap(f).fmap(g));
+//! assert_eq!(Ok::<i32, ()>(5).fmap(|x| g(f(x))), Ok::<i32, ()>(5).fmap(f).fmap(g));
+//!
+//! // Applicative identity: pure(id) <*> v == v
+//! let id = |x: i32| x;
+//! assert_eq!(Some(4).ap(Some(id)), Some(4));
+//! assert_eq!(vec![1, 2].ap(vec![id]), vec![1, 2]);
+//!
+//! // Applicative homomorphism: pure(x) <*> pure(f) == pure(f(x))
+//! assert_eq!(Option::pure(3).ap(Option::pure(f)), Option::pure(f(3)));
+//!
+//! // Monad left identity: pure(a).bind(k) == k(a)
+//! let k = |x: i32| if x > 0 { Some(x * 10) } else { None };
+//! assert_eq!(Option::pure(2).bind(k), k(2));
+//!
+//! // Monad right identity: m.bind(pure) == m
+//! assert_eq!(vec![1, 2, 3].bind(Vec::pure), vec![1, 2, 3]);
+//!
+//! // Monad associativity: m.bind(k).bind(h) == m.bind(|x| k(x).bind(h))
+//! let k = |x: i32| vec![x, x + 1];
+//! let h = |x: i32| vec![x * 10];
+//! assert_eq!(vec![1, 5].bind(k).bind(h), vec![1, 5].bind(|x| k(x).bind(h)));
+//! ```
+
+use crate::either::Either;
+use crate::validated::Validated;
+
+/// A container whose elements can be transformed without changing its shape.
+pub trait Functor {
+    /// The element type.
+    type Inner;
+    /// The same container holding `B`s instead.
+    type Wrapped<B>;
+
+    /// Applies `f` to every element.
+    fn fmap<B, F: FnMut(Self::Inner) -> B>(self, f: F) -> Self::Wrapped<B>;
+}
+
+/// A functor that can lift plain values and apply wrapped functions.
+pub trait Applicative: Functor {
+    /// Wraps a single value in the minimal context.
+    fn pure(value: Self::Inner) -> Self;
+
+    /// Applies wrapped functions to wrapped values.
+    fn ap<B, F: FnMut(Self::Inner) -> B>(self, fs: Self::Wrapped<F>) -> Self::Wrapped<B>;
+}
+
+/// An applicative whose next computation may depend on the previous result.
+pub trait Monad: Applicative {
+    /// Feeds each element to `f` and flattens the results.
+    fn bind<B, F: FnMut(Self::Inner) -> Self::Wrapped<B>>(self, f: F) -> Self::Wrapped<B>;
+}
+
+impl<A> Functor for Option<A> {
+    type Inner = A;
+    type Wrapped<B> = Option<B>;
+
+    fn fmap<B, F: FnMut(A) -> B>(self, f: F) -> Option<B> {
+        self.map(f)
+    }
+}
+
+impl<A> Applicative for Option<A> {
+    fn pure(value: A) -> Self {
+        Some(value)
+    }
+
+    fn ap<B, F: FnMut(A) -> B>(self, fs: Option<F>) -> Option<B> {
+        self.zip(fs).map(|(a, mut f)| f(a))
+    }
+}
+
+impl<A> Monad for Option<A> {
+    fn bind<B, F: FnMut(A) -> Option<B>>(self, f: F) -> Option<B> {
+        self.and_then(f)
+    }
+}
+
+impl<A, E> Functor for Result<A, E> {
+    type Inner = A;
+    type Wrapped<B> = Result<B, E>;
+
+    fn fmap<B, F: FnMut(A) -> B>(self, f: F) -> Result<B, E> {
+        self.map(f)
+    }
+}
+
+impl<A, E> Applicative for Result<A, E> {
+    fn pure(value: A) -> Self {
+        Ok(value)
+    }
+
+    fn ap<B, F: FnMut(A) -> B>(self, fs: Result<F, E>) -> Result<B, E> {
+        self.and_then(|a| fs.map(|mut f| f(a)))
+    }
+}
+
+impl<A, E> Monad for Result<A, E> {
+    fn bind<B, F: FnMut(A) -> Result<B, E>>(self, f: F) -> Result<B, E> {
+        self.and_then(f)
+    }
+}
+
+impl<A> Functor for Vec<A> {
+    type Inner = A;
+    type Wrapped<B> = Vec<B>;
+
+    fn fmap<B, F: FnMut(A) -> B>(self, f: F) -> Vec<B> {
+        self.into_iter().map(f).collect()
+    }
+}
+
+/// `ap` pairs every function with every element (a cartesian product), so
+/// the elements must be `Clone`.
+impl<A: Clone> Applicative for Vec<A> {
+    fn pure(value: A) -> Self {
+        vec![value]
+    }
+
+    fn ap<B, F: FnMut(A) -> B>(self, fs: Vec<F>) -> Vec<B> {
+        fs.into_iter()
+            .flat_map(|mut f| self.iter().cloned().map(&mut f).collect::<Vec<_>>())
+            .collect()
+    }
+}
+
+impl<A: Clone> Monad for Vec<A> {
+    fn bind<B, F: FnMut(A) -> Vec<B>>(self, f: F) -> Vec<B> {
+        self.into_iter().flat_map(f).collect()
+    }
+}
+
+impl<L, R> Functor for Either<L, R> {
+    type Inner = R;
+    type Wrapped<B> = Either<L, B>;
+
+    fn fmap<B, F: FnMut(R) -> B>(self, f: F) -> Either<L, B> {
+        self.map_right(f)
+    }
+}
+
+impl<L, R> Applicative for Either<L, R> {
+    fn pure(value: R) -> Self {
+        Either::Right(value)
+    }
+
+    fn ap<B, F: FnMut(R) -> B>(self, fs: Either<L, F>) -> Either<L, B> {
+        self.and_then(|r| fs.map_right(|mut f| f(r)))
+    }
+}
+
+impl<L, R> Monad for Either<L, R> {
+    fn bind<B, F: FnMut(R) -> Either<L, B>>(self, f: F) -> Either<L, B> {
+        self.and_then(f)
+    }
+}
+
+impl<T, E> Functor for Validated<T, E> {
+    type Inner = T;
+    type Wrapped<B> = Validated<B, E>;
+
+    fn fmap<B, F: FnMut(T) -> B>(self, f: F) -> Validated<B, E> {
+        self.map(f)
+    }
+}
+
+/// Unlike `Result`, `ap` keeps the errors of both sides.
+///
+/// ```
+/// use fp::typeclasses::Applicative;
+/// use fp::validated::Validated;
+///
+/// let value: Validated<i32, &str> = Validated::invalid("no value");
+/// let func: Validated<fn(i32) -> i32, &str> = Validated::invalid("no function");
+/// assert_eq!(*value.ap(func).into_result().unwrap_err(), ["no value", "no function"]);
+/// ```
+impl<T, E> Applicative for Validated<T, E> {
+    fn pure(value: T) -> Self {
+        Validated::Valid(value)
+    }
+
+    fn ap<B, F: FnMut(T) -> B>(self, fs: Validated<F, E>) -> Validated<B, E> {
+        self.zip_with(fs, |t, mut f| f(t))
+    }
+}
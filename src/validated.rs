@@ -0,0 +1,274 @@
+//! Error-accumulating validation.
+//!
+//! `Result` stops at the first error. `Validated` is its applicative cousin:
+//! combining two `Invalid` values keeps the errors of both, so a caller can
+//! report every problem at once. Errors live in a `NonEmptyVec`, since an
+//! `Invalid` without a reason cannot exist.
+
+use std::ops::Deref;
+
+/// A `Vec` that always holds at least one element.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NonEmptyVec<T>(Vec<T>);
+
+impl<T> NonEmptyVec<T> {
+    /// Creates a vector holding a single element.
+    ///
+    /// ```
+    /// use fp::validated::NonEmptyVec;
+    ///
+    /// let errors = NonEmptyVec::new("missing file");
+    /// assert_eq!(errors.len(), 1);
+    /// ```
+    pub fn new(first: T) -> Self {
+        Self(vec![first])
+    }
+
+    /// Wraps a `Vec`, returning `None` if it is empty.
+    ///
+    /// ```
+    /// use fp::validated::NonEmptyVec;
+    ///
+    /// assert!(NonEmptyVec::from_vec(vec![1, 2]).is_some());
+    /// assert!(NonEmptyVec::<i32>::from_vec(Vec::new()).is_none());
+    /// ```
+    pub fn from_vec(items: Vec<T>) -> Option<Self> {
+        if items.is_empty() { None } else { Some(Self(items)) }
+    }
+
+    /// The first element, which always exists.
+    ///
+    /// ```
+    /// use fp::validated::NonEmptyVec;
+    ///
+    /// let items = NonEmptyVec::from_vec(vec!["a", "b"]).unwrap();
+    /// assert_eq!(*items.first(), "a");
+    /// ```
+    pub fn first(&self) -> &T {
+        &self.0[0]
+    }
+
+    /// Appends an element.
+    ///
+    /// ```
+    /// use fp::validated::NonEmptyVec;
+    ///
+    /// let mut items = NonEmptyVec::new(1);
+    /// items.push(2);
+    /// assert_eq!(*items, [1, 2]);
+    /// ```
+    pub fn push(&mut self, item: T) {
+        self.0.push(item);
+    }
+
+    /// Appends every element of `other`, keeping order.
+    ///
+    /// ```
+    /// use fp::validated::NonEmptyVec;
+    ///
+    /// let both = NonEmptyVec::new("bad a").append(NonEmptyVec::new("bad b"));
+    /// assert_eq!(*both, ["bad a", "bad b"]);
+    /// ```
+    pub fn append(mut self, other: Self) -> Self {
+        self.0.extend(other.0);
+        self
+    }
+
+    /// Unwraps into a plain `Vec`.
+    ///
+    /// ```
+    /// use fp::validated::NonEmptyVec;
+    ///
+    /// assert_eq!(NonEmptyVec::new('x').into_vec(), vec!['x']);
+    /// ```
+    pub fn into_vec(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<T> Deref for NonEmptyVec<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.0
+    }
+}
+
+impl<T> IntoIterator for NonEmptyVec<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+/// Either a valid value or every error found while producing it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Validated<T, E> {
+    Valid(T),
+    Invalid(NonEmptyVec<E>),
+}
+
+use Validated::{Invalid, Valid};
+
+impl<T, E> Validated<T, E> {
+    /// An invalid value with a single error.
+    ///
+    /// ```
+    /// use fp::validated::Validated;
+    ///
+    /// let bad: Validated<i32, &str> = Validated::invalid("bad");
+    /// assert_eq!(*bad.into_result().unwrap_err(), ["bad"]);
+    /// ```
+    pub fn invalid(error: E) -> Self {
+        Invalid(NonEmptyVec::new(error))
+    }
+
+    /// Returns `true` if there are no errors.
+    ///
+    /// ```
+    /// use fp::validated::Validated;
+    ///
+    /// assert!(Validated::<i32, &str>::Valid(1).is_valid());
+    /// assert!(!Validated::<i32, &str>::invalid("bad").is_valid());
+    /// ```
+    pub fn is_valid(&self) -> bool {
+        matches!(self, Valid(_))
+    }
+
+    /// Transforms the valid value.
+    ///
+    /// ```
+    /// use fp::validated::Validated;
+    ///
+    /// assert_eq!(Validated::<i32, &str>::Valid(2).map(|n| n * 3), Validated::Valid(6));
+    /// assert!(!Validated::<i32, &str>::invalid("bad").map(|n| n * 3).is_valid());
+    /// ```
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Validated<U, E> {
+        match self {
+            Valid(t) => Valid(f(t)),
+            Invalid(errors) => Invalid(errors),
+        }
+    }
+
+    /// Transforms every error.
+    ///
+    /// ```
+    /// use fp::validated::Validated;
+    ///
+    /// let a: Validated<i32, &str> = Validated::invalid("a");
+    /// let errors = a.zip_with(Validated::invalid("b"), |x, y: i32| x + y);
+    /// let shouted = errors.map_err(str::to_uppercase).into_result().unwrap_err();
+    /// assert_eq!(*shouted, ["A", "B"]);
+    /// ```
+    pub fn map_err<F2, F: FnMut(E) -> F2>(self, f: F) -> Validated<T, F2> {
+        match self {
+            Valid(t) => Valid(t),
+            Invalid(errors) => Invalid(NonEmptyVec(errors.into_iter().map(f).collect())),
+        }
+    }
+
+    /// Pairs two validations, accumulating the errors of both sides.
+    ///
+    /// ```
+    /// use fp::validated::Validated;
+    ///
+    /// let a: Validated<i32, &str> = Validated::invalid("bad a");
+    /// let b: Validated<i32, &str> = Validated::invalid("bad b");
+    /// let errors = a.zip(b).into_result().unwrap_err();
+    /// assert_eq!(*errors, ["bad a", "bad b"]);
+    /// ```
+    pub fn zip<U>(self, other: Validated<U, E>) -> Validated<(T, U), E> {
+        self.zip_with(other, |t, u| (t, u))
+    }
+
+    /// Combines two validations with `f`, accumulating the errors of both.
+    ///
+    /// ```
+    /// use fp::validated::Validated;
+    ///
+    /// let width: Validated<u32, &str> = Validated::Valid(3);
+    /// let height: Validated<u32, &str> = Validated::Valid(4);
+    /// assert_eq!(width.zip_with(height, |w, h| w * h), Validated::Valid(12));
+    /// ```
+    pub fn zip_with<U, V, F: FnOnce(T, U) -> V>(self, other: Validated<U, E>, f: F) -> Validated<V, E> {
+        match (self, other) {
+            (Valid(t), Valid(u)) => Valid(f(t, u)),
+            (Valid(_), Invalid(errors)) | (Invalid(errors), Valid(_)) => Invalid(errors),
+            (Invalid(a), Invalid(b)) => Invalid(a.append(b)),
+        }
+    }
+
+    /// Chains a dependent validation. This short-circuits like `Result`,
+    /// because the next step needs the value this one failed to produce.
+    ///
+    /// ```
+    /// use fp::validated::Validated;
+    ///
+    /// let parse = |s: &str| -> Validated<u32, String> {
+    ///     s.parse().map_err(|_| format!("'{}' is not a number", s)).into()
+    /// };
+    /// let positive = |n: u32| if n > 0 { Validated::Valid(n) } else { Validated::invalid("must be positive".to_string()) };
+    ///
+    /// assert_eq!(parse("4").and_then(positive), Validated::Valid(4));
+    /// assert!(!parse("0").and_then(positive).is_valid());
+    /// assert_eq!(parse("x").and_then(positive).into_result().unwrap_err().first(), "'x' is not a number");
+    /// ```
+    pub fn and_then<U, F: FnOnce(T) -> Validated<U, E>>(self, f: F) -> Validated<U, E> {
+        match self {
+            Valid(t) => f(t),
+            Invalid(errors) => Invalid(errors),
+        }
+    }
+
+    /// Converts to a `Result` carrying every error.
+    ///
+    /// ```
+    /// use fp::validated::Validated;
+    ///
+    /// assert_eq!(Validated::<i32, &str>::Valid(1).into_result(), Ok(1));
+    /// let errors = Validated::<i32, &str>::invalid("bad").into_result().unwrap_err();
+    /// assert_eq!(errors.first(), &"bad");
+    /// ```
+    pub fn into_result(self) -> Result<T, NonEmptyVec<E>> {
+        match self {
+            Valid(t) => Ok(t),
+            Invalid(errors) => Err(errors),
+        }
+    }
+}
+
+impl<T, E> From<Result<T, E>> for Validated<T, E> {
+    fn from(result: Result<T, E>) -> Self {
+        match result {
+            Ok(t) => Valid(t),
+            Err(e) => Validated::invalid(e),
+        }
+    }
+}
+
+/// Collecting validations yields all values, or all errors if any failed.
+///
+/// ```
+/// use fp::validated::Validated;
+///
+/// let checks: Validated<Vec<u32>, String> = ["1", "two", "3", "four"]
+///     .iter()
+///     .map(|s| s.parse::<u32>().map_err(|_| format!("not a number: {s}")).into())
+///     .collect();
+/// assert_eq!(
+///     checks.into_result().unwrap_err().join(", "),
+///     "not a number: two, not a number: four"
+/// );
+/// ```
+impl<T, E> FromIterator<Validated<T, E>> for Validated<Vec<T>, E> {
+    fn from_iter<I: IntoIterator<Item = Validated<T, E>>>(iter: I) -> Self {
+        iter.into_iter().fold(Valid(Vec::new()), |acc, item| {
+            acc.zip_with(item, |mut values, value| {
+                values.push(value);
+                values
+            })
+        })
+    }
+}
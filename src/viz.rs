@@ -0,0 +1,104 @@
+//! Terminal Charts
+//!
+//! Horizontal bar charts drawn with Unicode block elements, for the
+//! `--chart` report and anything else that shows counts in a terminal. Bars
+//! are scaled so the largest value fills the width left over after the
+//! labels and numbers, in steps of an eighth of a cell (`▏` to `█`), and
+//! any non-zero value gets at least the thinnest sliver.
+//!
+//! ```
+//! use fp::viz::bar_chart;
+//!
+//! let chart = bar_chart([("fold", 8), ("map", 4), ("filter", 1)], 20);
+//! assert_eq!(chart, "fold   ███████████ 8\nmap    █████▌      4\nfilter █▍          1");
+//! assert!(chart.lines().all(|line| line.chars().count() == 20));
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Display;
+
+/// Block elements from one eighth of a cell to a full cell
+const EIGHTHS: [char; 8] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉', '█'];
+
+/// A bar of `value / max` of `cells` cells, in eighths of a cell and
+/// padded with spaces to exactly `cells`
+///
+/// ```
+/// use fp::viz::bar;
+///
+/// assert_eq!(bar(1, 2, 4), "██  ");
+/// assert_eq!(bar(1, 16, 4), "▎   ");
+/// assert_eq!(bar(0, 16, 4), "    ");
+/// ```
+pub fn bar(value: usize, max: usize, cells: usize) -> String {
+    let eighths = match (value, max) {
+        (0, _) | (_, 0) => 0,
+        // Round to the nearest eighth, but never to nothing
+        _ => ((value * cells * 8 + max / 2) / max).max(1),
+    };
+    let full = eighths / 8;
+    let partial = (eighths % 8).checked_sub(1).map(|i| EIGHTHS[i]);
+    let drawn = full + usize::from(partial.is_some());
+    std::iter::repeat_n(EIGHTHS[7], full)
+        .chain(partial)
+        .chain(std::iter::repeat_n(' ', cells.saturating_sub(drawn)))
+        .collect()
+}
+
+/// One line per row, `label bar value`, with labels and values aligned and
+/// the whole line at most `width` characters when the labels and values
+/// leave room for a bar at all
+///
+/// ```
+/// use fp::viz::bar_chart;
+///
+/// let chart = bar_chart([("fold", 4), ("map", 2)], 14);
+/// assert_eq!(chart, "fold ███████ 4\nmap  ███▌    2");
+/// ```
+pub fn bar_chart<L: Display>(rows: impl IntoIterator<Item = (L, usize)>, width: usize) -> String {
+    let rows: Vec<(String, usize)> = rows
+        .into_iter()
+        .map(|(label, value)| (label.to_string(), value))
+        .collect();
+    let label_width = rows
+        .iter()
+        .map(|(label, _)| label.chars().count())
+        .max()
+        .unwrap_or(0);
+    let max = rows.iter().map(|&(_, value)| value).max().unwrap_or(0);
+    let value_width = max.to_string().len();
+    let cells = width.saturating_sub(label_width + value_width + 2).max(1);
+    rows.iter()
+        .map(|(label, value)| {
+            format!(
+                "{:<label_width$} {} {:>value_width$}",
+                label,
+                bar(*value, max, cells),
+                value
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// How many words of each length in characters `counts` holds, counting
+/// every occurrence, shortest first
+///
+/// ```
+/// use fp::analysis::word_counts;
+/// use fp::viz::{bar_chart, length_histogram};
+///
+/// let histogram = length_histogram(&word_counts("a fold a map a filter"));
+/// assert_eq!(histogram, [(1, 3), (3, 1), (4, 1), (6, 1)]);
+/// assert_eq!(bar_chart(histogram, 10).lines().next(), Some("1 ██████ 3"));
+/// ```
+pub fn length_histogram(counts: &HashMap<String, usize>) -> Vec<(usize, usize)> {
+    counts
+        .iter()
+        .fold(BTreeMap::new(), |mut lengths, (word, &count)| {
+            *lengths.entry(word.chars().count()).or_insert(0) += count;
+            lengths
+        })
+        .into_iter()
+        .collect()
+}
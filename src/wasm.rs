@@ -0,0 +1,106 @@
+//! Browser Bindings
+//!
+//! `wasm-bindgen` exports for the teaching playground, enabled by the `wasm`
+//! feature. They are thin wrappers: options are read from a plain JS object,
+//! the work is done by [`analysis::analyze`](crate::analysis::analyze) and
+//! [`expr::eval`](crate::expr::eval), and results go back as JS values.
+//!
+//! ```js
+//! import init, { analyze, evalExpr } from "./pkg/fp.js";
+//!
+//! await init();
+//! analyze("the cat the hat", { minLength: 3, startsWith: "t", top: 5 });
+//! // { total_words: 2, unique_words: 1, reading_seconds: 1, speaking_seconds: 1,
+//! //   top_words: [{ word: "the", count: 2 }] }
+//! evalExpr(["*", ["+", 1, 2], 4]); // 12
+//! ```
+
+use crate::analysis::{self, DEFAULT_TOP, Filters, LengthMode};
+use crate::expr::{self, Expr};
+use crate::normalize::Normalization;
+use js_sys::{Array, JSON, Reflect};
+use wasm_bindgen::prelude::*;
+
+/// Analyze `text` with `options` (`normalize`, `stripAccents`,
+/// `minLength`, `maxLength`, `lengthMode`, `startsWith`, `endsWith`,
+/// `contains`, `fuzziness`, `locale`, `top`, all optional) and return the
+/// report as a JS object.
+///
+/// ```js
+/// analyze("fold map fold", { top: 1 }).top_words; // [{ word: "fold", count: 2 }]
+/// analyze("fold", { normalize: "nfd" });          // throws: unknown normalization
+/// ```
+#[wasm_bindgen]
+pub fn analyze(text: &str, options: JsValue) -> Result<JsValue, JsValue> {
+    let field = |name: &str| Reflect::get(&options, &JsValue::from_str(name));
+    let (filters, top) = if options.is_object() {
+        let filters = Filters {
+            min_length: field("minLength")?.as_f64().map(|n| n as usize),
+            max_length: field("maxLength")?.as_f64().map(|n| n as usize),
+            starts_with: field("startsWith")?.as_string(),
+            ends_with: field("endsWith")?.as_string(),
+            contains: field("contains")?.as_string(),
+            fuzziness: field("fuzziness")?.as_f64().map_or(0, |n| n as usize),
+            normalization: match field("normalize")?.as_string() {
+                Some(form) => form
+                    .parse()
+                    .map_err(|err: String| JsValue::from_str(&err))?,
+                None => Normalization::default(),
+            },
+            strip_accents: field("stripAccents")?.is_truthy(),
+            length_mode: match field("lengthMode")?.as_string() {
+                Some(mode) => mode
+                    .parse()
+                    .map_err(|err: String| JsValue::from_str(&err))?,
+                None => LengthMode::default(),
+            },
+            locale: field("locale")?
+                .as_string()
+                .map(|locale| locale.parse())
+                .transpose()
+                .map_err(|err: String| JsValue::from_str(&err))?,
+        };
+        let top = field("top")?.as_f64().map_or(DEFAULT_TOP, |n| n as usize);
+        (filters, top)
+    } else {
+        (Filters::default(), DEFAULT_TOP)
+    };
+    JSON::parse(&analysis::analyze(text, &filters, top).to_json())
+}
+
+/// Evaluate an expression written as nested arrays: a number is a constant
+/// and `[op, left, right]` applies `op` (`"+"`, `"-"` or `"*"`).
+///
+/// ```js
+/// evalExpr(["-", 10, ["*", 2, 3]]); // 4
+/// evalExpr(["/", 1, 2]);            // throws: unknown operator
+/// evalExpr(["+", 0.5, 1]);         // throws: expected a whole number
+/// ```
+#[wasm_bindgen(js_name = evalExpr)]
+pub fn eval_expr(tree: JsValue) -> Result<i32, JsValue> {
+    to_expr(&tree).map(|e| expr::eval_tramp(&e))
+}
+
+/// Convert a JS expression tree into an [`Expr`]
+fn to_expr(value: &JsValue) -> Result<Expr, JsValue> {
+    if let Some(n) = value.as_f64() {
+        // A float such as 2.5 or 1e10 would be silently truncated by `as`
+        return (n.fract() == 0.0)
+            .then(|| i32::try_from(n as i64).ok())
+            .flatten()
+            .map(Expr::Const)
+            .ok_or_else(|| JsValue::from_str("expected a whole number within the 32-bit range"));
+    }
+    let node = value
+        .dyn_ref::<Array>()
+        .filter(|node| node.length() == 3)
+        .ok_or_else(|| JsValue::from_str("expected a number or [op, left, right]"))?;
+    let left = Box::new(to_expr(&node.get(1))?);
+    let right = Box::new(to_expr(&node.get(2))?);
+    match node.get(0).as_string().as_deref() {
+        Some("+") => Ok(Expr::Add(left, right)),
+        Some("-") => Ok(Expr::Sub(left, right)),
+        Some("*") => Ok(Expr::Mul(left, right)),
+        _ => Err(JsValue::from_str("unknown operator, expected +, - or *")),
+    }
+}
@@ -0,0 +1,468 @@
+//! Zippers: a focused position inside an immutable structure.
+//!
+//! A zipper splits a structure into the element under focus and the
+//! "context" needed to rebuild the rest. Moving the focus and editing at it
+//! are O(1) (per step), without mutation and without re-walking from the
+//! root. Moving past an edge leaves the zipper unchanged.
+
+use crate::expr::Expr;
+
+/// A list with a cursor on one element.
+///
+/// ```
+/// use fp::zipper::ListZipper;
+///
+/// let z = ListZipper::from_vec(vec![1, 2, 3]).unwrap()
+///     .right()
+///     .modify(|x| x * 10)
+///     .insert_right(25)
+///     .right();
+/// assert_eq!(*z.focus(), 25);
+/// assert_eq!(z.into_vec(), [1, 20, 25, 3]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ListZipper<T> {
+    /// Elements before the focus, nearest last.
+    before: Vec<T>,
+    focus: T,
+    /// Elements after the focus, nearest last.
+    after: Vec<T>,
+}
+
+impl<T> ListZipper<T> {
+    /// Focuses the first element, or returns `None` for an empty vector.
+    ///
+    /// ```
+    /// use fp::zipper::ListZipper;
+    ///
+    /// assert_eq!(*ListZipper::from_vec(vec![1, 2]).unwrap().focus(), 1);
+    /// assert!(ListZipper::<i32>::from_vec(Vec::new()).is_none());
+    /// ```
+    pub fn from_vec(mut items: Vec<T>) -> Option<Self> {
+        items.reverse();
+        let focus = items.pop()?;
+        Some(Self {
+            before: Vec::new(),
+            focus,
+            after: items,
+        })
+    }
+
+    /// The focused element.
+    ///
+    /// ```
+    /// use fp::zipper::ListZipper;
+    ///
+    /// let z = ListZipper::from_vec(vec!["a", "b"]).unwrap().right();
+    /// assert_eq!(*z.focus(), "b");
+    /// ```
+    pub fn focus(&self) -> &T {
+        &self.focus
+    }
+
+    /// Index of the focused element.
+    ///
+    /// ```
+    /// use fp::zipper::ListZipper;
+    ///
+    /// let z = ListZipper::from_vec(vec!['a', 'b', 'c']).unwrap().right().right();
+    /// assert_eq!(z.index(), 2);
+    /// ```
+    pub fn index(&self) -> usize {
+        self.before.len()
+    }
+
+    /// Total number of elements.
+    ///
+    /// ```
+    /// use fp::zipper::ListZipper;
+    ///
+    /// let z = ListZipper::from_vec(vec![1, 2]).unwrap().insert_right(3);
+    /// assert_eq!(z.len(), 3);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.before.len() + 1 + self.after.len()
+    }
+
+    /// Always `false`: a zipper has at least the focused element.
+    ///
+    /// ```
+    /// use fp::zipper::ListZipper;
+    ///
+    /// assert!(!ListZipper::from_vec(vec![1]).unwrap().is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    /// Moves the focus one element to the left.
+    ///
+    /// ```
+    /// use fp::zipper::ListZipper;
+    ///
+    /// let z = ListZipper::from_vec(vec![1, 2]).unwrap().right().left();
+    /// assert_eq!(*z.focus(), 1);
+    /// assert_eq!(*z.left().focus(), 1, "moving past the edge changes nothing");
+    /// ```
+    pub fn left(mut self) -> Self {
+        if let Some(prev) = self.before.pop() {
+            self.after.push(std::mem::replace(&mut self.focus, prev));
+        }
+        self
+    }
+
+    /// Moves the focus one element to the right.
+    ///
+    /// ```
+    /// use fp::zipper::ListZipper;
+    ///
+    /// let z = ListZipper::from_vec(vec![1, 2]).unwrap().right();
+    /// assert_eq!(*z.focus(), 2);
+    /// assert_eq!(*z.right().focus(), 2, "moving past the edge changes nothing");
+    /// ```
+    pub fn right(mut self) -> Self {
+        if let Some(next) = self.after.pop() {
+            self.before.push(std::mem::replace(&mut self.focus, next));
+        }
+        self
+    }
+
+    /// Replaces the focused element.
+    ///
+    /// ```
+    /// use fp::zipper::ListZipper;
+    ///
+    /// let z = ListZipper::from_vec(vec![1, 2, 3]).unwrap().right().set(0);
+    /// assert_eq!(z.into_vec(), [1, 0, 3]);
+    /// ```
+    pub fn set(self, value: T) -> Self {
+        Self {
+            focus: value,
+            ..self
+        }
+    }
+
+    /// Transforms the focused element.
+    ///
+    /// ```
+    /// use fp::zipper::ListZipper;
+    ///
+    /// let z = ListZipper::from_vec(vec![1, 2, 3]).unwrap().right().modify(|x| x + 40);
+    /// assert_eq!(z.into_vec(), [1, 42, 3]);
+    /// ```
+    pub fn modify<F: FnOnce(T) -> T>(self, f: F) -> Self {
+        Self {
+            focus: f(self.focus),
+            ..self
+        }
+    }
+
+    /// Inserts an element just before the focus.
+    ///
+    /// ```
+    /// use fp::zipper::ListZipper;
+    ///
+    /// let z = ListZipper::from_vec(vec![2, 3]).unwrap().insert_left(1);
+    /// assert_eq!(*z.focus(), 2);
+    /// assert_eq!(z.into_vec(), [1, 2, 3]);
+    /// ```
+    pub fn insert_left(mut self, value: T) -> Self {
+        self.before.push(value);
+        self
+    }
+
+    /// Inserts an element just after the focus.
+    ///
+    /// ```
+    /// use fp::zipper::ListZipper;
+    ///
+    /// let z = ListZipper::from_vec(vec![1, 3]).unwrap().insert_right(2);
+    /// assert_eq!(*z.focus(), 1);
+    /// assert_eq!(z.into_vec(), [1, 2, 3]);
+    /// ```
+    pub fn insert_right(mut self, value: T) -> Self {
+        self.after.push(value);
+        self
+    }
+
+    /// Removes the focused element, focusing its right neighbour (or left
+    /// neighbour at the end). Returns `None` if it was the only element.
+    ///
+    /// ```
+    /// use fp::zipper::ListZipper;
+    ///
+    /// let z = ListZipper::from_vec(vec![1, 2, 3]).unwrap().right().delete().unwrap();
+    /// assert_eq!(*z.focus(), 3);
+    /// assert_eq!(z.into_vec(), [1, 3]);
+    /// assert!(ListZipper::from_vec(vec![1]).unwrap().delete().is_none());
+    /// ```
+    pub fn delete(mut self) -> Option<Self> {
+        let focus = self.after.pop().or_else(|| self.before.pop())?;
+        Some(Self { focus, ..self })
+    }
+
+    /// Rebuilds the whole list.
+    ///
+    /// ```
+    /// use fp::zipper::ListZipper;
+    ///
+    /// let z = ListZipper::from_vec(vec![1, 2, 3]).unwrap().right().right();
+    /// assert_eq!(z.into_vec(), [1, 2, 3], "wherever the focus is");
+    /// ```
+    pub fn into_vec(self) -> Vec<T> {
+        let mut items = self.before;
+        items.push(self.focus);
+        items.extend(self.after.into_iter().rev());
+        items
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BinOp {
+    Add,
+    Mul,
+    Sub,
+}
+
+impl BinOp {
+    fn join(self, left: Expr, right: Expr) -> Expr {
+        let (l, r) = (Box::new(left), Box::new(right));
+        match self {
+            BinOp::Add => Expr::Add(l, r),
+            BinOp::Mul => Expr::Mul(l, r),
+            BinOp::Sub => Expr::Sub(l, r),
+        }
+    }
+}
+
+/// Splits a binary node into its operator and operands.
+fn split(expr: Expr) -> Result<(BinOp, Expr, Expr), Expr> {
+    match expr {
+        Expr::Add(l, r) => Ok((BinOp::Add, *l, *r)),
+        Expr::Mul(l, r) => Ok((BinOp::Mul, *l, *r)),
+        Expr::Sub(l, r) => Ok((BinOp::Sub, *l, *r)),
+        leaf => Err(leaf),
+    }
+}
+
+/// One step of the path from the root down to the focus.
+#[derive(Debug, Clone, PartialEq)]
+enum Crumb {
+    /// We went into the left operand; the right one is kept here.
+    Left(BinOp, Expr),
+    /// We went into the right operand; the left one is kept here.
+    Right(BinOp, Expr),
+}
+
+/// A cursor on one subexpression of an [`Expr`] tree.
+///
+/// ```
+/// use fp::expr::{eval, Expr};
+/// use fp::zipper::ExprZipper;
+///
+/// // (2 + 3) * (5 - 1)
+/// let expr = Expr::Mul(
+///     Box::new(Expr::Add(Box::new(Expr::Const(2)), Box::new(Expr::Const(3)))),
+///     Box::new(Expr::Sub(Box::new(Expr::Const(5)), Box::new(Expr::Const(1)))),
+/// );
+///
+/// // Focus the 5 and replace it with 9: (2 + 3) * (9 - 1)
+/// let edited = ExprZipper::new(expr).down_right().down_left().replace(Expr::Const(9)).into_expr();
+/// assert_eq!(eval(&edited), 40);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExprZipper {
+    focus: Expr,
+    path: Vec<Crumb>,
+}
+
+impl ExprZipper {
+    /// Focuses the root of `expr`.
+    ///
+    /// ```
+    /// use fp::expr::Expr;
+    /// use fp::zipper::ExprZipper;
+    ///
+    /// let z = ExprZipper::new(Expr::Const(7));
+    /// assert!(z.is_root());
+    /// assert_eq!(z.focus(), &Expr::Const(7));
+    /// ```
+    pub fn new(expr: Expr) -> Self {
+        Self {
+            focus: expr,
+            path: Vec::new(),
+        }
+    }
+
+    /// The focused subexpression.
+    ///
+    /// ```
+    /// use fp::expr::Expr;
+    /// use fp::zipper::ExprZipper;
+    ///
+    /// let sum = Expr::Add(Box::new(Expr::Const(1)), Box::new(Expr::Const(2)));
+    /// assert_eq!(ExprZipper::new(sum).down_right().focus(), &Expr::Const(2));
+    /// ```
+    pub fn focus(&self) -> &Expr {
+        &self.focus
+    }
+
+    /// Number of steps from the root to the focus.
+    ///
+    /// ```
+    /// use fp::expr::Expr;
+    /// use fp::zipper::ExprZipper;
+    ///
+    /// let sum = Expr::Add(Box::new(Expr::Const(1)), Box::new(Expr::Const(2)));
+    /// assert_eq!(ExprZipper::new(sum).down_left().depth(), 1);
+    /// ```
+    pub fn depth(&self) -> usize {
+        self.path.len()
+    }
+
+    /// Returns `true` if the focus is the root.
+    ///
+    /// ```
+    /// use fp::expr::Expr;
+    /// use fp::zipper::ExprZipper;
+    ///
+    /// let sum = Expr::Add(Box::new(Expr::Const(1)), Box::new(Expr::Const(2)));
+    /// let z = ExprZipper::new(sum);
+    /// assert!(z.is_root());
+    /// assert!(!z.down_right().is_root());
+    /// ```
+    pub fn is_root(&self) -> bool {
+        self.path.is_empty()
+    }
+
+    /// Moves into the left operand of the focused operator.
+    ///
+    /// ```
+    /// use fp::expr::Expr;
+    /// use fp::zipper::ExprZipper;
+    ///
+    /// let sum = Expr::Add(Box::new(Expr::Const(1)), Box::new(Expr::Const(2)));
+    /// let z = ExprZipper::new(sum).down_left();
+    /// assert_eq!(z.focus(), &Expr::Const(1));
+    /// assert_eq!(z.down_left().depth(), 1, "a constant has no operands");
+    /// ```
+    pub fn down_left(self) -> Self {
+        self.down(|op, l, r| (l, Crumb::Left(op, r)))
+    }
+
+    /// Moves into the right operand of the focused operator.
+    ///
+    /// ```
+    /// use fp::expr::Expr;
+    /// use fp::zipper::ExprZipper;
+    ///
+    /// let sum = Expr::Add(Box::new(Expr::Const(1)), Box::new(Expr::Const(2)));
+    /// assert_eq!(ExprZipper::new(sum).down_right().focus(), &Expr::Const(2));
+    /// ```
+    pub fn down_right(self) -> Self {
+        self.down(|op, l, r| (r, Crumb::Right(op, l)))
+    }
+
+    fn down<F: FnOnce(BinOp, Expr, Expr) -> (Expr, Crumb)>(mut self, pick: F) -> Self {
+        match split(self.focus) {
+            Ok((op, l, r)) => {
+                let (focus, crumb) = pick(op, l, r);
+                self.path.push(crumb);
+                Self { focus, ..self }
+            }
+            Err(leaf) => Self {
+                focus: leaf,
+                ..self
+            },
+        }
+    }
+
+    /// Moves to the parent of the focus.
+    ///
+    /// ```
+    /// use fp::expr::Expr;
+    /// use fp::zipper::ExprZipper;
+    ///
+    /// let sum = Expr::Add(Box::new(Expr::Const(1)), Box::new(Expr::Const(2)));
+    /// let z = ExprZipper::new(sum.clone()).down_right().up();
+    /// assert_eq!(z.focus(), &sum);
+    /// ```
+    pub fn up(mut self) -> Self {
+        let focus = match self.path.pop() {
+            Some(Crumb::Left(op, right)) => op.join(self.focus, right),
+            Some(Crumb::Right(op, left)) => op.join(left, self.focus),
+            None => self.focus,
+        };
+        Self { focus, ..self }
+    }
+
+    /// Moves the focus back to the root.
+    ///
+    /// ```
+    /// use fp::expr::{Expr, eval};
+    /// use fp::zipper::ExprZipper;
+    ///
+    /// // 1 + (2 * 3), with the 3 replaced by 10
+    /// let expr = Expr::Add(
+    ///     Box::new(Expr::Const(1)),
+    ///     Box::new(Expr::Mul(Box::new(Expr::Const(2)), Box::new(Expr::Const(3)))),
+    /// );
+    /// let z = ExprZipper::new(expr).down_right().down_right().replace(Expr::Const(10)).top();
+    /// assert!(z.is_root());
+    /// assert_eq!(eval(z.focus()), 21);
+    /// ```
+    pub fn top(self) -> Self {
+        (0..self.depth()).fold(self, |z, _| z.up())
+    }
+
+    /// Replaces the focused subexpression.
+    ///
+    /// ```
+    /// use fp::expr::{Expr, eval};
+    /// use fp::zipper::ExprZipper;
+    ///
+    /// let sum = Expr::Add(Box::new(Expr::Const(1)), Box::new(Expr::Const(2)));
+    /// let z = ExprZipper::new(sum).down_left().replace(Expr::Const(40));
+    /// assert_eq!(eval(&z.into_expr()), 42);
+    /// ```
+    pub fn replace(self, expr: Expr) -> Self {
+        Self {
+            focus: expr,
+            ..self
+        }
+    }
+
+    /// Transforms the focused subexpression.
+    ///
+    /// ```
+    /// use fp::expr::{Expr, eval};
+    /// use fp::zipper::ExprZipper;
+    ///
+    /// // Negate the right operand of 5 - 3 by subtracting it from 0
+    /// let difference = Expr::Sub(Box::new(Expr::Const(5)), Box::new(Expr::Const(3)));
+    /// let z = ExprZipper::new(difference)
+    ///     .down_right()
+    ///     .modify(|e| Expr::Sub(Box::new(Expr::Const(0)), Box::new(e)));
+    /// assert_eq!(eval(&z.into_expr()), 8);
+    /// ```
+    pub fn modify<F: FnOnce(Expr) -> Expr>(self, f: F) -> Self {
+        Self {
+            focus: f(self.focus),
+            ..self
+        }
+    }
+
+    /// Rebuilds the whole, edited expression.
+    ///
+    /// ```
+    /// use fp::expr::Expr;
+    /// use fp::zipper::ExprZipper;
+    ///
+    /// let sum = Expr::Add(Box::new(Expr::Const(1)), Box::new(Expr::Const(2)));
+    /// let z = ExprZipper::new(sum.clone()).down_right();
+    /// assert_eq!(z.into_expr(), sum, "rebuilt from wherever the focus is");
+    /// ```
+    pub fn into_expr(self) -> Expr {
+        self.top().focus
+    }
+}
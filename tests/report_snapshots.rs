@@ -0,0 +1,175 @@
+//! Snapshot tests of every report the analyzer renders, for one fixture
+//! and a matrix of configurations, so that a change to the output format
+//! shows up as a reviewed snapshot diff rather than by surprise.
+//!
+//! The text reports come from running the `file_parser` binary; JSON is the
+//! `Report` the server, FFI and WASM bindings share; CSV, Markdown and HTML
+//! are the example `--template`s in `tests/fixtures/templates`.
+//!
+//! After an intended change, review and accept the new snapshots with
+//! `cargo insta review` (or rerun with `INSTA_UPDATE=always`).
+
+use std::process::Command;
+
+use fp::analysis::{DEFAULT_TOP, Filters, Report, analyze, word_counts};
+use fp::locale::Locale;
+
+const FIXTURE: &str = "tests/fixtures/report.txt";
+
+/// Text as it comes out of OCR: ligatures, a soft hyphen and words broken
+/// across lines
+const SCANNED: &str = "tests/fixtures/scanned.txt";
+
+/// What `file_parser FIXTURE args...` prints to stdout
+fn file_parser(args: &[&str]) -> String {
+    run(&[&[FIXTURE], args].concat())
+}
+
+/// What `file_parser command FIXTURE args...` prints to stdout
+fn subcommand(command: &str, args: &[&str]) -> String {
+    run(&[&[command, FIXTURE], args].concat())
+}
+
+/// What `file_parser args...` prints to stdout, asserting it succeeds
+fn run(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_file_parser"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        // Charts are as wide as the terminal
+        .env("COLUMNS", "60")
+        .args(args)
+        .output()
+        .expect("file_parser runs");
+    assert!(output.status.success(), "file_parser {:?} failed", args);
+    String::from_utf8(output.stdout).expect("the report is UTF-8")
+}
+
+fn fixture() -> String {
+    std::fs::read_to_string(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/report.txt"
+    ))
+    .expect("the fixture is readable")
+}
+
+#[test]
+fn text_reports() {
+    let matrix: &[(&str, &[&str])] = &[
+        ("text_default", &[]),
+        ("text_sorted", &["--sorted"]),
+        ("text_by_first_letter", &["--by-first-letter"]),
+        ("text_chart", &["--chart", "5"]),
+        ("text_goal", &["--goal", "100"]),
+        ("text_diversity", &["--diversity"]),
+        ("text_compress_demo", &["--compress-demo"]),
+        ("text_fun_facts", &["--fun-facts"]),
+        ("text_key_phrases", &["--keywords", "5"]),
+        ("text_summary", &["--summarize", "2"]),
+        ("text_anagrams", &["--anagrams"]),
+        ("text_phonetic", &["--phonetic"]),
+        ("text_complete", &["--complete", "f"]),
+        ("text_fuzzy", &["--fuzzy", "wurds", "--max-dist", "2"]),
+        (
+            "text_keywords",
+            &["--reference", "tests/fixtures/reference.txt"],
+        ),
+        ("text_export_wordfreq", &["--export", "wordfreq"]),
+        ("text_export_hunspell", &["--export", "hunspell"]),
+        ("text_min_length", &["--min-length", "5", "--sorted"]),
+        (
+            "text_fuzzy_filters",
+            &["--starts-with", "wurd", "--fuzzy-filters", "1", "--sorted"],
+        ),
+        ("text_strip_accents", &["--strip-accents", "--sorted"]),
+        (
+            "text_stopwords",
+            &["--locale", "en", "--filters", "stopwords", "--sorted"],
+        ),
+    ];
+    for (name, args) in matrix {
+        insta::assert_snapshot!(*name, file_parser(args));
+    }
+}
+
+#[test]
+fn json_reports() {
+    let text = fixture();
+    insta::assert_snapshot!(
+        "json_default",
+        Report::new(&word_counts(&text), DEFAULT_TOP).to_json()
+    );
+
+    let filters = Filters {
+        strip_accents: true,
+        min_length: Some(4),
+        locale: Some(Locale::German),
+        ..Filters::default()
+    };
+    insta::assert_snapshot!("json_filtered", analyze(&text, &filters, 5).to_json());
+}
+
+#[test]
+fn templated_reports() {
+    for format in ["csv", "md", "html"] {
+        let template = format!("tests/fixtures/templates/report.{}", format);
+        insta::assert_snapshot!(
+            format!("template_{}", format),
+            file_parser(&["--template", &template])
+        );
+    }
+}
+
+#[test]
+fn generated_text() {
+    insta::assert_snapshot!(
+        "generate_seeded",
+        subcommand("generate", &["--seed", "7", "--words", "30"])
+    );
+}
+
+#[test]
+fn scanned_reports() {
+    let matrix: &[(&str, &[&str])] = &[
+        ("scanned_raw", &["--sorted"]),
+        ("scanned_clean_ocr", &["--clean-ocr", "--sorted"]),
+        ("scanned_rejoin_hyphens", &["--rejoin-hyphens", "--sorted"]),
+    ];
+    for (name, args) in matrix {
+        let report = run(&[&[SCANNED], *args].concat());
+        // Streaming the lines through --spill cleans them the same way
+        let spill_dir = std::env::temp_dir();
+        let spilled = run(&[&[SCANNED, "--spill", spill_dir.to_str().unwrap()], *args].concat());
+        assert_eq!(spilled, report, "{} with --spill", name);
+        insta::assert_snapshot!(*name, report);
+    }
+}
+
+#[test]
+fn checkpointed_reports() {
+    let root = std::env::temp_dir().join("fp_checkpointed_reports");
+    let state = std::env::temp_dir().join("fp_checkpointed_reports.state");
+    std::fs::create_dir_all(root.join("nested")).unwrap();
+    std::fs::copy(FIXTURE, root.join("report.txt")).unwrap();
+    std::fs::copy(SCANNED, root.join("nested/scanned.txt")).unwrap();
+    let scan = |resume: &[&str]| {
+        let args = [
+            root.to_str().unwrap(),
+            "--checkpoint",
+            state.to_str().unwrap(),
+        ];
+        run(&[&args, resume, &["--sorted"]].concat())
+    };
+
+    let report = scan(&[]);
+    // Every file is finished, so resuming counts nothing again
+    assert_eq!(scan(&["--resume"]), report);
+    insta::assert_snapshot!("checkpoint_sorted", report);
+
+    // A run stopped after report.txt resumes with its counts and reads
+    // only the other file
+    let finished = std::fs::canonicalize(root.join("report.txt")).unwrap();
+    let stopped = format!("fp-checkpoint 1\nF {}\nW 3 resumed\n", finished.display());
+    std::fs::write(&state, stopped).unwrap();
+    insta::assert_snapshot!("checkpoint_resumed", scan(&["--resume"]));
+    std::fs::remove_dir_all(&root).unwrap();
+    std::fs::remove_file(&state).unwrap();
+}
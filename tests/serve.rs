@@ -0,0 +1,75 @@
+//! End-to-end tests of the `serve` binary: each test starts the service on
+//! a free local port and talks plain HTTP/1.1 to it over a socket.
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::process::{Child, Command};
+use std::thread::sleep;
+use std::time::Duration;
+
+/// The service's body limit, as `serve` sets it
+const MAX_BODY: usize = 16 * 1024 * 1024;
+
+/// A running `serve`, killed when dropped
+struct Service {
+    child: Child,
+    addr: String,
+}
+
+impl Service {
+    fn start() -> Self {
+        let addr = TcpListener::bind("127.0.0.1:0")
+            .and_then(|listener| listener.local_addr())
+            .expect("a free port")
+            .to_string();
+        let child = Command::new(env!("CARGO_BIN_EXE_serve"))
+            .args([addr.as_str(), "--log-level", "off"])
+            .spawn()
+            .expect("serve starts");
+        let service = Service { child, addr };
+        let listening = (0..100).any(|_| {
+            sleep(Duration::from_millis(50));
+            TcpStream::connect(&service.addr).is_ok()
+        });
+        assert!(listening, "serve listens on {}", service.addr);
+        service
+    }
+
+    /// The raw response to `POST path` with `body`
+    fn post(&self, path: &str, body: &[u8]) -> String {
+        let mut stream = TcpStream::connect(&self.addr).expect("serve accepts");
+        let head = format!(
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            path,
+            self.addr,
+            body.len()
+        );
+        stream
+            .write_all(head.as_bytes())
+            .expect("the request is sent");
+        stream.write_all(body).expect("the body is sent");
+        let mut response = String::new();
+        stream.read_to_string(&mut response).expect("serve answers");
+        response
+    }
+}
+
+impl Drop for Service {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[test]
+fn analyzes_a_text_body() {
+    let response = Service::start().post("/analyze?top=1", b"fold map fold");
+    assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+    assert!(response.contains(r#""top_words":[{"word":"fold","count":2}]"#));
+}
+
+#[test]
+fn refuses_a_body_over_the_limit() {
+    let response = Service::start().post("/analyze", &vec![b'a'; MAX_BODY + 1]);
+    assert!(response.starts_with("HTTP/1.1 413"), "{}", response);
+}